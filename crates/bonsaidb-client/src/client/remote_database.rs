@@ -2,19 +2,24 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bonsaidb_core::api::Api;
 use bonsaidb_core::connection::{
-    AccessPolicy, AsyncConnection, AsyncLowLevelConnection, HasSchema, HasSession, Range,
-    SerializedQueryKey, Session, Sort,
+    AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncResultChunks, HasSchema,
+    HasSession, Range, SerializedQueryKey, Session, Sort, ViewCursor, ViewSetQuery,
+};
+use bonsaidb_core::document::{
+    DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection,
 };
-use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::networking::{
-    ApplyTransaction, Compact, CompactCollection, CompactKeyValueStore, Count, DeleteDocs, Get,
-    GetMultiple, LastTransactionId, List, ListExecutedTransactions, ListHeaders, Query,
-    QueryWithDocs, Reduce, ReduceGrouped,
+    ApplyTransaction, CloseStream, Compact, CompactCollection, CompactKeyValueStore, Count,
+    DeleteDocs, Get, GetMultiple, LastTransactionId, List, ListExecutedTransactions, ListHeaders,
+    ListProjected, NextChunk, Query, QueryViewSet, QueryWithDocs, QueryWithProjectedDocs, Reduce,
+    ReduceGrouped, ResultChunk, StreamList, StreamQuery, StreamQueryWithDocs,
 };
-use bonsaidb_core::schema::view::map::MappedSerializedValue;
+use bonsaidb_core::schema::view::map::{MappedSerializedDocuments, MappedSerializedValue};
 use bonsaidb_core::schema::{self, CollectionName, Schematic, ViewName};
 use bonsaidb_core::transaction::{Executed, OperationResult, Transaction};
+use futures::TryStreamExt;
 
 use crate::AsyncClient;
 
@@ -36,6 +41,37 @@ impl AsyncRemoteDatabase {
     pub fn name(&self) -> &str {
         self.name.as_ref()
     }
+
+    /// Opens a stream on the server using `open`, returning each of its
+    /// chunks after converting it with `convert`.
+    fn result_chunks<Open, T>(
+        &self,
+        open: Open,
+        convert: fn(ResultChunk) -> Option<T>,
+    ) -> AsyncResultChunks<'static, T>
+    where
+        Open: Api<Response = u64, Error = bonsaidb_core::Error>,
+        T: Send + 'static,
+    {
+        let client = self.client.clone();
+        Box::pin(
+            futures::stream::once(async move {
+                let stream_id = client.send_api_request(&open).await?;
+                Ok::<_, bonsaidb_core::Error>(OpenStream::new(client, stream_id))
+            })
+            .map_ok(move |stream| {
+                futures::stream::try_unfold(stream, move |mut stream| async move {
+                    match stream.next_chunk().await? {
+                        Some(chunk) => {
+                            Ok(Some((convert(chunk).ok_or_else(unexpected_chunk)?, stream)))
+                        }
+                        None => Ok::<_, bonsaidb_core::Error>(None),
+                    }
+                })
+            })
+            .try_flatten(),
+        )
+    }
 }
 
 impl Deref for AsyncRemoteDatabase {
@@ -161,6 +197,7 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
         Ok(self
@@ -171,6 +208,32 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
                 ids,
                 order,
                 limit,
+                filter,
+            })
+            .await?)
+    }
+
+    async fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, bonsaidb_core::Error> {
+        Ok(self
+            .client
+            .send_api_request(&ListProjected {
+                list: List {
+                    database: self.name.to_string(),
+                    collection: collection.clone(),
+                    ids,
+                    order,
+                    limit,
+                    filter,
+                },
+                projection,
             })
             .await?)
     }
@@ -180,6 +243,7 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, bonsaidb_core::Error> {
         Ok(self
@@ -190,6 +254,7 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
                 ids,
                 order,
                 limit,
+                filter,
             }))
             .await?)
     }
@@ -227,6 +292,7 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, bonsaidb_core::Error> {
         Ok(self
@@ -237,6 +303,7 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
                 key,
                 order,
                 limit,
+                cursor,
                 access_policy,
             })
             .await?)
@@ -248,18 +315,71 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
         Ok(self
             .client
-            .send_api_request(&QueryWithDocs(Query {
+            .send_api_request(&QueryWithDocs {
+                query: Query {
+                    database: self.name.to_string(),
+                    view: view.clone(),
+                    key,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                },
+                filter,
+            })
+            .await?)
+    }
+
+    async fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<schema::view::map::MappedSerializedDocuments<ProjectedDocument>, bonsaidb_core::Error>
+    {
+        Ok(self
+            .client
+            .send_api_request(&QueryWithProjectedDocs {
+                query: QueryWithDocs {
+                    query: Query {
+                        database: self.name.to_string(),
+                        view: view.clone(),
+                        key,
+                        order,
+                        limit,
+                        cursor,
+                        access_policy,
+                    },
+                    filter,
+                },
+                projection,
+            })
+            .await?)
+    }
+
+    async fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        Ok(self
+            .client
+            .send_api_request(&QueryViewSet {
                 database: self.name.to_string(),
-                view: view.clone(),
-                key,
-                order,
+                query,
                 limit,
-                access_policy,
-            }))
+            })
             .await?)
     }
 
@@ -314,6 +434,149 @@ impl AsyncLowLevelConnection for AsyncRemoteDatabase {
             })
             .await?)
     }
+
+    fn list_chunks_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        chunk_size: u32,
+        collection: &CollectionName,
+    ) -> AsyncResultChunks<'_, Vec<OwnedDocument>> {
+        self.result_chunks(
+            StreamList {
+                list: List {
+                    database: self.name.to_string(),
+                    collection: collection.clone(),
+                    ids,
+                    order,
+                    limit,
+                    filter,
+                },
+                chunk_size,
+            },
+            ResultChunk::into_documents,
+        )
+    }
+
+    fn query_chunks_by_name(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, Vec<schema::view::map::Serialized>> {
+        self.result_chunks(
+            StreamQuery {
+                query: Query {
+                    database: self.name.to_string(),
+                    view: view.clone(),
+                    key,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                },
+                chunk_size,
+            },
+            ResultChunk::into_mappings,
+        )
+    }
+
+    fn query_chunks_by_name_with_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, MappedSerializedDocuments> {
+        self.result_chunks(
+            StreamQueryWithDocs {
+                query: QueryWithDocs {
+                    query: Query {
+                        database: self.name.to_string(),
+                        view: view.clone(),
+                        key,
+                        order,
+                        limit,
+                        cursor,
+                        access_policy,
+                    },
+                    filter,
+                },
+                chunk_size,
+            },
+            ResultChunk::into_mapped_documents,
+        )
+    }
+}
+
+/// A stream of results opened on the server. If the stream is dropped before
+/// every chunk has been retrieved, the server is asked to close it.
+#[derive(Debug)]
+pub(crate) struct OpenStream {
+    client: AsyncClient,
+    id: u64,
+    finished: bool,
+}
+
+impl OpenStream {
+    pub(crate) fn new(client: AsyncClient, id: u64) -> Self {
+        Self {
+            client,
+            id,
+            finished: false,
+        }
+    }
+
+    /// Retrieves the next chunk of results. Returns `None` once every chunk
+    /// has been retrieved.
+    pub(crate) async fn next_chunk(&mut self) -> Result<Option<ResultChunk>, bonsaidb_core::Error> {
+        let chunk = self
+            .client
+            .send_api_request(&NextChunk { stream_id: self.id })
+            .await;
+        // The server closes the stream once it has finished or failed.
+        self.finished = !matches!(chunk, Ok(Some(_)));
+        Ok(chunk?)
+    }
+
+    /// Retrieves the next chunk of results, blocking the current thread.
+    /// Returns `None` once every chunk has been retrieved.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn next_chunk_blocking(
+        &mut self,
+    ) -> Result<Option<ResultChunk>, bonsaidb_core::Error> {
+        let chunk = self
+            .client
+            .send_blocking_api_request(&NextChunk { stream_id: self.id });
+        self.finished = !matches!(chunk, Ok(Some(_)));
+        Ok(chunk?)
+    }
+}
+
+impl Drop for OpenStream {
+    fn drop(&mut self) {
+        if !self.finished {
+            drop(
+                self.client
+                    .invoke_blocking_api_request(&CloseStream { stream_id: self.id }),
+            );
+        }
+    }
+}
+
+/// Returns the error for a chunk that doesn't match the opened stream.
+pub(crate) fn unexpected_chunk() -> bonsaidb_core::Error {
+    bonsaidb_core::Error::other("bonsaidb-client streams", "unexpected result chunk")
 }
 
 impl HasSchema for AsyncRemoteDatabase {
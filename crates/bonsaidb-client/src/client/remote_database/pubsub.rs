@@ -3,9 +3,11 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::networking::{
-    CreateSubscriber, Publish, PublishToAll, SubscribeTo, UnsubscribeFrom,
+    CreateSubscriber, Publish, PublishToAll, SubscribeTo, SubscribeToCollectionChanges,
+    SubscribeToKeyChanges, UnsubscribeFrom,
 };
 use bonsaidb_core::pubsub::{AsyncPubSub, AsyncSubscriber, Receiver};
+use bonsaidb_core::schema::CollectionName;
 
 use crate::AsyncClient;
 
@@ -100,6 +102,34 @@ impl AsyncSubscriber for AsyncRemoteSubscriber {
         Ok(())
     }
 
+    async fn subscribe_to_collection_changes(
+        &self,
+        collection: &CollectionName,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.client
+            .send_api_request(&SubscribeToCollectionChanges {
+                database: self.database.to_string(),
+                subscriber_id: self.id,
+                collection: collection.clone(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn subscribe_to_key_changes(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.client
+            .send_api_request(&SubscribeToKeyChanges {
+                database: self.database.to_string(),
+                subscriber_id: self.id,
+                namespace: namespace.map(String::from),
+            })
+            .await?;
+        Ok(())
+    }
+
     fn receiver(&self) -> &Receiver {
         &self.receiver
     }
@@ -7,16 +7,21 @@ use bonsaidb_core::api;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::connection::{
     AccessPolicy, Connection, Database, HasSchema, HasSession, IdentityReference,
-    LowLevelConnection, Range, SerializedQueryKey, Sort, StorageConnection,
+    LowLevelConnection, Range, ResultChunks, SerializedQueryKey, Sort, StorageConnection,
+    ViewCursor, ViewSetQuery,
+};
+use bonsaidb_core::document::{
+    DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection,
 };
-use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::keyvalue::KeyValue;
 use bonsaidb_core::networking::{
     AlterUserPermissionGroupMembership, AlterUserRoleMembership, ApplyTransaction, AssumeIdentity,
     Compact, CompactCollection, CompactKeyValueStore, Count, CreateDatabase, CreateSubscriber,
     CreateUser, DeleteDatabase, DeleteDocs, DeleteUser, ExecuteKeyOperation, Get, GetMultiple,
     LastTransactionId, List, ListAvailableSchemas, ListDatabases, ListExecutedTransactions,
-    ListHeaders, Publish, PublishToAll, Query, QueryWithDocs, Reduce, ReduceGrouped, SubscribeTo,
+    ListHeaders, ListProjected, Publish, PublishToAll, Query, QueryViewSet, QueryWithDocs,
+    QueryWithProjectedDocs, Reduce, ReduceGrouped, ResultChunk, StreamList, StreamQuery,
+    StreamQueryWithDocs, SubscribeTo, SubscribeToCollectionChanges, SubscribeToKeyChanges,
     UnsubscribeFrom, CURRENT_PROTOCOL_VERSION,
 };
 use bonsaidb_core::pubsub::{AsyncSubscriber, PubSub, Receiver, Subscriber};
@@ -29,6 +34,7 @@ use tokio::task::JoinHandle;
 use url::Url;
 
 use crate::builder::Blocking;
+use crate::client::remote_database::{unexpected_chunk, OpenStream};
 use crate::client::ClientSession;
 use crate::{ApiError, AsyncClient, AsyncRemoteDatabase, AsyncRemoteSubscriber, Builder, Error};
 
@@ -304,6 +310,40 @@ impl HasSession for BlockingClient {
 #[derive(Debug, Clone)]
 pub struct BlockingRemoteDatabase(AsyncRemoteDatabase);
 
+impl BlockingRemoteDatabase {
+    /// Opens a stream on the server using `open`, returning each of its
+    /// chunks after converting it with `convert`.
+    fn result_chunks<Open, T>(
+        &self,
+        open: &Open,
+        convert: fn(ResultChunk) -> Option<T>,
+    ) -> ResultChunks<'static, T>
+    where
+        Open: api::Api<Response = u64, Error = bonsaidb_core::Error>,
+        T: 'static,
+    {
+        let stream_id = match self.0.client.send_blocking_api_request(open) {
+            Ok(stream_id) => stream_id,
+            Err(err) => return Box::new(std::iter::once(Err(err.into()))),
+        };
+        let mut stream = Some(OpenStream::new(self.0.client.clone(), stream_id));
+        Box::new(std::iter::from_fn(move || {
+            let result = match stream.as_mut()?.next_chunk_blocking() {
+                Ok(Some(chunk)) => convert(chunk).ok_or_else(unexpected_chunk),
+                Ok(None) => {
+                    stream = None;
+                    return None;
+                }
+                Err(err) => Err(err),
+            };
+            if result.is_err() {
+                stream = None;
+            }
+            Some(result)
+        }))
+    }
+}
+
 impl Connection for BlockingRemoteDatabase {
     type Storage = BlockingClient;
 
@@ -390,6 +430,7 @@ impl LowLevelConnection for BlockingRemoteDatabase {
         ids: Range<bonsaidb_core::document::DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
         Ok(self.0.client.send_blocking_api_request(&List {
@@ -398,6 +439,29 @@ impl LowLevelConnection for BlockingRemoteDatabase {
             ids,
             order,
             limit,
+            filter,
+        })?)
+    }
+
+    fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, bonsaidb_core::Error> {
+        Ok(self.0.client.send_blocking_api_request(&ListProjected {
+            list: List {
+                database: self.0.name.to_string(),
+                collection: collection.clone(),
+                ids,
+                order,
+                limit,
+                filter,
+            },
+            projection,
         })?)
     }
 
@@ -406,6 +470,7 @@ impl LowLevelConnection for BlockingRemoteDatabase {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, bonsaidb_core::Error> {
         Ok(self.0.client.send_blocking_api_request(&ListHeaders(List {
@@ -414,6 +479,7 @@ impl LowLevelConnection for BlockingRemoteDatabase {
             ids,
             order,
             limit,
+            filter,
         }))?)
     }
 
@@ -446,6 +512,7 @@ impl LowLevelConnection for BlockingRemoteDatabase {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<map::Serialized>, bonsaidb_core::Error> {
         Ok(self.0.client.send_blocking_api_request(&Query {
@@ -454,6 +521,7 @@ impl LowLevelConnection for BlockingRemoteDatabase {
             key,
             order,
             limit,
+            cursor,
             access_policy,
         })?)
     }
@@ -464,20 +532,66 @@ impl LowLevelConnection for BlockingRemoteDatabase {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<bonsaidb_core::schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error>
     {
-        Ok(self
-            .0
-            .client
-            .send_blocking_api_request(&QueryWithDocs(Query {
+        Ok(self.0.client.send_blocking_api_request(&QueryWithDocs {
+            query: Query {
                 database: self.0.name.to_string(),
                 view: view.clone(),
                 key,
                 order,
                 limit,
+                cursor,
                 access_policy,
-            }))?)
+            },
+            filter,
+        })?)
+    }
+
+    fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<map::MappedSerializedDocuments<ProjectedDocument>, bonsaidb_core::Error> {
+        Ok(self
+            .0
+            .client
+            .send_blocking_api_request(&QueryWithProjectedDocs {
+                query: QueryWithDocs {
+                    query: Query {
+                        database: self.0.name.to_string(),
+                        view: view.clone(),
+                        key,
+                        order,
+                        limit,
+                        cursor,
+                        access_policy,
+                    },
+                    filter,
+                },
+                projection,
+            })?)
+    }
+
+    fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        Ok(self.0.client.send_blocking_api_request(&QueryViewSet {
+            database: self.0.name.to_string(),
+            query,
+            limit,
+        })?)
     }
 
     fn reduce_by_name(
@@ -529,6 +643,89 @@ impl LowLevelConnection for BlockingRemoteDatabase {
             access_policy,
         })?)
     }
+
+    fn list_chunks_from_collection(
+        &self,
+        ids: Range<bonsaidb_core::document::DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        chunk_size: u32,
+        collection: &CollectionName,
+    ) -> ResultChunks<'_, Vec<OwnedDocument>> {
+        self.result_chunks(
+            &StreamList {
+                list: List {
+                    database: self.0.name.to_string(),
+                    collection: collection.clone(),
+                    ids,
+                    order,
+                    limit,
+                    filter,
+                },
+                chunk_size,
+            },
+            ResultChunk::into_documents,
+        )
+    }
+
+    fn query_chunks_by_name(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        chunk_size: u32,
+    ) -> ResultChunks<'_, Vec<map::Serialized>> {
+        self.result_chunks(
+            &StreamQuery {
+                query: Query {
+                    database: self.0.name.to_string(),
+                    view: view.clone(),
+                    key,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                },
+                chunk_size,
+            },
+            ResultChunk::into_mappings,
+        )
+    }
+
+    fn query_chunks_by_name_with_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        chunk_size: u32,
+    ) -> ResultChunks<'_, map::MappedSerializedDocuments> {
+        self.result_chunks(
+            &StreamQueryWithDocs {
+                query: QueryWithDocs {
+                    query: Query {
+                        database: self.0.name.to_string(),
+                        view: view.clone(),
+                        key,
+                        order,
+                        limit,
+                        cursor,
+                        access_policy,
+                    },
+                    filter,
+                },
+                chunk_size,
+            },
+            ResultChunk::into_mapped_documents,
+        )
+    }
 }
 
 impl HasSession for BlockingRemoteDatabase {
@@ -610,6 +807,34 @@ impl Subscriber for BlockingRemoteSubscriber {
         Ok(())
     }
 
+    fn subscribe_to_collection_changes(
+        &self,
+        collection: &CollectionName,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.0
+            .client
+            .send_blocking_api_request(&SubscribeToCollectionChanges {
+                database: self.0.database.to_string(),
+                subscriber_id: self.0.id,
+                collection: collection.clone(),
+            })?;
+        Ok(())
+    }
+
+    fn subscribe_to_key_changes(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.0
+            .client
+            .send_blocking_api_request(&SubscribeToKeyChanges {
+                database: self.0.database.to_string(),
+                subscriber_id: self.0.id,
+                namespace: namespace.map(String::from),
+            })?;
+        Ok(())
+    }
+
     fn receiver(&self) -> &Receiver {
         AsyncSubscriber::receiver(&self.0)
     }
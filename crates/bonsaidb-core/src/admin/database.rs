@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::define_basic_unique_mapped_view;
 use crate::document::{CollectionDocument, Emit};
-use crate::schema::{Collection, NamedCollection, SchemaName};
+use crate::schema::{Collection, CollectionName, NamedCollection, SchemaName};
 
 /// A database stored in BonsaiDb.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, Collection)]
@@ -12,6 +14,10 @@ pub struct Database {
     pub name: String,
     /// The schema defining the database.
     pub schema: SchemaName,
+    /// The versions of the collections stored in the database. Collections
+    /// that are not present are at version 0.
+    #[serde(default)]
+    pub collection_versions: BTreeMap<CollectionName, u64>,
 }
 
 define_basic_unique_mapped_view!(
@@ -1,4 +1,5 @@
 use std::borrow::Borrow;
+use std::collections::BTreeSet;
 use std::convert::Infallible;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
@@ -9,15 +10,17 @@ use actionable::{Action, Identifier};
 use arc_bytes::serde::Bytes;
 use async_trait::async_trait;
 use futures::future::BoxFuture;
-use futures::{Future, FutureExt};
+use futures::{Future, FutureExt, Stream};
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
 
 use crate::admin::{Role, User};
 use crate::document::{
-    CollectionDocument, CollectionHeader, Document, HasHeader, Header, OwnedDocument,
+    CollectionDocument, CollectionHeader, Document, DocumentId, Filter, HasHeader, Header,
+    OwnedDocument, ProjectedDocument, Projection,
 };
 use crate::key::{ByteSource, IntoPrefixRange, Key, KeyEncoding, KeyKind, KeyVisitor};
+use crate::limits::STREAM_DEFAULT_CHUNK_SIZE;
 use crate::permissions::Permissions;
 use crate::schema::view::map::MappedDocuments;
 use crate::schema::{
@@ -28,9 +31,16 @@ use crate::{transaction, Error};
 
 mod has_session;
 mod lowlevel;
+mod stream;
+mod view_set;
+mod watch;
 
 pub use self::has_session::HasSession;
-pub use self::lowlevel::{AsyncLowLevelConnection, HasSchema, LowLevelConnection};
+pub use self::lowlevel::{
+    AsyncLowLevelConnection, AsyncResultChunks, HasSchema, LowLevelConnection, ResultChunks,
+};
+pub use self::view_set::{AsyncViewSet, SerializedViewQuery, ViewSet, ViewSetQuery};
+pub use self::watch::{AsyncCollectionWatcher, CollectionWatcher};
 
 /// A connection to a database's [`Schema`](schema::Schema), giving access to
 /// [`Collection`s](crate::schema::Collection) and
@@ -69,6 +79,43 @@ pub trait Connection: LowLevelConnection + Sized + Send + Sync {
     /// Fetches the last transaction id that has been committed, if any.
     fn last_transaction_id(&self) -> Result<Option<u64>, Error>;
 
+    /// Watches the collection `C` for changes. Changes are delivered as
+    /// [`CollectionChanges`](transaction::CollectionChanges) after the
+    /// transaction that made them has been committed.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # use bonsaidb_core::pubsub::PubSub;
+    /// # fn test_fn<C: Connection + PubSub>(db: &C, last_seen_transaction: u64) -> Result<(), Error> {
+    /// for changes in db
+    ///     .watch_collection::<MyCollection>()?
+    ///     .resume_after(last_seen_transaction)
+    /// {
+    ///     let changes = changes?;
+    ///     println!(
+    ///         "Transaction {} changed {} documents",
+    ///         changes.transaction_id,
+    ///         changes.changes.len()
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Changes are delivered using [`PubSub`](crate::pubsub::PubSub). Watching
+    /// requires permission to create a subscriber, and permission to list and
+    /// get the collection's documents. Resuming from a transaction id
+    /// additionally requires permission to list executed transactions.
+    fn watch_collection<C: schema::Collection>(
+        &self,
+    ) -> Result<CollectionWatcher<'_, Self, C>, Error>
+    where
+        Self: crate::pubsub::PubSub,
+    {
+        CollectionWatcher::new(self)
+    }
+
     /// Compacts the entire database to reclaim unused disk space.
     ///
     /// This process is done by writing data to a new file and swapping the file
@@ -477,6 +524,9 @@ where
     range: RangeRef<'a, Cl::PrimaryKey, PrimaryKey>,
     sort: Sort,
     limit: Option<u32>,
+    chunk_size: Option<u32>,
+    cursor: Option<ListCursor>,
+    filter: Option<Filter>,
 }
 
 impl<'a, Cn, Cl, PrimaryKey> List<'a, Cn, Cl, PrimaryKey>
@@ -495,6 +545,9 @@ where
             range,
             sort: Sort::Ascending,
             limit: None,
+            chunk_size: None,
+            cursor: None,
+            filter: None,
         }
     }
 
@@ -516,9 +569,69 @@ where
         self
     }
 
+    /// Sets the number of documents [`iter()`](Self::iter) retrieves at a
+    /// time. If not set, [`STREAM_DEFAULT_CHUNK_SIZE`] documents are retrieved
+    /// at a time.
+    pub const fn chunk_size(mut self, documents: u32) -> Self {
+        self.chunk_size = Some(documents);
+        self
+    }
+
+    /// Resumes listing documents from `cursor`, in the direction of the
+    /// list's sort order.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::{Connection, ListCursor};
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in db
+    ///     .collection::<MyCollection>()
+    ///     .all()
+    ///     .after(ListCursor::after(&42_u64)?)
+    ///     .query()?
+    /// {
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn after(mut self, cursor: ListCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Only returns documents whose contents match `filter`. The filter is
+    /// evaluated by the database before documents are returned, and only
+    /// matching documents count towards [`limit`](Self::limit).
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # use bonsaidb_core::document::Filter;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in db
+    ///     .collection::<MyCollection>()
+    ///     .all()
+    ///     .with_filter(Filter::field("rank").greater_than(42))
+    ///     .query()?
+    /// {
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Returns the number of documents contained within the range.
     ///
-    /// Order and limit are ignored if they were set.
+    /// Order, limit, and cursor are ignored if they were set. If a
+    /// [filter](Self::with_filter) was set, only matching documents are
+    /// counted.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -537,9 +650,24 @@ where
     /// ```
     pub fn count(self) -> Result<u64, Error> {
         let Self {
-            collection, range, ..
+            collection,
+            range,
+            filter,
+            ..
         } = self;
-        collection.connection.count::<Cl, _, _>(range)
+        match filter {
+            Some(filter) => {
+                let headers = collection.connection.list_headers_from_collection(
+                    range.map_result(|id| DocumentId::new(id))?,
+                    Sort::Ascending,
+                    None,
+                    Some(filter),
+                    &Cl::collection_name(),
+                )?;
+                Ok(headers.len() as u64)
+            }
+            None => collection.connection.count::<Cl, _, _>(range),
+        }
     }
 
     /// Returns the list of headers for documents contained within the range.
@@ -567,11 +695,20 @@ where
             range,
             sort,
             limit,
+            cursor,
+            filter,
             ..
         } = self;
-        collection
-            .connection
-            .list_headers::<Cl, _, PrimaryKey>(range, sort, limit)
+        match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+            Some(ids) => collection.connection.list_headers_from_collection(
+                ids,
+                sort,
+                limit,
+                filter,
+                &Cl::collection_name(),
+            ),
+            None => Ok(Vec::new()),
+        }
     }
 
     /// Retrieves the matching documents.
@@ -594,8 +731,158 @@ where
             range,
             sort,
             limit,
+            cursor,
+            filter,
+            ..
+        } = self;
+        match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+            Some(ids) => collection.connection.list_from_collection(
+                ids,
+                sort,
+                limit,
+                filter,
+                &Cl::collection_name(),
+            ),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Retrieves only the selected `fields` of each matching document's
+    /// contents. The fields are selected by the database before documents are
+    /// returned, which reduces the amount of data sent to clients of remote
+    /// databases. See [`Projection`] for how fields are located and encoded.
+    ///
+    /// The returned [`ProjectedDocument`]s can't be used to update the
+    /// documents they were selected from.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error before querying the database if the collection's
+    /// contents can't be projected. See
+    /// [`SerializedCollection::supports_projections()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # use bonsaidb_core::document::FilterValue;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in db.collection::<MyCollection>().all().project(["rank"])? {
+    ///     let contents = doc.deserialize::<FilterValue>()?;
+    ///     println!("Retrieved #{} with rank {:?}", doc.header.id, contents.field("rank"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn project<Fields, Field>(self, fields: Fields) -> Result<Vec<ProjectedDocument>, Error>
+    where
+        Cl: SerializedCollection,
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        let projection = projection_of::<Cl, _, _>(fields)?;
+        let Self {
+            collection,
+            range,
+            sort,
+            limit,
+            cursor,
+            filter,
+            ..
+        } = self;
+        match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+            Some(ids) => collection.connection.list_projected_from_collection(
+                ids,
+                sort,
+                limit,
+                filter,
+                projection,
+                &Cl::collection_name(),
+            ),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Retrieves a page of at most [`limit`](Self::limit) matching documents,
+    /// along with a cursor that can be passed to [`after()`](Self::after) to
+    /// retrieve the next page.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// let mut page = db.collection::<MyCollection>().all().limit(10).next_page()?;
+    /// loop {
+    ///     for doc in &page.results {
+    ///         println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    ///     }
+    ///     match page.next {
+    ///         Some(cursor) => {
+    ///             page = db
+    ///                 .collection::<MyCollection>()
+    ///                 .all()
+    ///                 .limit(10)
+    ///                 .after(cursor)
+    ///                 .next_page()?;
+    ///         }
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_page(self) -> Result<Page<Vec<OwnedDocument>, ListCursor>, Error> {
+        let limit = self.limit;
+        let documents = self.query()?;
+        Ok(Page::of_documents(documents, limit))
+    }
+
+    /// Returns an iterator over the matching documents. Rather than retrieving
+    /// every document at once, the documents are retrieved in chunks of
+    /// [`chunk_size`](Self::chunk_size) documents as the iterator is advanced.
+    /// At most [`limit`](Self::limit) documents are returned.
+    ///
+    /// Each chunk is retrieved using a separate query that resumes after the
+    /// last document of the previous chunk. No query remains open while the
+    /// documents are being iterated, so the collection can be modified during
+    /// iteration.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in db.collection::<MyCollection>().all().chunk_size(100).iter()? {
+    ///     let doc = doc?;
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(self) -> Result<impl Iterator<Item = Result<OwnedDocument, Error>> + 'a, Error>
+    where
+        Cl: 'a,
+    {
+        let Self {
+            collection,
+            range,
+            sort,
+            limit,
+            chunk_size,
+            cursor,
+            filter,
         } = self;
-        collection.connection.list::<Cl, _, _>(range, sort, limit)
+        let connection = collection.connection;
+        let chunks: ResultChunks<'a, _> = match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+            Some(ids) => connection.list_chunks_from_collection(
+                ids,
+                sort,
+                limit,
+                filter,
+                chunk_size.unwrap_or(STREAM_DEFAULT_CHUNK_SIZE),
+                &Cl::collection_name(),
+            ),
+            None => Box::new(std::iter::empty()),
+        };
+        Ok(stream::flatten_chunks(chunks, Ok))
     }
 }
 
@@ -665,6 +952,16 @@ where
     /// The maximum number of results to return.
     pub limit: Option<u32>,
 
+    /// The number of results to retrieve at a time when iterating over the
+    /// results. If `None`, [`STREAM_DEFAULT_CHUNK_SIZE`] is used.
+    pub chunk_size: Option<u32>,
+
+    /// The position to resume the query after.
+    pub cursor: Option<ViewCursor>,
+
+    /// The filter applied to source documents when querying with documents.
+    pub filter: Option<Filter>,
+
     _view: PhantomData<V>,
 }
 
@@ -682,6 +979,9 @@ where
             access_policy: AccessPolicy::UpdateBefore,
             sort: Sort::Ascending,
             limit: None,
+            chunk_size: None,
+            cursor: None,
+            filter: None,
             _view: PhantomData,
         }
     }
@@ -712,6 +1012,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -745,6 +1048,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -775,6 +1081,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -809,6 +1118,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -838,9 +1150,8 @@ where
     /// default sorting behavior.
     ///
     /// When more than one mapping exists for a single key, all matching
-    /// mappings are returned as a unique entry. The resulting mappings are
-    /// sorted only by the key, and as such, the order of mappings with the same
-    /// key is undefined.
+    /// mappings are returned as a unique entry. Mappings with the same key are
+    /// ordered by their source document's id.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -861,9 +1172,8 @@ where
     /// Returns the matching mappings in descending key order.
     ///
     /// When more than one mapping exists for a single key, all matching
-    /// mappings are returned as a unique entry. The resulting mappings are
-    /// sorted only by the key, and as such, the order of mappings with the same
-    /// key is undefined.
+    /// mappings are returned as a unique entry. Mappings with the same key are
+    /// ordered by their source document's id.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -898,6 +1208,76 @@ where
         self
     }
 
+    /// Sets the number of results [`iter()`](Self::iter) and the
+    /// functions like it retrieve at a time. If not set,
+    /// [`STREAM_DEFAULT_CHUNK_SIZE`] results are retrieved at a time.
+    pub const fn chunk_size(mut self, results: u32) -> Self {
+        self.chunk_size = Some(results);
+        self
+    }
+
+    /// Resumes the query after the position `cursor` points to. Only mappings
+    /// that sort after the cursor's key and document id are returned.
+    ///
+    /// Cursors are returned from the paginated query functions, such as
+    /// [`query_page()`](Self::query_page).
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// let mut cursor = None;
+    /// loop {
+    ///     let mut query = ScoresByRank::entries(&db).limit(10);
+    ///     if let Some(cursor) = cursor {
+    ///         query = query.after(cursor);
+    ///     }
+    ///     let page = query.query_page()?;
+    ///     for mapping in &page.results {
+    ///         println!("Rank {} has a score of {:3}", mapping.key, mapping.value);
+    ///     }
+    ///     match page.next {
+    ///         Some(next) => cursor = Some(next),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn after(mut self, cursor: ViewCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Only returns mappings whose source document's contents match `filter`.
+    /// The filter is evaluated by the database before documents are returned,
+    /// and only matching mappings count towards [`limit`](Self::limit).
+    ///
+    /// Filters are applied by the functions that retrieve documents, such as
+    /// [`query_with_docs()`](Self::query_with_docs), and are ignored by all
+    /// other functions.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # use bonsaidb_core::document::Filter;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// for mapping in &ScoresByRank::entries(&db)
+    ///     .with_filter(Filter::field("score").greater_than(0.5))
+    ///     .query_with_collection_docs()?
+    /// {
+    ///     println!("Rank {} has a score of {:3}", mapping.key, mapping.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Executes the query and retrieves the results.
     ///
     /// ```rust
@@ -912,8 +1292,84 @@ where
     /// # }
     /// ```
     pub fn query(self) -> Result<ViewMappings<V>, Error> {
-        self.connection
-            .query::<V, Key>(self.key, self.sort, self.limit, self.access_policy)
+        self.connection.query::<V, Key>(
+            self.key,
+            self.sort,
+            self.limit,
+            self.cursor,
+            self.access_policy,
+        )
+    }
+
+    /// Executes the query and retrieves a page of at most
+    /// [`limit`](Self::limit) results, along with a cursor that can be passed
+    /// to [`after()`](Self::after) to retrieve the next page.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// let page = ScoresByRank::entries(&db).limit(10).query_page()?;
+    /// if let Some(next) = page.next {
+    ///     let next_page = ScoresByRank::entries(&db)
+    ///         .limit(10)
+    ///         .after(next)
+    ///         .query_page()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_page(self) -> Result<Page<ViewMappings<V>>, Error> {
+        let limit = self.limit;
+        let mappings = self.query()?;
+        Page::of_mappings(mappings, limit)
+    }
+
+    /// Executes the query and returns an iterator over the results. Rather
+    /// than retrieving every result at once, the results are retrieved in
+    /// chunks of [`chunk_size`](Self::chunk_size) results as the iterator is
+    /// advanced. At most [`limit`](Self::limit) results are returned.
+    ///
+    /// Each chunk is retrieved using a separate query that resumes after the
+    /// last mapping of the previous chunk, and the access policy is only
+    /// applied before retrieving the first chunk. No query remains open while
+    /// the results are being iterated, so the view's collection can be
+    /// modified during iteration.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// for mapping in ScoresByRank::entries(&db).chunk_size(100).iter()? {
+    ///     let mapping = mapping?;
+    ///     println!("Rank {} has a score of {:3}", mapping.key, mapping.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<Map<V::Key, V::Value>, Error>> + 'a, Error>
+    where
+        V: 'a,
+    {
+        let view = self.connection.schematic().view::<V>()?.view_name();
+        let key = self.key.map(|key| key.serialized()).transpose()?;
+        let chunks = self.connection.query_chunks_by_name(
+            &view,
+            key,
+            self.sort,
+            self.limit,
+            self.cursor,
+            self.access_policy,
+            self.chunk_size.unwrap_or(STREAM_DEFAULT_CHUNK_SIZE),
+        );
+        Ok(stream::flatten_chunks(chunks, |mappings| {
+            mappings
+                .iter()
+                .map(schema::view::map::Serialized::deserialized::<V>)
+                .collect()
+        }))
     }
 
     /// Executes the query and retrieves the results with the associated [`Document`s](crate::document::OwnedDocument).
@@ -939,10 +1395,68 @@ where
             self.key,
             self.sort,
             self.limit,
+            self.cursor,
             self.access_policy,
+            self.filter,
         )
     }
 
+    /// Executes the query and retrieves a page of at most
+    /// [`limit`](Self::limit) results with the associated
+    /// [`Document`s](crate::document::OwnedDocument), along with a cursor that
+    /// can be passed to [`after()`](Self::after) to retrieve the next page.
+    pub fn query_with_docs_page(self) -> Result<Page<MappedDocuments<OwnedDocument, V>>, Error> {
+        let limit = self.limit;
+        let mapped = self.query_with_docs()?;
+        Page::of_mapped_documents(mapped, limit)
+    }
+
+    /// Executes the query and returns an iterator over the results, each
+    /// paired with its source document. The results are retrieved in chunks
+    /// the same way as [`iter()`](Self::iter). If a document is the source of
+    /// multiple results, each result is paired with a copy of the document.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// for result in ScoresByRank::entries(&db).chunk_size(100).iter_with_docs()? {
+    ///     let (mapping, document) = result?;
+    ///     println!(
+    ///         "Mapping from #{} with rank: {} and score: {}. Document bytes: {:?}",
+    ///         document.header.id, mapping.key, mapping.value, document.contents
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn iter_with_docs(
+        self,
+    ) -> Result<
+        impl Iterator<Item = Result<(Map<V::Key, V::Value>, OwnedDocument), Error>> + 'a,
+        Error,
+    >
+    where
+        V: 'a,
+    {
+        let view = self.connection.schematic().view::<V>()?.view_name();
+        let key = self.key.map(|key| key.serialized()).transpose()?;
+        let chunks = self.connection.query_chunks_by_name_with_docs(
+            &view,
+            key,
+            self.sort,
+            self.limit,
+            self.cursor,
+            self.access_policy,
+            self.filter,
+            self.chunk_size.unwrap_or(STREAM_DEFAULT_CHUNK_SIZE),
+        );
+        Ok(stream::flatten_chunks(chunks, |mapped| {
+            Ok(stream::with_documents(mapped.deserialized::<V>()?))
+        }))
+    }
+
     /// Executes the query and retrieves the results with the associated [`CollectionDocument`s](crate::document::CollectionDocument).
     ///
     /// ```rust
@@ -972,30 +1486,97 @@ where
             self.key,
             self.sort,
             self.limit,
+            self.cursor,
             self.access_policy,
+            self.filter,
         )
     }
 
-    /// Executes a reduce over the results of the query
+    /// Executes the query and retrieves a page of at most
+    /// [`limit`](Self::limit) results with the associated
+    /// [`CollectionDocument`s](crate::document::CollectionDocument), along
+    /// with a cursor that can be passed to [`after()`](Self::after) to
+    /// retrieve the next page.
+    pub fn query_with_collection_docs_page(
+        self,
+    ) -> Result<Page<MappedDocuments<CollectionDocument<V::Collection>, V>>, Error>
+    where
+        V::Collection: SerializedCollection,
+        <V::Collection as SerializedCollection>::Contents: std::fmt::Debug,
+    {
+        let limit = self.limit;
+        let mapped = self.query_with_collection_docs()?;
+        Page::of_mapped_documents(mapped, limit)
+    }
+
+    /// Executes the query and retrieves the results with only the selected
+    /// `fields` of each source document's contents. The fields are selected
+    /// by the database after the [filter](Self::with_filter) is applied, and
+    /// before documents are returned. See [`Projection`] for how fields are
+    /// located and encoded.
+    ///
+    /// The returned [`ProjectedDocument`]s can't be used to update the
+    /// documents they were selected from.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error before querying the database if the view's
+    /// collection's contents can't be projected. See
+    /// [`SerializedCollection::supports_projections()`].
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
     /// # use bonsaidb_core::connection::Connection;
+    /// # use bonsaidb_core::document::FilterValue;
     /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
-    /// // score is an f32 in this example
-    /// let score = ScoresByRank::entries(&db).reduce()?;
-    /// println!("Average score: {:3}", score);
+    /// for mapping in &ScoresByRank::entries(&db).project(["rank"])? {
+    ///     let contents = mapping.document.deserialize::<FilterValue>()?;
+    ///     println!("Rank {:?} has a score of {:3}", contents.field("rank"), mapping.value);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn reduce(self) -> Result<V::Value, Error> {
-        self.connection
-            .reduce::<V, Key>(self.key, self.access_policy)
-    }
-
-    /// Executes a reduce over the results of the query, grouping by key.
-    ///
-    /// ```rust
+    pub fn project<Fields, Field>(
+        self,
+        fields: Fields,
+    ) -> Result<MappedDocuments<ProjectedDocument, V>, Error>
+    where
+        V::Collection: SerializedCollection,
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        let projection = projection_of::<V::Collection, _, _>(fields)?;
+        self.connection.query_with_projected_docs::<V, Key>(
+            self.key,
+            self.sort,
+            self.limit,
+            self.cursor,
+            self.access_policy,
+            self.filter,
+            projection,
+        )
+    }
+
+    /// Executes a reduce over the results of the query
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// // score is an f32 in this example
+    /// let score = ScoresByRank::entries(&db).reduce()?;
+    /// println!("Average score: {:3}", score);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reduce(self) -> Result<V::Value, Error> {
+        self.connection
+            .reduce::<V, Key>(self.key, self.access_policy)
+    }
+
+    /// Executes a reduce over the results of the query, grouping by key.
+    ///
+    /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
     /// # use bonsaidb_core::connection::Connection;
     /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
@@ -1028,6 +1609,50 @@ where
         self.connection
             .delete_docs::<V, Key>(self.key, self.access_policy)
     }
+
+    /// Returns a [`ViewSet`] that matches the documents matched by both this
+    /// query and `other`. `other` can be another view query of the same
+    /// collection or a [`ViewSet`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// let documents = ScoresByRank::entries(&db)
+    ///     .with_key(&42)
+    ///     .intersect(MyCollectionByName::entries(&db).with_key("Alice"))
+    ///     .query()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intersect(
+        self,
+        other: impl Into<ViewSet<'a, Cn, V::Collection>>,
+    ) -> ViewSet<'a, Cn, V::Collection> {
+        ViewSet::from(self).intersect(other)
+    }
+
+    /// Returns a [`ViewSet`] that matches the documents matched by either
+    /// this query or `other`. `other` can be another view query of the same
+    /// collection or a [`ViewSet`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+    /// let documents = ScoresByRank::entries(&db)
+    ///     .with_key(&42)
+    ///     .union(MyCollectionByName::entries(&db).with_key("Alice"))
+    ///     .query()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn union(
+        self,
+        other: impl Into<ViewSet<'a, Cn, V::Collection>>,
+    ) -> ViewSet<'a, Cn, V::Collection> {
+        ViewSet::from(self).union(other)
+    }
 }
 
 /// This type is the result of `query()`. It is a list of mappings, which
@@ -1079,6 +1704,49 @@ pub trait AsyncConnection: AsyncLowLevelConnection + Sized + Send + Sync {
     /// Fetches the last transaction id that has been committed, if any.
     async fn last_transaction_id(&self) -> Result<Option<u64>, Error>;
 
+    /// Watches the collection `C` for changes. Changes are delivered as
+    /// [`CollectionChanges`](transaction::CollectionChanges) after the
+    /// transaction that made them has been committed.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use bonsaidb_core::pubsub::AsyncPubSub;
+    /// # fn test_fn<C: AsyncConnection + AsyncPubSub>(db: &C, last_seen_transaction: u64) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// use futures::StreamExt;
+    /// let mut changes = Box::pin(
+    ///     db.watch_collection::<MyCollection>()
+    ///         .await?
+    ///         .resume_after(last_seen_transaction)
+    ///         .into_stream(),
+    /// );
+    /// while let Some(changes) = changes.next().await {
+    ///     let changes = changes?;
+    ///     println!(
+    ///         "Transaction {} changed {} documents",
+    ///         changes.transaction_id,
+    ///         changes.changes.len()
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    ///
+    /// Changes are delivered using [`AsyncPubSub`](crate::pubsub::AsyncPubSub).
+    /// Watching requires permission to create a subscriber, and permission to
+    /// list and get the collection's documents. Resuming from a transaction id
+    /// additionally requires permission to list executed transactions.
+    async fn watch_collection<C: schema::Collection>(
+        &self,
+    ) -> Result<AsyncCollectionWatcher<'_, Self, C>, Error>
+    where
+        Self: crate::pubsub::AsyncPubSub,
+    {
+        AsyncCollectionWatcher::new(self).await
+    }
+
     /// Compacts the entire database to reclaim unused disk space.
     ///
     /// This process is done by writing data to a new file and swapping the file
@@ -1515,6 +2183,9 @@ where
     range: RangeRef<'a, Cl::PrimaryKey, PrimaryKey>,
     sort: Sort,
     limit: Option<u32>,
+    chunk_size: Option<u32>,
+    cursor: Option<ListCursor>,
+    filter: Option<Filter>,
 }
 
 /// A value that may be owned or not. Similar to [`std::borrow::Cow`] but does
@@ -1644,6 +2315,9 @@ where
                 range,
                 sort: Sort::Ascending,
                 limit: None,
+                chunk_size: None,
+                cursor: None,
+                filter: None,
             })),
         }
     }
@@ -1674,6 +2348,251 @@ where
         self
     }
 
+    /// Sets the number of documents [`stream()`](Self::stream) retrieves at a
+    /// time. If not set, [`STREAM_DEFAULT_CHUNK_SIZE`] documents are retrieved
+    /// at a time.
+    pub fn chunk_size(mut self, documents: u32) -> Self {
+        self.builder().chunk_size = Some(documents);
+        self
+    }
+
+    /// Resumes listing documents from `cursor`, in the direction of the
+    /// list's sort order.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::{AsyncConnection, ListCursor};
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// for doc in db
+    ///     .collection::<MyCollection>()
+    ///     .all()
+    ///     .after(ListCursor::after(&42_u64)?)
+    ///     .await?
+    /// {
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn after(mut self, cursor: ListCursor) -> Self {
+        self.builder().cursor = Some(cursor);
+        self
+    }
+
+    /// Only returns documents whose contents match `filter`. The filter is
+    /// evaluated by the database before documents are returned, and only
+    /// matching documents count towards [`limit`](Self::limit).
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use bonsaidb_core::document::Filter;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// for doc in db
+    ///     .collection::<MyCollection>()
+    ///     .all()
+    ///     .with_filter(Filter::field("rank").greater_than(42))
+    ///     .await?
+    /// {
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.builder().filter = Some(filter);
+        self
+    }
+
+    /// Retrieves a page of at most [`limit`](Self::limit) matching documents,
+    /// along with a cursor that can be passed to [`after()`](Self::after) to
+    /// retrieve the next page.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let page = db
+    ///     .collection::<MyCollection>()
+    ///     .all()
+    ///     .limit(10)
+    ///     .next_page()
+    ///     .await?;
+    /// if let Some(cursor) = page.next {
+    ///     let next_page = db
+    ///         .collection::<MyCollection>()
+    ///         .all()
+    ///         .limit(10)
+    ///         .after(cursor)
+    ///         .next_page()
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub async fn next_page(self) -> Result<Page<Vec<OwnedDocument>, ListCursor>, Error> {
+        match self.state {
+            ListState::Pending(Some(AsyncListBuilder {
+                collection,
+                range,
+                sort,
+                limit,
+                cursor,
+                filter,
+                ..
+            })) => {
+                let documents = match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+                    Some(ids) => {
+                        collection
+                            .connection
+                            .list_from_collection(ids, sort, limit, filter, &Cl::collection_name())
+                            .await?
+                    }
+                    None => Vec::new(),
+                };
+                Ok(Page::of_documents(documents, limit))
+            }
+            _ => unreachable!("Attempted to use after retrieving the result"),
+        }
+    }
+
+    /// Retrieves only the selected `fields` of each matching document's
+    /// contents. The fields are selected by the database before documents are
+    /// returned, which reduces the amount of data sent to clients of remote
+    /// databases. See [`Projection`] for how fields are located and encoded.
+    ///
+    /// The returned [`ProjectedDocument`]s can't be used to update the
+    /// documents they were selected from.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error before querying the database if the collection's
+    /// contents can't be projected. See
+    /// [`SerializedCollection::supports_projections()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use bonsaidb_core::document::FilterValue;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// for doc in db.collection::<MyCollection>().all().project(["rank"]).await? {
+    ///     let contents = doc.deserialize::<FilterValue>()?;
+    ///     println!("Retrieved #{} with rank {:?}", doc.header.id, contents.field("rank"));
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub async fn project<Fields, Field>(
+        self,
+        fields: Fields,
+    ) -> Result<Vec<ProjectedDocument>, Error>
+    where
+        Cl: SerializedCollection,
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        let projection = projection_of::<Cl, _, _>(fields)?;
+        match self.state {
+            ListState::Pending(Some(AsyncListBuilder {
+                collection,
+                range,
+                sort,
+                limit,
+                cursor,
+                filter,
+                ..
+            })) => match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+                Some(ids) => {
+                    collection
+                        .connection
+                        .list_projected_from_collection(
+                            ids,
+                            sort,
+                            limit,
+                            filter,
+                            projection,
+                            &Cl::collection_name(),
+                        )
+                        .await
+                }
+                None => Ok(Vec::new()),
+            },
+            _ => unreachable!("Attempted to use after retrieving the result"),
+        }
+    }
+
+    /// Returns a [`Stream`] of the matching documents. Rather than retrieving
+    /// every document at once, the documents are retrieved in chunks of
+    /// [`chunk_size`](Self::chunk_size) documents as the stream is polled. At
+    /// most [`limit`](Self::limit) documents are returned.
+    ///
+    /// Each chunk is retrieved using a separate query that resumes after the
+    /// last document of the previous chunk. No query remains open while the
+    /// documents are being streamed, so the collection can be modified while
+    /// streaming.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::TryStreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut docs = Box::pin(
+    ///     db.collection::<MyCollection>()
+    ///         .all()
+    ///         .chunk_size(100)
+    ///         .stream()?,
+    /// );
+    /// while let Some(doc) = docs.try_next().await? {
+    ///     println!("Retrieved #{} with bytes {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn stream(
+        self,
+    ) -> Result<impl Stream<Item = Result<OwnedDocument, Error>> + Send + 'a, Error>
+    where
+        Cl: 'a,
+    {
+        match self.state {
+            ListState::Pending(Some(AsyncListBuilder {
+                collection,
+                range,
+                sort,
+                limit,
+                chunk_size,
+                cursor,
+                filter,
+            })) => {
+                let connection = collection.connection;
+                let chunks: AsyncResultChunks<'a, _> =
+                    match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+                        Some(ids) => connection.list_chunks_from_collection(
+                            ids,
+                            sort,
+                            limit,
+                            filter,
+                            chunk_size.unwrap_or(STREAM_DEFAULT_CHUNK_SIZE),
+                            &Cl::collection_name(),
+                        ),
+                        None => Box::pin(futures::stream::empty()),
+                    };
+                Ok(stream::flatten_chunk_stream(chunks, Ok))
+            }
+            _ => unreachable!("Attempted to use after retrieving the result"),
+        }
+    }
+
     /// Returns the list of headers for documents contained within the range.
     ///
     /// ```rust
@@ -1700,20 +2619,33 @@ where
                 range,
                 sort,
                 limit,
+                cursor,
+                filter,
                 ..
-            })) => {
-                collection
-                    .connection
-                    .list_headers::<Cl, _, _>(range, sort, limit)
-                    .await
-            }
+            })) => match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+                Some(ids) => {
+                    collection
+                        .connection
+                        .list_headers_from_collection(
+                            ids,
+                            sort,
+                            limit,
+                            filter,
+                            &Cl::collection_name(),
+                        )
+                        .await
+                }
+                None => Ok(Vec::new()),
+            },
             _ => unreachable!("Attempted to use after retrieving the result"),
         }
     }
 
     /// Returns the number of documents contained within the range.
     ///
-    /// Order and limit are ignored if they were set.
+    /// Order, limit, and cursor are ignored if they were set. If a
+    /// [filter](Self::with_filter) was set, only matching documents are
+    /// counted.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -1734,6 +2666,24 @@ where
     /// ```
     pub async fn count(self) -> Result<u64, Error> {
         match self.state {
+            ListState::Pending(Some(AsyncListBuilder {
+                collection,
+                range,
+                filter: Some(filter),
+                ..
+            })) => {
+                let headers = collection
+                    .connection
+                    .list_headers_from_collection(
+                        range.map_result(|id| DocumentId::new(id))?,
+                        Sort::Ascending,
+                        None,
+                        Some(filter),
+                        &Cl::collection_name(),
+                    )
+                    .await?;
+                Ok(headers.len() as u64)
+            }
             ListState::Pending(Some(AsyncListBuilder {
                 collection, range, ..
             })) => collection.connection.count::<Cl, _, _>(range).await,
@@ -1764,13 +2714,27 @@ where
                     range,
                     sort,
                     limit,
+                    cursor,
+                    filter,
+                    ..
                 } = builder.take().unwrap();
 
                 let future = async move {
-                    collection
-                        .connection
-                        .list::<Cl, _, _>(range, sort, limit)
-                        .await
+                    match list_ids::<Cl, _>(range, sort, cursor.as_ref())? {
+                        Some(ids) => {
+                            collection
+                                .connection
+                                .list_from_collection(
+                                    ids,
+                                    sort,
+                                    limit,
+                                    filter,
+                                    &Cl::collection_name(),
+                                )
+                                .await
+                        }
+                        None => Ok(Vec::new()),
+                    }
                 }
                 .boxed();
 
@@ -1847,6 +2811,16 @@ where
     /// The maximum number of results to return.
     pub limit: Option<u32>,
 
+    /// The number of results to retrieve at a time when iterating over the
+    /// results. If `None`, [`STREAM_DEFAULT_CHUNK_SIZE`] is used.
+    pub chunk_size: Option<u32>,
+
+    /// The position to resume the query after.
+    pub cursor: Option<ViewCursor>,
+
+    /// The filter applied to source documents when querying with documents.
+    pub filter: Option<Filter>,
+
     _view: PhantomData<V>,
 }
 
@@ -1864,6 +2838,9 @@ where
             access_policy: AccessPolicy::UpdateBefore,
             sort: Sort::Ascending,
             limit: None,
+            chunk_size: None,
+            cursor: None,
+            filter: None,
             _view: PhantomData,
         }
     }
@@ -1900,6 +2877,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -1939,6 +2919,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -1977,6 +2960,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -2017,6 +3003,9 @@ where
             access_policy: self.access_policy,
             sort: self.sort,
             limit: self.limit,
+            chunk_size: self.chunk_size,
+            cursor: self.cursor,
+            filter: self.filter,
             _view: PhantomData,
         }
     }
@@ -2049,9 +3038,8 @@ where
     /// default sorting behavior.
     ///
     /// When more than one mapping exists for a single key, all matching
-    /// mappings are returned as a unique entry. The resulting mappings are
-    /// sorted only by the key, and as such, the order of mappings with the same
-    /// key is undefined.
+    /// mappings are returned as a unique entry. Mappings with the same key are
+    /// ordered by their source document's id.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -2074,9 +3062,8 @@ where
     /// Returns the matching mappings in descending key order.
     ///
     /// When more than one mapping exists for a single key, all matching
-    /// mappings are returned as a unique entry. The resulting mappings are
-    /// sorted only by the key, and as such, the order of mappings with the same
-    /// key is undefined.
+    /// mappings are returned as a unique entry. Mappings with the same key are
+    /// ordered by their source document's id.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -2119,6 +3106,81 @@ where
         self
     }
 
+    /// Sets the number of results [`stream()`](Self::stream) and the
+    /// functions like it retrieve at a time. If not set,
+    /// [`STREAM_DEFAULT_CHUNK_SIZE`] results are retrieved at a time.
+    pub const fn chunk_size(mut self, results: u32) -> Self {
+        self.chunk_size = Some(results);
+        self
+    }
+
+    /// Resumes the query after the position `cursor` points to. Only mappings
+    /// that sort after the cursor's key and document id are returned.
+    ///
+    /// Cursors are returned from the paginated query functions, such as
+    /// [`query_page()`](Self::query_page).
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut cursor = None;
+    /// loop {
+    ///     let mut query = ScoresByRank::entries_async(&db).limit(10);
+    ///     if let Some(cursor) = cursor {
+    ///         query = query.after(cursor);
+    ///     }
+    ///     let page = query.query_page().await?;
+    ///     for mapping in &page.results {
+    ///         println!("Rank {} has a score of {:3}", mapping.key, mapping.value);
+    ///     }
+    ///     match page.next {
+    ///         Some(next) => cursor = Some(next),
+    ///         None => break,
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn after(mut self, cursor: ViewCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Only returns mappings whose source document's contents match `filter`.
+    /// The filter is evaluated by the database before documents are returned,
+    /// and only matching mappings count towards [`limit`](Self::limit).
+    ///
+    /// Filters are applied by the functions that retrieve documents, such as
+    /// [`query_with_docs()`](Self::query_with_docs), and are ignored by all
+    /// other functions.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use bonsaidb_core::document::Filter;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// for mapping in &ScoresByRank::entries_async(&db)
+    ///     .with_filter(Filter::field("score").greater_than(0.5))
+    ///     .query_with_collection_docs()
+    ///     .await?
+    /// {
+    ///     println!("Rank {} has a score of {:3}", mapping.key, mapping.value);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Executes the query and retrieves the results.
     ///
     /// ```rust
@@ -2136,10 +3198,93 @@ where
     /// ```
     pub async fn query(self) -> Result<Vec<Map<V::Key, V::Value>>, Error> {
         self.connection
-            .query::<V, Key>(self.key, self.sort, self.limit, self.access_policy)
+            .query::<V, Key>(
+                self.key,
+                self.sort,
+                self.limit,
+                self.cursor,
+                self.access_policy,
+            )
             .await
     }
 
+    /// Executes the query and retrieves a page of at most
+    /// [`limit`](Self::limit) results, along with a cursor that can be passed
+    /// to [`after()`](Self::after) to retrieve the next page.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let page = ScoresByRank::entries_async(&db).limit(10).query_page().await?;
+    /// if let Some(next) = page.next {
+    ///     let next_page = ScoresByRank::entries_async(&db)
+    ///         .limit(10)
+    ///         .after(next)
+    ///         .query_page()
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub async fn query_page(self) -> Result<Page<ViewMappings<V>>, Error> {
+        let limit = self.limit;
+        let mappings = self.query().await?;
+        Page::of_mappings(mappings, limit)
+    }
+
+    /// Executes the query and returns a [`Stream`] of the results. Rather
+    /// than retrieving every result at once, the results are retrieved in
+    /// chunks of [`chunk_size`](Self::chunk_size) results as the stream is
+    /// polled. At most [`limit`](Self::limit) results are returned.
+    ///
+    /// Each chunk is retrieved using a separate query that resumes after the
+    /// last mapping of the previous chunk, and the access policy is only
+    /// applied before retrieving the first chunk. No query remains open while
+    /// the results are being streamed, so the view's collection can be
+    /// modified while streaming.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::TryStreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut mappings = Box::pin(ScoresByRank::entries_async(&db).chunk_size(100).stream()?);
+    /// while let Some(mapping) = mappings.try_next().await? {
+    ///     println!("Rank {} has a score of {:3}", mapping.key, mapping.value);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn stream(
+        self,
+    ) -> Result<impl Stream<Item = Result<Map<V::Key, V::Value>, Error>> + Send + 'a, Error>
+    where
+        V: 'a,
+    {
+        let view = self.connection.schematic().view::<V>()?.view_name();
+        let key = self.key.map(|key| key.serialized()).transpose()?;
+        let chunks = self.connection.query_chunks_by_name(
+            &view,
+            key,
+            self.sort,
+            self.limit,
+            self.cursor,
+            self.access_policy,
+            self.chunk_size.unwrap_or(STREAM_DEFAULT_CHUNK_SIZE),
+        );
+        Ok(stream::flatten_chunk_stream(chunks, |mappings| {
+            mappings
+                .iter()
+                .map(schema::view::map::Serialized::deserialized::<V>)
+                .collect()
+        }))
+    }
+
     /// Executes the query and retrieves the results with the associated [`Document`s](crate::document::OwnedDocument).
     ///
     /// ```rust
@@ -2163,10 +3308,82 @@ where
     /// ```
     pub async fn query_with_docs(self) -> Result<MappedDocuments<OwnedDocument, V>, Error> {
         self.connection
-            .query_with_docs::<V, _>(self.key, self.sort, self.limit, self.access_policy)
+            .query_with_docs::<V, _>(
+                self.key,
+                self.sort,
+                self.limit,
+                self.cursor,
+                self.access_policy,
+                self.filter,
+            )
             .await
     }
 
+    /// Executes the query and retrieves a page of at most
+    /// [`limit`](Self::limit) results with the associated
+    /// [`Document`s](crate::document::OwnedDocument), along with a cursor that
+    /// can be passed to [`after()`](Self::after) to retrieve the next page.
+    pub async fn query_with_docs_page(
+        self,
+    ) -> Result<Page<MappedDocuments<OwnedDocument, V>>, Error> {
+        let limit = self.limit;
+        let mapped = self.query_with_docs().await?;
+        Page::of_mapped_documents(mapped, limit)
+    }
+
+    /// Executes the query and returns a [`Stream`] of the results, each paired
+    /// with its source document. The results are retrieved in chunks the same
+    /// way as [`stream()`](Self::stream). If a document is the source of
+    /// multiple results, each result is paired with a copy of the document.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::TryStreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut results = Box::pin(
+    ///     ScoresByRank::entries_async(&db)
+    ///         .chunk_size(100)
+    ///         .stream_with_docs()?,
+    /// );
+    /// while let Some((mapping, document)) = results.try_next().await? {
+    ///     println!(
+    ///         "Mapping from #{} with rank: {} and score: {}. Document bytes: {:?}",
+    ///         document.header.id, mapping.key, mapping.value, document.contents
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn stream_with_docs(
+        self,
+    ) -> Result<
+        impl Stream<Item = Result<(Map<V::Key, V::Value>, OwnedDocument), Error>> + Send + 'a,
+        Error,
+    >
+    where
+        V: 'a,
+    {
+        let view = self.connection.schematic().view::<V>()?.view_name();
+        let key = self.key.map(|key| key.serialized()).transpose()?;
+        let chunks = self.connection.query_chunks_by_name_with_docs(
+            &view,
+            key,
+            self.sort,
+            self.limit,
+            self.cursor,
+            self.access_policy,
+            self.filter,
+            self.chunk_size.unwrap_or(STREAM_DEFAULT_CHUNK_SIZE),
+        );
+        Ok(stream::flatten_chunk_stream(chunks, |mapped| {
+            Ok(stream::with_documents(mapped.deserialized::<V>()?))
+        }))
+    }
+
     /// Executes the query and retrieves the results with the associated [`CollectionDocument`s](crate::document::CollectionDocument).
     ///
     /// ```rust
@@ -2196,7 +3413,83 @@ where
         <V::Collection as SerializedCollection>::Contents: std::fmt::Debug,
     {
         self.connection
-            .query_with_collection_docs::<V, _>(self.key, self.sort, self.limit, self.access_policy)
+            .query_with_collection_docs::<V, _>(
+                self.key,
+                self.sort,
+                self.limit,
+                self.cursor,
+                self.access_policy,
+                self.filter,
+            )
+            .await
+    }
+
+    /// Executes the query and retrieves a page of at most
+    /// [`limit`](Self::limit) results with the associated
+    /// [`CollectionDocument`s](crate::document::CollectionDocument), along
+    /// with a cursor that can be passed to [`after()`](Self::after) to
+    /// retrieve the next page.
+    pub async fn query_with_collection_docs_page(
+        self,
+    ) -> Result<Page<MappedDocuments<CollectionDocument<V::Collection>, V>>, Error>
+    where
+        V::Collection: SerializedCollection,
+        <V::Collection as SerializedCollection>::Contents: std::fmt::Debug,
+    {
+        let limit = self.limit;
+        let mapped = self.query_with_collection_docs().await?;
+        Page::of_mapped_documents(mapped, limit)
+    }
+
+    /// Executes the query and retrieves the results with only the selected
+    /// `fields` of each source document's contents. The fields are selected
+    /// by the database after the [filter](Self::with_filter) is applied, and
+    /// before documents are returned. See [`Projection`] for how fields are
+    /// located and encoded.
+    ///
+    /// The returned [`ProjectedDocument`]s can't be used to update the
+    /// documents they were selected from.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error before querying the database if the view's
+    /// collection's contents can't be projected. See
+    /// [`SerializedCollection::supports_projections()`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use bonsaidb_core::document::FilterValue;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// for mapping in &ScoresByRank::entries_async(&db).project(["rank"]).await? {
+    ///     let contents = mapping.document.deserialize::<FilterValue>()?;
+    ///     println!("Rank {:?} has a score of {:3}", contents.field("rank"), mapping.value);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub async fn project<Fields, Field>(
+        self,
+        fields: Fields,
+    ) -> Result<MappedDocuments<ProjectedDocument, V>, Error>
+    where
+        V::Collection: SerializedCollection,
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        let projection = projection_of::<V::Collection, _, _>(fields)?;
+        self.connection
+            .query_with_projected_docs::<V, _>(
+                self.key,
+                self.sort,
+                self.limit,
+                self.cursor,
+                self.access_policy,
+                self.filter,
+                projection,
+            )
             .await
     }
 
@@ -2261,6 +3554,56 @@ where
             .delete_docs::<V, _>(self.key, self.access_policy)
             .await
     }
+
+    /// Returns an [`AsyncViewSet`] that matches the documents matched by both
+    /// this query and `other`. `other` can be another view query of the same
+    /// collection or an [`AsyncViewSet`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let documents = ScoresByRank::entries_async(&db)
+    ///     .with_key(&42)
+    ///     .intersect(MyCollectionByName::entries_async(&db).with_key("Alice"))
+    ///     .query()
+    ///     .await?;
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn intersect(
+        self,
+        other: impl Into<AsyncViewSet<'a, Cn, V::Collection>>,
+    ) -> AsyncViewSet<'a, Cn, V::Collection> {
+        AsyncViewSet::from(self).intersect(other)
+    }
+
+    /// Returns an [`AsyncViewSet`] that matches the documents matched by
+    /// either this query or `other`. `other` can be another view query of the
+    /// same collection or an [`AsyncViewSet`].
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let documents = ScoresByRank::entries_async(&db)
+    ///     .with_key(&42)
+    ///     .union(MyCollectionByName::entries_async(&db).with_key("Alice"))
+    ///     .query()
+    ///     .await?;
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn union(
+        self,
+        other: impl Into<AsyncViewSet<'a, Cn, V::Collection>>,
+    ) -> AsyncViewSet<'a, Cn, V::Collection> {
+        AsyncViewSet::from(self).union(other)
+    }
 }
 
 /// A sort order.
@@ -2272,6 +3615,305 @@ pub enum Sort {
     Descending,
 }
 
+/// A position within a [`View`]'s mappings that a query can resume after.
+///
+/// A cursor identifies a single mapping by its serialized key and its source
+/// document's id. Because the contents are opaque and serializable, cursors
+/// can be handed to clients and sent over the network as-is.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct ViewCursor {
+    key: Bytes,
+    document_id: DocumentId,
+}
+
+impl ViewCursor {
+    /// Returns a cursor that resumes after `mapping`.
+    pub fn after<K: for<'k> Key<'k>, V>(mapping: &Map<K, V>) -> Result<Self, Error> {
+        let key = mapping
+            .key
+            .as_ord_bytes()
+            .map_err(|err| Error::other("key serialization", err))?;
+        Ok(Self {
+            key: Bytes::from(key.to_vec()),
+            document_id: mapping.source.id.clone(),
+        })
+    }
+
+    /// Returns a cursor that resumes after the serialized `mapping`.
+    #[must_use]
+    pub fn after_serialized(mapping: &schema::view::map::Serialized) -> Self {
+        Self {
+            key: mapping.key.clone(),
+            document_id: mapping.source.id.clone(),
+        }
+    }
+
+    /// Returns the serialized key of the mapping this cursor points to.
+    #[must_use]
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Returns the id of the source document of the mapping this cursor points
+    /// to.
+    #[must_use]
+    pub const fn document_id(&self) -> &DocumentId {
+        &self.document_id
+    }
+}
+
+/// A position within a collection's documents that a [`List`] or
+/// [`AsyncList`] can resume from.
+///
+/// When listing in ascending order, documents with ids after the cursor's id
+/// are returned. When listing in descending order, documents with ids before
+/// the cursor's id are returned. The document with the cursor's id is only
+/// returned if the cursor is inclusive.
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct ListCursor {
+    id: DocumentId,
+    inclusive: bool,
+}
+
+impl ListCursor {
+    /// Returns a cursor that resumes after the document with `id`.
+    pub fn after<PrimaryKey: for<'k> Key<'k>>(id: &PrimaryKey) -> Result<Self, Error> {
+        DocumentId::new::<PrimaryKey, PrimaryKey>(id).map(Self::after_document_id)
+    }
+
+    /// Returns a cursor that resumes at the document with `id`, including it
+    /// in the results.
+    pub fn starting_at<PrimaryKey: for<'k> Key<'k>>(id: &PrimaryKey) -> Result<Self, Error> {
+        DocumentId::new::<PrimaryKey, PrimaryKey>(id).map(Self::starting_at_document_id)
+    }
+
+    /// Returns a cursor that resumes after the document with `id`.
+    #[must_use]
+    pub const fn after_document_id(id: DocumentId) -> Self {
+        Self {
+            id,
+            inclusive: false,
+        }
+    }
+
+    /// Returns a cursor that resumes at the document with `id`, including it
+    /// in the results.
+    #[must_use]
+    pub const fn starting_at_document_id(id: DocumentId) -> Self {
+        Self {
+            id,
+            inclusive: true,
+        }
+    }
+
+    /// Returns the id of the document this cursor resumes from.
+    #[must_use]
+    pub const fn document_id(&self) -> &DocumentId {
+        &self.id
+    }
+
+    /// Returns true if the document with [`document_id()`](Self::document_id)
+    /// is included in the results.
+    #[must_use]
+    pub const fn is_inclusive(&self) -> bool {
+        self.inclusive
+    }
+
+    /// Narrows `ids` to the ids this cursor resumes from when listing in
+    /// `sort` order. Returns `None` if no ids can match.
+    fn narrow(&self, mut ids: Range<DocumentId>, sort: Sort) -> Option<Range<DocumentId>> {
+        let forwards = matches!(sort, Sort::Ascending);
+        let bound = if forwards {
+            &mut ids.start
+        } else {
+            &mut ids.end
+        };
+        let narrows = match bound {
+            Bound::Unbounded => true,
+            Bound::Included(id) if forwards => *id <= self.id,
+            Bound::Included(id) => *id >= self.id,
+            Bound::Excluded(id) if forwards => *id < self.id,
+            Bound::Excluded(id) => *id > self.id,
+        };
+        if narrows {
+            *bound = if self.inclusive {
+                Bound::Included(self.id.clone())
+            } else {
+                Bound::Excluded(self.id.clone())
+            };
+        }
+
+        let is_empty = match (&ids.start, &ids.end) {
+            (Bound::Included(start), Bound::Included(end)) => start > end,
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) => start >= end,
+            _ => false,
+        };
+        (!is_empty).then_some(ids)
+    }
+}
+
+/// Serializes `range` and narrows it using `cursor`, if provided. Returns
+/// `None` if no ids can match.
+fn list_ids<Cl, PrimaryKey>(
+    range: RangeRef<'_, Cl::PrimaryKey, PrimaryKey>,
+    sort: Sort,
+    cursor: Option<&ListCursor>,
+) -> Result<Option<Range<DocumentId>>, Error>
+where
+    Cl: schema::Collection,
+    PrimaryKey: for<'k> KeyEncoding<'k, Cl::PrimaryKey> + PartialEq + ?Sized,
+    Cl::PrimaryKey: Borrow<PrimaryKey> + PartialEq<PrimaryKey>,
+{
+    let ids = range.map_result(|id| DocumentId::new(id))?;
+    Ok(match cursor {
+        Some(cursor) => cursor.narrow(ids, sort),
+        None => Some(ids),
+    })
+}
+
+/// Returns a [`Projection`] of `fields`, or an error if the contents of `Cl`
+/// can't be projected.
+fn projection_of<Cl, Fields, Field>(fields: Fields) -> Result<Projection, Error>
+where
+    Cl: SerializedCollection,
+    Fields: IntoIterator<Item = Field>,
+    Field: Into<String>,
+{
+    if Cl::supports_projections() {
+        Ok(Projection::new(fields))
+    } else {
+        Err(Error::other(
+            "projection",
+            format!(
+                "the contents of {} can only be projected if they are serialized using Pot or JSON",
+                Cl::collection_name()
+            ),
+        ))
+    }
+}
+
+/// A page of results from a paginated query.
+#[derive(Clone, Debug)]
+pub struct Page<T, Cursor = ViewCursor> {
+    /// The results in this page.
+    pub results: T,
+    /// The cursor to resume after this page. If `None`, no more results exist.
+    pub next: Option<Cursor>,
+}
+
+impl Page<Vec<OwnedDocument>, ListCursor> {
+    fn of_documents(documents: Vec<OwnedDocument>, limit: Option<u32>) -> Self {
+        let next = match limit {
+            Some(limit) if documents.len() >= limit as usize => documents
+                .last()
+                .map(|doc| ListCursor::after_document_id(doc.header.id.clone())),
+            _ => None,
+        };
+        Self {
+            results: documents,
+            next,
+        }
+    }
+}
+
+impl<K: for<'k> Key<'k>, V> Page<Vec<Map<K, V>>> {
+    fn of_mappings(mut mappings: Vec<Map<K, V>>, limit: Option<u32>) -> Result<Self, Error> {
+        let next = match limit {
+            // Limits are applied to the number of keys rather than the number
+            // of mappings, so the results may need to be truncated. A full
+            // page may be followed by more results.
+            Some(limit) if mappings.len() >= limit as usize => {
+                mappings.truncate(limit as usize);
+                mappings.last().map(ViewCursor::after).transpose()?
+            }
+            _ => None,
+        };
+        Ok(Self {
+            results: mappings,
+            next,
+        })
+    }
+}
+
+impl Page<Vec<schema::view::map::Serialized>> {
+    fn of_serialized_mappings(
+        mut mappings: Vec<schema::view::map::Serialized>,
+        limit: Option<u32>,
+    ) -> Self {
+        let next = match limit {
+            // See `of_mappings()` for why the results may need to be truncated.
+            Some(limit) if mappings.len() >= limit as usize => {
+                mappings.truncate(limit as usize);
+                mappings.last().map(ViewCursor::after_serialized)
+            }
+            _ => None,
+        };
+        Self {
+            results: mappings,
+            next,
+        }
+    }
+}
+
+impl<D> Page<schema::view::map::MappedSerializedDocuments<D>> {
+    fn of_serialized_mapped_documents(
+        mapped: schema::view::map::MappedSerializedDocuments<D>,
+        limit: Option<u32>,
+    ) -> Self {
+        let schema::view::map::MappedSerializedDocuments {
+            mappings,
+            mut documents,
+        } = mapped;
+        let page = Page::of_serialized_mappings(mappings, limit);
+        if page.next.is_some() {
+            let referenced = page
+                .results
+                .iter()
+                .map(|mapping| &mapping.source.id)
+                .collect::<BTreeSet<_>>();
+            documents.retain(|id, _| referenced.contains(id));
+        }
+        Self {
+            results: schema::view::map::MappedSerializedDocuments {
+                mappings: page.results,
+                documents,
+            },
+            next: page.next,
+        }
+    }
+}
+
+impl<D, V: schema::View> Page<MappedDocuments<D, V>> {
+    fn of_mapped_documents(
+        mapped: MappedDocuments<D, V>,
+        limit: Option<u32>,
+    ) -> Result<Self, Error> {
+        let MappedDocuments {
+            mappings,
+            mut documents,
+        } = mapped;
+        let page = Page::of_mappings(mappings, limit)?;
+        if page.next.is_some() {
+            let referenced = page
+                .results
+                .iter()
+                .map(|mapping| &mapping.source.id)
+                .collect::<BTreeSet<_>>();
+            documents.retain(|id, _| referenced.contains(id));
+        }
+        Ok(Self {
+            results: MappedDocuments {
+                mappings: page.results,
+                documents,
+            },
+            next: page.next,
+        })
+    }
+}
+
 /// Filters a [`View`] by key.
 #[derive(Clone, Debug)]
 pub enum QueryKey<'k, KOwned, KBorrowed = KOwned>
@@ -1,15 +1,20 @@
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use arc_bytes::serde::Bytes;
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 
+use super::stream;
+use super::view_set::limit_document_ids;
 use super::GroupedReductions;
 use crate::connection::{
-    AccessPolicy, HasSession, QueryKey, Range, RangeRef, SerializedQueryKey, Sort, ViewMappings,
+    AccessPolicy, HasSession, ListCursor, Page, QueryKey, Range, RangeRef, SerializedQueryKey,
+    Sort, ViewCursor, ViewMappings, ViewSetQuery,
 };
 use crate::document::{
-    CollectionDocument, CollectionHeader, Document, DocumentId, HasHeader, Header, OwnedDocument,
+    CollectionDocument, CollectionHeader, Document, DocumentId, Filter, HasHeader, Header,
+    OwnedDocument, ProjectedDocument, Projection,
 };
 use crate::key::{self, ByteSource, Key, KeyEncoding};
 use crate::schema::view::map::{MappedDocuments, MappedSerializedValue};
@@ -20,6 +25,16 @@ use crate::schema::{
 use crate::transaction::{OperationResult, Transaction};
 use crate::Error;
 
+/// The chunks of results returned by functions such as
+/// [`LowLevelConnection::list_chunks_from_collection()`]. Each item contains
+/// the results of a single chunk.
+pub type ResultChunks<'a, T> = Box<dyn Iterator<Item = Result<T, Error>> + 'a>;
+
+/// The chunks of results returned by functions such as
+/// [`AsyncLowLevelConnection::list_chunks_from_collection()`]. Each item
+/// contains the results of a single chunk.
+pub type AsyncResultChunks<'a, T> = BoxStream<'a, Result<T, Error>>;
+
 /// The low-level interface to a database's [`schema::Schema`], giving access to
 /// [`Collection`s](crate::schema::Collection) and
 /// [`Views`s](crate::schema::View). This trait is not safe to use within async
@@ -186,7 +201,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         C::PrimaryKey: Borrow<PrimaryKey> + PartialEq<PrimaryKey>,
     {
         let ids = ids.into().map_result(|id| DocumentId::new(id))?;
-        self.list_from_collection(ids, order, limit, &C::collection_name())
+        self.list_from_collection(ids, order, limit, None, &C::collection_name())
     }
 
     /// Retrieves all documents within the range of `ids`. To retrieve all
@@ -212,7 +227,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         C::PrimaryKey: Borrow<PrimaryKey> + PartialEq<PrimaryKey>,
     {
         let ids = ids.into().map_result(|id| DocumentId::new(id))?;
-        self.list_headers_from_collection(ids, order, limit, &C::collection_name())
+        self.list_headers_from_collection(ids, order, limit, None, &C::collection_name())
     }
 
     /// Counts the number of documents within the range of `ids`.
@@ -271,6 +286,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         key: Option<QueryKey<'_, V::Key, Key>>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<ViewMappings<V>, Error>
     where
@@ -283,6 +299,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
             key.map(|key| key.serialized()).transpose()?,
             order,
             limit,
+            cursor,
             access_policy,
         )?;
         mappings
@@ -315,14 +332,32 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         key: Option<QueryKey<'_, V::Key, Key>>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<MappedDocuments<OwnedDocument, V>, Error>
     where
         Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
         V::Key: Borrow<Key> + PartialEq<Key>,
     {
+        if let Some(filter) = filter {
+            // The filter must be evaluated where the documents are stored.
+            let view = self.schematic().view::<V>()?;
+            return self
+                .query_by_name_with_docs(
+                    &view.view_name(),
+                    key.map(|key| key.serialized()).transpose()?,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                    Some(filter),
+                )?
+                .deserialized();
+        }
+
         // Query permission is checked by the query call
-        let results = self.query::<V, Key>(key, order, limit, access_policy)?;
+        let results = self.query::<V, Key>(key, order, limit, cursor, access_policy)?;
 
         // Verify that there is permission to fetch each document
         let documents = self
@@ -353,7 +388,9 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         key: Option<QueryKey<'_, V::Key, Key>>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<MappedDocuments<CollectionDocument<V::Collection>, V>, Error>
     where
         Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
@@ -362,7 +399,8 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         V::Collection: SerializedCollection,
         <V::Collection as SerializedCollection>::Contents: std::fmt::Debug,
     {
-        let mapped_docs = self.query_with_docs::<V, Key>(key, order, limit, access_policy)?;
+        let mapped_docs =
+            self.query_with_docs::<V, Key>(key, order, limit, cursor, access_policy, filter)?;
         let mut collection_docs = BTreeMap::new();
         for (id, doc) in mapped_docs.documents {
             collection_docs.insert(id, CollectionDocument::<V::Collection>::try_from(&doc)?);
@@ -373,6 +411,45 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         })
     }
 
+    /// Queries for view entries matching [`View`](schema::View) with the
+    /// fields of their source documents selected by `projection`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider querying the
+    /// view using [`View::entries(self).project()`](super::View::project)
+    /// instead. The parameters for the query can be customized on the builder
+    /// returned from
+    /// [`SerializedView::entries()`](schema::SerializedView::entries),
+    /// [`SerializedView::entries_async()`](schema::SerializedView::entries_async),
+    /// or [`Connection::view()`](super::Connection::view).
+    #[allow(clippy::too_many_arguments)]
+    fn query_with_projected_docs<V: schema::SerializedView, Key>(
+        &self,
+        key: Option<QueryKey<'_, V::Key, Key>>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<MappedDocuments<ProjectedDocument, V>, Error>
+    where
+        Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
+        V::Key: Borrow<Key> + PartialEq<Key>,
+    {
+        let view = self.schematic().view::<V>()?;
+        self.query_by_name_with_projected_docs(
+            &view.view_name(),
+            key.map(|key| key.serialized()).transpose()?,
+            order,
+            limit,
+            cursor,
+            access_policy,
+            filter,
+            projection,
+        )?
+        .deserialized()
+    }
+
     /// Reduces the view entries matching [`View`](schema::View).
     ///
     /// This is a lower-level API. For better ergonomics, consider reducing the
@@ -497,7 +574,9 @@ pub trait LowLevelConnection: HasSchema + HasSession {
     ) -> Result<Vec<OwnedDocument>, Error>;
 
     /// Retrieves all documents within the range of `ids` from the named
-    /// `collection`. To retrieve all documents, pass in `..` for `ids`.
+    /// `collection`. To retrieve all documents, pass in `..` for `ids`. If
+    /// `filter` is provided, only documents whose contents match it are
+    /// returned, and only matching documents count towards `limit`.
     ///
     /// This is a lower-level API. For better ergonomics, consider using one of:
     ///
@@ -510,11 +589,91 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, Error>;
 
+    /// Retrieves the fields selected by `projection` of all documents within
+    /// the range of `ids` from the named `collection`. If `filter` is
+    /// provided, only documents whose contents match it are returned, and
+    /// only matching documents count towards `limit`.
+    ///
+    /// The default implementation retrieves the documents using
+    /// [`list_from_collection()`](Self::list_from_collection) and selects the
+    /// fields after they have been retrieved.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider using
+    /// [`self.collection::<Collection>().all().project()`](super::List::project)
+    /// instead.
+    fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, Error> {
+        self.list_from_collection(ids, order, limit, filter, collection)?
+            .into_iter()
+            .map(|document| projection.project_document(document.header, &document.contents))
+            .collect()
+    }
+
+    /// Retrieves all documents within the range of `ids` from the named
+    /// `collection` in chunks of at most `chunk_size` documents. If `filter`
+    /// is provided, only documents whose contents match it are returned. At
+    /// most `limit` documents are returned across all chunks.
+    ///
+    /// The default implementation retrieves each chunk using
+    /// [`list_from_collection()`](Self::list_from_collection), resuming after
+    /// the last document of the previous chunk.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider using
+    /// [`self.collection::<Collection>().all().iter()`](super::List::iter)
+    /// instead.
+    fn list_chunks_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        chunk_size: u32,
+        collection: &CollectionName,
+    ) -> ResultChunks<'_, Vec<OwnedDocument>> {
+        let collection = collection.clone();
+        Box::new(stream::iter_chunks(
+            None,
+            limit,
+            chunk_size,
+            move |cursor: Option<ListCursor>, requested| {
+                let ids = match cursor {
+                    Some(cursor) => cursor.narrow(ids.clone(), order),
+                    None => Some(ids.clone()),
+                };
+                let Some(ids) = ids else {
+                    return Ok(Page {
+                        results: Vec::new(),
+                        next: None,
+                    });
+                };
+                let documents = self.list_from_collection(
+                    ids,
+                    order,
+                    Some(requested),
+                    filter.clone(),
+                    &collection,
+                )?;
+                Ok(Page::of_documents(documents, Some(requested)))
+            },
+        ))
+    }
+
     /// Retrieves all headers within the range of `ids` from the named
-    /// `collection`. To retrieve all documents, pass in `..` for `ids`.
+    /// `collection`. To retrieve all documents, pass in `..` for `ids`. If
+    /// `filter` is provided, only the headers of documents whose contents
+    /// match it are returned, and only matching documents count towards
+    /// `limit`.
     ///
     /// This is a lower-level API. For better ergonomics, consider using one of:
     ///
@@ -527,6 +686,7 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, Error>;
 
@@ -570,26 +730,205 @@ pub trait LowLevelConnection: HasSchema + HasSession {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, Error>;
 
     /// Queries for view entries from the named `view` with their source
-    /// documents.
+    /// documents. If `filter` is provided, only entries whose source document
+    /// matches it are returned, and only matching entries count towards
+    /// `limit`.
     ///
     /// This is a lower-level API. For better ergonomics, consider querying the
     /// view using
     /// [`View::entries(self).query_with_docs()`](super::View::query_with_docs)
     /// instead. The parameters for the query can be customized on the builder
     /// returned from [`Connection::view()`](super::Connection::view).
+    #[allow(clippy::too_many_arguments)]
     fn query_by_name_with_docs(
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, Error>;
 
+    /// Queries for view entries from the named `view` with the fields of their
+    /// source documents selected by `projection`. If `filter` is provided,
+    /// only entries whose source document matches it are returned, and only
+    /// matching entries count towards `limit`.
+    ///
+    /// The default implementation retrieves the documents using
+    /// [`query_by_name_with_docs()`](Self::query_by_name_with_docs) and
+    /// selects the fields after they have been retrieved.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider querying the
+    /// view using [`View::entries(self).project()`](super::View::project)
+    /// instead. The parameters for the query can be customized on the builder
+    /// returned from [`Connection::view()`](super::Connection::view).
+    #[allow(clippy::too_many_arguments)]
+    fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<schema::view::map::MappedSerializedDocuments<ProjectedDocument>, Error> {
+        self.query_by_name_with_docs(view, key, order, limit, cursor, access_policy, filter)?
+            .project(&projection)
+    }
+
+    /// Queries for view entries from the named `view`, returning the mappings
+    /// in chunks of at most `chunk_size` mappings. At most `limit` mappings
+    /// are returned across all chunks.
+    ///
+    /// The default implementation retrieves each chunk using
+    /// [`query_by_name()`](Self::query_by_name), resuming after the last
+    /// mapping of the previous chunk. Only the first chunk applies
+    /// `access_policy`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider querying the
+    /// view using [`View::entries(self).iter()`](super::View::iter) instead.
+    #[allow(clippy::too_many_arguments)]
+    fn query_chunks_by_name(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        chunk_size: u32,
+    ) -> ResultChunks<'_, Vec<schema::view::map::Serialized>> {
+        let view = view.clone();
+        let mut access_policy = access_policy;
+        Box::new(stream::iter_chunks(
+            cursor,
+            limit,
+            chunk_size,
+            move |cursor, requested| {
+                let access_policy = std::mem::replace(&mut access_policy, AccessPolicy::NoUpdate);
+                let mappings = self.query_by_name(
+                    &view,
+                    key.clone(),
+                    order,
+                    Some(requested),
+                    cursor,
+                    access_policy,
+                )?;
+                Ok(Page::of_serialized_mappings(mappings, Some(requested)))
+            },
+        ))
+    }
+
+    /// Queries for view entries from the named `view` with their source
+    /// documents, returning the mappings in chunks of at most `chunk_size`
+    /// mappings. If `filter` is provided, only entries whose source document
+    /// matches it are returned. At most `limit` mappings are returned across
+    /// all chunks.
+    ///
+    /// The default implementation retrieves each chunk using
+    /// [`query_by_name_with_docs()`](Self::query_by_name_with_docs), resuming
+    /// after the last mapping of the previous chunk. Only the first chunk
+    /// applies `access_policy`.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider querying the
+    /// view using
+    /// [`View::entries(self).iter_with_docs()`](super::View::iter_with_docs)
+    /// instead.
+    #[allow(clippy::too_many_arguments)]
+    fn query_chunks_by_name_with_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        chunk_size: u32,
+    ) -> ResultChunks<'_, schema::view::map::MappedSerializedDocuments> {
+        let view = view.clone();
+        let mut access_policy = access_policy;
+        Box::new(stream::iter_chunks(
+            cursor,
+            limit,
+            chunk_size,
+            move |cursor, requested| {
+                let access_policy = std::mem::replace(&mut access_policy, AccessPolicy::NoUpdate);
+                let mapped = self.query_by_name_with_docs(
+                    &view,
+                    key.clone(),
+                    order,
+                    Some(requested),
+                    cursor,
+                    access_policy,
+                    filter.clone(),
+                )?;
+                Ok(Page::of_serialized_mapped_documents(
+                    mapped,
+                    Some(requested),
+                ))
+            },
+        ))
+    }
+
+    /// Returns the documents matched by the view queries in `query`, combined
+    /// by intersecting or unioning the sets of matched documents. Each
+    /// document is returned once, ordered by its id, and at most `limit`
+    /// documents are returned. Every view in `query` must belong to the same
+    /// collection.
+    ///
+    /// The ids of the documents matched by each view are collected before
+    /// being combined, so memory use grows with the number of entries matched
+    /// by the queried views. Only the documents being returned are loaded.
+    ///
+    /// The default implementation queries each view using
+    /// [`query_by_name()`](Self::query_by_name) and loads the documents using
+    /// [`get_multiple_from_collection()`](Self::get_multiple_from_collection).
+    ///
+    /// This is a lower-level API. For better ergonomics, consider combining
+    /// view queries using [`View::intersect()`](super::View::intersect) or
+    /// [`View::union()`](super::View::union) instead.
+    fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, Error> {
+        let Some(collection) = query.collection(self.schematic())? else {
+            return Ok(Vec::new());
+        };
+        let mut matched = Vec::new();
+        for view in query.views() {
+            let mappings = self.query_by_name(
+                &view.view,
+                view.key.clone(),
+                Sort::Ascending,
+                None,
+                None,
+                view.access_policy,
+            )?;
+            matched.push(
+                mappings
+                    .into_iter()
+                    .map(|mapping| mapping.source.id)
+                    .collect::<BTreeSet<_>>(),
+            );
+        }
+        let ids = limit_document_ids(query.combine_document_ids(&mut matched.into_iter()), limit);
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.get_multiple_from_collection(&ids, &collection)
+    }
+
     /// Reduces the view entries from the named `view`.
     ///
     /// This is a lower-level API. For better ergonomics, consider reducing the
@@ -809,7 +1148,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         C::PrimaryKey: Borrow<PrimaryKey> + PartialEq<PrimaryKey>,
     {
         let ids = ids.into().map_result(|id| DocumentId::new(id))?;
-        self.list_from_collection(ids, order, limit, &C::collection_name())
+        self.list_from_collection(ids, order, limit, None, &C::collection_name())
             .await
     }
 
@@ -836,7 +1175,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         C::PrimaryKey: Borrow<PrimaryKey> + PartialEq<PrimaryKey>,
     {
         let ids = ids.into().map_result(|id| DocumentId::new(id))?;
-        self.list_headers_from_collection(ids, order, limit, &C::collection_name())
+        self.list_headers_from_collection(ids, order, limit, None, &C::collection_name())
             .await
     }
 
@@ -896,6 +1235,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         key: Option<QueryKey<'_, V::Key, Key>>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<ViewMappings<V>, Error>
     where
@@ -909,6 +1249,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
                 key.map(|key| key.serialized()).transpose()?,
                 order,
                 limit,
+                cursor,
                 access_policy,
             )
             .await?;
@@ -938,15 +1279,34 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         key: Option<QueryKey<'_, V::Key, Key>>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<MappedDocuments<OwnedDocument, V>, Error>
     where
         Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
         V::Key: Borrow<Key> + PartialEq<Key>,
     {
+        if let Some(filter) = filter {
+            // The filter must be evaluated where the documents are stored.
+            let view = self.schematic().view::<V>()?;
+            return self
+                .query_by_name_with_docs(
+                    &view.view_name(),
+                    key.map(|key| key.serialized()).transpose()?,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                    Some(filter),
+                )
+                .await?
+                .deserialized();
+        }
+
         // Query permission is checked by the query call
         let results = self
-            .query::<V, Key>(key, order, limit, access_policy)
+            .query::<V, Key>(key, order, limit, cursor, access_policy)
             .await?;
 
         // Verify that there is permission to fetch each document
@@ -977,7 +1337,9 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         key: Option<QueryKey<'_, V::Key, Key>>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<MappedDocuments<CollectionDocument<V::Collection>, V>, Error>
     where
         Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
@@ -987,7 +1349,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         <V::Collection as SerializedCollection>::Contents: std::fmt::Debug,
     {
         let mapped_docs = self
-            .query_with_docs::<V, Key>(key, order, limit, access_policy)
+            .query_with_docs::<V, Key>(key, order, limit, cursor, access_policy, filter)
             .await?;
         let mut collection_docs = BTreeMap::new();
         for (id, doc) in mapped_docs.documents {
@@ -999,6 +1361,45 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         })
     }
 
+    /// Queries for view entries matching [`View`](schema::View) with the
+    /// fields of their source documents selected by `projection`.
+    ///
+    /// This is the lower-level API. For better ergonomics, consider querying
+    /// the view using
+    /// [`View::entries(self).project()`](super::AsyncView::project) instead.
+    /// The parameters for the query can be customized on the builder returned
+    /// from [`AsyncConnection::view()`](super::AsyncConnection::view).
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    async fn query_with_projected_docs<V: schema::SerializedView, Key>(
+        &self,
+        key: Option<QueryKey<'_, V::Key, Key>>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<MappedDocuments<ProjectedDocument, V>, Error>
+    where
+        Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
+        V::Key: Borrow<Key> + PartialEq<Key>,
+    {
+        let view = self.schematic().view::<V>()?;
+        self.query_by_name_with_projected_docs(
+            &view.view_name(),
+            key.map(|key| key.serialized()).transpose()?,
+            order,
+            limit,
+            cursor,
+            access_policy,
+            filter,
+            projection,
+        )
+        .await?
+        .deserialized()
+    }
+
     /// Reduces the view entries matching [`View`](schema::View).
     ///
     /// This is the lower-level API. For better ergonomics, consider querying
@@ -1123,7 +1524,9 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
     ) -> Result<Vec<OwnedDocument>, Error>;
 
     /// Retrieves all documents within the range of `ids` from the named
-    /// `collection`. To retrieve all documents, pass in `..` for `ids`.
+    /// `collection`. To retrieve all documents, pass in `..` for `ids`. If
+    /// `filter` is provided, only documents whose contents match it are
+    /// returned, and only matching documents count towards `limit`.
     ///
     /// This is a lower-level API. For better ergonomics, consider using one of:
     ///
@@ -1136,11 +1539,92 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, Error>;
 
+    /// Retrieves the fields selected by `projection` of all documents within
+    /// the range of `ids` from the named `collection`. If `filter` is
+    /// provided, only documents whose contents match it are returned, and
+    /// only matching documents count towards `limit`.
+    ///
+    /// The default implementation retrieves the documents using
+    /// [`list_from_collection()`](Self::list_from_collection) and selects the
+    /// fields after they have been retrieved.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider using
+    /// [`self.collection::<Collection>().all().project()`](super::AsyncList::project)
+    /// instead.
+    async fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, Error> {
+        self.list_from_collection(ids, order, limit, filter, collection)
+            .await?
+            .into_iter()
+            .map(|document| projection.project_document(document.header, &document.contents))
+            .collect()
+    }
+
+    /// Retrieves all documents within the range of `ids` from the named
+    /// `collection` in chunks of at most `chunk_size` documents. If `filter`
+    /// is provided, only documents whose contents match it are returned. At
+    /// most `limit` documents are returned across all chunks.
+    ///
+    /// The default implementation retrieves each chunk using
+    /// [`list_from_collection()`](Self::list_from_collection), resuming after
+    /// the last document of the previous chunk.
+    ///
+    /// This is a lower-level API. For better ergonomics, consider using
+    /// [`self.collection::<Collection>().all().stream()`](super::AsyncList::stream)
+    /// instead.
+    fn list_chunks_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        chunk_size: u32,
+        collection: &CollectionName,
+    ) -> AsyncResultChunks<'_, Vec<OwnedDocument>> {
+        let collection = collection.clone();
+        Box::pin(stream::stream_chunks(
+            None,
+            limit,
+            chunk_size,
+            move |cursor: Option<ListCursor>, requested| {
+                let ids = match cursor {
+                    Some(cursor) => cursor.narrow(ids.clone(), order),
+                    None => Some(ids.clone()),
+                };
+                let filter = filter.clone();
+                let collection = collection.clone();
+                async move {
+                    let Some(ids) = ids else {
+                        return Ok(Page {
+                            results: Vec::new(),
+                            next: None,
+                        });
+                    };
+                    let documents = self
+                        .list_from_collection(ids, order, Some(requested), filter, &collection)
+                        .await?;
+                    Ok(Page::of_documents(documents, Some(requested)))
+                }
+            },
+        ))
+    }
+
     /// Retrieves all headers within the range of `ids` from the named
-    /// `collection`. To retrieve all documents, pass in `..` for `ids`.
+    /// `collection`. To retrieve all documents, pass in `..` for `ids`. If
+    /// `filter` is provided, only the headers of documents whose contents
+    /// match it are returned, and only matching documents count towards
+    /// `limit`.
     ///
     /// This is a lower-level API. For better ergonomics, consider using one of:
     ///
@@ -1153,6 +1637,7 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, Error>;
 
@@ -1196,25 +1681,214 @@ pub trait AsyncLowLevelConnection: HasSchema + HasSession + Send + Sync {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, Error>;
 
     /// Queries for view entries from the named `view` with their source
-    /// documents.
+    /// documents. If `filter` is provided, only entries whose source document
+    /// matches it are returned, and only matching entries count towards
+    /// `limit`.
     ///
     /// This is the lower-level API. For better ergonomics, consider querying
     /// the view using [`View::entries(self).query_with_docs()`](super::AsyncView::query_with_docs) instead.
     /// The parameters for the query can be customized on the builder returned
     /// from [`AsyncConnection::view()`](super::AsyncConnection::view).
+    #[allow(clippy::too_many_arguments)]
     async fn query_by_name_with_docs(
         &self,
         view: &ViewName,
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, Error>;
 
+    /// Queries for view entries from the named `view` with the fields of their
+    /// source documents selected by `projection`. If `filter` is provided,
+    /// only entries whose source document matches it are returned, and only
+    /// matching entries count towards `limit`.
+    ///
+    /// The default implementation retrieves the documents using
+    /// [`query_by_name_with_docs()`](Self::query_by_name_with_docs) and
+    /// selects the fields after they have been retrieved.
+    ///
+    /// This is the lower-level API. For better ergonomics, consider querying
+    /// the view using [`View::entries(self).project()`](super::AsyncView::project) instead.
+    /// The parameters for the query can be customized on the builder returned
+    /// from [`AsyncConnection::view()`](super::AsyncConnection::view).
+    #[allow(clippy::too_many_arguments)]
+    async fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<schema::view::map::MappedSerializedDocuments<ProjectedDocument>, Error> {
+        self.query_by_name_with_docs(view, key, order, limit, cursor, access_policy, filter)
+            .await?
+            .project(&projection)
+    }
+
+    /// Queries for view entries from the named `view`, returning the mappings
+    /// in chunks of at most `chunk_size` mappings. At most `limit` mappings
+    /// are returned across all chunks.
+    ///
+    /// The default implementation retrieves each chunk using
+    /// [`query_by_name()`](Self::query_by_name), resuming after the last
+    /// mapping of the previous chunk. Only the first chunk applies
+    /// `access_policy`.
+    ///
+    /// This is the lower-level API. For better ergonomics, consider querying
+    /// the view using [`View::entries(self).stream()`](super::AsyncView::stream)
+    /// instead.
+    #[allow(clippy::too_many_arguments)]
+    fn query_chunks_by_name(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, Vec<schema::view::map::Serialized>> {
+        let view = view.clone();
+        let mut access_policy = access_policy;
+        Box::pin(stream::stream_chunks(
+            cursor,
+            limit,
+            chunk_size,
+            move |cursor, requested| {
+                let access_policy = std::mem::replace(&mut access_policy, AccessPolicy::NoUpdate);
+                let view = view.clone();
+                let key = key.clone();
+                async move {
+                    let mappings = self
+                        .query_by_name(&view, key, order, Some(requested), cursor, access_policy)
+                        .await?;
+                    Ok(Page::of_serialized_mappings(mappings, Some(requested)))
+                }
+            },
+        ))
+    }
+
+    /// Queries for view entries from the named `view` with their source
+    /// documents, returning the mappings in chunks of at most `chunk_size`
+    /// mappings. If `filter` is provided, only entries whose source document
+    /// matches it are returned. At most `limit` mappings are returned across
+    /// all chunks.
+    ///
+    /// The default implementation retrieves each chunk using
+    /// [`query_by_name_with_docs()`](Self::query_by_name_with_docs), resuming
+    /// after the last mapping of the previous chunk. Only the first chunk
+    /// applies `access_policy`.
+    ///
+    /// This is the lower-level API. For better ergonomics, consider querying
+    /// the view using
+    /// [`View::entries(self).stream_with_docs()`](super::AsyncView::stream_with_docs)
+    /// instead.
+    #[allow(clippy::too_many_arguments)]
+    fn query_chunks_by_name_with_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, schema::view::map::MappedSerializedDocuments> {
+        let view = view.clone();
+        let mut access_policy = access_policy;
+        Box::pin(stream::stream_chunks(
+            cursor,
+            limit,
+            chunk_size,
+            move |cursor, requested| {
+                let access_policy = std::mem::replace(&mut access_policy, AccessPolicy::NoUpdate);
+                let view = view.clone();
+                let key = key.clone();
+                let filter = filter.clone();
+                async move {
+                    let mapped = self
+                        .query_by_name_with_docs(
+                            &view,
+                            key,
+                            order,
+                            Some(requested),
+                            cursor,
+                            access_policy,
+                            filter,
+                        )
+                        .await?;
+                    Ok(Page::of_serialized_mapped_documents(
+                        mapped,
+                        Some(requested),
+                    ))
+                }
+            },
+        ))
+    }
+
+    /// Returns the documents matched by the view queries in `query`, combined
+    /// by intersecting or unioning the sets of matched documents. Each
+    /// document is returned once, ordered by its id, and at most `limit`
+    /// documents are returned. Every view in `query` must belong to the same
+    /// collection.
+    ///
+    /// The ids of the documents matched by each view are collected before
+    /// being combined, so memory use grows with the number of entries matched
+    /// by the queried views. Only the documents being returned are loaded.
+    ///
+    /// The default implementation queries each view using
+    /// [`query_by_name()`](Self::query_by_name) and loads the documents using
+    /// [`get_multiple_from_collection()`](Self::get_multiple_from_collection).
+    ///
+    /// This is the lower-level API. For better ergonomics, consider combining
+    /// view queries using [`AsyncView::intersect()`](super::AsyncView::intersect)
+    /// or [`AsyncView::union()`](super::AsyncView::union) instead.
+    async fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, Error> {
+        let Some(collection) = query.collection(self.schematic())? else {
+            return Ok(Vec::new());
+        };
+        let mut matched = Vec::new();
+        for view in query.views() {
+            let mappings = self
+                .query_by_name(
+                    &view.view,
+                    view.key.clone(),
+                    Sort::Ascending,
+                    None,
+                    None,
+                    view.access_policy,
+                )
+                .await?;
+            matched.push(
+                mappings
+                    .into_iter()
+                    .map(|mapping| mapping.source.id)
+                    .collect::<BTreeSet<_>>(),
+            );
+        }
+        let ids = limit_document_ids(query.combine_document_ids(&mut matched.into_iter()), limit);
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.get_multiple_from_collection(&ids, &collection).await
+    }
+
     /// Reduces the view entries from the named `view`.
     ///
     /// This is the lower-level API. For better ergonomics, consider querying
@@ -0,0 +1,177 @@
+use futures::{Future, Stream, StreamExt, TryStreamExt};
+
+use crate::connection::Page;
+use crate::document::OwnedDocument;
+use crate::schema::view::map::{Map, MappedDocuments, MappedSerializedDocuments};
+use crate::schema::View;
+use crate::Error;
+
+/// A chunk of results retrieved from a database.
+pub(crate) trait Chunk {
+    /// Returns the number of results in this chunk.
+    fn result_count(&self) -> usize;
+}
+
+impl<T> Chunk for Vec<T> {
+    fn result_count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<D> Chunk for MappedSerializedDocuments<D> {
+    fn result_count(&self) -> usize {
+        self.mappings.len()
+    }
+}
+
+/// Returns the number of results to request in the next chunk, or `None` if
+/// `limit` results have already been returned.
+fn next_chunk_size(remaining: Option<u32>, chunk_size: u32) -> Option<u32> {
+    let requested = remaining.map_or(chunk_size, |remaining| remaining.min(chunk_size));
+    (requested > 0).then_some(requested)
+}
+
+/// Returns the number of results that can still be returned after `chunk`.
+fn remaining_after<T: Chunk>(remaining: Option<u32>, chunk: &T) -> Option<u32> {
+    remaining.map(|remaining| {
+        remaining.saturating_sub(u32::try_from(chunk.result_count()).unwrap_or(u32::MAX))
+    })
+}
+
+/// Returns an iterator over the chunks returned from `fetch`. `fetch` is
+/// invoked with `cursor` and the number of results to request to retrieve the
+/// first chunk, and then with each chunk's `next` cursor until a chunk without
+/// one is returned or `limit` results have been returned. Each chunk holds at
+/// most `chunk_size` results.
+pub(crate) fn iter_chunks<'a, T, Cursor, Fetch>(
+    cursor: Option<Cursor>,
+    limit: Option<u32>,
+    chunk_size: u32,
+    mut fetch: Fetch,
+) -> impl Iterator<Item = Result<T, Error>> + 'a
+where
+    Fetch: FnMut(Option<Cursor>, u32) -> Result<Page<T, Cursor>, Error> + 'a,
+    T: Chunk + 'a,
+    Cursor: 'a,
+{
+    let chunk_size = chunk_size.max(1);
+    let mut next = Some(cursor);
+    let mut remaining = limit;
+    std::iter::from_fn(move || {
+        let requested = next_chunk_size(remaining, chunk_size)?;
+        match fetch(next.take()?, requested) {
+            Ok(page) if page.results.result_count() == 0 => None,
+            Ok(page) => {
+                remaining = remaining_after(remaining, &page.results);
+                next = page.next.map(Some);
+                Some(Ok(page.results))
+            }
+            // Errors end the iterator after being returned.
+            Err(err) => Some(Err(err)),
+        }
+    })
+}
+
+/// Returns a stream of the chunks returned from `fetch`. See [`iter_chunks()`]
+/// for how chunks are retrieved.
+pub(crate) fn stream_chunks<'a, T, Cursor, Fetch, ChunkFuture>(
+    cursor: Option<Cursor>,
+    limit: Option<u32>,
+    chunk_size: u32,
+    fetch: Fetch,
+) -> impl Stream<Item = Result<T, Error>> + Send + 'a
+where
+    Fetch: FnMut(Option<Cursor>, u32) -> ChunkFuture + Send + 'a,
+    ChunkFuture: Future<Output = Result<Page<T, Cursor>, Error>> + Send + 'a,
+    T: Chunk + Send + 'a,
+    Cursor: Send + 'a,
+{
+    let chunk_size = chunk_size.max(1);
+    futures::stream::try_unfold(
+        (Some(cursor), limit, fetch),
+        move |(next, remaining, mut fetch)| async move {
+            let (Some(cursor), Some(requested)) = (next, next_chunk_size(remaining, chunk_size))
+            else {
+                return Ok(None);
+            };
+            let page = fetch(cursor, requested).await?;
+            if page.results.result_count() == 0 {
+                return Ok(None);
+            }
+            let remaining = remaining_after(remaining, &page.results);
+            Ok(Some((
+                page.results,
+                (page.next.map(Some), remaining, fetch),
+            )))
+        },
+    )
+}
+
+/// Returns an iterator over the results of each chunk in `chunks`, after
+/// being converted by `convert`. Only one chunk of results is held at a time.
+pub(crate) fn flatten_chunks<'a, C, T>(
+    mut chunks: impl Iterator<Item = Result<C, Error>> + 'a,
+    mut convert: impl FnMut(C) -> Result<Vec<T>, Error> + 'a,
+) -> impl Iterator<Item = Result<T, Error>> + 'a
+where
+    T: 'a,
+{
+    let mut results = Vec::new().into_iter();
+    let mut failed = false;
+    std::iter::from_fn(move || loop {
+        if let Some(result) = results.next() {
+            return Some(Ok(result));
+        } else if failed {
+            return None;
+        }
+
+        match chunks.next()?.and_then(&mut convert) {
+            Ok(chunk) => results = chunk.into_iter(),
+            // Errors end the iterator after being returned.
+            Err(err) => {
+                failed = true;
+                return Some(Err(err));
+            }
+        }
+    })
+}
+
+/// Returns a stream of the results of each chunk in `chunks`, after being
+/// converted by `convert`. See [`flatten_chunks()`] for more information.
+pub(crate) fn flatten_chunk_stream<'a, C, T>(
+    chunks: impl Stream<Item = Result<C, Error>> + Send + 'a,
+    convert: impl FnMut(C) -> Result<Vec<T>, Error> + Send + 'a,
+) -> impl Stream<Item = Result<T, Error>> + Send + 'a
+where
+    T: Send + 'a,
+{
+    futures::stream::try_unfold(
+        (Box::pin(chunks), convert),
+        |(mut chunks, mut convert)| async move {
+            let Some(chunk) = chunks.next().await else {
+                return Ok(None);
+            };
+            let results = futures::stream::iter(convert(chunk?)?.into_iter().map(Ok));
+            Ok(Some((results, (chunks, convert))))
+        },
+    )
+    .try_flatten()
+}
+
+/// Pairs each mapping in `mapped` with its source document. Mappings whose
+/// source document wasn't returned are skipped.
+pub(crate) fn with_documents<V: View>(
+    mapped: MappedDocuments<OwnedDocument, V>,
+) -> Vec<(Map<V::Key, V::Value>, OwnedDocument)> {
+    let MappedDocuments {
+        mappings,
+        documents,
+    } = mapped;
+    mappings
+        .into_iter()
+        .filter_map(|mapping| {
+            let document = documents.get(&mapping.source.id)?.clone();
+            Some((mapping, document))
+        })
+        .collect()
+}
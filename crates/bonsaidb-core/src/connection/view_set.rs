@@ -0,0 +1,371 @@
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{
+    AccessPolicy, AsyncConnection, AsyncView, Connection, HasSchema, QueryKey, SerializedQueryKey,
+    View,
+};
+use crate::document::{CollectionDocument, DocumentId, OwnedDocument};
+use crate::key::KeyEncoding;
+use crate::schema::{
+    self, CollectionName, Schematic, SerializedCollection, SerializedView, ViewName,
+};
+use crate::Error;
+
+/// A combination of view queries whose matching documents are intersected or
+/// unioned.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum ViewSetQuery {
+    /// The documents matched by a single view query.
+    View(SerializedViewQuery),
+    /// The documents matched by every query.
+    Intersection(Vec<ViewSetQuery>),
+    /// The documents matched by any query.
+    Union(Vec<ViewSetQuery>),
+}
+
+impl ViewSetQuery {
+    /// Returns a query that matches the documents matched by both `self` and
+    /// `other`.
+    #[must_use]
+    pub fn intersect(self, other: Self) -> Self {
+        match self {
+            Self::Intersection(mut queries) => {
+                queries.push(other);
+                Self::Intersection(queries)
+            }
+            query => Self::Intersection(vec![query, other]),
+        }
+    }
+
+    /// Returns a query that matches the documents matched by either `self` or
+    /// `other`.
+    #[must_use]
+    pub fn union(self, other: Self) -> Self {
+        match self {
+            Self::Union(mut queries) => {
+                queries.push(other);
+                Self::Union(queries)
+            }
+            query => Self::Union(vec![query, other]),
+        }
+    }
+
+    /// Returns every view query contained in this query.
+    #[must_use]
+    pub fn views(&self) -> Vec<&SerializedViewQuery> {
+        match self {
+            Self::View(view) => vec![view],
+            Self::Intersection(queries) | Self::Union(queries) => {
+                queries.iter().flat_map(Self::views).collect()
+            }
+        }
+    }
+
+    /// Returns the collection the views in this query belong to, or `None` if
+    /// this query contains no views.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a view isn't defined in `schematic` or if the views
+    /// belong to more than one collection.
+    pub fn collection(&self, schematic: &Schematic) -> Result<Option<CollectionName>, Error> {
+        let mut collection = None;
+        for query in self.views() {
+            let view_collection = schematic.view_by_name(&query.view)?.collection();
+            match &collection {
+                Some(collection) if collection != &view_collection => {
+                    return Err(Error::other(
+                        "view set",
+                        format!(
+                            "view {} does not belong to collection {collection}",
+                            query.view
+                        ),
+                    ));
+                }
+                Some(_) => {}
+                None => collection = Some(view_collection),
+            }
+        }
+        Ok(collection)
+    }
+
+    /// Combines the ids of the documents matched by each view in this query.
+    /// `matched` must return the ids matched by each view in the order the
+    /// views are returned from [`views()`](Self::views).
+    #[must_use]
+    pub fn combine_document_ids(
+        &self,
+        matched: &mut impl Iterator<Item = BTreeSet<DocumentId>>,
+    ) -> BTreeSet<DocumentId> {
+        match self {
+            Self::View(_) => matched.next().unwrap_or_default(),
+            Self::Intersection(queries) => {
+                let mut queries = queries.iter();
+                let mut ids = queries
+                    .next()
+                    .map(|query| query.combine_document_ids(matched))
+                    .unwrap_or_default();
+                for query in queries {
+                    let other = query.combine_document_ids(matched);
+                    ids.retain(|id| other.contains(id));
+                }
+                ids
+            }
+            Self::Union(queries) => {
+                let mut ids = BTreeSet::new();
+                for query in queries {
+                    ids.extend(query.combine_document_ids(matched));
+                }
+                ids
+            }
+        }
+    }
+}
+
+/// Returns the first `limit` ids of `ids`, in ascending order.
+pub(crate) fn limit_document_ids(ids: BTreeSet<DocumentId>, limit: Option<u32>) -> Vec<DocumentId> {
+    let limit = limit.map_or(usize::MAX, |limit| {
+        usize::try_from(limit).unwrap_or(usize::MAX)
+    });
+    ids.into_iter().take(limit).collect()
+}
+
+/// A serialized query of a single view within a [`ViewSetQuery`].
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SerializedViewQuery {
+    /// The name of the view.
+    pub view: ViewName,
+    /// The filter for the view.
+    pub key: Option<SerializedQueryKey>,
+    /// The access policy for the view.
+    pub access_policy: AccessPolicy,
+}
+
+/// Combines the documents matched by several view queries of the same
+/// collection. Each document is returned at most once, in order of its id.
+///
+/// This type is returned from [`View::intersect()`] and [`View::union()`].
+/// Each view is queried using its own [`AccessPolicy`].
+///
+/// ```rust
+/// # bonsaidb_core::__doctest_prelude!();
+/// # use bonsaidb_core::connection::Connection;
+/// # fn test_fn<C: Connection>(db: C) -> Result<(), Error> {
+/// for document in ScoresByRank::entries(&db)
+///     .with_key_range(42..=44)
+///     .intersect(MyCollectionByName::entries(&db).with_key("Alice"))
+///     .query_with_collection_docs()?
+/// {
+///     println!("Alice has a rank of {}", document.contents.rank);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub struct ViewSet<'a, Cn, C> {
+    connection: &'a Cn,
+    query: Result<ViewSetQuery, Error>,
+    limit: Option<u32>,
+    _collection: PhantomData<C>,
+}
+
+impl<'a, Cn, C> ViewSet<'a, Cn, C>
+where
+    Cn: Connection,
+    C: schema::Collection,
+{
+    /// Restricts the results to documents also matched by `other`.
+    pub fn intersect(self, other: impl Into<Self>) -> Self {
+        self.combine(other.into(), ViewSetQuery::intersect)
+    }
+
+    /// Extends the results with the documents matched by `other`.
+    pub fn union(self, other: impl Into<Self>) -> Self {
+        self.combine(other.into(), ViewSetQuery::union)
+    }
+
+    fn combine(
+        self,
+        other: Self,
+        combine: impl FnOnce(ViewSetQuery, ViewSetQuery) -> ViewSetQuery,
+    ) -> Self {
+        let query = match (self.query, other.query) {
+            (Ok(query), Ok(other)) => Ok(combine(query, other)),
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        };
+        Self { query, ..self }
+    }
+
+    /// Limits the results to the `limit` documents with the lowest ids.
+    pub const fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Executes the query and returns the matched documents.
+    pub fn query(self) -> Result<Vec<OwnedDocument>, Error> {
+        self.connection.query_view_set(self.query?, self.limit)
+    }
+
+    /// Executes the query and returns the matched documents deserialized as
+    /// [`CollectionDocument`]s.
+    pub fn query_with_collection_docs(self) -> Result<Vec<CollectionDocument<C>>, Error>
+    where
+        C: SerializedCollection,
+    {
+        self.query()?
+            .iter()
+            .map(CollectionDocument::try_from)
+            .collect()
+    }
+}
+
+impl<'a, Cn, V, Key> From<View<'a, Cn, V, Key>> for ViewSet<'a, Cn, V::Collection>
+where
+    V::Key: Borrow<Key> + PartialEq<Key>,
+    V: SerializedView,
+    Cn: Connection,
+    Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
+{
+    fn from(view: View<'a, Cn, V, Key>) -> Self {
+        Self {
+            connection: view.connection,
+            query: serialized_view_query::<V, Key>(
+                view.connection.schematic(),
+                view.key,
+                view.access_policy,
+            ),
+            limit: None,
+            _collection: PhantomData,
+        }
+    }
+}
+
+/// Combines the documents matched by several view queries of the same
+/// collection. Each document is returned at most once, in order of its id.
+///
+/// This type is returned from [`AsyncView::intersect()`] and
+/// [`AsyncView::union()`]. Each view is queried using its own
+/// [`AccessPolicy`].
+///
+/// ```rust
+/// # bonsaidb_core::__doctest_prelude!();
+/// # use bonsaidb_core::connection::AsyncConnection;
+/// # fn test_fn<C: AsyncConnection>(db: C) -> Result<(), Error> {
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// for document in ScoresByRank::entries_async(&db)
+///     .with_key_range(42..=44)
+///     .intersect(MyCollectionByName::entries_async(&db).with_key("Alice"))
+///     .query_with_collection_docs()
+///     .await?
+/// {
+///     println!("Alice has a rank of {}", document.contents.rank);
+/// }
+/// # Ok(())
+/// # })
+/// # }
+/// ```
+#[must_use]
+pub struct AsyncViewSet<'a, Cn, C> {
+    connection: &'a Cn,
+    query: Result<ViewSetQuery, Error>,
+    limit: Option<u32>,
+    _collection: PhantomData<C>,
+}
+
+impl<'a, Cn, C> AsyncViewSet<'a, Cn, C>
+where
+    Cn: AsyncConnection,
+    C: schema::Collection,
+{
+    /// Restricts the results to documents also matched by `other`.
+    pub fn intersect(self, other: impl Into<Self>) -> Self {
+        self.combine(other.into(), ViewSetQuery::intersect)
+    }
+
+    /// Extends the results with the documents matched by `other`.
+    pub fn union(self, other: impl Into<Self>) -> Self {
+        self.combine(other.into(), ViewSetQuery::union)
+    }
+
+    fn combine(
+        self,
+        other: Self,
+        combine: impl FnOnce(ViewSetQuery, ViewSetQuery) -> ViewSetQuery,
+    ) -> Self {
+        let query = match (self.query, other.query) {
+            (Ok(query), Ok(other)) => Ok(combine(query, other)),
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        };
+        Self { query, ..self }
+    }
+
+    /// Limits the results to the `limit` documents with the lowest ids.
+    pub const fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Executes the query and returns the matched documents.
+    pub async fn query(self) -> Result<Vec<OwnedDocument>, Error> {
+        self.connection
+            .query_view_set(self.query?, self.limit)
+            .await
+    }
+
+    /// Executes the query and returns the matched documents deserialized as
+    /// [`CollectionDocument`]s.
+    pub async fn query_with_collection_docs(self) -> Result<Vec<CollectionDocument<C>>, Error>
+    where
+        C: SerializedCollection,
+    {
+        self.query()
+            .await?
+            .iter()
+            .map(CollectionDocument::try_from)
+            .collect()
+    }
+}
+
+impl<'a, Cn, V, Key> From<AsyncView<'a, Cn, V, Key>> for AsyncViewSet<'a, Cn, V::Collection>
+where
+    V::Key: Borrow<Key> + PartialEq<Key>,
+    V: SerializedView,
+    Cn: AsyncConnection,
+    Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
+{
+    fn from(view: AsyncView<'a, Cn, V, Key>) -> Self {
+        Self {
+            connection: view.connection,
+            query: serialized_view_query::<V, Key>(
+                view.connection.schematic(),
+                view.key,
+                view.access_policy,
+            ),
+            limit: None,
+            _collection: PhantomData,
+        }
+    }
+}
+
+fn serialized_view_query<V, Key>(
+    schematic: &schema::Schematic,
+    key: Option<QueryKey<'_, V::Key, Key>>,
+    access_policy: AccessPolicy,
+) -> Result<ViewSetQuery, Error>
+where
+    V::Key: Borrow<Key> + PartialEq<Key>,
+    V: SerializedView,
+    Key: for<'k> KeyEncoding<'k, V::Key> + PartialEq + ?Sized,
+{
+    let view = schematic.view::<V>()?;
+    Ok(ViewSetQuery::View(SerializedViewQuery {
+        view: view.view_name(),
+        key: key.map(|key| key.serialized()).transpose()?,
+        access_policy,
+    }))
+}
@@ -0,0 +1,245 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use futures::Stream;
+
+use crate::connection::{AsyncConnection, Connection};
+use crate::pubsub::{AsyncPubSub, AsyncSubscriber, PubSub, Receiver, Subscriber};
+use crate::schema::{self, CollectionName};
+use crate::transaction::{CollectionChanges, Executed};
+use crate::Error;
+
+/// A live feed of the changes made to a collection. Changes are delivered
+/// after the transaction that made them has been committed.
+///
+/// The watcher subscribes to changes as soon as it is created. By default,
+/// only transactions committed after the watcher was created are delivered. To
+/// resume watching from a known transaction, use
+/// [`resume_after()`](Self::resume_after).
+///
+/// This type is returned from
+/// [`Connection::watch_collection()`](crate::connection::Connection::watch_collection).
+#[must_use]
+pub struct CollectionWatcher<'a, Cn, Cl>
+where
+    Cn: PubSub,
+{
+    connection: &'a Cn,
+    subscriber: Cn::Subscriber,
+    replay: Replay,
+    _collection: PhantomData<Cl>,
+}
+
+impl<'a, Cn, Cl> CollectionWatcher<'a, Cn, Cl>
+where
+    Cn: Connection + PubSub,
+    Cl: schema::Collection,
+{
+    pub(crate) fn new(connection: &'a Cn) -> Result<Self, Error> {
+        let collection = Cl::collection_name();
+        let subscriber = connection.create_subscriber()?;
+        subscriber.subscribe_to_collection_changes(&collection)?;
+        Ok(Self {
+            connection,
+            subscriber,
+            replay: Replay::new(collection),
+            _collection: PhantomData,
+        })
+    }
+
+    /// Delivers all changes made by transactions committed after
+    /// `transaction_id` before delivering live changes. Each transaction is
+    /// delivered at most once.
+    pub fn resume_after(mut self, transaction_id: u64) -> Self {
+        self.replay.resume_after(transaction_id);
+        self
+    }
+
+    /// Returns the next set of changes, blocking the current thread until one
+    /// is available. Returns `Ok(None)` if the underlying subscriber has been
+    /// disconnected.
+    pub fn next_changes(&mut self) -> Result<Option<CollectionChanges>, Error> {
+        loop {
+            if let Some(changes) = self.replay.pending.pop_front() {
+                return Ok(Some(changes));
+            } else if let Some(starting_id) = self.replay.next_id {
+                let executed = self
+                    .connection
+                    .list_executed_transactions(Some(starting_id), None)?;
+                self.replay.fill(executed);
+            } else if let Ok(message) = self.subscriber.receiver().receive() {
+                let changes = message.payload::<CollectionChanges>()?;
+                if !self.replay.was_replayed(&changes) {
+                    return Ok(Some(changes));
+                }
+            } else {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Returns the [`Receiver`] of the underlying subscriber. The payloads
+    /// received are pot-encoded [`CollectionChanges`]. Receiving messages
+    /// directly bypasses replaying any changes requested by
+    /// [`resume_after()`](Self::resume_after).
+    pub fn receiver(&self) -> &Receiver {
+        self.subscriber.receiver()
+    }
+}
+
+impl<'a, Cn, Cl> Iterator for CollectionWatcher<'a, Cn, Cl>
+where
+    Cn: Connection + PubSub,
+    Cl: schema::Collection,
+{
+    type Item = Result<CollectionChanges, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_changes().transpose()
+    }
+}
+
+/// A live feed of the changes made to a collection. Changes are delivered
+/// after the transaction that made them has been committed.
+///
+/// The watcher subscribes to changes as soon as it is created. By default,
+/// only transactions committed after the watcher was created are delivered. To
+/// resume watching from a known transaction, use
+/// [`resume_after()`](Self::resume_after).
+///
+/// This type is returned from
+/// [`AsyncConnection::watch_collection()`](crate::connection::AsyncConnection::watch_collection).
+#[must_use]
+pub struct AsyncCollectionWatcher<'a, Cn, Cl>
+where
+    Cn: AsyncPubSub,
+{
+    connection: &'a Cn,
+    subscriber: Cn::Subscriber,
+    replay: Replay,
+    _collection: PhantomData<Cl>,
+}
+
+impl<'a, Cn, Cl> AsyncCollectionWatcher<'a, Cn, Cl>
+where
+    Cn: AsyncConnection + AsyncPubSub,
+    Cl: schema::Collection,
+{
+    pub(crate) async fn new(connection: &'a Cn) -> Result<Self, Error> {
+        let collection = Cl::collection_name();
+        let subscriber = connection.create_subscriber().await?;
+        subscriber
+            .subscribe_to_collection_changes(&collection)
+            .await?;
+        Ok(Self {
+            connection,
+            subscriber,
+            replay: Replay::new(collection),
+            _collection: PhantomData,
+        })
+    }
+
+    /// Delivers all changes made by transactions committed after
+    /// `transaction_id` before delivering live changes. Each transaction is
+    /// delivered at most once.
+    pub fn resume_after(mut self, transaction_id: u64) -> Self {
+        self.replay.resume_after(transaction_id);
+        self
+    }
+
+    /// Returns the next set of changes, waiting until one is available.
+    /// Returns `Ok(None)` if the underlying subscriber has been disconnected.
+    pub async fn next_changes(&mut self) -> Result<Option<CollectionChanges>, Error> {
+        loop {
+            if let Some(changes) = self.replay.pending.pop_front() {
+                return Ok(Some(changes));
+            } else if let Some(starting_id) = self.replay.next_id {
+                let executed = self
+                    .connection
+                    .list_executed_transactions(Some(starting_id), None)
+                    .await?;
+                self.replay.fill(executed);
+            } else if let Ok(message) = self.subscriber.receiver().receive_async().await {
+                let changes = message.payload::<CollectionChanges>()?;
+                if !self.replay.was_replayed(&changes) {
+                    return Ok(Some(changes));
+                }
+            } else {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Returns the [`Receiver`] of the underlying subscriber. The payloads
+    /// received are pot-encoded [`CollectionChanges`]. Receiving messages
+    /// directly bypasses replaying any changes requested by
+    /// [`resume_after()`](Self::resume_after).
+    pub fn receiver(&self) -> &Receiver {
+        self.subscriber.receiver()
+    }
+
+    /// Converts this watcher into a [`Stream`] of changes. The stream ends
+    /// when the underlying subscriber is disconnected.
+    pub fn into_stream(self) -> impl Stream<Item = Result<CollectionChanges, Error>> + Send + 'a
+    where
+        Cl: 'a,
+    {
+        futures::stream::unfold(Some(self), |watcher| async move {
+            let mut watcher = watcher?;
+            match watcher.next_changes().await {
+                Ok(Some(changes)) => Some((Ok(changes), Some(watcher))),
+                Ok(None) => None,
+                // Errors end the stream after being yielded.
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+}
+
+/// Tracks the historical transactions that still need to be delivered to a
+/// watcher before live changes are.
+struct Replay {
+    collection: CollectionName,
+    next_id: Option<u64>,
+    last_replayed_id: Option<u64>,
+    pending: VecDeque<CollectionChanges>,
+}
+
+impl Replay {
+    const fn new(collection: CollectionName) -> Self {
+        Self {
+            collection,
+            next_id: None,
+            last_replayed_id: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn resume_after(&mut self, transaction_id: u64) {
+        self.next_id = transaction_id.checked_add(1);
+        self.last_replayed_id = Some(transaction_id);
+    }
+
+    fn fill(&mut self, executed: Vec<Executed>) {
+        let Some(last) = executed.last() else {
+            // Once the log has been exhausted, live changes take over.
+            self.next_id = None;
+            return;
+        };
+        self.next_id = last.id.checked_add(1);
+        self.last_replayed_id = Some(last.id);
+        self.pending.extend(
+            executed
+                .iter()
+                .filter_map(|executed| executed.collection_changes(&self.collection)),
+        );
+    }
+
+    /// Returns true if `changes` has already been delivered while replaying
+    /// the transaction log.
+    fn was_replayed(&self, changes: &CollectionChanges) -> bool {
+        self.last_replayed_id.map_or(false, |last_replayed| {
+            changes.transaction_id <= last_replayed
+        })
+    }
+}
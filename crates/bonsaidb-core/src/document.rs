@@ -32,12 +32,16 @@ use crate::key::KeyEncoding;
 use crate::schema::{Collection, SerializedCollection};
 
 mod collection;
+mod filter;
 mod header;
 mod id;
+mod projection;
 mod revision;
 pub use self::collection::{CollectionDocument, OwnedDocuments};
+pub use self::filter::{Comparison, Filter, FilterField, FilterValue};
 pub use self::header::{AnyHeader, CollectionHeader, Emit, HasHeader, Header};
 pub use self::id::{DocumentId, InvalidHexadecimal};
+pub use self::projection::{ProjectedDocument, Projection};
 pub use self::revision::Revision;
 /// Contains a serialized document in the database.
 #[derive(Clone, Debug, Serialize, Deserialize)]
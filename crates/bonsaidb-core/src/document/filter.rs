@@ -0,0 +1,647 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Error;
+
+/// A predicate evaluated against the contents of documents before they are
+/// returned from a query.
+///
+/// Filters are serializable, which allows them to be evaluated by the database
+/// that stores the documents. When querying a remote database, only the
+/// documents that match the filter are sent to the client.
+///
+/// Fields are located using dot-separated paths. Each segment of a path looks
+/// up a key in a map or struct, or an index in a sequence. An empty path refers
+/// to the document's contents as a whole. Comparisons against a field that
+/// doesn't exist never match.
+///
+/// ```rust
+/// use bonsaidb_core::document::Filter;
+///
+/// let filter = Filter::field("rank")
+///     .greater_than_or_equal(42)
+///     .and(Filter::field("name").equals("Alice").or(Filter::field("name").equals("Bob")))
+///     .and(!Filter::field("retired").equals(true));
+/// ```
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum Filter {
+    /// Matches when the value at `field` compares to `value` using
+    /// `comparison`.
+    Compare {
+        /// The path to the field being compared.
+        field: String,
+        /// The comparison to perform.
+        comparison: Comparison,
+        /// The value to compare against.
+        value: FilterValue,
+    },
+    /// Matches when a value is present at the contained path.
+    Exists(String),
+    /// Matches when every contained filter matches.
+    And(Vec<Filter>),
+    /// Matches when any contained filter matches.
+    Or(Vec<Filter>),
+    /// Matches when the contained filter does not match.
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// Returns a builder for a filter on the value at `path`.
+    pub fn field(path: impl Into<String>) -> FilterField {
+        FilterField { path: path.into() }
+    }
+
+    /// Returns a filter that matches when both `self` and `other` match.
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            Self::And(mut filters) => {
+                filters.push(other);
+                Self::And(filters)
+            }
+            filter => Self::And(vec![filter, other]),
+        }
+    }
+
+    /// Returns a filter that matches when either `self` or `other` match.
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            Self::Or(mut filters) => {
+                filters.push(other);
+                Self::Or(filters)
+            }
+            filter => Self::Or(vec![filter, other]),
+        }
+    }
+
+    /// Returns true if `value` matches this filter.
+    #[must_use]
+    pub fn matches(&self, value: &FilterValue) -> bool {
+        match self {
+            Self::Compare {
+                field,
+                comparison,
+                value: expected,
+            } => value
+                .field(field)
+                .map_or(false, |actual| comparison.compare(actual, expected)),
+            Self::Exists(field) => value.field(field).is_some(),
+            Self::And(filters) => filters.iter().all(|filter| filter.matches(value)),
+            Self::Or(filters) => filters.iter().any(|filter| filter.matches(value)),
+            Self::Not(filter) => !filter.matches(value),
+        }
+    }
+
+    /// Decodes `contents` and returns true if they match this filter.
+    ///
+    /// Contents serialized using [Pot](https://github.com/khonsulabs/pot) or
+    /// JSON are supported.
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::Other`]: `contents` could not be decoded.
+    pub fn matches_contents(&self, contents: &[u8]) -> Result<bool, Error> {
+        FilterValue::decode(contents).map(|value| self.matches(&value))
+    }
+}
+
+impl std::ops::Not for Filter {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Not(filter) => *filter,
+            filter => Self::Not(Box::new(filter)),
+        }
+    }
+}
+
+/// Builds a [`Filter`] for a single field. Returned from [`Filter::field()`].
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct FilterField {
+    path: String,
+}
+
+impl FilterField {
+    /// Matches when the field is equal to `value`.
+    pub fn equals(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::Equal, value)
+    }
+
+    /// Matches when the field is present and not equal to `value`.
+    pub fn not_equals(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::NotEqual, value)
+    }
+
+    /// Matches when the field is less than `value`.
+    pub fn less_than(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::LessThan, value)
+    }
+
+    /// Matches when the field is less than or equal to `value`.
+    pub fn less_than_or_equal(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::LessThanOrEqual, value)
+    }
+
+    /// Matches when the field is greater than `value`.
+    pub fn greater_than(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::GreaterThan, value)
+    }
+
+    /// Matches when the field is greater than or equal to `value`.
+    pub fn greater_than_or_equal(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::GreaterThanOrEqual, value)
+    }
+
+    /// Matches when the field contains `value`. See [`Comparison::Contains`].
+    pub fn contains(self, value: impl Into<FilterValue>) -> Filter {
+        self.compare(Comparison::Contains, value)
+    }
+
+    /// Matches when the field is present.
+    pub fn exists(self) -> Filter {
+        Filter::Exists(self.path)
+    }
+
+    /// Matches when the field compares to `value` using `comparison`.
+    pub fn compare(self, comparison: Comparison, value: impl Into<FilterValue>) -> Filter {
+        Filter::Compare {
+            field: self.path,
+            comparison,
+            value: value.into(),
+        }
+    }
+}
+
+/// A comparison between a field and a value in a [`Filter`].
+///
+/// Integers and floats are compared numerically with each other. Strings,
+/// bytes, and booleans are ordered only against values of the same type. An
+/// ordering comparison between any other values does not match.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    /// The field is equal to the value.
+    Equal,
+    /// The field is not equal to the value.
+    NotEqual,
+    /// The field is less than the value.
+    LessThan,
+    /// The field is less than or equal to the value.
+    LessThanOrEqual,
+    /// The field is greater than the value.
+    GreaterThan,
+    /// The field is greater than or equal to the value.
+    GreaterThanOrEqual,
+    /// The field is a string containing the value as a substring, a sequence
+    /// containing an element equal to the value, or a map containing the value
+    /// as a key.
+    Contains,
+}
+
+impl Comparison {
+    fn compare(self, actual: &FilterValue, expected: &FilterValue) -> bool {
+        match self {
+            Self::Equal => actual.value_eq(expected),
+            Self::NotEqual => !actual.value_eq(expected),
+            Self::LessThan => actual.value_cmp(expected) == Some(Ordering::Less),
+            Self::LessThanOrEqual => matches!(
+                actual.value_cmp(expected),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            Self::GreaterThan => actual.value_cmp(expected) == Some(Ordering::Greater),
+            Self::GreaterThanOrEqual => matches!(
+                actual.value_cmp(expected),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Self::Contains => match (actual, expected) {
+                (FilterValue::String(actual), FilterValue::String(expected)) => {
+                    actual.contains(expected.as_str())
+                }
+                (FilterValue::Sequence(values), expected) => {
+                    values.iter().any(|value| value.value_eq(expected))
+                }
+                (FilterValue::Map(entries), expected) => {
+                    entries.iter().any(|(key, _)| key.value_eq(expected))
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+/// A self-describing value used in [`Filter`]s. Document contents are decoded
+/// into this type when evaluating a filter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterValue {
+    /// No value.
+    None,
+    /// A boolean.
+    Bool(bool),
+    /// An integer.
+    Integer(i128),
+    /// A floating point number.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// A sequence of bytes.
+    Bytes(Vec<u8>),
+    /// A sequence of values.
+    Sequence(Vec<FilterValue>),
+    /// A list of key-value pairs. Structs are decoded as maps keyed by their
+    /// field names.
+    Map(Vec<(FilterValue, FilterValue)>),
+}
+
+impl FilterValue {
+    /// Converts `value` into a `FilterValue` by serializing it.
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::Other`]: `value` could not be serialized.
+    pub fn from_serialize<T: Serialize>(value: &T) -> Result<Self, Error> {
+        let bytes = pot::to_vec(value).map_err(|err| Error::other("filter", err))?;
+        pot::from_slice(&bytes).map_err(|err| Error::other("filter", err))
+    }
+
+    /// Decodes serialized document contents. Contents serialized using
+    /// [Pot](https://github.com/khonsulabs/pot) or JSON are supported.
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::Other`]: `contents` could not be decoded.
+    pub fn decode(contents: &[u8]) -> Result<Self, Error> {
+        pot::from_slice(contents).or_else(|pot_err| {
+            serde_json::from_slice(contents).map_err(|_| Error::other("filter", pot_err))
+        })
+    }
+
+    /// Returns the value at the dot-separated `path`, if present. An empty
+    /// path returns `self`.
+    #[must_use]
+    pub fn field(&self, path: &str) -> Option<&Self> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        path.split('.')
+            .try_fold(self, |value, segment| match value {
+                Self::Map(entries) => entries.iter().find_map(|(key, value)| match key {
+                    Self::String(key) if key == segment => Some(value),
+                    _ => None,
+                }),
+                Self::Sequence(values) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| values.get(index)),
+                _ => None,
+            })
+    }
+
+    fn value_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Sequence(a), Self::Sequence(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.value_eq(b))
+            }
+            (Self::Map(a), Self::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((ak, av), (bk, bv))| ak.value_eq(bk) && av.value_eq(bv))
+            }
+            (Self::None, Self::None) => true,
+            _ => self.value_cmp(other) == Some(Ordering::Equal),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn value_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Bool(a), Self::Bool(b)) => Some(a.cmp(b)),
+            (Self::Integer(a), Self::Integer(b)) => Some(a.cmp(b)),
+            (Self::Integer(a), Self::Float(b)) => (*a as f64).partial_cmp(b),
+            (Self::Float(a), Self::Integer(b)) => a.partial_cmp(&(*b as f64)),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::String(a), Self::String(b)) => Some(a.cmp(b)),
+            (Self::Bytes(a), Self::Bytes(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for FilterValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::None => serializer.serialize_none(),
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::Integer(value) => {
+                if let Ok(value) = i64::try_from(*value) {
+                    serializer.serialize_i64(value)
+                } else {
+                    serializer.serialize_i128(*value)
+                }
+            }
+            Self::Float(value) => serializer.serialize_f64(*value),
+            Self::String(value) => serializer.serialize_str(value),
+            Self::Bytes(value) => serializer.serialize_bytes(value),
+            Self::Sequence(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Self::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FilterValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(FilterValueVisitor)
+    }
+}
+
+struct FilterValueVisitor;
+
+impl<'de> Visitor<'de> for FilterValueVisitor {
+    type Value = FilterValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(FilterValue::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(FilterValue::Integer(i128::from(v)))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(FilterValue::Integer(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(FilterValue::Integer(i128::from(v)))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(i128::try_from(v).map_or(FilterValue::Float(v as f64), FilterValue::Integer))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(FilterValue::Float(v))
+    }
+
+    fn visit_char<E: de::Error>(self, v: char) -> Result<Self::Value, E> {
+        Ok(FilterValue::String(v.to_string()))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(FilterValue::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(FilterValue::String(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(FilterValue::Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(FilterValue::Bytes(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(FilterValue::None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        FilterValue::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(FilterValue::None)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        FilterValue::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(FilterValue::Sequence(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(FilterValue::Map(entries))
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($type:ty),+) => {
+        $(
+            impl From<$type> for FilterValue {
+                fn from(value: $type) -> Self {
+                    Self::Integer(i128::from(value))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<f32> for FilterValue {
+    fn from(value: f32) -> Self {
+        Self::Float(f64::from(value))
+    }
+}
+
+impl From<f64> for FilterValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<FilterValue>> for FilterValue {
+    fn from(values: Vec<FilterValue>) -> Self {
+        Self::Sequence(values)
+    }
+}
+
+impl<T> From<Option<T>> for FilterValue
+where
+    T: Into<FilterValue>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::None, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{Filter, FilterValue};
+
+    #[derive(Serialize, Deserialize)]
+    struct Player {
+        name: String,
+        rank: u32,
+        score: f32,
+        tags: Vec<String>,
+        team: Option<Team>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Team {
+        name: String,
+    }
+
+    fn alice() -> Player {
+        Player {
+            name: String::from("Alice"),
+            rank: 42,
+            score: 1.5,
+            tags: vec![String::from("veteran"), String::from("captain")],
+            team: Some(Team {
+                name: String::from("Ferris"),
+            }),
+        }
+    }
+
+    #[test]
+    fn comparisons() {
+        let contents = pot::to_vec(&alice()).unwrap();
+        let matches = |filter: Filter| filter.matches_contents(&contents).unwrap();
+
+        assert!(matches(Filter::field("name").equals("Alice")));
+        assert!(!matches(Filter::field("name").equals("Bob")));
+        assert!(matches(Filter::field("name").not_equals("Bob")));
+        assert!(matches(Filter::field("name").contains("lic")));
+        assert!(matches(Filter::field("rank").equals(42)));
+        assert!(matches(Filter::field("rank").equals(42.)));
+        assert!(matches(Filter::field("rank").greater_than(41.5)));
+        assert!(matches(Filter::field("rank").less_than_or_equal(42)));
+        assert!(!matches(Filter::field("rank").less_than(42)));
+        assert!(matches(Filter::field("score").greater_than(1)));
+        assert!(matches(Filter::field("tags").contains("captain")));
+        assert!(matches(Filter::field("tags.1").equals("captain")));
+        assert!(matches(Filter::field("team.name").equals("Ferris")));
+        // Ordering strings against numbers never matches.
+        assert!(!matches(Filter::field("name").greater_than(1)));
+    }
+
+    #[test]
+    fn missing_fields() {
+        let contents = pot::to_vec(&alice()).unwrap();
+        let matches = |filter: Filter| filter.matches_contents(&contents).unwrap();
+
+        assert!(matches(Filter::field("team").exists()));
+        assert!(!matches(Filter::field("coach").exists()));
+        assert!(!matches(Filter::field("coach").equals("Bob")));
+        assert!(!matches(Filter::field("coach").not_equals("Bob")));
+        assert!(!matches(Filter::field("tags.2").exists()));
+        assert!(matches(!Filter::field("coach").exists()));
+    }
+
+    #[test]
+    fn combinators() {
+        let contents = pot::to_vec(&alice()).unwrap();
+        let matches = |filter: Filter| filter.matches_contents(&contents).unwrap();
+
+        assert!(matches(
+            Filter::field("rank")
+                .equals(42)
+                .and(Filter::field("name").equals("Alice"))
+        ));
+        assert!(!matches(
+            Filter::field("rank")
+                .equals(42)
+                .and(Filter::field("name").equals("Bob"))
+        ));
+        assert!(matches(
+            Filter::field("rank")
+                .equals(1)
+                .or(Filter::field("name").equals("Alice"))
+        ));
+        assert!(!matches(!Filter::field("rank").equals(42)));
+        assert_eq!(
+            !!Filter::field("rank").equals(42),
+            Filter::field("rank").equals(42)
+        );
+    }
+
+    #[test]
+    fn json() {
+        let contents = br#"{"name":"Alice","rank":42,"tags":["captain"]}"#;
+        let filter = Filter::field("rank")
+            .equals(42)
+            .and(Filter::field("tags.0").equals("captain"));
+        assert!(filter.matches_contents(contents).unwrap());
+        assert!(Filter::field("rank")
+            .equals(1)
+            .matches_contents(b"not contents")
+            .is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let filter = Filter::field("rank")
+            .equals(42)
+            .and(Filter::field("name").equals(FilterValue::from_serialize(&"Alice").unwrap()))
+            .or(!Filter::field("tags").contains(Some(1.5)));
+        let bytes = pot::to_vec(&filter).unwrap();
+        assert_eq!(pot::from_slice::<Filter>(&bytes).unwrap(), filter);
+    }
+}
@@ -0,0 +1,294 @@
+use arc_bytes::serde::Bytes;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::document::{FilterValue, HasHeader, Header};
+use crate::Error;
+
+/// A selection of fields to return from the contents of documents, rather
+/// than their entire contents.
+///
+/// Projections are evaluated by the database that stores the documents. When
+/// querying a remote database, only the selected fields are sent to the
+/// client.
+///
+/// Fields are located using the same dot-separated paths as
+/// [`Filter`](super::Filter). The projected contents are a
+/// [Pot](https://github.com/khonsulabs/pot)-encoded map containing each
+/// selected field that is present, nested under the segments of its path.
+/// Fields that aren't present are omitted. An empty path selects the entire
+/// contents.
+///
+/// Projections can only select fields from contents serialized using Pot or
+/// JSON. Queries of a [`SerializedCollection`](crate::schema::SerializedCollection)
+/// whose [`supports_projections()`](crate::schema::SerializedCollection::supports_projections)
+/// returns false are rejected before they are executed.
+///
+/// Projected documents are returned as [`ProjectedDocument`]s, which can't be
+/// written back to the database.
+///
+/// ```rust
+/// use bonsaidb_core::document::Projection;
+///
+/// let projection = Projection::new(["name", "address.city"]);
+/// ```
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct Projection {
+    fields: Vec<String>,
+}
+
+impl Projection {
+    /// Returns a projection that selects `fields`.
+    pub fn new<Fields, Field>(fields: Fields) -> Self
+    where
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        Self {
+            fields: fields.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the paths of the selected fields.
+    #[must_use]
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    /// Returns a map containing the selected fields of `value`.
+    #[must_use]
+    pub fn project(&self, value: &FilterValue) -> FilterValue {
+        let mut projected = FilterValue::Map(Vec::new());
+        for path in &self.fields {
+            if path.is_empty() {
+                return value.clone();
+            }
+
+            if let Some(field) = value.field(path) {
+                insert(&mut projected, path, field.clone());
+            }
+        }
+        projected
+    }
+
+    /// Decodes `contents` and returns the selected fields, encoded using Pot.
+    ///
+    /// Contents serialized using [Pot](https://github.com/khonsulabs/pot) or
+    /// JSON are supported.
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::Other`]: `contents` could not be decoded.
+    pub fn project_contents(&self, contents: &[u8]) -> Result<Vec<u8>, Error> {
+        let value = FilterValue::decode(contents)?;
+        pot::to_vec(&self.project(&value)).map_err(|err| Error::other("projection", err))
+    }
+
+    /// Returns the selected fields of the document identified by `header`,
+    /// whose contents are `contents`.
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::Other`]: `contents` could not be decoded.
+    pub fn project_document(
+        &self,
+        header: Header,
+        contents: &[u8],
+    ) -> Result<ProjectedDocument, Error> {
+        Ok(ProjectedDocument {
+            header,
+            fields: Bytes::from(self.project_contents(contents)?),
+        })
+    }
+}
+
+/// The fields of a document selected by a [`Projection`].
+///
+/// Unlike [`OwnedDocument`](super::OwnedDocument), a projected document can't
+/// be converted into a [`CollectionDocument`](super::CollectionDocument) or
+/// used to update the document it was selected from. Its fields are
+/// incomplete, and writing them back would replace the fields that weren't
+/// selected.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ProjectedDocument {
+    /// The header of the document the fields were selected from.
+    pub header: Header,
+    /// The selected fields, encoded using Pot as a map.
+    pub fields: Bytes,
+}
+
+impl ProjectedDocument {
+    /// Deserializes the selected fields as `T`. Fields that weren't selected
+    /// or aren't present are missing, so `T` should mark them with
+    /// `#[serde(default)]`.
+    ///
+    /// ## Errors
+    ///
+    /// * [`Error::Other`]: the fields could not be deserialized as `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        pot::from_slice(&self.fields).map_err(|err| Error::other("projection", err))
+    }
+}
+
+impl HasHeader for ProjectedDocument {
+    fn header(&self) -> Result<Header, Error> {
+        self.header.header()
+    }
+}
+
+fn insert(target: &mut FilterValue, path: &str, value: FilterValue) {
+    let (segment, remaining) = match path.split_once('.') {
+        Some((segment, remaining)) => (segment, Some(remaining)),
+        None => (path, None),
+    };
+
+    let FilterValue::Map(entries) = target else {
+        // A previously selected field contains this one.
+        return;
+    };
+    let index = if let Some(index) = entries
+        .iter()
+        .position(|(key, _)| matches!(key, FilterValue::String(key) if key == segment))
+    {
+        index
+    } else {
+        entries.push((
+            FilterValue::String(segment.to_string()),
+            FilterValue::Map(Vec::new()),
+        ));
+        entries.len() - 1
+    };
+
+    match remaining {
+        Some(remaining) => insert(&mut entries[index].1, remaining, value),
+        None => entries[index].1 = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Address {
+        city: String,
+        country: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Person {
+        name: String,
+        email: String,
+        address: Address,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct PartialPerson {
+        name: String,
+        #[serde(default)]
+        email: Option<String>,
+        address: PartialAddress,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct PartialAddress {
+        city: String,
+    }
+
+    fn person() -> Person {
+        Person {
+            name: String::from("Alice"),
+            email: String::from("alice@example.com"),
+            address: Address {
+                city: String::from("Portland"),
+                country: String::from("USA"),
+            },
+        }
+    }
+
+    #[test]
+    fn nested_fields() {
+        let contents = pot::to_vec(&person()).unwrap();
+        let projected = Projection::new(["name", "address.city", "missing"])
+            .project_contents(&contents)
+            .unwrap();
+        assert!(projected.len() < contents.len());
+        assert_eq!(
+            pot::from_slice::<PartialPerson>(&projected).unwrap(),
+            PartialPerson {
+                name: String::from("Alice"),
+                email: None,
+                address: PartialAddress {
+                    city: String::from("Portland"),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn overlapping_fields() {
+        let value = FilterValue::from_serialize(&person()).unwrap();
+        let whole_address = FilterValue::from_serialize(&person().address).unwrap();
+        for fields in [["address", "address.city"], ["address.city", "address"]] {
+            let projected = Projection::new(fields).project(&value);
+            assert_eq!(projected.field("address"), Some(&whole_address));
+        }
+
+        assert_eq!(Projection::new(["name", ""]).project(&value), value);
+    }
+
+    #[test]
+    fn documents() {
+        let header = Header {
+            id: crate::document::DocumentId::from_u64(1),
+            revision: crate::document::Revision::new(b"person"),
+        };
+        let contents = pot::to_vec(&person()).unwrap();
+        let projected = Projection::new(["name", "address.city"])
+            .project_document(header.clone(), &contents)
+            .unwrap();
+        assert_eq!(projected.header, header);
+        assert_eq!(
+            projected.deserialize::<PartialPerson>().unwrap(),
+            PartialPerson {
+                name: String::from("Alice"),
+                email: None,
+                address: PartialAddress {
+                    city: String::from("Portland"),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn json() {
+        let contents = serde_json::to_vec(&person()).unwrap();
+        let projected = Projection::new(["email"])
+            .project_contents(&contents)
+            .unwrap();
+        let value = pot::from_slice::<FilterValue>(&projected).unwrap();
+        assert_eq!(
+            value,
+            FilterValue::Map(vec![(
+                FilterValue::from("email"),
+                FilterValue::from("alice@example.com")
+            )])
+        );
+    }
+
+    #[test]
+    fn supported_formats() {
+        use crate::schema::{Collection, SerializedCollection};
+
+        #[derive(Collection, Serialize, Deserialize, Debug)]
+        #[collection(name = "pot", core = crate)]
+        struct PotPerson;
+
+        #[derive(Collection, Serialize, Deserialize, Debug)]
+        #[collection(name = "bincode", serialization = transmog_bincode::Bincode, core = crate)]
+        struct BincodePerson;
+
+        assert!(PotPerson::supports_projections());
+        assert!(!BincodePerson::supports_projections());
+    }
+}
@@ -1,28 +1,49 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::Duration;
+
+use actionable::ResourceName;
 use arc_bytes::serde::Bytes;
+pub use bonsaidb_macros::KeyValueNamespace;
 use serde::{Deserialize, Serialize};
 
 mod timestamp;
 
 pub use self::timestamp::Timestamp;
+use crate::connection::Range;
+use crate::key::{ByteSource, Key};
+use crate::permissions::bonsai::keyvalue_namespace_resource_name;
 use crate::Error;
 
 mod implementation {
+    use std::time::Duration;
+
     use arc_bytes::serde::Bytes;
     use async_trait::async_trait;
     use futures::future::BoxFuture;
     use serde::Serialize;
 
-    use crate::keyvalue::{Command, KeyCheck, KeyOperation, KeyStatus, Output, Timestamp};
+    use crate::keyvalue::{
+        Command, KeyCheck, KeyOperation, KeyStatus, KeyValueNamespace, Output, RateLimit,
+        RateLimitStatus, Timestamp,
+    };
     use crate::Error;
 
+    /// Types for executing batches of operations.
+    pub mod batch;
     /// Types for executing get operations.
     pub mod get;
     /// Types for executing increment/decrement operations.
     pub mod increment;
     /// Types for handling key namespaces.
     pub mod namespaced;
+    /// Types for executing scan operations.
+    pub mod scan;
     /// Types for executing set operations.
     pub mod set;
+    /// Types for operating on lists, sets, and hashes stored in keys.
+    pub mod structures;
+    /// Types for accessing [`KeyValueNamespace`](crate::keyvalue::KeyValueNamespace)s.
+    pub mod typed;
 
     use namespaced::Namespaced;
 
@@ -160,10 +181,93 @@ mod implementation {
                 command: Command::Delete,
             })? {
                 Output::Status(status) => Ok(status),
-                Output::Value(_) => unreachable!("invalid output from delete operation"),
+                Output::Value(_)
+                | Output::Entry(_)
+                | Output::Keys(_)
+                | Output::Batch(_)
+                | Output::Contains(_)
+                | Output::RateLimit(_) => {
+                    unreachable!("invalid output from delete operation")
+                }
             }
         }
 
+        /// Sets `key` to expire after `duration` from now without changing its
+        /// value. Returns [`KeyStatus::Updated`] if the key exists, otherwise
+        /// [`KeyStatus::NotChanged`].
+        fn expire_key<S: Into<String> + Send>(
+            &'_ self,
+            key: S,
+            duration: Duration,
+        ) -> Result<KeyStatus, Error> {
+            self.execute_key_operation(expire_operation(
+                self.key_namespace(),
+                key.into(),
+                Some(Timestamp::now() + duration),
+            ))
+            .map(expire_status)
+        }
+
+        /// Removes the expiration from `key` without changing its value.
+        /// Returns [`KeyStatus::Updated`] if the key exists, otherwise
+        /// [`KeyStatus::NotChanged`].
+        fn persist_key<S: Into<String> + Send>(&'_ self, key: S) -> Result<KeyStatus, Error> {
+            self.execute_key_operation(expire_operation(self.key_namespace(), key.into(), None))
+                .map(expire_status)
+        }
+
+        /// Atomically consumes [`RateLimit::cost`] tokens from the token bucket
+        /// stored in `key`. The returned status indicates whether the request
+        /// is allowed, and if not, how long until it would be.
+        fn rate_limit<S: Into<String> + Send>(
+            &'_ self,
+            key: S,
+            limit: RateLimit,
+        ) -> Result<RateLimitStatus, Error> {
+            self.execute_key_operation(KeyOperation {
+                namespace: self.key_namespace().map(ToOwned::to_owned),
+                key: key.into(),
+                command: Command::RateLimit(limit),
+            })
+            .map(rate_limit_status)
+        }
+
+        /// Returns a handle to the list stored in `key`. Lists are created
+        /// when the first value is pushed, and are deleted when the last value
+        /// is popped.
+        fn key_list<S: Into<String>>(&'_ self, key: S) -> structures::List<'_, Self> {
+            structures::List::new(self, self.key_namespace().map(Into::into), key.into())
+        }
+
+        /// Returns a handle to the set stored in `key`. Sets are created when
+        /// the first member is added, and are deleted when the last member is
+        /// removed.
+        fn key_set<S: Into<String>>(&'_ self, key: S) -> structures::Set<'_, Self> {
+            structures::Set::new(self, self.key_namespace().map(Into::into), key.into())
+        }
+
+        /// Returns a handle to the hash stored in `key`. Hashes are created
+        /// when the first field is set, and are deleted when the last field
+        /// is deleted.
+        fn key_hash<S: Into<String>>(&'_ self, key: S) -> structures::Hash<'_, Self> {
+            structures::Hash::new(self, self.key_namespace().map(Into::into), key.into())
+        }
+
+        /// Executes several operations atomically. This function returns a
+        /// builder that operations can be added to. Either all operations in
+        /// the batch are performed, or none are.
+        fn batch(&'_ self) -> batch::Builder<'_, Self> {
+            batch::Builder::new(self, self.key_namespace().map(Into::into))
+        }
+
+        /// Lists the keys stored in the current namespace. This function
+        /// returns a builder that can limit the keys listed to a range or a
+        /// prefix, and can page through the results. Keys are returned in
+        /// ascending order.
+        fn scan_keys(&'_ self) -> scan::Builder<'_, Self> {
+            scan::Builder::new(self, self.key_namespace().map(Into::into))
+        }
+
         /// The current namespace.
         fn key_namespace(&self) -> Option<&'_ str> {
             None
@@ -175,6 +279,13 @@ mod implementation {
         fn with_key_namespace(&'_ self, namespace: &str) -> Namespaced<'_, Self> {
             Namespaced::new(namespace.to_string(), self)
         }
+
+        /// Access this Key-Value store within the [`KeyValueNamespace`] `N`.
+        /// The returned handle only accepts keys of type `N::Key` and values
+        /// of type `N::Value`.
+        fn typed_namespace<N: KeyValueNamespace>(&'_ self) -> typed::TypedNamespace<'_, N, Self> {
+            typed::TypedNamespace::new(self.with_key_namespace(N::NAME))
+        }
     }
 
     /// Key-Value store methods. The Key-Value store is designed to be a
@@ -314,10 +425,98 @@ mod implementation {
                 .await?
             {
                 Output::Status(status) => Ok(status),
-                Output::Value(_) => unreachable!("invalid output from delete operation"),
+                Output::Value(_)
+                | Output::Entry(_)
+                | Output::Keys(_)
+                | Output::Batch(_)
+                | Output::Contains(_)
+                | Output::RateLimit(_) => {
+                    unreachable!("invalid output from delete operation")
+                }
             }
         }
 
+        /// Sets `key` to expire after `duration` from now without changing its
+        /// value. Returns [`KeyStatus::Updated`] if the key exists, otherwise
+        /// [`KeyStatus::NotChanged`].
+        async fn expire_key<S: Into<String> + Send>(
+            &'_ self,
+            key: S,
+            duration: Duration,
+        ) -> Result<KeyStatus, Error> {
+            self.execute_key_operation(expire_operation(
+                self.key_namespace(),
+                key.into(),
+                Some(Timestamp::now() + duration),
+            ))
+            .await
+            .map(expire_status)
+        }
+
+        /// Removes the expiration from `key` without changing its value.
+        /// Returns [`KeyStatus::Updated`] if the key exists, otherwise
+        /// [`KeyStatus::NotChanged`].
+        async fn persist_key<S: Into<String> + Send>(&'_ self, key: S) -> Result<KeyStatus, Error> {
+            self.execute_key_operation(expire_operation(self.key_namespace(), key.into(), None))
+                .await
+                .map(expire_status)
+        }
+
+        /// Atomically consumes [`RateLimit::cost`] tokens from the token bucket
+        /// stored in `key`. The returned status indicates whether the request
+        /// is allowed, and if not, how long until it would be.
+        async fn rate_limit<S: Into<String> + Send>(
+            &'_ self,
+            key: S,
+            limit: RateLimit,
+        ) -> Result<RateLimitStatus, Error> {
+            self.execute_key_operation(KeyOperation {
+                namespace: self.key_namespace().map(ToOwned::to_owned),
+                key: key.into(),
+                command: Command::RateLimit(limit),
+            })
+            .await
+            .map(rate_limit_status)
+        }
+
+        /// Returns a handle to the list stored in `key`. Lists are created
+        /// when the first value is pushed, and are deleted when the last value
+        /// is popped.
+        fn key_list<S: Into<String>>(&'_ self, key: S) -> structures::AsyncList<'_, Self> {
+            structures::AsyncList::new(self, self.key_namespace().map(Into::into), key.into())
+        }
+
+        /// Returns a handle to the set stored in `key`. Sets are created when
+        /// the first member is added, and are deleted when the last member is
+        /// removed.
+        fn key_set<S: Into<String>>(&'_ self, key: S) -> structures::AsyncSet<'_, Self> {
+            structures::AsyncSet::new(self, self.key_namespace().map(Into::into), key.into())
+        }
+
+        /// Returns a handle to the hash stored in `key`. Hashes are created
+        /// when the first field is set, and are deleted when the last field
+        /// is deleted.
+        fn key_hash<S: Into<String>>(&'_ self, key: S) -> structures::AsyncHash<'_, Self> {
+            structures::AsyncHash::new(self, self.key_namespace().map(Into::into), key.into())
+        }
+
+        /// Executes several operations atomically. This function returns a
+        /// builder that operations can be added to. Either all operations in
+        /// the batch are performed, or none are. Awaiting the builder will
+        /// execute [`Command::Batch`].
+        fn batch(&'_ self) -> batch::AsyncBuilder<'_, Self> {
+            batch::AsyncBuilder::new(self, self.key_namespace().map(Into::into))
+        }
+
+        /// Lists the keys stored in the current namespace. This function
+        /// returns a builder that can limit the keys listed to a range or a
+        /// prefix, and can page through the results. Keys are returned in
+        /// ascending order. Awaiting the builder will execute
+        /// [`Command::Scan`] with the options given.
+        fn scan_keys(&'_ self) -> scan::AsyncBuilder<'_, Self> {
+            scan::AsyncBuilder::new(self, self.key_namespace().map(Into::into))
+        }
+
         /// The current namespace.
         fn key_namespace(&self) -> Option<&'_ str> {
             None
@@ -329,6 +528,15 @@ mod implementation {
         fn with_key_namespace(&'_ self, namespace: &str) -> Namespaced<'_, Self> {
             Namespaced::new(namespace.to_string(), self)
         }
+
+        /// Access this Key-Value store within the [`KeyValueNamespace`] `N`.
+        /// The returned handle only accepts keys of type `N::Key` and values
+        /// of type `N::Value`.
+        fn typed_namespace<N: KeyValueNamespace>(
+            &'_ self,
+        ) -> typed::AsyncTypedNamespace<'_, N, Self> {
+            typed::AsyncTypedNamespace::new(self.with_key_namespace(N::NAME))
+        }
     }
 
     enum BuilderState<'a, T, V> {
@@ -355,17 +563,50 @@ mod implementation {
             }
         }
     }
+
+    fn expire_operation(
+        namespace: Option<&str>,
+        key: String,
+        expiration: Option<Timestamp>,
+    ) -> KeyOperation {
+        KeyOperation {
+            namespace: namespace.map(ToOwned::to_owned),
+            key,
+            command: Command::Expire { expiration },
+        }
+    }
+
+    fn expire_status(output: Output) -> KeyStatus {
+        if let Output::Status(status) = output {
+            status
+        } else {
+            unreachable!("invalid output from expire operation")
+        }
+    }
+
+    fn rate_limit_status(output: Output) -> RateLimitStatus {
+        if let Output::RateLimit(status) = output {
+            status
+        } else {
+            unreachable!("invalid output from rate limit operation")
+        }
+    }
 }
 
 pub use implementation::*;
 
 /// Checks for existing keys.
-#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum KeyCheck {
     /// Only allow the operation if an existing key is present.
     OnlyIfPresent,
     /// Only allow the opeartion if the key isn't present.
     OnlyIfVacant,
+    /// Only allow the operation if the key is present and its value is equal
+    /// to the contained value. [`Value::Bytes`] are compared byte-for-byte.
+    /// [`Value::Numeric`] values are compared by their numeric value, which
+    /// allows an [`Numeric::Integer`] to equal a [`Numeric::UnsignedInteger`].
+    OnlyIfEqual(Value),
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -389,6 +630,18 @@ pub enum Command {
         /// Remove the key after retrieving the value.
         delete: bool,
     },
+    /// Get the value from a key along with its expiration and the time it
+    /// was last updated. Returns [`Output::Entry`].
+    GetEntry {
+        /// Remove the key after retrieving the entry.
+        delete: bool,
+    },
+    /// Changes the expiration of an existing key without changing its value.
+    /// Returns [`KeyStatus::Updated`] if the key exists.
+    Expire {
+        /// The new expiration. If `None`, the key will no longer expire.
+        expiration: Option<Timestamp>,
+    },
     /// Increment a numeric key. Returns an error if the key cannot be
     /// deserialized to the same numeric type as `amount`. If `saturating` is
     /// true, overflows will be prevented and the value will remain within the
@@ -413,6 +666,110 @@ pub enum Command {
     },
     /// Delete a key.
     Delete,
+    /// List the keys within the operation's namespace. The `key` of the
+    /// operation is ignored.
+    Scan(ScanCommand),
+    /// Operate on the list stored in a key. The key's expiration is only
+    /// changed by pushes that specify an expiration.
+    List(ListCommand),
+    /// Operate on the set stored in a key. The key's expiration is only
+    /// changed by additions that specify an expiration.
+    SetMembers(SetMembersCommand),
+    /// Operate on the hash stored in a key. The key's expiration is only
+    /// changed by setting fields with an expiration.
+    Hash(HashCommand),
+    /// Atomically refills the token bucket stored in a key and attempts to
+    /// consume tokens from it. The key expires once the bucket would be full
+    /// again. Returns [`Output::RateLimit`].
+    RateLimit(RateLimit),
+    /// Execute several operations atomically. If any operation's check fails
+    /// or any operation returns an error, none of the operations are
+    /// performed. The `namespace` and `key` of the operation are ignored.
+    Batch(Vec<BatchOperation>),
+}
+
+/// An end of a list.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub enum ListEnd {
+    /// The start of the list.
+    Front,
+    /// The end of the list.
+    Back,
+}
+
+/// An operation on a list stored in a key.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum ListCommand {
+    /// Pushes `value` onto an end of the list, creating the list if it does
+    /// not exist. Returns the length of the list as a [`Value::Numeric`].
+    Push {
+        /// The value to push.
+        value: Bytes,
+        /// The end of the list to push onto.
+        end: ListEnd,
+        /// If present, the key is updated to expire at this time.
+        expiration: Option<Timestamp>,
+    },
+    /// Removes and returns the value at an end of the list. Removing the last
+    /// value deletes the key.
+    Pop {
+        /// The end of the list to pop from.
+        end: ListEnd,
+    },
+}
+
+/// An operation on a set stored in a key.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum SetMembersCommand {
+    /// Adds a member to the set, creating the set if it does not exist.
+    /// Returns [`KeyStatus::Inserted`] if the member was added.
+    Add {
+        /// The member to add.
+        member: Bytes,
+        /// If present, the key is updated to expire at this time.
+        expiration: Option<Timestamp>,
+    },
+    /// Removes a member from the set. Removing the last member deletes the
+    /// key. Returns [`KeyStatus::Deleted`] if the member was removed.
+    Remove(Bytes),
+    /// Checks whether the set contains a member. Returns
+    /// [`Output::Contains`].
+    Contains(Bytes),
+}
+
+/// An operation on a hash stored in a key.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum HashCommand {
+    /// Sets `field` to `value`, creating the hash if it does not exist.
+    SetField {
+        /// The name of the field.
+        field: String,
+        /// The value to store.
+        value: Bytes,
+        /// If present, the key is updated to expire at this time.
+        expiration: Option<Timestamp>,
+    },
+    /// Returns the value stored in `field`.
+    GetField {
+        /// The name of the field.
+        field: String,
+    },
+    /// Deletes `field`. Deleting the last field deletes the key.
+    DeleteField {
+        /// The name of the field.
+        field: String,
+    },
+}
+
+/// An operation executed as part of a [`Command::Batch`].
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct BatchOperation {
+    /// The operation to execute.
+    pub operation: KeyOperation,
+    /// If provided, the batch is only executed if this check succeeds for the
+    /// operation's key. Checks are evaluated before any operations in the
+    /// batch are performed.
+    pub check: Option<KeyCheck>,
 }
 
 /// Set a key/value pair.
@@ -432,6 +789,19 @@ pub struct SetCommand {
     pub return_previous_value: bool,
 }
 
+/// List the keys within a namespace.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct ScanCommand {
+    /// The range of keys to list.
+    pub range: Range<String>,
+    /// If provided, only keys that start with `prefix` are listed.
+    pub prefix: Option<String>,
+    /// The maximum number of keys to list.
+    pub limit: Option<u32>,
+    /// If true, the value stored in each key is returned alongside the key.
+    pub include_values: bool,
+}
+
 /// A value stored in a key.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Value {
@@ -439,6 +809,12 @@ pub enum Value {
     Bytes(Bytes),
     /// A numeric value.
     Numeric(Numeric),
+    /// An ordered list of values.
+    List(VecDeque<Bytes>),
+    /// A set of unique values.
+    Set(BTreeSet<Bytes>),
+    /// A collection of named fields containing values.
+    Hash(BTreeMap<String, Bytes>),
 }
 
 impl Value {
@@ -446,7 +822,7 @@ impl Value {
     pub fn validate(self) -> Result<Self, Error> {
         match self {
             Self::Numeric(numeric) => numeric.validate().map(Self::Numeric),
-            Self::Bytes(vec) => Ok(Self::Bytes(vec)),
+            other => Ok(other),
         }
     }
 
@@ -459,6 +835,10 @@ impl Value {
                 "key-value",
                 "key contains numeric value, not serialized data",
             )),
+            Self::List(_) | Self::Set(_) | Self::Hash(_) => Err(Error::other(
+                "key-value",
+                "key contains a list, set, or hash, not serialized data",
+            )),
         }
     }
 
@@ -466,8 +846,8 @@ impl Value {
     #[must_use]
     pub fn as_i64_lossy(&self, saturating: bool) -> Option<i64> {
         match self {
-            Self::Bytes(_) => None,
             Self::Numeric(value) => Some(value.as_i64_lossy(saturating)),
+            _ => None,
         }
     }
 
@@ -475,8 +855,8 @@ impl Value {
     #[must_use]
     pub fn as_u64_lossy(&self, saturating: bool) -> Option<u64> {
         match self {
-            Self::Bytes(_) => None,
             Self::Numeric(value) => Some(value.as_u64_lossy(saturating)),
+            _ => None,
         }
     }
 
@@ -484,8 +864,8 @@ impl Value {
     #[must_use]
     pub const fn as_f64_lossy(&self) -> Option<f64> {
         match self {
-            Self::Bytes(_) => None,
             Self::Numeric(value) => Some(value.as_f64_lossy()),
+            _ => None,
         }
     }
 
@@ -493,8 +873,8 @@ impl Value {
     #[must_use]
     pub fn as_i64(&self) -> Option<i64> {
         match self {
-            Self::Bytes(_) => None,
             Self::Numeric(value) => value.as_i64(),
+            _ => None,
         }
     }
 
@@ -502,8 +882,8 @@ impl Value {
     #[must_use]
     pub fn as_u64(&self) -> Option<u64> {
         match self {
-            Self::Bytes(_) => None,
             Self::Numeric(value) => value.as_u64(),
+            _ => None,
         }
     }
 
@@ -511,8 +891,8 @@ impl Value {
     #[must_use]
     pub const fn as_f64(&self) -> Option<f64> {
         match self {
-            Self::Bytes(_) => None,
             Self::Numeric(value) => value.as_f64(),
+            _ => None,
         }
     }
 }
@@ -727,6 +1107,111 @@ pub enum Output {
     Status(KeyStatus),
     /// A value was returned.
     Value(Option<Value>),
+    /// A value and its metadata were returned.
+    Entry(Option<KeyEntry>),
+    /// A list of keys was returned.
+    Keys(Vec<ScannedKey>),
+    /// Whether a set contains a member was returned.
+    Contains(bool),
+    /// The outputs of each operation in a [`Command::Batch`] were returned.
+    /// If `None`, a check failed and no operations were performed.
+    Batch(Option<Vec<Output>>),
+    /// The result of a [`Command::RateLimit`] was returned.
+    RateLimit(RateLimitStatus),
+}
+
+/// A token bucket rate limit. A bucket starts with `capacity` tokens, and
+/// `refill_amount` tokens are added to it each `refill_interval` until it is
+/// full again. Each request consumes `cost` tokens.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use bonsaidb_core::keyvalue::RateLimit;
+/// // Allow bursts of 10 requests, refilling one token per second.
+/// let limit = RateLimit::token_bucket(10, 1, Duration::from_secs(1));
+/// assert_eq!(limit.cost, 1);
+/// ```
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[must_use]
+pub struct RateLimit {
+    /// The maximum number of tokens the bucket can hold.
+    pub capacity: u64,
+    /// The number of tokens added each `refill_interval`.
+    pub refill_amount: u64,
+    /// The interval at which tokens are added.
+    pub refill_interval: Duration,
+    /// The number of tokens consumed by this request.
+    pub cost: u64,
+}
+
+impl RateLimit {
+    /// Returns a rate limit allowing bursts of up to `capacity` requests,
+    /// refilling `refill_amount` tokens every `refill_interval`. Each request
+    /// costs one token.
+    pub const fn token_bucket(
+        capacity: u64,
+        refill_amount: u64,
+        refill_interval: Duration,
+    ) -> Self {
+        Self {
+            capacity,
+            refill_amount,
+            refill_interval,
+            cost: 1,
+        }
+    }
+
+    /// Sets the number of tokens consumed by the request to `cost` and
+    /// returns self.
+    pub const fn with_cost(mut self, cost: u64) -> Self {
+        self.cost = cost;
+        self
+    }
+}
+
+/// The result of a [`Command::RateLimit`].
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct RateLimitStatus {
+    /// True if the request was allowed and its tokens were consumed.
+    pub allowed: bool,
+    /// The number of tokens remaining in the bucket.
+    pub remaining: u64,
+    /// If the request was not allowed, the amount of time until enough tokens
+    /// will be available. `None` if the request was allowed, or if its cost
+    /// exceeds the bucket's capacity.
+    pub retry_after: Option<Duration>,
+}
+
+/// A value stored in a key, along with its metadata. Returned by
+/// [`Command::GetEntry`].
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct KeyEntry {
+    /// The value stored in the key.
+    pub value: Value,
+    /// The time the key will expire at, if an expiration is set.
+    pub expiration: Option<Timestamp>,
+    /// The time the key was last updated.
+    pub last_updated: Timestamp,
+}
+
+impl KeyEntry {
+    /// Returns the amount of time remaining until this key expires. Returns
+    /// `None` if the key does not expire or if it has already expired.
+    #[must_use]
+    pub fn expires_in(&self) -> Option<Duration> {
+        self.expiration
+            .and_then(|expiration| expiration - Timestamp::now())
+    }
+}
+
+/// A key listed by a [`Command::Scan`] operation.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ScannedKey {
+    /// The key.
+    pub key: String,
+    /// The value stored in the key, if
+    /// [`ScanCommand::include_values`] was true.
+    pub value: Option<Value>,
 }
 /// The status of an operation on a Key.
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -740,3 +1225,114 @@ pub enum KeyStatus {
     /// No changes were made.
     NotChanged,
 }
+
+/// A change made to a key. When key-value notifications are enabled, changes
+/// are published to [`key_changes_topic()`](crate::pubsub::key_changes_topic)
+/// for the key's namespace. Subscribers can receive changes by calling
+/// [`Subscriber::subscribe_to_key_changes()`](crate::pubsub::Subscriber::subscribe_to_key_changes).
+#[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct KeyChange {
+    /// The namespace of the key.
+    pub namespace: Option<String>,
+    /// The key that was changed.
+    pub key: String,
+    /// The kind of change that was made.
+    pub kind: KeyChangeKind,
+}
+
+/// The kind of change made to a key.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub enum KeyChangeKind {
+    /// The key was set to a new value.
+    Set,
+    /// The key was deleted.
+    Deleted,
+    /// The key was deleted because it expired.
+    Expired,
+    /// The key was deleted to keep the key-value store within its configured
+    /// size limits.
+    Evicted,
+}
+
+/// A key-value namespace whose keys and values have fixed types. This trait
+/// can be derived:
+///
+/// ```rust
+/// # use bonsaidb_core::keyvalue::KeyValueNamespace;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Debug)]
+/// pub struct Session {
+///     pub user_id: u64,
+/// }
+///
+/// #[derive(KeyValueNamespace)]
+/// #[key_value_namespace(name = "sessions", key = u64, value = Session)]
+/// # #[key_value_namespace(core = bonsaidb_core)]
+/// pub struct Sessions;
+///
+/// assert_eq!(Sessions::NAME, "sessions");
+/// ```
+///
+/// Typed namespaces are accessed using
+/// [`KeyValue::typed_namespace()`]/[`AsyncKeyValue::typed_namespace()`].
+/// Keys are stored using the hexadecimal representation of their
+/// [`Key`] encoding, which preserves the order of the keys.
+pub trait KeyValueNamespace: Send + Sync {
+    /// The type of the keys in this namespace.
+    type Key: for<'k> Key<'k>;
+    /// The type of the values in this namespace.
+    type Value: Serialize + for<'de> Deserialize<'de> + Send + Sync;
+
+    /// The name of this namespace.
+    const NAME: &'static str;
+
+    /// Encodes `key` into the string stored in the key-value store.
+    fn encode_key(key: &Self::Key) -> Result<String, Error> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let bytes = key
+            .as_ord_bytes()
+            .map_err(|err| Error::other("key serialization", err))?;
+        let mut encoded = String::with_capacity(bytes.len() * 2);
+        for byte in bytes.iter() {
+            encoded.push(char::from(HEX[usize::from(byte >> 4)]));
+            encoded.push(char::from(HEX[usize::from(byte & 0xF)]));
+        }
+        Ok(encoded)
+    }
+
+    /// Decodes a key previously encoded with
+    /// [`encode_key()`](Self::encode_key).
+    fn decode_key(encoded: &str) -> Result<Self::Key, Error> {
+        let bytes = decode_hex(encoded)
+            .ok_or_else(|| Error::other("key serialization", "invalid key encoding"))?;
+        Self::Key::from_ord_bytes(ByteSource::Owned(bytes))
+            .map_err(|err| Error::other("key serialization", err))
+    }
+
+    /// Returns the resource name of this namespace within `database`, for use
+    /// in permission statements.
+    #[must_use]
+    fn namespace_resource_name(database: &str) -> ResourceName<'_> {
+        keyvalue_namespace_resource_name(database, Some(Self::NAME))
+    }
+
+    /// Returns the resource name of `key` within this namespace in
+    /// `database`, for use in permission statements.
+    fn key_resource_name<'a>(
+        database: &'a str,
+        key: &Self::Key,
+    ) -> Result<ResourceName<'a>, Error> {
+        Ok(Self::namespace_resource_name(database).and(Self::encode_key(key)?))
+    }
+}
+
+fn decode_hex(encoded: &str) -> Option<Vec<u8>> {
+    if encoded.len() % 2 == 0 {
+        (0..encoded.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(encoded.get(index..index + 2)?, 16).ok())
+            .collect()
+    } else {
+        None
+    }
+}
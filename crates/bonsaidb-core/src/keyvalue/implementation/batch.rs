@@ -0,0 +1,347 @@
+use arc_bytes::serde::Bytes;
+use futures::{Future, FutureExt};
+use serde::Serialize;
+
+use super::{BuilderState, Command, KeyCheck, KeyOperation, KeyValue, Output};
+use crate::keyvalue::{AsyncKeyValue, BatchOperation, Numeric, SetCommand, Value};
+use crate::Error;
+
+/// Builder for a [`Command::Batch`] key-value operation.
+///
+/// Each operation added to the batch can be given a [`KeyCheck`] using
+/// [`Self::only_if_exists()`] or [`Self::only_if_vacant()`]. If any check
+/// fails, none of the operations are performed.
+#[must_use = "the key-value operation is not performed until execute() is called"]
+pub struct Builder<'a, KeyValue> {
+    kv: &'a KeyValue,
+    batch: Batch,
+}
+
+impl<'a, K> Builder<'a, K>
+where
+    K: KeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>) -> Self {
+        Self {
+            kv,
+            batch: Batch::new(namespace),
+        }
+    }
+
+    /// Sets `key` to `value` after serializing it.
+    pub fn set_key<S: Into<String>, V: Serialize>(mut self, key: S, value: &V) -> Self {
+        self.batch.set_key(key.into(), value);
+        self
+    }
+
+    /// Sets `key` to `bytes`.
+    pub fn set_binary_key<S: Into<String>>(mut self, key: S, bytes: &[u8]) -> Self {
+        self.batch
+            .set(key.into(), Value::Bytes(Bytes::from(bytes.to_vec())));
+        self
+    }
+
+    /// Sets `key` to the numeric `value`.
+    pub fn set_numeric_key<S: Into<String>, V: Into<Numeric>>(mut self, key: S, value: V) -> Self {
+        self.batch.set(key.into(), Value::Numeric(value.into()));
+        self
+    }
+
+    /// Increments `key` by `amount`, saturating at the numeric bounds of
+    /// `amount`'s type.
+    pub fn increment_key_by<S: Into<String>, V: Into<Numeric>>(
+        mut self,
+        key: S,
+        amount: V,
+    ) -> Self {
+        self.batch.push(
+            key.into(),
+            Command::Increment {
+                amount: amount.into(),
+                saturating: true,
+            },
+        );
+        self
+    }
+
+    /// Decrements `key` by `amount`, saturating at the numeric bounds of
+    /// `amount`'s type.
+    pub fn decrement_key_by<S: Into<String>, V: Into<Numeric>>(
+        mut self,
+        key: S,
+        amount: V,
+    ) -> Self {
+        self.batch.push(
+            key.into(),
+            Command::Decrement {
+                amount: amount.into(),
+                saturating: true,
+            },
+        );
+        self
+    }
+
+    /// Deletes `key`.
+    pub fn delete_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.batch.push(key.into(), Command::Delete);
+        self
+    }
+
+    /// Only executes the batch if the key of the most recently added
+    /// operation exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to the batch.
+    pub fn only_if_exists(mut self) -> Self {
+        self.batch.check(KeyCheck::OnlyIfPresent);
+        self
+    }
+
+    /// Only executes the batch if the key of the most recently added
+    /// operation isn't present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to the batch.
+    pub fn only_if_vacant(mut self) -> Self {
+        self.batch.check(KeyCheck::OnlyIfVacant);
+        self
+    }
+
+    /// Executes the batch. Returns the output of each operation in the order
+    /// they were added, or `None` if a check failed and no operations were
+    /// performed.
+    pub fn execute(self) -> Result<Option<Vec<Output>>, Error> {
+        let Self { kv, batch } = self;
+        let result = kv.execute_key_operation(batch.into_operation()?)?;
+        if let Output::Batch(outputs) = result {
+            Ok(outputs)
+        } else {
+            unreachable!("Unexpected result from batch")
+        }
+    }
+}
+
+/// Builder for a [`Command::Batch`] key-value operation. Executes the batch
+/// when awaited.
+///
+/// Each operation added to the batch can be given a [`KeyCheck`] using
+/// [`Self::only_if_exists()`] or [`Self::only_if_vacant()`]. If any check
+/// fails, none of the operations are performed.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AsyncBuilder<'a, KeyValue> {
+    state: BuilderState<'a, Options<'a, KeyValue>, Result<Option<Vec<Output>>, Error>>,
+}
+
+struct Options<'a, KeyValue> {
+    kv: &'a KeyValue,
+    batch: Batch,
+}
+
+impl<'a, K> AsyncBuilder<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>) -> Self {
+        Self {
+            state: BuilderState::Pending(Some(Options {
+                kv,
+                batch: Batch::new(namespace),
+            })),
+        }
+    }
+
+    fn batch(&mut self) -> &mut Batch {
+        if let BuilderState::Pending(Some(options)) = &mut self.state {
+            &mut options.batch
+        } else {
+            panic!("Attempted to use after retrieving the result")
+        }
+    }
+
+    /// Sets `key` to `value` after serializing it.
+    pub fn set_key<S: Into<String>, V: Serialize>(mut self, key: S, value: &V) -> Self {
+        self.batch().set_key(key.into(), value);
+        self
+    }
+
+    /// Sets `key` to `bytes`.
+    pub fn set_binary_key<S: Into<String>>(mut self, key: S, bytes: &[u8]) -> Self {
+        self.batch()
+            .set(key.into(), Value::Bytes(Bytes::from(bytes.to_vec())));
+        self
+    }
+
+    /// Sets `key` to the numeric `value`.
+    pub fn set_numeric_key<S: Into<String>, V: Into<Numeric>>(mut self, key: S, value: V) -> Self {
+        self.batch().set(key.into(), Value::Numeric(value.into()));
+        self
+    }
+
+    /// Increments `key` by `amount`, saturating at the numeric bounds of
+    /// `amount`'s type.
+    pub fn increment_key_by<S: Into<String>, V: Into<Numeric>>(
+        mut self,
+        key: S,
+        amount: V,
+    ) -> Self {
+        self.batch().push(
+            key.into(),
+            Command::Increment {
+                amount: amount.into(),
+                saturating: true,
+            },
+        );
+        self
+    }
+
+    /// Decrements `key` by `amount`, saturating at the numeric bounds of
+    /// `amount`'s type.
+    pub fn decrement_key_by<S: Into<String>, V: Into<Numeric>>(
+        mut self,
+        key: S,
+        amount: V,
+    ) -> Self {
+        self.batch().push(
+            key.into(),
+            Command::Decrement {
+                amount: amount.into(),
+                saturating: true,
+            },
+        );
+        self
+    }
+
+    /// Deletes `key`.
+    pub fn delete_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.batch().push(key.into(), Command::Delete);
+        self
+    }
+
+    /// Only executes the batch if the key of the most recently added
+    /// operation exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to the batch.
+    pub fn only_if_exists(mut self) -> Self {
+        self.batch().check(KeyCheck::OnlyIfPresent);
+        self
+    }
+
+    /// Only executes the batch if the key of the most recently added
+    /// operation isn't present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no operations have been added to the batch.
+    pub fn only_if_vacant(mut self) -> Self {
+        self.batch().check(KeyCheck::OnlyIfVacant);
+        self
+    }
+}
+
+impl<'a, K> Future for AsyncBuilder<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    type Output = Result<Option<Vec<Output>>, Error>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        match &mut self.state {
+            BuilderState::Executing(future) => future.as_mut().poll(cx),
+            BuilderState::Pending(builder) => {
+                let Options { kv, batch } =
+                    builder.take().expect("expected builder to have options");
+                let future = async move {
+                    let result = kv.execute_key_operation(batch.into_operation()?).await?;
+                    if let Output::Batch(outputs) = result {
+                        Ok(outputs)
+                    } else {
+                        unreachable!("Unexpected result from batch")
+                    }
+                }
+                .boxed();
+
+                self.state = BuilderState::Executing(future);
+                self.poll(cx)
+            }
+        }
+    }
+}
+
+/// The operations gathered by a batch builder.
+struct Batch {
+    namespace: Option<String>,
+    operations: Vec<BatchOperation>,
+    /// The first error encountered while preparing an operation. Errors are
+    /// returned when the batch is executed.
+    error: Option<Error>,
+}
+
+impl Batch {
+    const fn new(namespace: Option<String>) -> Self {
+        Self {
+            namespace,
+            operations: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn push(&mut self, key: String, command: Command) {
+        self.operations.push(BatchOperation {
+            operation: KeyOperation {
+                namespace: self.namespace.clone(),
+                key,
+                command,
+            },
+            check: None,
+        });
+    }
+
+    fn set(&mut self, key: String, value: Value) {
+        self.push(
+            key,
+            Command::Set(SetCommand {
+                value,
+                expiration: None,
+                keep_existing_expiration: false,
+                check: None,
+                return_previous_value: false,
+            }),
+        );
+    }
+
+    fn set_key<V: Serialize>(&mut self, key: String, value: &V) {
+        match pot::to_vec(value) {
+            Ok(bytes) => self.set(key, Value::Bytes(Bytes::from(bytes))),
+            Err(err) => {
+                self.error.get_or_insert(Error::from(err));
+            }
+        }
+    }
+
+    fn check(&mut self, check: KeyCheck) {
+        match self.operations.last_mut() {
+            Some(operation) => operation.check = Some(check),
+            // The operation may have failed to be prepared, in which case the
+            // error will be returned when executed.
+            None => assert!(self.error.is_some(), "a check must follow an operation"),
+        }
+    }
+
+    fn into_operation(self) -> Result<KeyOperation, Error> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(KeyOperation {
+                namespace: self.namespace,
+                key: String::new(),
+                command: Command::Batch(self.operations),
+            }),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use futures::{Future, FutureExt};
 use serde::Deserialize;
 
 use super::{BuilderState, Command, KeyOperation, KeyValue, Output};
-use crate::keyvalue::{AsyncKeyValue, Value};
+use crate::keyvalue::{AsyncKeyValue, KeyEntry, Value};
 use crate::Error;
 
 /// Builder for a [`Command::Get`] key-value operation.
@@ -136,6 +136,27 @@ where
         }
     }
 
+    /// Retrieves the value for the key along with its expiration and the time
+    /// it was last updated, using the configured options.
+    pub fn with_metadata(self) -> Result<Option<KeyEntry>, Error> {
+        let Self {
+            kv,
+            namespace,
+            key,
+            delete,
+        } = self;
+        let result = kv.execute_key_operation(KeyOperation {
+            namespace,
+            key,
+            command: Command::GetEntry { delete },
+        })?;
+        if let Output::Entry(entry) = result {
+            Ok(entry)
+        } else {
+            unreachable!("Unexpected result from get")
+        }
+    }
+
     /// Retrieves the value for the key, using the configured options.
     pub fn query(self) -> Result<Option<Value>, Error> {
         let Self {
@@ -303,6 +324,33 @@ where
             None => Ok(None),
         }
     }
+
+    /// Retrieves the value for the key along with its expiration and the time
+    /// it was last updated, using the configured options.
+    pub async fn with_metadata(self) -> Result<Option<KeyEntry>, Error> {
+        if let BuilderState::Pending(Some(options)) = self.state {
+            let Options {
+                kv,
+                namespace,
+                key,
+                delete,
+            } = options;
+            let result = kv
+                .execute_key_operation(KeyOperation {
+                    namespace,
+                    key,
+                    command: Command::GetEntry { delete },
+                })
+                .await?;
+            if let Output::Entry(entry) = result {
+                Ok(entry)
+            } else {
+                unreachable!("Unexpected result from get")
+            }
+        } else {
+            panic!("Using future after it's been executed")
+        }
+    }
 }
 
 impl<'a, K> Future for AsyncBuilder<'a, K>
@@ -0,0 +1,280 @@
+use futures::{Future, FutureExt};
+
+use super::{BuilderState, Command, KeyOperation, KeyValue, Output};
+use crate::connection::{Bound, Page, Range};
+use crate::keyvalue::{AsyncKeyValue, ScanCommand, ScannedKey, Value};
+use crate::Error;
+
+/// Builder for a [`Command::Scan`] key-value operation.
+#[must_use = "the key-value operation is not performed until query() or keys() is called"]
+pub struct Builder<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    scan: ScanCommand,
+}
+
+impl<'a, K> Builder<'a, K>
+where
+    K: KeyValue,
+{
+    pub(crate) fn new(kv: &'a K, namespace: Option<String>) -> Self {
+        Self {
+            kv,
+            namespace,
+            scan: ScanCommand::default(),
+        }
+    }
+
+    /// Only lists keys contained within `range`.
+    pub fn with_range<R: Into<Range<String>>>(mut self, range: R) -> Self {
+        self.scan.range = range.into();
+        self
+    }
+
+    /// Only lists keys that start with `prefix`.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.scan.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Resumes listing after `key`. This is typically used with the cursor
+    /// returned from [`Self::keys_page()`] or [`Self::query_page()`].
+    pub fn after<S: Into<String>>(mut self, key: S) -> Self {
+        self.scan.range = resume_after(std::mem::take(&mut self.scan.range), key.into());
+        self
+    }
+
+    /// Lists at most `maximum_results` keys.
+    pub const fn limit(mut self, maximum_results: u32) -> Self {
+        self.scan.limit = Some(maximum_results);
+        self
+    }
+
+    /// Returns the matching keys.
+    pub fn keys(mut self) -> Result<Vec<String>, Error> {
+        self.scan.include_values = false;
+        Ok(self.execute()?.into_iter().map(|entry| entry.key).collect())
+    }
+
+    /// Returns a page of matching keys. The returned cursor can be passed to
+    /// [`Self::after()`] to retrieve the next page.
+    pub fn keys_page(self) -> Result<Page<Vec<String>, String>, Error> {
+        let limit = self.scan.limit;
+        Ok(page_of(self.keys()?, limit, Clone::clone))
+    }
+
+    /// Returns the matching keys and their values.
+    pub fn query(mut self) -> Result<Vec<(String, Value)>, Error> {
+        self.scan.include_values = true;
+        self.execute()?.into_iter().map(key_and_value).collect()
+    }
+
+    /// Returns a page of matching keys and their values. The returned cursor
+    /// can be passed to [`Self::after()`] to retrieve the next page.
+    pub fn query_page(self) -> Result<Page<Vec<(String, Value)>, String>, Error> {
+        let limit = self.scan.limit;
+        Ok(page_of(self.query()?, limit, |(key, _)| key.clone()))
+    }
+
+    fn execute(self) -> Result<Vec<ScannedKey>, Error> {
+        let Self {
+            kv,
+            namespace,
+            scan,
+        } = self;
+        let result = kv.execute_key_operation(KeyOperation {
+            namespace,
+            key: String::new(),
+            command: Command::Scan(scan),
+        })?;
+        if let Output::Keys(keys) = result {
+            Ok(keys)
+        } else {
+            unreachable!("Unexpected result from scan")
+        }
+    }
+}
+
+/// Builder for a [`Command::Scan`] key-value operation. Queries the matching
+/// keys and their values when awaited.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct AsyncBuilder<'a, KeyValue> {
+    state: BuilderState<'a, Options<'a, KeyValue>, Result<Vec<(String, Value)>, Error>>,
+}
+
+struct Options<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    scan: ScanCommand,
+}
+
+impl<'a, K> Options<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    async fn execute(self) -> Result<Vec<ScannedKey>, Error> {
+        let Self {
+            kv,
+            namespace,
+            scan,
+        } = self;
+        let result = kv
+            .execute_key_operation(KeyOperation {
+                namespace,
+                key: String::new(),
+                command: Command::Scan(scan),
+            })
+            .await?;
+        if let Output::Keys(keys) = result {
+            Ok(keys)
+        } else {
+            unreachable!("Unexpected result from scan")
+        }
+    }
+}
+
+impl<'a, K> AsyncBuilder<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    pub(crate) fn new(kv: &'a K, namespace: Option<String>) -> Self {
+        Self {
+            state: BuilderState::Pending(Some(Options {
+                kv,
+                namespace,
+                scan: ScanCommand::default(),
+            })),
+        }
+    }
+
+    fn options(&mut self) -> &mut Options<'a, K> {
+        if let BuilderState::Pending(Some(options)) = &mut self.state {
+            options
+        } else {
+            unreachable!("Attempted to use after retrieving the result")
+        }
+    }
+
+    fn into_options(self) -> Options<'a, K> {
+        if let BuilderState::Pending(Some(options)) = self.state {
+            options
+        } else {
+            unreachable!("Attempted to use after retrieving the result")
+        }
+    }
+
+    /// Only lists keys contained within `range`.
+    pub fn with_range<R: Into<Range<String>>>(mut self, range: R) -> Self {
+        self.options().scan.range = range.into();
+        self
+    }
+
+    /// Only lists keys that start with `prefix`.
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.options().scan.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Resumes listing after `key`. This is typically used with the cursor
+    /// returned from [`Self::keys_page()`] or [`Self::query_page()`].
+    pub fn after<S: Into<String>>(mut self, key: S) -> Self {
+        let scan = &mut self.options().scan;
+        scan.range = resume_after(std::mem::take(&mut scan.range), key.into());
+        self
+    }
+
+    /// Lists at most `maximum_results` keys.
+    pub fn limit(mut self, maximum_results: u32) -> Self {
+        self.options().scan.limit = Some(maximum_results);
+        self
+    }
+
+    /// Returns the matching keys.
+    pub async fn keys(self) -> Result<Vec<String>, Error> {
+        let mut options = self.into_options();
+        options.scan.include_values = false;
+        Ok(options
+            .execute()
+            .await?
+            .into_iter()
+            .map(|entry| entry.key)
+            .collect())
+    }
+
+    /// Returns a page of matching keys. The returned cursor can be passed to
+    /// [`Self::after()`] to retrieve the next page.
+    pub async fn keys_page(mut self) -> Result<Page<Vec<String>, String>, Error> {
+        let limit = self.options().scan.limit;
+        Ok(page_of(self.keys().await?, limit, Clone::clone))
+    }
+
+    /// Returns a page of matching keys and their values. The returned cursor
+    /// can be passed to [`Self::after()`] to retrieve the next page.
+    pub async fn query_page(mut self) -> Result<Page<Vec<(String, Value)>, String>, Error> {
+        let limit = self.options().scan.limit;
+        Ok(page_of(self.await?, limit, |(key, _)| key.clone()))
+    }
+}
+
+impl<'a, K> Future for AsyncBuilder<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    type Output = Result<Vec<(String, Value)>, Error>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        match &mut self.state {
+            BuilderState::Executing(future) => future.as_mut().poll(cx),
+            BuilderState::Pending(builder) => {
+                let mut options = builder.take().expect("expected builder to have options");
+                options.scan.include_values = true;
+                let future = async move {
+                    options
+                        .execute()
+                        .await?
+                        .into_iter()
+                        .map(key_and_value)
+                        .collect()
+                }
+                .boxed();
+
+                self.state = BuilderState::Executing(future);
+                self.poll(cx)
+            }
+        }
+    }
+}
+
+/// Narrows `range` to only include keys greater than `key`.
+fn resume_after(mut range: Range<String>, key: String) -> Range<String> {
+    let narrows = match &range.start {
+        Bound::Unbounded => true,
+        Bound::Included(start) | Bound::Excluded(start) => start <= &key,
+    };
+    if narrows {
+        range.start = Bound::Excluded(key);
+    }
+    range
+}
+
+fn key_and_value(entry: ScannedKey) -> Result<(String, Value), Error> {
+    match entry.value {
+        Some(value) => Ok((entry.key, value)),
+        None => Err(Error::other("key-value", "scan did not return a value")),
+    }
+}
+
+fn page_of<T, F: Fn(&T) -> String>(
+    results: Vec<T>,
+    limit: Option<u32>,
+    key: F,
+) -> Page<Vec<T>, String> {
+    let next = match limit {
+        Some(limit) if results.len() >= limit as usize => results.last().map(key),
+        _ => None,
+    };
+    Page { results, next }
+}
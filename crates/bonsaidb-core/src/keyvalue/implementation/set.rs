@@ -8,7 +8,7 @@ use super::{
     BuilderState, Command, KeyCheck, KeyOperation, KeyStatus, KeyValue, Output, PendingValue,
     Timestamp,
 };
-use crate::keyvalue::{AsyncKeyValue, SetCommand, Value};
+use crate::keyvalue::{AsyncKeyValue, Numeric, SetCommand, Value};
 use crate::Error;
 
 /// Builder for a [`Command::Set`] key-value operation.
@@ -20,7 +20,7 @@ pub struct Builder<'a, KeyValue, V> {
     value: PendingValue<'a, V>,
     expiration: Option<Timestamp>,
     keep_existing_expiration: bool,
-    check: Option<KeyCheck>,
+    check: Option<PendingCheck<'a, V>>,
 }
 
 impl<'a, K, V> Builder<'a, K, V>
@@ -67,13 +67,43 @@ where
 
     /// Only set the value if this key already exists.
     pub const fn only_if_exists(mut self) -> Self {
-        self.check = Some(KeyCheck::OnlyIfPresent);
+        self.check = Some(PendingCheck::OnlyIfPresent);
         self
     }
 
     /// Only set the value if this key isn't present.
     pub const fn only_if_vacant(mut self) -> Self {
-        self.check = Some(KeyCheck::OnlyIfVacant);
+        self.check = Some(PendingCheck::OnlyIfVacant);
+        self
+    }
+
+    /// Only set the value if this key's current value is equal to
+    /// `expected` after serializing it. If the value is swapped,
+    /// [`KeyStatus::Updated`] is returned. Otherwise,
+    /// [`KeyStatus::NotChanged`] is returned.
+    pub const fn only_if_equal(mut self, expected: &'a V) -> Self {
+        self.check = Some(PendingCheck::OnlyIfEqual(PendingValue::Serializeable(
+            expected,
+        )));
+        self
+    }
+
+    /// Only set the value if this key's current value is equal to `bytes`.
+    /// If the value is swapped, [`KeyStatus::Updated`] is returned.
+    /// Otherwise, [`KeyStatus::NotChanged`] is returned.
+    pub const fn only_if_equal_bytes(mut self, bytes: &'a [u8]) -> Self {
+        self.check = Some(PendingCheck::OnlyIfEqual(PendingValue::Bytes(bytes)));
+        self
+    }
+
+    /// Only set the value if this key's current value is equal to the
+    /// numeric value `expected`. If the value is swapped,
+    /// [`KeyStatus::Updated`] is returned. Otherwise,
+    /// [`KeyStatus::NotChanged`] is returned.
+    pub fn only_if_equal_numeric<N: Into<Numeric>>(mut self, expected: N) -> Self {
+        self.check = Some(PendingCheck::OnlyIfEqual(PendingValue::Numeric(
+            expected.into(),
+        )));
         self
     }
 
@@ -98,14 +128,21 @@ where
                 value: value.prepare()?,
                 expiration,
                 keep_existing_expiration,
-                check,
+                check: PendingCheck::prepare(check)?,
                 return_previous_value: true,
             }),
         })?;
         match result {
             Output::Value(value) => Ok(value),
             Output::Status(KeyStatus::NotChanged) => Ok(None),
-            Output::Status(_) => unreachable!("Unexpected output from Set"),
+            Output::Status(_)
+            | Output::Entry(_)
+            | Output::Keys(_)
+            | Output::Batch(_)
+            | Output::Contains(_)
+            | Output::RateLimit(_) => {
+                unreachable!("Unexpected output from Set")
+            }
         }
     }
 
@@ -138,7 +175,7 @@ where
                 value: value.prepare()?,
                 expiration,
                 keep_existing_expiration,
-                check,
+                check: PendingCheck::prepare(check)?,
                 return_previous_value: false,
             }),
         })?;
@@ -163,7 +200,7 @@ struct Options<'a, KeyValue, V> {
     value: PendingValue<'a, V>,
     expiration: Option<Timestamp>,
     keep_existing_expiration: bool,
-    check: Option<KeyCheck>,
+    check: Option<PendingCheck<'a, V>>,
 }
 
 impl<'a, K, V> AsyncBuilder<'a, K, V>
@@ -220,13 +257,43 @@ where
 
     /// Only set the value if this key already exists.
     pub fn only_if_exists(mut self) -> Self {
-        self.options().check = Some(KeyCheck::OnlyIfPresent);
+        self.options().check = Some(PendingCheck::OnlyIfPresent);
         self
     }
 
     /// Only set the value if this key isn't present.
     pub fn only_if_vacant(mut self) -> Self {
-        self.options().check = Some(KeyCheck::OnlyIfVacant);
+        self.options().check = Some(PendingCheck::OnlyIfVacant);
+        self
+    }
+
+    /// Only set the value if this key's current value is equal to
+    /// `expected` after serializing it. If the value is swapped,
+    /// [`KeyStatus::Updated`] is returned. Otherwise,
+    /// [`KeyStatus::NotChanged`] is returned.
+    pub fn only_if_equal(mut self, expected: &'a V) -> Self {
+        self.options().check = Some(PendingCheck::OnlyIfEqual(PendingValue::Serializeable(
+            expected,
+        )));
+        self
+    }
+
+    /// Only set the value if this key's current value is equal to `bytes`.
+    /// If the value is swapped, [`KeyStatus::Updated`] is returned.
+    /// Otherwise, [`KeyStatus::NotChanged`] is returned.
+    pub fn only_if_equal_bytes(mut self, bytes: &'a [u8]) -> Self {
+        self.options().check = Some(PendingCheck::OnlyIfEqual(PendingValue::Bytes(bytes)));
+        self
+    }
+
+    /// Only set the value if this key's current value is equal to the
+    /// numeric value `expected`. If the value is swapped,
+    /// [`KeyStatus::Updated`] is returned. Otherwise,
+    /// [`KeyStatus::NotChanged`] is returned.
+    pub fn only_if_equal_numeric<N: Into<Numeric>>(mut self, expected: N) -> Self {
+        self.options().check = Some(PendingCheck::OnlyIfEqual(PendingValue::Numeric(
+            expected.into(),
+        )));
         self
     }
 
@@ -253,7 +320,7 @@ where
                         value: value.prepare()?,
                         expiration,
                         keep_existing_expiration,
-                        check,
+                        check: PendingCheck::prepare(check)?,
                         return_previous_value: true,
                     }),
                 })
@@ -261,7 +328,14 @@ where
             match result {
                 Output::Value(value) => Ok(value),
                 Output::Status(KeyStatus::NotChanged) => Ok(None),
-                Output::Status(_) => unreachable!("Unexpected output from Set"),
+                Output::Status(_)
+                | Output::Entry(_)
+                | Output::Keys(_)
+                | Output::Batch(_)
+                | Output::Contains(_)
+                | Output::RateLimit(_) => {
+                    unreachable!("Unexpected output from Set")
+                }
             }
         } else {
             panic!("Using future after it's been executed")
@@ -313,7 +387,7 @@ where
                                 value: value.prepare()?,
                                 expiration,
                                 keep_existing_expiration,
-                                check,
+                                check: PendingCheck::prepare(check)?,
                                 return_previous_value: false,
                             }),
                         })
@@ -332,3 +406,25 @@ where
         }
     }
 }
+
+/// A [`KeyCheck`] whose expected value has not been serialized yet.
+enum PendingCheck<'a, V> {
+    OnlyIfPresent,
+    OnlyIfVacant,
+    OnlyIfEqual(PendingValue<'a, V>),
+}
+
+impl<'a, V> PendingCheck<'a, V>
+where
+    V: Serialize,
+{
+    fn prepare(check: Option<Self>) -> Result<Option<KeyCheck>, Error> {
+        check
+            .map(|check| match check {
+                Self::OnlyIfPresent => Ok(KeyCheck::OnlyIfPresent),
+                Self::OnlyIfVacant => Ok(KeyCheck::OnlyIfVacant),
+                Self::OnlyIfEqual(expected) => expected.prepare().map(KeyCheck::OnlyIfEqual),
+            })
+            .transpose()
+    }
+}
@@ -0,0 +1,562 @@
+use std::ops::Add;
+use std::time::{Duration, SystemTime};
+
+use arc_bytes::serde::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::{Command, KeyOperation, KeyStatus, KeyValue, Output, Timestamp};
+use crate::keyvalue::{
+    AsyncKeyValue, HashCommand, ListCommand, ListEnd, Numeric, SetMembersCommand, Value,
+};
+use crate::Error;
+
+/// A handle to a list stored in a key.
+#[must_use]
+pub struct List<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    key: String,
+    expiration: Option<Timestamp>,
+}
+
+impl<'a, K> List<'a, K>
+where
+    K: KeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>, key: String) -> Self {
+        Self {
+            kv,
+            namespace,
+            key,
+            expiration: None,
+        }
+    }
+
+    /// Set the key to expire after `duration` from now when this handle
+    /// pushes.
+    pub fn expire_in(mut self, duration: Duration) -> Self {
+        self.expiration = Some(Timestamp::from(SystemTime::now().add(duration)));
+        self
+    }
+
+    /// Set the key to expire at the provided `time` when this handle pushes.
+    pub fn expire_at(mut self, time: SystemTime) -> Self {
+        self.expiration = Some(Timestamp::from(time));
+        self
+    }
+
+    /// Pushes `value` onto the start of the list. Returns the length of the
+    /// list after pushing.
+    pub fn push_front<V: Serialize>(&self, value: &V) -> Result<u64, Error> {
+        self.push(value, ListEnd::Front)
+    }
+
+    /// Pushes `value` onto the end of the list. Returns the length of the list
+    /// after pushing.
+    pub fn push_back<V: Serialize>(&self, value: &V) -> Result<u64, Error> {
+        self.push(value, ListEnd::Back)
+    }
+
+    /// Removes and returns the value at the start of the list.
+    pub fn pop_front<V: for<'de> Deserialize<'de>>(&self) -> Result<Option<V>, Error> {
+        self.pop(ListEnd::Front)
+    }
+
+    /// Removes and returns the value at the end of the list.
+    pub fn pop_back<V: for<'de> Deserialize<'de>>(&self) -> Result<Option<V>, Error> {
+        self.pop(ListEnd::Back)
+    }
+
+    fn push<V: Serialize>(&self, value: &V, end: ListEnd) -> Result<u64, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::List(ListCommand::Push {
+                value: Bytes::from(pot::to_vec(value)?),
+                end,
+                expiration: self.expiration,
+            }),
+        )?;
+        Ok(into_length(output))
+    }
+
+    fn pop<V: for<'de> Deserialize<'de>>(&self, end: ListEnd) -> Result<Option<V>, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::List(ListCommand::Pop { end }),
+        )?;
+        into_value(output)
+    }
+}
+
+/// A handle to a set stored in a key.
+#[must_use]
+pub struct Set<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    key: String,
+    expiration: Option<Timestamp>,
+}
+
+impl<'a, K> Set<'a, K>
+where
+    K: KeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>, key: String) -> Self {
+        Self {
+            kv,
+            namespace,
+            key,
+            expiration: None,
+        }
+    }
+
+    /// Set the key to expire after `duration` from now when this handle
+    /// adds members.
+    pub fn expire_in(mut self, duration: Duration) -> Self {
+        self.expiration = Some(Timestamp::from(SystemTime::now().add(duration)));
+        self
+    }
+
+    /// Set the key to expire at the provided `time` when this handle adds members.
+    pub fn expire_at(mut self, time: SystemTime) -> Self {
+        self.expiration = Some(Timestamp::from(time));
+        self
+    }
+
+    /// Adds `member` to the set. Returns true if `member` was not already in
+    /// the set.
+    pub fn add<V: Serialize>(&self, member: &V) -> Result<bool, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::SetMembers(SetMembersCommand::Add {
+                member: Bytes::from(pot::to_vec(member)?),
+                expiration: self.expiration,
+            }),
+        )?;
+        Ok(into_status(output) == KeyStatus::Inserted)
+    }
+
+    /// Removes `member` from the set. Returns true if `member` was in the set.
+    pub fn remove<V: Serialize>(&self, member: &V) -> Result<bool, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::SetMembers(SetMembersCommand::Remove(Bytes::from(pot::to_vec(member)?))),
+        )?;
+        Ok(into_status(output) == KeyStatus::Deleted)
+    }
+
+    /// Returns true if the set contains `member`.
+    pub fn contains<V: Serialize>(&self, member: &V) -> Result<bool, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::SetMembers(SetMembersCommand::Contains(Bytes::from(pot::to_vec(
+                member,
+            )?))),
+        )?;
+        Ok(into_contains(output))
+    }
+}
+
+/// A handle to a hash stored in a key.
+#[must_use]
+pub struct Hash<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    key: String,
+    expiration: Option<Timestamp>,
+}
+
+impl<'a, K> Hash<'a, K>
+where
+    K: KeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>, key: String) -> Self {
+        Self {
+            kv,
+            namespace,
+            key,
+            expiration: None,
+        }
+    }
+
+    /// Set the key to expire after `duration` from now when this handle
+    /// sets fields.
+    pub fn expire_in(mut self, duration: Duration) -> Self {
+        self.expiration = Some(Timestamp::from(SystemTime::now().add(duration)));
+        self
+    }
+
+    /// Set the key to expire at the provided `time` when this handle sets fields.
+    pub fn expire_at(mut self, time: SystemTime) -> Self {
+        self.expiration = Some(Timestamp::from(time));
+        self
+    }
+
+    /// Sets `field` to `value`. Returns [`KeyStatus::Inserted`] if the field
+    /// did not exist, otherwise [`KeyStatus::Updated`].
+    pub fn set_field<S: Into<String>, V: Serialize>(
+        &self,
+        field: S,
+        value: &V,
+    ) -> Result<KeyStatus, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::Hash(HashCommand::SetField {
+                field: field.into(),
+                value: Bytes::from(pot::to_vec(value)?),
+                expiration: self.expiration,
+            }),
+        )?;
+        Ok(into_status(output))
+    }
+
+    /// Returns the value stored in `field`.
+    pub fn get_field<S: Into<String>, V: for<'de> Deserialize<'de>>(
+        &self,
+        field: S,
+    ) -> Result<Option<V>, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::Hash(HashCommand::GetField {
+                field: field.into(),
+            }),
+        )?;
+        into_value(output)
+    }
+
+    /// Deletes `field`. Returns true if the field existed.
+    pub fn delete_field<S: Into<String>>(&self, field: S) -> Result<bool, Error> {
+        let output = execute(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::Hash(HashCommand::DeleteField {
+                field: field.into(),
+            }),
+        )?;
+        Ok(into_status(output) == KeyStatus::Deleted)
+    }
+}
+
+/// A handle to a list stored in a key.
+#[must_use]
+pub struct AsyncList<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    key: String,
+    expiration: Option<Timestamp>,
+}
+
+impl<'a, K> AsyncList<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>, key: String) -> Self {
+        Self {
+            kv,
+            namespace,
+            key,
+            expiration: None,
+        }
+    }
+
+    /// Set the key to expire after `duration` from now when this handle
+    /// pushes.
+    pub fn expire_in(mut self, duration: Duration) -> Self {
+        self.expiration = Some(Timestamp::from(SystemTime::now().add(duration)));
+        self
+    }
+
+    /// Set the key to expire at the provided `time` when this handle pushes.
+    pub fn expire_at(mut self, time: SystemTime) -> Self {
+        self.expiration = Some(Timestamp::from(time));
+        self
+    }
+
+    /// Pushes `value` onto the start of the list. Returns the length of the
+    /// list after pushing.
+    pub async fn push_front<V: Serialize + Sync>(&self, value: &V) -> Result<u64, Error> {
+        self.push(value, ListEnd::Front).await
+    }
+
+    /// Pushes `value` onto the end of the list. Returns the length of the list
+    /// after pushing.
+    pub async fn push_back<V: Serialize + Sync>(&self, value: &V) -> Result<u64, Error> {
+        self.push(value, ListEnd::Back).await
+    }
+
+    /// Removes and returns the value at the start of the list.
+    pub async fn pop_front<V: for<'de> Deserialize<'de>>(&self) -> Result<Option<V>, Error> {
+        self.pop(ListEnd::Front).await
+    }
+
+    /// Removes and returns the value at the end of the list.
+    pub async fn pop_back<V: for<'de> Deserialize<'de>>(&self) -> Result<Option<V>, Error> {
+        self.pop(ListEnd::Back).await
+    }
+
+    async fn push<V: Serialize + Sync>(&self, value: &V, end: ListEnd) -> Result<u64, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::List(ListCommand::Push {
+                value: Bytes::from(pot::to_vec(value)?),
+                end,
+                expiration: self.expiration,
+            }),
+        )
+        .await?;
+        Ok(into_length(output))
+    }
+
+    async fn pop<V: for<'de> Deserialize<'de>>(&self, end: ListEnd) -> Result<Option<V>, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::List(ListCommand::Pop { end }),
+        )
+        .await?;
+        into_value(output)
+    }
+}
+
+/// A handle to a set stored in a key.
+#[must_use]
+pub struct AsyncSet<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    key: String,
+    expiration: Option<Timestamp>,
+}
+
+impl<'a, K> AsyncSet<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>, key: String) -> Self {
+        Self {
+            kv,
+            namespace,
+            key,
+            expiration: None,
+        }
+    }
+
+    /// Set the key to expire after `duration` from now when this handle
+    /// adds members.
+    pub fn expire_in(mut self, duration: Duration) -> Self {
+        self.expiration = Some(Timestamp::from(SystemTime::now().add(duration)));
+        self
+    }
+
+    /// Set the key to expire at the provided `time` when this handle adds members.
+    pub fn expire_at(mut self, time: SystemTime) -> Self {
+        self.expiration = Some(Timestamp::from(time));
+        self
+    }
+
+    /// Adds `member` to the set. Returns true if `member` was not already in
+    /// the set.
+    pub async fn add<V: Serialize + Sync>(&self, member: &V) -> Result<bool, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::SetMembers(SetMembersCommand::Add {
+                member: Bytes::from(pot::to_vec(member)?),
+                expiration: self.expiration,
+            }),
+        )
+        .await?;
+        Ok(into_status(output) == KeyStatus::Inserted)
+    }
+
+    /// Removes `member` from the set. Returns true if `member` was in the set.
+    pub async fn remove<V: Serialize + Sync>(&self, member: &V) -> Result<bool, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::SetMembers(SetMembersCommand::Remove(Bytes::from(pot::to_vec(member)?))),
+        )
+        .await?;
+        Ok(into_status(output) == KeyStatus::Deleted)
+    }
+
+    /// Returns true if the set contains `member`.
+    pub async fn contains<V: Serialize + Sync>(&self, member: &V) -> Result<bool, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::SetMembers(SetMembersCommand::Contains(Bytes::from(pot::to_vec(
+                member,
+            )?))),
+        )
+        .await?;
+        Ok(into_contains(output))
+    }
+}
+
+/// A handle to a hash stored in a key.
+#[must_use]
+pub struct AsyncHash<'a, KeyValue> {
+    kv: &'a KeyValue,
+    namespace: Option<String>,
+    key: String,
+    expiration: Option<Timestamp>,
+}
+
+impl<'a, K> AsyncHash<'a, K>
+where
+    K: AsyncKeyValue,
+{
+    pub(crate) const fn new(kv: &'a K, namespace: Option<String>, key: String) -> Self {
+        Self {
+            kv,
+            namespace,
+            key,
+            expiration: None,
+        }
+    }
+
+    /// Set the key to expire after `duration` from now when this handle
+    /// sets fields.
+    pub fn expire_in(mut self, duration: Duration) -> Self {
+        self.expiration = Some(Timestamp::from(SystemTime::now().add(duration)));
+        self
+    }
+
+    /// Set the key to expire at the provided `time` when this handle sets fields.
+    pub fn expire_at(mut self, time: SystemTime) -> Self {
+        self.expiration = Some(Timestamp::from(time));
+        self
+    }
+
+    /// Sets `field` to `value`. Returns [`KeyStatus::Inserted`] if the field
+    /// did not exist, otherwise [`KeyStatus::Updated`].
+    pub async fn set_field<S: Into<String> + Send, V: Serialize + Sync>(
+        &self,
+        field: S,
+        value: &V,
+    ) -> Result<KeyStatus, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::Hash(HashCommand::SetField {
+                field: field.into(),
+                value: Bytes::from(pot::to_vec(value)?),
+                expiration: self.expiration,
+            }),
+        )
+        .await?;
+        Ok(into_status(output))
+    }
+
+    /// Returns the value stored in `field`.
+    pub async fn get_field<S: Into<String> + Send, V: for<'de> Deserialize<'de>>(
+        &self,
+        field: S,
+    ) -> Result<Option<V>, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::Hash(HashCommand::GetField {
+                field: field.into(),
+            }),
+        )
+        .await?;
+        into_value(output)
+    }
+
+    /// Deletes `field`. Returns true if the field existed.
+    pub async fn delete_field<S: Into<String> + Send>(&self, field: S) -> Result<bool, Error> {
+        let output = execute_async(
+            self.kv,
+            self.namespace.clone(),
+            self.key.clone(),
+            Command::Hash(HashCommand::DeleteField {
+                field: field.into(),
+            }),
+        )
+        .await?;
+        Ok(into_status(output) == KeyStatus::Deleted)
+    }
+}
+
+fn execute<K: KeyValue>(
+    kv: &K,
+    namespace: Option<String>,
+    key: String,
+    command: Command,
+) -> Result<Output, Error> {
+    kv.execute_key_operation(KeyOperation {
+        namespace,
+        key,
+        command,
+    })
+}
+
+async fn execute_async<K: AsyncKeyValue>(
+    kv: &K,
+    namespace: Option<String>,
+    key: String,
+    command: Command,
+) -> Result<Output, Error> {
+    kv.execute_key_operation(KeyOperation {
+        namespace,
+        key,
+        command,
+    })
+    .await
+}
+
+fn into_length(output: Output) -> u64 {
+    if let Output::Value(Some(Value::Numeric(Numeric::UnsignedInteger(length)))) = output {
+        length
+    } else {
+        unreachable!("Unexpected output from list push")
+    }
+}
+
+fn into_value<V: for<'de> Deserialize<'de>>(output: Output) -> Result<Option<V>, Error> {
+    if let Output::Value(value) = output {
+        value.as_ref().map(Value::deserialize).transpose()
+    } else {
+        unreachable!("Unexpected output from key-value operation")
+    }
+}
+
+fn into_status(output: Output) -> KeyStatus {
+    if let Output::Status(status) = output {
+        status
+    } else {
+        unreachable!("Unexpected output from key-value operation")
+    }
+}
+
+fn into_contains(output: Output) -> bool {
+    if let Output::Contains(contains) = output {
+        contains
+    } else {
+        unreachable!("Unexpected output from set contains")
+    }
+}
@@ -0,0 +1,106 @@
+use std::marker::PhantomData;
+
+use super::namespaced::Namespaced;
+use super::{set, KeyStatus, KeyValue};
+use crate::keyvalue::{AsyncKeyValue, KeyValueNamespace};
+use crate::Error;
+
+/// A handle to a [`KeyValueNamespace`]. Keys and values are checked against
+/// the namespace's types at compile time.
+#[must_use]
+pub struct TypedNamespace<'a, N, KeyValue> {
+    namespace: Namespaced<'a, KeyValue>,
+    _namespace: PhantomData<N>,
+}
+
+impl<'a, N, K> TypedNamespace<'a, N, K>
+where
+    N: KeyValueNamespace,
+    K: KeyValue,
+{
+    pub(crate) const fn new(namespace: Namespaced<'a, K>) -> Self {
+        Self {
+            namespace,
+            _namespace: PhantomData,
+        }
+    }
+
+    /// Returns the value stored for `key`, if present.
+    pub fn get(&self, key: &N::Key) -> Result<Option<N::Value>, Error> {
+        self.namespace.get_key(N::encode_key(key)?).into()
+    }
+
+    /// Removes and returns the value stored for `key`, if present.
+    pub fn take(&self, key: &N::Key) -> Result<Option<N::Value>, Error> {
+        self.namespace
+            .get_key(N::encode_key(key)?)
+            .and_delete()
+            .into()
+    }
+
+    /// Sets `key` to `value`. This function returns a builder that can be
+    /// used to customize the operation before calling
+    /// [`execute()`](set::Builder::execute).
+    pub fn set<'v>(
+        &'v self,
+        key: &N::Key,
+        value: &'v N::Value,
+    ) -> Result<set::Builder<'v, Namespaced<'a, K>, N::Value>, Error> {
+        Ok(self.namespace.set_key(N::encode_key(key)?, value))
+    }
+
+    /// Deletes the value stored for `key`.
+    pub fn delete(&self, key: &N::Key) -> Result<KeyStatus, Error> {
+        self.namespace.delete_key(N::encode_key(key)?)
+    }
+}
+
+/// A handle to a [`KeyValueNamespace`]. Keys and values are checked against
+/// the namespace's types at compile time.
+#[must_use]
+pub struct AsyncTypedNamespace<'a, N, KeyValue> {
+    namespace: Namespaced<'a, KeyValue>,
+    _namespace: PhantomData<N>,
+}
+
+impl<'a, N, K> AsyncTypedNamespace<'a, N, K>
+where
+    N: KeyValueNamespace,
+    K: AsyncKeyValue,
+{
+    pub(crate) const fn new(namespace: Namespaced<'a, K>) -> Self {
+        Self {
+            namespace,
+            _namespace: PhantomData,
+        }
+    }
+
+    /// Returns the value stored for `key`, if present.
+    pub async fn get(&self, key: &N::Key) -> Result<Option<N::Value>, Error> {
+        self.namespace.get_key(N::encode_key(key)?).into().await
+    }
+
+    /// Removes and returns the value stored for `key`, if present.
+    pub async fn take(&self, key: &N::Key) -> Result<Option<N::Value>, Error> {
+        self.namespace
+            .get_key(N::encode_key(key)?)
+            .and_delete()
+            .into()
+            .await
+    }
+
+    /// Sets `key` to `value`. This function returns a builder that is also a
+    /// Future. Awaiting the builder will execute the operation.
+    pub fn set<'v>(
+        &'v self,
+        key: &N::Key,
+        value: &'v N::Value,
+    ) -> Result<set::AsyncBuilder<'v, Namespaced<'a, K>, N::Value>, Error> {
+        Ok(self.namespace.set_key(N::encode_key(key)?, value))
+    }
+
+    /// Deletes the value stored for `key`.
+    pub async fn delete(&self, key: &N::Key) -> Result<KeyStatus, Error> {
+        self.namespace.delete_key(N::encode_key(key)?).await
+    }
+}
@@ -156,6 +156,20 @@ pub enum Error {
         existing_document: Box<Header>,
     },
 
+    /// The documents stored in `collection` are at `stored_version`, and no
+    /// sequence of migrations is able to upgrade them to `version`.
+    #[error(
+        "collection {collection} can not be migrated from version {stored_version} to {version}"
+    )]
+    MigrationUnavailable {
+        /// The collection that could not be migrated.
+        collection: CollectionName,
+        /// The version the stored documents are at.
+        stored_version: u64,
+        /// The version the collection is defined as.
+        version: u64,
+    },
+
     /// When pushing a document, an error occurred while generating the next unique id.
     #[error("an error occurred generating a new unique id for {0}: {1}")]
     DocumentPush(CollectionName, NextValueError),
@@ -198,6 +212,11 @@ pub enum Error {
     #[error("time error: {0}")]
     Time(#[from] TimeError),
 
+    /// A message was published or subscribed to using a topic reserved for
+    /// BonsaiDb's own use. See [`pubsub::is_reserved_topic()`].
+    #[error("topic is reserved")]
+    ReservedTopic,
+
     /// An error from another crate.
     #[error("error from {origin}: {error}")]
     Other {
@@ -13,6 +13,20 @@
 //! [`Connection::list_executed_transactions()`](crate::connection::Connection::list_executed_transactions),
 //! the result set will be limited to [`LIST_TRANSACTIONS_MAX_RESULTS`] entries.
 //!
+//! # Streaming Limits
+//!
+//! When iterating over the results of a list or view query as they are
+//! retrieved, such as with
+//! [`View::iter()`](crate::connection::View::iter), the results are requested
+//! in chunks. Each chunk contains at most the query's chunk size, or
+//! [`STREAM_DEFAULT_CHUNK_SIZE`] results if no chunk size was set. The query's
+//! limit caps the total number of results returned.
+//!
+//! When iterating over results from a server, each session can have at most
+//! [`STREAM_MAX_OPEN_PER_SESSION`] streams open at once. A stream whose next
+//! chunk isn't requested within [`STREAM_IDLE_TIMEOUT`] is closed by the
+//! server.
+//!
 //! # Document Limits
 //!
 //! ## Primary Key Limits
@@ -48,7 +62,17 @@
 //!
 //! [nebari]: https://github.com/khonsulabs/nebari
 
+use std::time::Duration;
+
 /// The maximum number of results allowed to be returned from `list_executed_transactions`.
 pub const LIST_TRANSACTIONS_MAX_RESULTS: u32 = 1000;
 /// If no `result_limit` is specified, this value is the limit used by default.
 pub const LIST_TRANSACTIONS_DEFAULT_RESULT_COUNT: u32 = 100;
+/// When streaming results without a chunk size, this is the number of results
+/// requested at a time.
+pub const STREAM_DEFAULT_CHUNK_SIZE: u32 = 1000;
+/// The maximum number of streams a session can have open on a server at once.
+pub const STREAM_MAX_OPEN_PER_SESSION: u32 = 16;
+/// The amount of time a server waits for a stream's next chunk to be requested
+/// before closing the stream.
+pub const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
@@ -4,8 +4,9 @@ use serde::{Deserialize, Serialize};
 use crate::api::{Api, ApiName};
 use crate::connection::{
     AccessPolicy, Database, IdentityReference, Range, SerializedQueryKey, Session, SessionId, Sort,
+    ViewCursor, ViewSetQuery,
 };
-use crate::document::{DocumentId, Header, OwnedDocument};
+use crate::document::{DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection};
 use crate::keyvalue::{KeyOperation, Output};
 use crate::schema::view::map::{self, MappedSerializedDocuments};
 use crate::schema::{CollectionName, NamedReference, Qualified, SchemaSummary, ViewName};
@@ -280,6 +281,8 @@ pub struct List {
     pub order: Sort,
     /// The maximum number of results to return.
     pub limit: Option<u32>,
+    /// The filter documents must match to be returned.
+    pub filter: Option<Filter>,
 }
 
 impl Api for List {
@@ -291,6 +294,24 @@ impl Api for List {
     }
 }
 
+/// Retrieve the selected fields of multiple documents.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct ListProjected {
+    /// The documents to list.
+    pub list: List,
+    /// The fields of each document's contents to return.
+    pub projection: Projection,
+}
+
+impl Api for ListProjected {
+    type Error = crate::Error;
+    type Response = Vec<ProjectedDocument>;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "ListProjected")
+    }
+}
+
 /// Retrieve multiple document headers.
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct ListHeaders(pub List);
@@ -337,6 +358,8 @@ pub struct Query {
     pub order: Sort,
     /// The maximum number of results to return.
     pub limit: Option<u32>,
+    /// The position to resume the query after.
+    pub cursor: Option<ViewCursor>,
     /// The access policy for the query.
     pub access_policy: AccessPolicy,
 }
@@ -352,7 +375,12 @@ impl Api for Query {
 
 /// Queries a view with the associated documents.
 #[derive(Clone, Deserialize, Serialize, Debug)]
-pub struct QueryWithDocs(pub Query);
+pub struct QueryWithDocs {
+    /// The view query.
+    pub query: Query,
+    /// The filter source documents must match to be returned.
+    pub filter: Option<Filter>,
+}
 
 impl Api for QueryWithDocs {
     type Error = crate::Error;
@@ -363,6 +391,177 @@ impl Api for QueryWithDocs {
     }
 }
 
+/// Queries a view with the selected fields of the associated documents.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct QueryWithProjectedDocs {
+    /// The view query.
+    pub query: QueryWithDocs,
+    /// The fields of each source document's contents to return.
+    pub projection: Projection,
+}
+
+impl Api for QueryWithProjectedDocs {
+    type Error = crate::Error;
+    type Response = MappedSerializedDocuments<ProjectedDocument>;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "QueryWithProjectedDocs")
+    }
+}
+
+/// Opens a stream of the documents listed by `list`. The id of the stream is
+/// returned, and its results can be retrieved using [`NextChunk`].
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct StreamList {
+    /// The documents to list.
+    pub list: List,
+    /// The maximum number of documents to return in each chunk.
+    pub chunk_size: u32,
+}
+
+impl Api for StreamList {
+    type Error = crate::Error;
+    type Response = u64;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "StreamList")
+    }
+}
+
+/// Opens a stream of the mappings returned by `query`. The id of the stream
+/// is returned, and its results can be retrieved using [`NextChunk`].
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct StreamQuery {
+    /// The view query.
+    pub query: Query,
+    /// The maximum number of mappings to return in each chunk.
+    pub chunk_size: u32,
+}
+
+impl Api for StreamQuery {
+    type Error = crate::Error;
+    type Response = u64;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "StreamQuery")
+    }
+}
+
+/// Opens a stream of the mappings returned by `query` with their source
+/// documents. The id of the stream is returned, and its results can be
+/// retrieved using [`NextChunk`].
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct StreamQueryWithDocs {
+    /// The view query.
+    pub query: QueryWithDocs,
+    /// The maximum number of mappings to return in each chunk.
+    pub chunk_size: u32,
+}
+
+impl Api for StreamQueryWithDocs {
+    type Error = crate::Error;
+    type Response = u64;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "StreamQueryWithDocs")
+    }
+}
+
+/// Retrieves the next chunk of results from a stream. Returns `None` once
+/// every result has been returned, after which the stream is closed.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct NextChunk {
+    /// The id of the stream.
+    pub stream_id: u64,
+}
+
+impl Api for NextChunk {
+    type Error = crate::Error;
+    type Response = Option<ResultChunk>;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "NextChunk")
+    }
+}
+
+/// A chunk of results returned from [`NextChunk`].
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub enum ResultChunk {
+    /// Documents from a [`StreamList`] stream.
+    Documents(Vec<OwnedDocument>),
+    /// Mappings from a [`StreamQuery`] stream.
+    Mappings(Vec<map::Serialized>),
+    /// Mappings and their source documents from a [`StreamQueryWithDocs`]
+    /// stream.
+    MappedDocuments(MappedSerializedDocuments),
+}
+
+impl ResultChunk {
+    /// Returns the documents of a [`ResultChunk::Documents`] chunk.
+    #[must_use]
+    pub fn into_documents(self) -> Option<Vec<OwnedDocument>> {
+        match self {
+            Self::Documents(documents) => Some(documents),
+            _ => None,
+        }
+    }
+
+    /// Returns the mappings of a [`ResultChunk::Mappings`] chunk.
+    #[must_use]
+    pub fn into_mappings(self) -> Option<Vec<map::Serialized>> {
+        match self {
+            Self::Mappings(mappings) => Some(mappings),
+            _ => None,
+        }
+    }
+
+    /// Returns the mappings and documents of a
+    /// [`ResultChunk::MappedDocuments`] chunk.
+    #[must_use]
+    pub fn into_mapped_documents(self) -> Option<MappedSerializedDocuments> {
+        match self {
+            Self::MappedDocuments(mapped) => Some(mapped),
+            _ => None,
+        }
+    }
+}
+
+/// Closes a stream before all of its results have been retrieved.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct CloseStream {
+    /// The id of the stream.
+    pub stream_id: u64,
+}
+
+impl Api for CloseStream {
+    type Error = crate::Error;
+    type Response = ();
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "CloseStream")
+    }
+}
+
+/// Queries the documents matched by a combination of view queries.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct QueryViewSet {
+    /// The name of the database.
+    pub database: String,
+    /// The combination of view queries.
+    pub query: ViewSetQuery,
+    /// The maximum number of documents to return.
+    pub limit: Option<u32>,
+}
+
+impl Api for QueryViewSet {
+    type Error = crate::Error;
+    type Response = Vec<OwnedDocument>;
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "QueryViewSet")
+    }
+}
+
 /// Reduces a view.
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct Reduce {
@@ -550,6 +749,50 @@ impl Api for SubscribeTo {
     }
 }
 
+/// Subscribes `subscriber_id` to the changes made to `collection`. Unlike
+/// [`SubscribeTo`], this requires permission to list and get the collection's
+/// documents.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SubscribeToCollectionChanges {
+    /// The name of the database.
+    pub database: String,
+    /// The id of the [`Subscriber`](crate::pubsub::Subscriber).
+    pub subscriber_id: u64,
+    /// The collection to receive changes for.
+    pub collection: CollectionName,
+}
+
+impl Api for SubscribeToCollectionChanges {
+    type Error = crate::Error;
+    type Response = ();
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "SubscribeToCollectionChanges")
+    }
+}
+
+/// Subscribes `subscriber_id` to the changes made to keys in `namespace`.
+/// Unlike [`SubscribeTo`], this requires permission to execute key-value
+/// operations in the namespace.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SubscribeToKeyChanges {
+    /// The name of the database.
+    pub database: String,
+    /// The id of the [`Subscriber`](crate::pubsub::Subscriber).
+    pub subscriber_id: u64,
+    /// The namespace to receive key changes for.
+    pub namespace: Option<String>,
+}
+
+impl Api for SubscribeToKeyChanges {
+    type Error = crate::Error;
+    type Response = ();
+
+    fn name() -> ApiName {
+        ApiName::new("bonsaidb", "SubscribeToKeyChanges")
+    }
+}
+
 /// A PubSub message was received.
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct MessageReceived {
@@ -60,6 +60,15 @@ pub fn kv_resource_name(database: &str) -> ResourceName<'_> {
     database_resource_name(database).and("keyvalue")
 }
 
+/// Creates a resource name for `namespace` within the key-value store of `database`.
+#[must_use]
+pub fn keyvalue_namespace_resource_name<'a>(
+    database: &'a str,
+    namespace: Option<&'a str>,
+) -> ResourceName<'a> {
+    kv_resource_name(database).and(namespace.unwrap_or(""))
+}
+
 /// Creates a resource name for `key` within `namespace` within the key-value store of `database`.
 #[must_use]
 pub fn keyvalue_key_resource_name<'a>(
@@ -274,6 +283,8 @@ pub enum KeyValueAction {
     /// Allows executing a key-value store operation with
     /// [`KeyValue::execute_key_operation()`](crate::keyvalue::KeyValue::execute_key_operation).
     /// See [`keyvalue_key_resource_name()`] for the format of key resource names.
+    /// Listing keys with [`Command::Scan`](crate::keyvalue::Command::Scan) is
+    /// checked against [`keyvalue_namespace_resource_name()`] instead.
     ExecuteOperation,
 }
 
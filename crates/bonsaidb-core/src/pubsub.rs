@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use circulate::{flume, Message};
 use serde::Serialize;
 
+use crate::schema::CollectionName;
 use crate::Error;
 
 /// Publishes and Subscribes to messages on topics.
@@ -68,6 +69,30 @@ pub trait Subscriber {
     /// Unsubscribe from [`Message`]s published to `topic`.
     fn unsubscribe_from_bytes(&self, topic: &[u8]) -> Result<(), Error>;
 
+    /// Subscribe to the
+    /// [`CollectionChanges`](crate::transaction::CollectionChanges) made to
+    /// `collection`. The payload of each [`Message`] is a pot-encoded
+    /// `CollectionChanges`.
+    ///
+    /// Because the changes contain the ids of the changed documents,
+    /// subscribing requires permission to list and get the collection's
+    /// documents rather than permission to subscribe to a topic.
+    fn subscribe_to_collection_changes(&self, collection: &CollectionName) -> Result<(), Error> {
+        self.subscribe_to_bytes(collection_changes_topic(collection))
+    }
+
+    /// Subscribe to the [`KeyChange`](crate::keyvalue::KeyChange)s made to
+    /// keys in `namespace`. Changes are only published if key-value
+    /// notifications have been enabled on the server. The payload of each
+    /// [`Message`] is a pot-encoded `KeyChange`.
+    ///
+    /// Because the changes contain the names of the changed keys, subscribing
+    /// requires permission to execute key-value operations in `namespace`
+    /// rather than permission to subscribe to a topic.
+    fn subscribe_to_key_changes(&self, namespace: Option<&str>) -> Result<(), Error> {
+        self.subscribe_to_bytes(key_changes_topic(namespace))
+    }
+
     /// Returns the receiver to receive [`Message`]s.
     fn receiver(&self) -> &Receiver;
 }
@@ -147,6 +172,34 @@ pub trait AsyncSubscriber: Send + Sync {
     /// Unsubscribe from [`Message`]s published to `topic`.
     async fn unsubscribe_from_bytes(&self, topic: &[u8]) -> Result<(), Error>;
 
+    /// Subscribe to the
+    /// [`CollectionChanges`](crate::transaction::CollectionChanges) made to
+    /// `collection`. The payload of each [`Message`] is a pot-encoded
+    /// `CollectionChanges`.
+    ///
+    /// Because the changes contain the ids of the changed documents,
+    /// subscribing requires permission to list and get the collection's
+    /// documents rather than permission to subscribe to a topic.
+    async fn subscribe_to_collection_changes(
+        &self,
+        collection: &CollectionName,
+    ) -> Result<(), Error> {
+        self.subscribe_to_bytes(collection_changes_topic(collection))
+            .await
+    }
+
+    /// Subscribe to the [`KeyChange`](crate::keyvalue::KeyChange)s made to
+    /// keys in `namespace`. Changes are only published if key-value
+    /// notifications have been enabled on the server. The payload of each
+    /// [`Message`] is a pot-encoded `KeyChange`.
+    ///
+    /// Because the changes contain the names of the changed keys, subscribing
+    /// requires permission to execute key-value operations in `namespace`
+    /// rather than permission to subscribe to a topic.
+    async fn subscribe_to_key_changes(&self, namespace: Option<&str>) -> Result<(), Error> {
+        self.subscribe_to_bytes(key_changes_topic(namespace)).await
+    }
+
     /// Returns the receiver to receive [`Message`]s.
     fn receiver(&self) -> &Receiver;
 }
@@ -265,6 +318,61 @@ pub fn database_topic(database: &str, topic: &[u8]) -> Vec<u8> {
     namespaced_topic
 }
 
+/// The prefix of all topics reserved for BonsaiDb's own use. User-provided
+/// topics that begin with this prefix are rejected when publishing.
+const RESERVED_TOPIC_PREFIX: &[u8] = b"\0bonsaidb\0";
+
+/// The segment following [`RESERVED_TOPIC_PREFIX`] in the topics that
+/// collection changes are published to.
+const COLLECTION_CHANGES_SEGMENT: &[u8] = b"collection\0";
+
+/// The segment following [`RESERVED_TOPIC_PREFIX`] in the topics that key
+/// changes are published to.
+const KEY_CHANGES_SEGMENT: &[u8] = b"keys\0";
+
+/// Returns true if `topic` is reserved for BonsaiDb's own use. Attempting to
+/// publish to a reserved topic returns [`Error::ReservedTopic`], as does
+/// subscribing to a reserved topic without using
+/// [`Subscriber::subscribe_to_collection_changes()`] or
+/// [`Subscriber::subscribe_to_key_changes()`].
+#[must_use]
+pub fn is_reserved_topic(topic: &[u8]) -> bool {
+    topic.starts_with(RESERVED_TOPIC_PREFIX)
+}
+
+/// Creates the reserved topic that
+/// [`CollectionChanges`](crate::transaction::CollectionChanges) for
+/// `collection` are published to. This is an internal API, which is why the
+/// documentation is hidden. Both Client and Server must agree on this format,
+/// which is why it lives in core.
+#[doc(hidden)]
+#[must_use]
+pub fn collection_changes_topic(collection: &CollectionName) -> Vec<u8> {
+    let collection = collection.to_string();
+    let mut topic = Vec::with_capacity(
+        RESERVED_TOPIC_PREFIX.len() + COLLECTION_CHANGES_SEGMENT.len() + collection.len(),
+    );
+    topic.extend(RESERVED_TOPIC_PREFIX);
+    topic.extend(COLLECTION_CHANGES_SEGMENT);
+    topic.extend(collection.bytes());
+    topic
+}
+
+/// Returns the reserved topic that
+/// [`KeyChange`](crate::keyvalue::KeyChange)s for keys in `namespace` are
+/// published to. An empty namespace is treated the same as no namespace.
+#[must_use]
+pub fn key_changes_topic(namespace: Option<&str>) -> Vec<u8> {
+    let namespace = namespace.unwrap_or_default();
+    let mut topic = Vec::with_capacity(
+        RESERVED_TOPIC_PREFIX.len() + KEY_CHANGES_SEGMENT.len() + namespace.len(),
+    );
+    topic.extend(RESERVED_TOPIC_PREFIX);
+    topic.extend(KEY_CHANGES_SEGMENT);
+    topic.extend(namespace.bytes());
+    topic
+}
+
 /// Expands into a suite of pubsub unit tests using the passed type as the test harness.
 #[cfg(feature = "test-util")]
 #[macro_export]
@@ -445,6 +553,59 @@ macro_rules! define_async_pubsub_test_suite {
 
                 Ok(())
             }
+
+            #[tokio::test]
+            async fn watch_collection_test() -> anyhow::Result<()> {
+                use $crate::connection::AsyncConnection;
+                use $crate::document::DocumentId;
+                use $crate::schema::{Collection, SerializedCollection};
+                use $crate::test_util::Basic;
+
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::PubSubWatchCollection).await?;
+                let db = harness.connect().await?;
+                let mut watcher = db.watch_collection::<Basic>().await?;
+
+                let doc = Basic::new("a").push_into_async(&db).await?;
+                let pushed = watcher.next_changes().await?.expect("watcher disconnected");
+                assert_eq!(pushed.collection, Basic::collection_name());
+                assert_eq!(pushed.changes.len(), 1);
+                assert_eq!(pushed.changes[0].id, DocumentId::from_u64(doc.header.id));
+                assert!(!pushed.changes[0].deleted);
+
+                db.collection::<Basic>().delete(&doc).await?;
+                let deleted = watcher.next_changes().await?.expect("watcher disconnected");
+                assert!(deleted.transaction_id > pushed.transaction_id);
+                assert!(deleted.changes[0].deleted);
+
+                // Resuming replays the transaction log before delivering live
+                // changes, without delivering any transaction twice.
+                let mut resumed = db
+                    .watch_collection::<Basic>()
+                    .await?
+                    .resume_after(pushed.transaction_id);
+                let doc = Basic::new("b").push_into_async(&db).await?;
+                let replayed = resumed.next_changes().await?.expect("watcher disconnected");
+                assert_eq!(replayed.transaction_id, deleted.transaction_id);
+                let replayed = resumed.next_changes().await?.expect("watcher disconnected");
+                assert_eq!(replayed.changes[0].id, DocumentId::from_u64(doc.header.id));
+                let doc = Basic::new("c").push_into_async(&db).await?;
+                let live = resumed.next_changes().await?.expect("watcher disconnected");
+                assert_eq!(live.changes[0].id, DocumentId::from_u64(doc.header.id));
+
+                // Change notifications can't be forged.
+                assert!(matches!(
+                    AsyncPubSub::publish_bytes(
+                        &db,
+                        $crate::pubsub::collection_changes_topic(&Basic::collection_name()),
+                        Vec::new()
+                    )
+                    .await,
+                    Err($crate::Error::ReservedTopic)
+                ));
+
+                Ok(())
+            }
         }
     };
 }
@@ -594,6 +755,56 @@ macro_rules! define_blocking_pubsub_test_suite {
 
                 Ok(())
             }
+
+            #[test]
+            fn watch_collection_test() -> anyhow::Result<()> {
+                use $crate::connection::Connection;
+                use $crate::document::DocumentId;
+                use $crate::schema::{Collection, SerializedCollection};
+                use $crate::test_util::Basic;
+
+                let harness = $harness::new($crate::test_util::HarnessTest::PubSubWatchCollection)?;
+                let db = harness.connect()?;
+                let mut watcher = db.watch_collection::<Basic>()?;
+
+                let doc = Basic::new("a").push_into(&db)?;
+                let pushed = watcher.next_changes()?.expect("watcher disconnected");
+                assert_eq!(pushed.collection, Basic::collection_name());
+                assert_eq!(pushed.changes.len(), 1);
+                assert_eq!(pushed.changes[0].id, DocumentId::from_u64(doc.header.id));
+                assert!(!pushed.changes[0].deleted);
+
+                db.collection::<Basic>().delete(&doc)?;
+                let deleted = watcher.next_changes()?.expect("watcher disconnected");
+                assert!(deleted.transaction_id > pushed.transaction_id);
+                assert!(deleted.changes[0].deleted);
+
+                // Resuming replays the transaction log before delivering live
+                // changes, without delivering any transaction twice.
+                let mut resumed = db
+                    .watch_collection::<Basic>()?
+                    .resume_after(pushed.transaction_id);
+                let doc = Basic::new("b").push_into(&db)?;
+                let replayed = resumed.next().expect("watcher disconnected")?;
+                assert_eq!(replayed.transaction_id, deleted.transaction_id);
+                let replayed = resumed.next().expect("watcher disconnected")?;
+                assert_eq!(replayed.changes[0].id, DocumentId::from_u64(doc.header.id));
+                let doc = Basic::new("c").push_into(&db)?;
+                let live = resumed.next().expect("watcher disconnected")?;
+                assert_eq!(live.changes[0].id, DocumentId::from_u64(doc.header.id));
+
+                // Change notifications can't be forged.
+                assert!(matches!(
+                    PubSub::publish_bytes(
+                        &db,
+                        $crate::pubsub::collection_changes_topic(&Basic::collection_name()),
+                        Vec::new()
+                    ),
+                    Err($crate::Error::ReservedTopic)
+                ));
+
+                Ok(())
+            }
         }
     };
 }
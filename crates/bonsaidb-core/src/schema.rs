@@ -9,8 +9,8 @@ use std::fmt::Debug;
 pub use bonsaidb_macros::{Collection, Schema, View};
 
 pub use self::collection::{
-    AsyncEntry, AsyncList, Collection, DefaultSerialization, InsertError, List, Nameable,
-    NamedCollection, NamedReference, SerializedCollection,
+    AsyncEntry, AsyncList, Collection, DefaultSerialization, InsertError, List, Migration,
+    Nameable, NamedCollection, NamedReference, SerializedCollection,
 };
 pub use self::names::{
     Authority, CollectionName, InvalidNameError, Name, Qualified, QualifiedName, SchemaName,
@@ -18,6 +18,7 @@ pub use self::names::{
 };
 pub use self::schematic::Schematic;
 pub use self::summary::{CollectionSummary, SchemaSummary, ViewSummary};
+pub use self::view::full_text::{Analyzer, FullTextIndex};
 pub use self::view::map::{Map, MappedValue, ViewMappedValue};
 pub use self::view::{
     CollectionViewSchema, DefaultViewSerialization, ReduceResult, SerializedView, View,
@@ -5,16 +5,16 @@ use std::task::Poll;
 
 use async_trait::async_trait;
 use futures::future::BoxFuture;
-use futures::{ready, Future, FutureExt};
+use futures::{ready, Future, FutureExt, Stream, StreamExt};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use transmog::{Format, OwnedDeserializer};
 use transmog_pot::Pot;
 
-use crate::connection::{self, AsyncConnection, Connection, RangeRef};
+use crate::connection::{self, AsyncConnection, Connection, ListCursor, Page, RangeRef};
 use crate::document::{
-    BorrowedDocument, CollectionDocument, CollectionHeader, Document, DocumentId, Header, KeyId,
-    OwnedDocument, OwnedDocuments, Revision,
+    BorrowedDocument, CollectionDocument, CollectionHeader, Document, DocumentId, Filter, Header,
+    KeyId, OwnedDocument, OwnedDocuments, ProjectedDocument, Revision,
 };
 use crate::key::{IntoPrefixRange, Key, KeyEncoding};
 use crate::schema::{CollectionName, Schematic};
@@ -177,6 +177,82 @@ use crate::Error;
 /// pub struct MyCollection;
 /// ```
 ///
+/// ### Versioning and Migrations
+///
+/// When the structure of a collection's documents changes in a way that
+/// prevents previously stored documents from being deserialized, the
+/// collection's `version` can be increased and a migration function can be
+/// provided for each version. Each function upgrades a stored document from the
+/// previous version to the next. The first function in `migrations` upgrades
+/// from `version - migrations.len()`:
+///
+/// ```rust
+/// use bonsaidb_core::document::OwnedDocument;
+/// use bonsaidb_core::schema::{Collection, SerializedCollection};
+/// use bonsaidb_core::Error;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Default, Collection)]
+/// #[collection(name = "MyCollection", version = 1, migrations = [add_score])]
+/// # #[collection(core = bonsaidb_core)]
+/// pub struct MyCollection {
+///     pub rank: u32,
+///     pub score: f32,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct MyCollectionV0 {
+///     rank: u32,
+/// }
+///
+/// fn add_score(document: &mut OwnedDocument) -> Result<(), Error> {
+///     let previous = pot::from_slice::<MyCollectionV0>(&document.contents)?;
+///     document.contents = MyCollection::serialize(&MyCollection {
+///         rank: previous.rank,
+///         score: 0.,
+///     })?
+///     .into();
+///     Ok(())
+/// }
+/// ```
+///
+/// Pending migrations are applied when the database is opened by the storage.
+///
+/// ### Unique Constraints
+///
+/// The `unique` parameter generates a unique view for each listed field or
+/// tuple of fields. Storing a document whose fields match an existing
+/// document's fields returns [`Error::UniqueKeyViolation`](crate::Error::UniqueKeyViolation)
+/// naming the generated view:
+///
+/// ```rust
+/// use bonsaidb_core::schema::Collection;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Default, Collection)]
+/// #[collection(name = "users", unique = [(tenant_id, email)])]
+/// # #[collection(core = bonsaidb_core)]
+/// pub struct User {
+///     pub tenant_id: u32,
+///     pub email: String,
+/// }
+///
+/// // The generated view is named after the collection and its fields.
+/// let _view = UserUniqueTenantIdAndEmail;
+/// ```
+///
+/// The generated views are named `{Collection}Unique{Fields}`, and their
+/// [`Name`](crate::schema::Name)s are `unique-` followed by the field names
+/// separated by `-`. Unique constraints require a struct with named fields
+/// that is its own [`SerializedCollection::Contents`].
+///
+/// ### Full-Text Indexes
+///
+/// The `full_text` parameter registers each listed
+/// [`FullTextIndex`](crate::schema::FullTextIndex) with the collection:
+/// `#[collection(name = "messages", full_text = [MessageSearch])]`. See
+/// [`FullTextIndex`](crate::schema::FullTextIndex) for a complete example.
+///
 /// ### Changing the serialization strategy
 ///
 /// BonsaiDb uses [`transmog`](https://github.com/khonsulabs/transmog) to allow
@@ -231,8 +307,27 @@ pub trait Collection: Debug + Send + Sync {
     fn encryption_key() -> Option<KeyId> {
         None
     }
+
+    /// The version of this collection's document format. When a database is
+    /// opened, documents stored with an older version are upgraded using the
+    /// migrations defined in [`Self::define_migrations()`].
+    #[must_use]
+    fn version() -> u64 {
+        0
+    }
+
+    /// Defines all migrations for this collection in `schema` using
+    /// [`Schematic::define_migration()`].
+    #[allow(unused_variables)]
+    fn define_migrations(schema: &mut Schematic) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
+/// A function that upgrades a stored document to the next version of its
+/// collection. See [`Collection::version()`] for more information.
+pub type Migration = fn(&mut OwnedDocument) -> Result<(), Error>;
+
 /// A collection that knows how to serialize and deserialize documents to an associated type.
 ///
 /// These examples for this type use this basic collection definition:
@@ -272,6 +367,20 @@ pub trait SerializedCollection: Collection {
     // TODO allow configuration to be passed here, such as max allocation bytes.
     fn format() -> Self::Format;
 
+    /// Returns true if the database can select fields from this collection's
+    /// contents using a [`Projection`](crate::document::Projection).
+    /// Projections can only decode contents serialized using Pot or JSON, and
+    /// projected queries of collections that return false are rejected before
+    /// they are executed.
+    ///
+    /// The default implementation returns true if [`Self::Format`] is
+    /// `transmog_pot::Pot` or `transmog_json::Json`.
+    #[must_use]
+    fn supports_projections() -> bool {
+        let format = std::any::type_name::<Self::Format>();
+        format == std::any::type_name::<Pot>() || format == "transmog_json::Json"
+    }
+
     /// Deserialize `data` as `Self::Contents` using this collection's format.
     fn deserialize(data: &[u8]) -> Result<Self::Contents, Error> {
         Self::format()
@@ -2028,6 +2137,53 @@ where
         self
     }
 
+    /// Sets the number of documents [`iter()`](Self::iter) retrieves at a
+    /// time. If not set, [`STREAM_DEFAULT_CHUNK_SIZE`](crate::limits::STREAM_DEFAULT_CHUNK_SIZE)
+    /// documents are retrieved at a time.
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn chunk_size(mut self, documents: u32) -> Self {
+        self.0 = self.0.chunk_size(documents);
+        self
+    }
+
+    /// Resumes listing documents from `cursor`, in the direction of the
+    /// list's sort order.
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn after(mut self, cursor: ListCursor) -> Self {
+        self.0 = self.0.after(cursor);
+        self
+    }
+
+    /// Only returns documents whose contents match `filter`. The filter is
+    /// evaluated by the database before documents are returned, and only
+    /// matching documents count towards [`limit`](Self::limit).
+    #[allow(clippy::missing_const_for_fn)] // false positive, destructors
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.0 = self.0.with_filter(filter);
+        self
+    }
+
+    /// Retrieves only the selected `fields` of each matching document's
+    /// contents. See [`Projection`](crate::document::Projection) for how
+    /// fields are located and encoded.
+    ///
+    /// Projected contents can't be deserialized as
+    /// [`SerializedCollection::Contents`], and the returned
+    /// [`ProjectedDocument`]s can't be used to update the documents they were
+    /// selected from.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the collection's contents can't be projected. See
+    /// [`SerializedCollection::supports_projections()`].
+    pub fn project<Fields, Field>(self, fields: Fields) -> Result<Vec<ProjectedDocument>, Error>
+    where
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        self.0.project(fields)
+    }
+
     /// Returns the list of document headers contained within the range.
     ///
     /// ```rust
@@ -2053,7 +2209,9 @@ where
 
     /// Returns the number of documents contained within the range.
     ///
-    /// Order and limit are ignored if they were set.
+    /// Order, limit, and cursor are ignored if they were set. If a
+    /// [filter](Self::with_filter) was set, only matching documents are
+    /// counted.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -2078,6 +2236,57 @@ where
     pub fn query(self) -> Result<Vec<CollectionDocument<Cl>>, Error> {
         self.0.query().and_then(|docs| docs.collection_documents())
     }
+
+    /// Retrieves a page of at most [`limit`](Self::limit) matching documents,
+    /// along with a cursor that can be passed to [`after()`](Self::after) to
+    /// retrieve the next page.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// let page = MyCollection::all(db).limit(10).next_page()?;
+    /// if let Some(cursor) = page.next {
+    ///     let next_page = MyCollection::all(db).limit(10).after(cursor).next_page()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_page(self) -> Result<Page<Vec<CollectionDocument<Cl>>, ListCursor>, Error> {
+        let page = self.0.next_page()?;
+        Ok(Page {
+            results: page.results.collection_documents()?,
+            next: page.next,
+        })
+    }
+
+    /// Returns an iterator over the matching documents, retrieving them in
+    /// chunks of [`chunk_size`](Self::chunk_size) documents. At most
+    /// [`limit`](Self::limit) documents are returned. See
+    /// [`connection::List::iter()`] for more information.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::Connection;
+    /// # fn test_fn<C: Connection>(db: &C) -> Result<(), Error> {
+    /// for doc in MyCollection::all(db).chunk_size(100).iter()? {
+    ///     let doc = doc?;
+    ///     println!("Retrieved #{} with contents {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(
+        self,
+    ) -> Result<impl Iterator<Item = Result<CollectionDocument<Cl>, Error>> + 'a, Error>
+    where
+        Cl: 'a,
+    {
+        Ok(self
+            .0
+            .iter()?
+            .map(|doc| doc.and_then(|doc| CollectionDocument::try_from(&doc))))
+    }
 }
 
 /// Retrieves a list of documents from a collection, when awaited. This
@@ -2114,9 +2323,122 @@ where
         self
     }
 
+    /// Sets the number of documents [`stream()`](Self::stream) retrieves at a
+    /// time. If not set, [`STREAM_DEFAULT_CHUNK_SIZE`](crate::limits::STREAM_DEFAULT_CHUNK_SIZE)
+    /// documents are retrieved at a time.
+    pub fn chunk_size(mut self, documents: u32) -> Self {
+        self.0 = self.0.chunk_size(documents);
+        self
+    }
+
+    /// Resumes listing documents from `cursor`, in the direction of the
+    /// list's sort order.
+    pub fn after(mut self, cursor: ListCursor) -> Self {
+        self.0 = self.0.after(cursor);
+        self
+    }
+
+    /// Only returns documents whose contents match `filter`. The filter is
+    /// evaluated by the database before documents are returned, and only
+    /// matching documents count towards [`limit`](Self::limit).
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.0 = self.0.with_filter(filter);
+        self
+    }
+
+    /// Retrieves only the selected `fields` of each matching document's
+    /// contents. See [`Projection`](crate::document::Projection) for how
+    /// fields are located and encoded.
+    ///
+    /// Projected contents can't be deserialized as
+    /// [`SerializedCollection::Contents`], and the returned
+    /// [`ProjectedDocument`]s can't be used to update the documents they were
+    /// selected from.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the collection's contents can't be projected. See
+    /// [`SerializedCollection::supports_projections()`].
+    pub async fn project<Fields, Field>(
+        self,
+        fields: Fields,
+    ) -> Result<Vec<ProjectedDocument>, Error>
+    where
+        Cl: SerializedCollection,
+        Fields: IntoIterator<Item = Field>,
+        Field: Into<String>,
+    {
+        self.0.project(fields).await
+    }
+
+    /// Retrieves a page of at most [`limit`](Self::limit) matching documents,
+    /// along with a cursor that can be passed to [`after()`](Self::after) to
+    /// retrieve the next page.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let page = MyCollection::all_async(db).limit(10).next_page().await?;
+    /// if let Some(cursor) = page.next {
+    ///     let next_page = MyCollection::all_async(db)
+    ///         .limit(10)
+    ///         .after(cursor)
+    ///         .next_page()
+    ///         .await?;
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub async fn next_page(self) -> Result<Page<Vec<CollectionDocument<Cl>>, ListCursor>, Error>
+    where
+        Cl: SerializedCollection,
+    {
+        let page = self.0.next_page().await?;
+        Ok(Page {
+            results: page.results.collection_documents()?,
+            next: page.next,
+        })
+    }
+
+    /// Returns a [`Stream`] of the matching documents, retrieving them in
+    /// chunks of [`chunk_size`](Self::chunk_size) documents. At most
+    /// [`limit`](Self::limit) documents are returned. See
+    /// [`connection::AsyncList::stream()`] for more information.
+    ///
+    /// ```rust
+    /// # bonsaidb_core::__doctest_prelude!();
+    /// # use bonsaidb_core::connection::AsyncConnection;
+    /// # use futures::TryStreamExt;
+    /// # fn test_fn<C: AsyncConnection>(db: &C) -> Result<(), Error> {
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut docs = Box::pin(MyCollection::all_async(db).chunk_size(100).stream()?);
+    /// while let Some(doc) = docs.try_next().await? {
+    ///     println!("Retrieved #{} with contents {:?}", doc.header.id, doc.contents);
+    /// }
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn stream(
+        self,
+    ) -> Result<impl Stream<Item = Result<CollectionDocument<Cl>, Error>> + Send + 'a, Error>
+    where
+        Cl: SerializedCollection + 'a,
+    {
+        Ok(self
+            .0
+            .stream()?
+            .map(|doc| doc.and_then(|doc| CollectionDocument::try_from(&doc))))
+    }
+
     /// Returns the number of documents contained within the range.
     ///
-    /// Order and limit are ignored if they were set.
+    /// Order, limit, and cursor are ignored if they were set. If a
+    /// [filter](Self::with_filter) was set, only matching documents are
+    /// counted.
     ///
     /// ```rust
     /// # bonsaidb_core::__doctest_prelude!();
@@ -7,7 +7,8 @@ use derive_where::derive_where;
 
 use crate::document::{BorrowedDocument, DocumentId, KeyId};
 use crate::key::{ByteSource, Key, KeyDescription};
-use crate::schema::collection::Collection;
+use crate::schema::collection::{Collection, Migration};
+use crate::schema::view::full_text::{FullTextIndex, FullTextView};
 use crate::schema::view::map::{self, MappedValue};
 use crate::schema::view::{self, Serialized, SerializedView, ViewSchema};
 use crate::schema::{CollectionName, Schema, SchemaName, View, ViewName};
@@ -22,6 +23,8 @@ pub struct Schematic {
     collections_by_type_id: HashMap<TypeId, CollectionName>,
     collection_encryption_keys: HashMap<CollectionName, KeyId>,
     collection_id_generators: HashMap<CollectionName, Box<dyn IdGenerator>>,
+    collection_versions: HashMap<CollectionName, u64>,
+    migrations: HashMap<(CollectionName, u64), Migration>,
     views: HashMap<TypeId, Box<dyn view::Serialized>>,
     views_by_name: HashMap<ViewName, TypeId>,
     views_by_collection: HashMap<CollectionName, Vec<TypeId>>,
@@ -37,6 +40,8 @@ impl Schematic {
             collections_by_type_id: HashMap::new(),
             collection_encryption_keys: HashMap::new(),
             collection_id_generators: HashMap::new(),
+            collection_versions: HashMap::new(),
+            migrations: HashMap::new(),
             views: HashMap::new(),
             views_by_name: HashMap::new(),
             views_by_collection: HashMap::new(),
@@ -57,9 +62,11 @@ impl Schematic {
                     self.collection_encryption_keys.insert(name.clone(), key);
                 }
                 self.collection_id_generators
-                    .insert(name, Box::<KeyIdGenerator<C>>::default());
+                    .insert(name.clone(), Box::<KeyIdGenerator<C>>::default());
+                self.collection_versions.insert(name, C::version());
                 entry.insert(KeyDescription::for_key::<C::PrimaryKey>());
-                C::define_views(self)
+                C::define_views(self)?;
+                C::define_migrations(self)
             }
             hash_map::Entry::Occupied(_) => Err(Error::CollectionAlreadyDefined),
         }
@@ -73,6 +80,12 @@ impl Schematic {
         self.define_view_with_schema(view.clone(), view)
     }
 
+    /// Adds the full-text index `I`, which is stored as a
+    /// [`FullTextView<I>`](view::full_text::FullTextView).
+    pub fn define_full_text_index<I: FullTextIndex>(&mut self, index: I) -> Result<(), Error> {
+        self.define_view(FullTextView::new(index))
+    }
+
     /// Adds the view `V`.
     pub fn define_view_with_schema<
         V: SerializedView + 'static,
@@ -109,6 +122,42 @@ impl Schematic {
         Ok(())
     }
 
+    /// Adds a `migration` that upgrades documents stored in `C` from
+    /// `from_version` to `from_version + 1`.
+    pub fn define_migration<C: Collection + 'static>(
+        &mut self,
+        from_version: u64,
+        migration: Migration,
+    ) -> Result<(), Error> {
+        let collection = C::collection_name();
+        if from_version >= C::version() {
+            return Err(Error::MigrationUnavailable {
+                collection,
+                stored_version: from_version,
+                version: C::version(),
+            });
+        }
+        self.migrations
+            .insert((collection, from_version), migration);
+        Ok(())
+    }
+
+    /// Returns the version of the collection with the given name, or `None`
+    /// if the collection can't be found.
+    #[must_use]
+    pub fn collection_version(&self, collection: &CollectionName) -> Option<u64> {
+        self.collection_versions.get(collection).copied()
+    }
+
+    /// Returns the migration that upgrades documents stored in `collection`
+    /// from `from_version` to `from_version + 1`, if one was defined.
+    #[must_use]
+    pub fn migration(&self, collection: &CollectionName, from_version: u64) -> Option<Migration> {
+        self.migrations
+            .get(&(collection.clone(), from_version))
+            .copied()
+    }
+
     /// Returns `true` if this schema contains the collection `C`.
     #[must_use]
     pub fn contains_collection<C: Collection + 'static>(&self) -> bool {
@@ -236,6 +285,10 @@ where
         self.schema.version()
     }
 
+    fn cache_reductions(&self) -> bool {
+        self.schema.cache_reductions()
+    }
+
     fn view_name(&self) -> ViewName {
         self.view.view_name()
     }
@@ -305,7 +358,7 @@ fn schema_tests() -> anyhow::Result<()> {
         schema.collections_by_type_id[&TypeId::of::<Basic>()],
         Basic::collection_name()
     );
-    assert_eq!(schema.views.len(), 5);
+    assert_eq!(schema.views.len(), 6);
     assert_eq!(
         schema.views[&TypeId::of::<BasicCount>()].view_name(),
         View::view_name(&BasicCount)
@@ -15,6 +15,9 @@ use crate::AnyError;
 /// Types for defining a `Map` within a `View`.
 pub mod map;
 
+/// Types for defining full-text search indexes.
+pub mod full_text;
+
 /// Errors that arise when interacting with views.
 #[derive(thiserror::Error, Debug)]
 // TODO add which view name and collection
@@ -112,6 +115,21 @@ pub trait ViewSchema: Send + Sync + Debug + 'static {
         0
     }
 
+    /// Returns whether the database should cache partial reductions of this
+    /// view. If true, reduced values for groups of neighboring keys are stored
+    /// as the view is updated, which allows reducing a range of keys by
+    /// re-reducing a small number of cached values rather than every entry in
+    /// the range. This trades additional work when updating the view for
+    /// faster [`reduce()`](crate::connection::View::reduce) queries over large
+    /// ranges.
+    ///
+    /// When cached values are re-reduced, each mapping's key is the lowest key
+    /// that its value was reduced from. Changing this value will cause indexes
+    /// to be rebuilt.
+    fn cache_reductions(&self) -> bool {
+        false
+    }
+
     /// The map function for this view. This function is responsible for
     /// emitting entries for any documents that should be contained in this
     /// View. If None is returned, the View will not include the document. See [the user guide's chapter on
@@ -223,6 +241,12 @@ where
         0
     }
 
+    /// Returns whether the database should cache partial reductions of this
+    /// view. See [`ViewSchema::cache_reductions`] for more information.
+    fn cache_reductions(&self) -> bool {
+        false
+    }
+
     /// The map function for this view. This function is responsible for
     /// emitting entries for any documents that should be contained in this
     /// View. If None is returned, the View will not include the document.
@@ -277,6 +301,10 @@ where
     fn lazy(&self) -> bool {
         T::lazy(self)
     }
+
+    fn cache_reductions(&self) -> bool {
+        T::cache_reductions(self)
+    }
 }
 
 /// Wraps a [`View`] with serialization to erase the associated types
@@ -298,6 +326,8 @@ pub trait Serialized: Send + Sync + Debug {
 
     /// Wraps [`ViewSchema::version`]
     fn version(&self) -> u64;
+    /// Wraps [`ViewSchema::cache_reductions`]
+    fn cache_reductions(&self) -> bool;
     /// Wraps [`View::view_name`]
     fn view_name(&self) -> ViewName;
     /// Wraps [`ViewSchema::map`]
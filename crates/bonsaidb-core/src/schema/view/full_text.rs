@@ -0,0 +1,794 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{
+    AccessPolicy, AsyncConnection, AsyncLowLevelConnection, Connection, LowLevelConnection,
+    ViewMappings,
+};
+use crate::document::{BorrowedDocument, CollectionDocument, DocumentId, Header, OwnedDocument};
+use crate::schema::view::map::{Map, Mappings, ViewMappedValue};
+use crate::schema::view::{DefaultViewSerialization, View, ViewMapResult, ViewSchema};
+use crate::schema::{Collection, Name, SerializedCollection};
+use crate::Error;
+
+/// The BM25 term frequency saturation parameter.
+const BM25_K1: f64 = 1.2;
+/// The BM25 document length normalization parameter.
+const BM25_B: f64 = 0.75;
+
+/// A full-text index over a [`Collection`](crate::schema::Collection).
+///
+/// A full-text index is stored as a [`FullTextView`], which means it is
+/// updated using the same machinery as any other view. Documents are split
+/// into terms using the index's [`Analyzer`], and searches return documents
+/// ranked using [Okapi BM25](https://en.wikipedia.org/wiki/Okapi_BM25).
+///
+/// Indexes are registered with a collection by listing them in the
+/// `full_text` parameter of the `Collection` derive macro, or by calling
+/// [`Schematic::define_full_text_index()`](crate::schema::Schematic::define_full_text_index).
+///
+/// ```rust
+/// use bonsaidb_core::document::CollectionDocument;
+/// use bonsaidb_core::schema::{Collection, FullTextIndex, Name};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, Collection)]
+/// #[collection(name = "messages", full_text = [MessageSearch])]
+/// # #[collection(core = bonsaidb_core)]
+/// pub struct Message {
+///     pub subject: String,
+///     pub body: String,
+/// }
+///
+/// #[derive(Debug)]
+/// pub struct MessageSearch;
+///
+/// impl FullTextIndex for MessageSearch {
+///     type Collection = Message;
+///
+///     fn name(&self) -> Name {
+///         Name::new("search")
+///     }
+///
+///     fn text(
+///         &self,
+///         document: CollectionDocument<Message>,
+///     ) -> Result<Vec<String>, bonsaidb_core::Error> {
+///         Ok(vec![document.contents.subject, document.contents.body])
+///     }
+/// }
+/// ```
+pub trait FullTextIndex: Sized + Send + Sync + Debug + 'static {
+    /// The collection this index belongs to.
+    type Collection: SerializedCollection;
+
+    /// The name of the index. Must be unique among the collection's views.
+    fn name(&self) -> Name;
+
+    /// The version of the index. Changing this value will cause the index to
+    /// be rebuilt.
+    fn version(&self) -> u64 {
+        0
+    }
+
+    /// Returns the [`Analyzer`] used to split text into terms. The same
+    /// analyzer is used when indexing documents and when parsing queries. The
+    /// default is [`Analyzer::english()`].
+    fn analyzer(&self) -> Analyzer {
+        Analyzer::english()
+    }
+
+    /// Returns the text to index for `document`. Each returned string is
+    /// analyzed separately, which prevents phrases from matching across two
+    /// fields.
+    fn text(&self, document: CollectionDocument<Self::Collection>) -> Result<Vec<String>, Error>;
+
+    /// Returns a builder for a search of this index. Terms in `query` are
+    /// optional and contribute to each result's score, while phrases
+    /// surrounded by double quotes (`"`) must be present in every result.
+    fn search<'a, Database: Connection>(
+        &'a self,
+        database: &'a Database,
+        query: &'a str,
+    ) -> Search<'a, Database, Self> {
+        Search::new(self, database, query)
+    }
+
+    /// Returns a builder for a search of this index. Terms in `query` are
+    /// optional and contribute to each result's score, while phrases
+    /// surrounded by double quotes (`"`) must be present in every result.
+    fn search_async<'a, Database: AsyncConnection>(
+        &'a self,
+        database: &'a Database,
+        query: &'a str,
+    ) -> AsyncSearch<'a, Database, Self> {
+        AsyncSearch::new(self, database, query)
+    }
+}
+
+/// The [`View`] that stores a [`FullTextIndex`].
+///
+/// Each document emits one entry per distinct term it contains, keyed by the
+/// term, and one entry with an empty key that is used to compute collection
+/// statistics.
+#[derive(Debug)]
+pub struct FullTextView<I>(Arc<I>);
+
+impl<I> FullTextView<I> {
+    /// Returns a view for `index`.
+    pub fn new(index: I) -> Self {
+        Self(Arc::new(index))
+    }
+}
+
+impl<I> Clone for FullTextView<I> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<I> View for FullTextView<I>
+where
+    I: FullTextIndex,
+{
+    type Collection = I::Collection;
+    type Key = String;
+    type Value = FullTextEntry;
+
+    fn name(&self) -> Name {
+        self.0.name()
+    }
+}
+
+impl<I> DefaultViewSerialization for FullTextView<I> where I: FullTextIndex {}
+
+impl<I> ViewSchema for FullTextView<I>
+where
+    I: FullTextIndex,
+{
+    type View = Self;
+
+    fn version(&self) -> u64 {
+        self.0.version()
+    }
+
+    fn map(&self, document: &BorrowedDocument<'_>) -> ViewMapResult<Self::View> {
+        let fields = self.0.text(CollectionDocument::try_from(document)?)?;
+        let analyzer = self.0.analyzer();
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        for field in &fields {
+            // Skipping a position between fields prevents phrases from
+            // matching across them.
+            position = analyzer
+                .analyze(field, position, &mut tokens)
+                .saturating_add(1);
+        }
+
+        let length = u64::try_from(tokens.len()).unwrap_or(u64::MAX);
+        let mut positions_by_term = BTreeMap::<String, Vec<u32>>::new();
+        for token in tokens {
+            positions_by_term
+                .entry(token.term)
+                .or_default()
+                .push(token.position);
+        }
+
+        let mut mappings = vec![Map::new(
+            document.header.clone(),
+            String::new(),
+            FullTextEntry {
+                documents: 1,
+                length,
+                positions: Vec::new(),
+            },
+        )];
+        mappings.extend(positions_by_term.into_iter().map(|(term, positions)| {
+            Map::new(
+                document.header.clone(),
+                term,
+                FullTextEntry {
+                    documents: 1,
+                    length,
+                    positions,
+                },
+            )
+        }));
+
+        Ok(Mappings::List(mappings))
+    }
+
+    fn reduce(
+        &self,
+        mappings: &[ViewMappedValue<Self::View>],
+        _rereduce: bool,
+    ) -> Result<FullTextEntry, Error> {
+        Ok(mappings
+            .iter()
+            .fold(FullTextEntry::default(), |total, mapping| FullTextEntry {
+                documents: total.documents + mapping.value.documents,
+                length: total.length + mapping.value.length,
+                positions: Vec::new(),
+            }))
+    }
+}
+
+/// The value stored in a [`FullTextView`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+pub struct FullTextEntry {
+    /// The number of documents this entry represents.
+    pub documents: u64,
+    /// The number of terms in the document. When reduced, the total number of
+    /// terms in all documents.
+    pub length: u64,
+    /// The positions the term appeared at within the document, in ascending
+    /// order. Reduced values never contain positions.
+    pub positions: Vec<u32>,
+}
+
+/// Splits text into terms for a [`FullTextIndex`].
+///
+/// Text is split on every character that isn't alphanumeric, and each word is
+/// lowercased. Words found in the stop word list are skipped, but still
+/// occupy a position so that phrases continue to match. When stemming is
+/// enabled, common English suffixes are removed from each term.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Analyzer {
+    stop_words: &'static [&'static str],
+    stemming: bool,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Analyzer {
+    /// Returns an analyzer that removes [`ENGLISH_STOP_WORDS`] and stems
+    /// English suffixes.
+    pub const fn english() -> Self {
+        Self {
+            stop_words: ENGLISH_STOP_WORDS,
+            stemming: true,
+        }
+    }
+
+    /// Returns an analyzer that only splits and lowercases words.
+    pub const fn plain() -> Self {
+        Self {
+            stop_words: &[],
+            stemming: false,
+        }
+    }
+
+    /// Replaces the stop words with `stop_words` and returns self. Stop words
+    /// must be lowercase.
+    pub const fn with_stop_words(mut self, stop_words: &'static [&'static str]) -> Self {
+        self.stop_words = stop_words;
+        self
+    }
+
+    /// Sets whether terms are stemmed and returns self.
+    pub const fn with_stemming(mut self, stemming: bool) -> Self {
+        self.stemming = stemming;
+        self
+    }
+
+    /// Returns the terms contained in `text`.
+    #[must_use]
+    pub fn tokens(&self, text: &str) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        self.analyze(text, 0, &mut tokens);
+        tokens
+    }
+
+    /// Pushes the terms contained in `text` onto `tokens`, numbering words
+    /// starting at `position`. Returns the position after the last word.
+    fn analyze(&self, text: &str, mut position: u32, tokens: &mut Vec<Token>) -> u32 {
+        for word in text.split(|ch: char| !ch.is_alphanumeric()) {
+            if word.is_empty() {
+                continue;
+            }
+
+            let word = word.to_lowercase();
+            if !self.stop_words.contains(&word.as_str()) {
+                let term = if self.stemming { stem(&word) } else { word };
+                tokens.push(Token { term, position });
+            }
+            position = position.saturating_add(1);
+        }
+
+        position
+    }
+}
+
+/// A term produced by an [`Analyzer`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Token {
+    /// The analyzed term.
+    pub term: String,
+    /// The position of the word this term was produced from.
+    pub position: u32,
+}
+
+/// The stop words used by [`Analyzer::english()`].
+pub const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// Removes common English suffixes from `word`. Only ASCII words longer than
+/// three characters are stemmed.
+fn stem(word: &str) -> String {
+    if word.len() <= 3 || !word.bytes().all(|ch| ch.is_ascii_alphabetic()) {
+        return word.to_string();
+    }
+
+    let mut stemmed = if let Some(stem) = word.strip_suffix("sses") {
+        format!("{stem}ss")
+    } else if let Some(stem) = word.strip_suffix("ies").filter(|stem| stem.len() > 1) {
+        format!("{stem}y")
+    } else if word.ends_with("ss") || word.ends_with("us") || word.ends_with("is") {
+        word.to_string()
+    } else if let Some(stem) = word.strip_suffix('s') {
+        stem.to_string()
+    } else {
+        word.to_string()
+    };
+
+    for suffix in ["ing", "ed"] {
+        if let Some(stem) = stemmed.strip_suffix(suffix) {
+            if stem.len() >= 3 && stem.bytes().any(is_vowel) {
+                stemmed = undouble(stem).to_string();
+                break;
+            }
+        }
+    }
+
+    if let Some(length) = stemmed
+        .strip_suffix("ly")
+        .map(str::len)
+        .filter(|length| *length >= 3)
+    {
+        stemmed.truncate(length);
+    }
+
+    stemmed
+}
+
+const fn is_vowel(ch: u8) -> bool {
+    matches!(ch, b'a' | b'e' | b'i' | b'o' | b'u' | b'y')
+}
+
+/// Removes the last character of `stem` if it ends with a doubled consonant,
+/// turning "runn" into "run".
+fn undouble(stem: &str) -> &str {
+    let bytes = stem.as_bytes();
+    match bytes {
+        [.., a, b] if a == b && !is_vowel(*b) && !matches!(*b, b'l' | b's' | b'z') => {
+            &stem[..stem.len() - 1]
+        }
+        _ => stem,
+    }
+}
+
+/// A document matched by a search of a [`FullTextIndex`].
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// The header of the matched document.
+    pub source: Header,
+    /// The document's BM25 score. Higher scores are better matches.
+    pub score: f64,
+}
+
+/// A document matched by a search of a [`FullTextIndex`], along with its
+/// contents.
+#[derive(Debug)]
+pub struct RankedDocument<C>
+where
+    C: SerializedCollection,
+{
+    /// The matched document.
+    pub document: CollectionDocument<C>,
+    /// The document's BM25 score. Higher scores are better matches.
+    pub score: f64,
+}
+
+/// A parsed search query.
+#[derive(Debug, Default)]
+struct Query {
+    terms: Vec<String>,
+    phrases: Vec<Vec<Token>>,
+}
+
+impl Query {
+    fn parse(analyzer: &Analyzer, query: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut terms = BTreeSet::new();
+        // Splitting on quotes yields phrases at every odd index. A quoted
+        // single word is a phrase of one term, which requires the term to be
+        // present.
+        for (index, part) in query.split('"').enumerate() {
+            let tokens = analyzer.tokens(part);
+            terms.extend(tokens.iter().map(|token| token.term.clone()));
+            if index % 2 == 1 && !tokens.is_empty() {
+                parsed.phrases.push(tokens);
+            }
+        }
+        parsed.terms = terms.into_iter().collect();
+        parsed
+    }
+}
+
+/// The matched terms of a single document.
+struct DocumentMatch<'a> {
+    source: Header,
+    length: u64,
+    terms: BTreeMap<&'a str, &'a [u32]>,
+}
+
+impl DocumentMatch<'_> {
+    fn contains_phrase(&self, phrase: &[Token]) -> bool {
+        let Some((first, rest)) = phrase.split_first() else {
+            return true;
+        };
+        let Some(starts) = self.terms.get(first.term.as_str()) else {
+            return false;
+        };
+
+        starts.iter().any(|start| {
+            rest.iter().all(|token| {
+                let Some(expected) = start.checked_add(token.position - first.position) else {
+                    return false;
+                };
+                self.terms
+                    .get(token.term.as_str())
+                    .map_or(false, |positions| {
+                        positions.binary_search(&expected).is_ok()
+                    })
+            })
+        })
+    }
+}
+
+/// Ranks the documents in `mappings` using BM25.
+#[allow(clippy::cast_precision_loss)]
+fn rank(
+    query: &Query,
+    statistics: &FullTextEntry,
+    mappings: &[Map<String, FullTextEntry>],
+    limit: Option<usize>,
+) -> Vec<SearchHit> {
+    let mut documents = BTreeMap::<DocumentId, DocumentMatch<'_>>::new();
+    let mut document_frequencies = BTreeMap::<&str, u64>::new();
+    for mapping in mappings {
+        *document_frequencies
+            .entry(mapping.key.as_str())
+            .or_default() += 1;
+        documents
+            .entry(mapping.source.id.clone())
+            .or_insert_with(|| DocumentMatch {
+                source: mapping.source.clone(),
+                length: mapping.value.length,
+                terms: BTreeMap::new(),
+            })
+            .terms
+            .insert(mapping.key.as_str(), &mapping.value.positions);
+    }
+
+    let total_documents = statistics.documents as f64;
+    let average_length = if statistics.documents > 0 {
+        statistics.length as f64 / total_documents
+    } else {
+        0.
+    };
+
+    let mut hits = documents
+        .into_values()
+        .filter(|document| {
+            query
+                .phrases
+                .iter()
+                .all(|phrase| document.contains_phrase(phrase))
+        })
+        .map(|document| {
+            let length_ratio = if average_length > 0. {
+                document.length as f64 / average_length
+            } else {
+                1.
+            };
+            let score = document
+                .terms
+                .iter()
+                .map(|(term, positions)| {
+                    let matching = document_frequencies[term] as f64;
+                    let idf = ((total_documents - matching + 0.5) / (matching + 0.5) + 1.).ln();
+                    let frequency = positions.len() as f64;
+                    idf * frequency * (BM25_K1 + 1.)
+                        / (frequency + BM25_K1 * (1. - BM25_B + BM25_B * length_ratio))
+                })
+                .sum();
+            SearchHit {
+                source: document.source,
+                score,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.source.id.cmp(&b.source.id))
+    });
+    if let Some(limit) = limit {
+        hits.truncate(limit);
+    }
+    hits
+}
+
+/// Pairs each hit with its document, preserving the order of `hits`.
+fn ranked_documents<C: SerializedCollection>(
+    hits: Vec<SearchHit>,
+    documents: &[OwnedDocument],
+) -> Result<Vec<RankedDocument<C>>, Error> {
+    let documents = documents
+        .iter()
+        .map(|document| (document.header.id.clone(), document))
+        .collect::<BTreeMap<_, _>>();
+    hits.into_iter()
+        .filter_map(|hit| {
+            documents.get(&hit.source.id).map(|document| {
+                Ok(RankedDocument {
+                    document: CollectionDocument::try_from(*document)?,
+                    score: hit.score,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Searches a [`FullTextIndex`]. Returned by [`FullTextIndex::search()`].
+#[must_use]
+pub struct Search<'a, Cn, I> {
+    index: &'a I,
+    connection: &'a Cn,
+    query: &'a str,
+
+    /// The maximum number of results to return. Because each result's score
+    /// depends on every document containing the query's terms, the limit is
+    /// applied after all matching entries have been loaded and ranked.
+    pub limit: Option<usize>,
+
+    /// The index's data access policy. The default value is
+    /// [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+}
+
+impl<'a, Cn, I> Search<'a, Cn, I>
+where
+    Cn: Connection,
+    I: FullTextIndex,
+{
+    const fn new(index: &'a I, connection: &'a Cn, query: &'a str) -> Self {
+        Self {
+            index,
+            connection,
+            query,
+            limit: None,
+            access_policy: AccessPolicy::UpdateBefore,
+        }
+    }
+
+    /// Limits the number of results returned to the `limit` best matches.
+    /// This reduces the size of the results, but not the number of index
+    /// entries loaded to rank them.
+    pub const fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the access policy for the search.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the search and returns the matched documents' headers, best
+    /// matches first.
+    ///
+    /// Every index entry for each of the query's terms is loaded, including
+    /// term positions, so the cost of a search grows with the number of
+    /// documents containing its most common term regardless of
+    /// [`limit`](Self::limit). Queries containing very common terms should be
+    /// avoided on large collections.
+    pub fn query(self) -> Result<Vec<SearchHit>, Error> {
+        let query = Query::parse(&self.index.analyzer(), self.query);
+        if query.terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mappings: ViewMappings<FullTextView<I>> = self
+            .connection
+            .view::<FullTextView<I>>()
+            .with_keys(&query.terms)
+            .with_access_policy(self.access_policy)
+            .query()?;
+        let statistics = self
+            .connection
+            .view::<FullTextView<I>>()
+            .with_key("")
+            .with_access_policy(AccessPolicy::NoUpdate)
+            .reduce()?;
+
+        Ok(rank(&query, &statistics, &mappings, self.limit))
+    }
+
+    /// Executes the search and returns the matched documents, best matches
+    /// first.
+    pub fn query_with_docs(self) -> Result<Vec<RankedDocument<I::Collection>>, Error> {
+        let connection = self.connection;
+        let hits = self.query()?;
+        let ids = hits
+            .iter()
+            .map(|hit| hit.source.id.clone())
+            .collect::<Vec<_>>();
+        let documents = connection.get_multiple_from_collection(
+            &ids,
+            &<I::Collection as Collection>::collection_name(),
+        )?;
+        ranked_documents(hits, &documents)
+    }
+}
+
+/// Searches a [`FullTextIndex`]. Returned by [`FullTextIndex::search_async()`].
+#[must_use]
+pub struct AsyncSearch<'a, Cn, I> {
+    index: &'a I,
+    connection: &'a Cn,
+    query: &'a str,
+
+    /// The maximum number of results to return. Because each result's score
+    /// depends on every document containing the query's terms, the limit is
+    /// applied after all matching entries have been loaded and ranked.
+    pub limit: Option<usize>,
+
+    /// The index's data access policy. The default value is
+    /// [`AccessPolicy::UpdateBefore`].
+    pub access_policy: AccessPolicy,
+}
+
+impl<'a, Cn, I> AsyncSearch<'a, Cn, I>
+where
+    Cn: AsyncConnection,
+    I: FullTextIndex,
+{
+    const fn new(index: &'a I, connection: &'a Cn, query: &'a str) -> Self {
+        Self {
+            index,
+            connection,
+            query,
+            limit: None,
+            access_policy: AccessPolicy::UpdateBefore,
+        }
+    }
+
+    /// Limits the number of results returned to the `limit` best matches.
+    /// This reduces the size of the results, but not the number of index
+    /// entries loaded to rank them.
+    pub const fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the access policy for the search.
+    pub const fn with_access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = policy;
+        self
+    }
+
+    /// Executes the search and returns the matched documents' headers, best
+    /// matches first.
+    ///
+    /// Every index entry for each of the query's terms is loaded, including
+    /// term positions, so the cost of a search grows with the number of
+    /// documents containing its most common term regardless of
+    /// [`limit`](Self::limit). Queries containing very common terms should be
+    /// avoided on large collections.
+    pub async fn query(self) -> Result<Vec<SearchHit>, Error> {
+        let query = Query::parse(&self.index.analyzer(), self.query);
+        if query.terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mappings: ViewMappings<FullTextView<I>> = self
+            .connection
+            .view::<FullTextView<I>>()
+            .with_keys(&query.terms)
+            .with_access_policy(self.access_policy)
+            .query()
+            .await?;
+        let statistics = self
+            .connection
+            .view::<FullTextView<I>>()
+            .with_key("")
+            .with_access_policy(AccessPolicy::NoUpdate)
+            .reduce()
+            .await?;
+
+        Ok(rank(&query, &statistics, &mappings, self.limit))
+    }
+
+    /// Executes the search and returns the matched documents, best matches
+    /// first.
+    pub async fn query_with_docs(self) -> Result<Vec<RankedDocument<I::Collection>>, Error> {
+        let connection = self.connection;
+        let hits = self.query().await?;
+        let ids = hits
+            .iter()
+            .map(|hit| hit.source.id.clone())
+            .collect::<Vec<_>>();
+        let documents = connection
+            .get_multiple_from_collection(&ids, &<I::Collection as Collection>::collection_name())
+            .await?;
+        ranked_documents(hits, &documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stem, Analyzer, Query, Token};
+
+    #[test]
+    fn stemming() {
+        assert_eq!(stem("eggs"), "egg");
+        assert_eq!(stem("groceries"), "grocery");
+        assert_eq!(stem("grocery"), "grocery");
+        assert_eq!(stem("classes"), "class");
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("picked"), "pick");
+        assert_eq!(stem("quickly"), "quick");
+        assert_eq!(stem("bus"), "bus");
+        assert_eq!(stem("red"), "red");
+    }
+
+    #[test]
+    fn analyzer_positions() {
+        assert_eq!(
+            Analyzer::english().tokens("Pick up the Eggs!"),
+            vec![
+                Token {
+                    term: String::from("pick"),
+                    position: 0
+                },
+                Token {
+                    term: String::from("up"),
+                    position: 1
+                },
+                Token {
+                    term: String::from("egg"),
+                    position: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn query_parsing() {
+        let query = Query::parse(&Analyzer::english(), r#"milk "dozen eggs" "the""#);
+        assert_eq!(query.terms, vec!["dozen", "egg", "milk"]);
+        assert_eq!(query.phrases.len(), 1);
+        assert_eq!(query.phrases[0].len(), 2);
+
+        // A quoted single word is required.
+        let query = Query::parse(&Analyzer::english(), r#"milk "eggs""#);
+        assert_eq!(query.terms, vec!["egg", "milk"]);
+        assert_eq!(query.phrases.len(), 1);
+        assert_eq!(query.phrases[0][0].term, "egg");
+    }
+}
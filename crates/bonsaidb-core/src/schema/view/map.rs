@@ -4,7 +4,7 @@ use std::fmt::Debug;
 use arc_bytes::serde::Bytes;
 use serde::{Deserialize, Serialize};
 
-use crate::document::{DocumentId, Header, OwnedDocument};
+use crate::document::{DocumentId, Header, OwnedDocument, ProjectedDocument, Projection};
 use crate::schema::view::{self, ByteSource, Key, SerializedView, View};
 
 /// A document's entry in a View's mappings.
@@ -262,18 +262,41 @@ impl Serialized {
 
 /// A serialized [`MappedDocument`](MappedDocument).
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct MappedSerializedDocuments {
+pub struct MappedSerializedDocuments<D = OwnedDocument> {
     /// The serialized mapped value.
     pub mappings: Vec<Serialized>,
     /// The source document.
-    pub documents: BTreeMap<DocumentId, OwnedDocument>,
+    pub documents: BTreeMap<DocumentId, D>,
 }
 
 impl MappedSerializedDocuments {
+    /// Returns these mappings with the fields of each source document
+    /// selected by `projection`.
+    pub fn project(
+        self,
+        projection: &Projection,
+    ) -> Result<MappedSerializedDocuments<ProjectedDocument>, crate::Error> {
+        let documents = self
+            .documents
+            .into_iter()
+            .map(|(id, document)| {
+                projection
+                    .project_document(document.header, &document.contents)
+                    .map(|document| (id, document))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(MappedSerializedDocuments {
+            mappings: self.mappings,
+            documents,
+        })
+    }
+}
+
+impl<D> MappedSerializedDocuments<D> {
     /// Deserialize into a [`MappedDocument`](MappedDocument).
     pub fn deserialized<View: SerializedView>(
         self,
-    ) -> Result<MappedDocuments<OwnedDocument, View>, crate::Error> {
+    ) -> Result<MappedDocuments<D, View>, crate::Error> {
         let mappings = self
             .mappings
             .iter()
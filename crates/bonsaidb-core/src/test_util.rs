@@ -14,18 +14,20 @@ use transmog_pot::Pot;
 
 use crate::admin::{PermissionGroup, Role, User};
 use crate::connection::{
-    AccessPolicy, AsyncConnection, AsyncStorageConnection, Connection, StorageConnection,
+    AccessPolicy, AsyncConnection, AsyncStorageConnection, Connection, ListCursor,
+    StorageConnection,
 };
 use crate::document::{
-    BorrowedDocument, CollectionDocument, CollectionHeader, DocumentId, Emit, Header, KeyId,
+    BorrowedDocument, CollectionDocument, CollectionHeader, DocumentId, Emit, Filter, FilterValue,
+    Header, KeyId,
 };
 use crate::keyvalue::{AsyncKeyValue, KeyValue};
 use crate::limits::{LIST_TRANSACTIONS_DEFAULT_RESULT_COUNT, LIST_TRANSACTIONS_MAX_RESULTS};
 use crate::schema::view::map::{Mappings, ViewMappedValue};
 use crate::schema::view::{ReduceResult, ViewSchema};
 use crate::schema::{
-    Collection, CollectionName, MappedValue, NamedCollection, Qualified, Schema, SchemaName,
-    Schematic, SerializedCollection, View, ViewMapResult,
+    Collection, CollectionName, FullTextIndex, MappedValue, Name, NamedCollection, Qualified,
+    Schema, SchemaName, Schematic, SerializedCollection, View, ViewMapResult,
 };
 use crate::transaction::{Operation, Transaction};
 use crate::Error;
@@ -38,7 +40,7 @@ use crate::{
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Default, Clone, Collection)]
 // This collection purposely uses names with characters that need
 // escaping, since it's used in backup/restore.
-#[collection(name = "_basic", authority = "khonsulabs_", views = [BasicCount, BasicByParentId, BasicByParentIdEager, BasicByTag, BasicByCategory], core = crate)]
+#[collection(name = "_basic", authority = "khonsulabs_", views = [BasicCount, BasicByParentId, BasicByParentIdEager, BasicByParentIdCached, BasicByTag, BasicByCategory], full_text = [BasicSearch], core = crate)]
 #[must_use]
 pub struct Basic {
     pub value: String,
@@ -147,6 +149,35 @@ impl ViewSchema for BasicByParentIdEager {
     }
 }
 
+#[derive(Debug, Clone, View)]
+#[view(collection = Basic, key = Option<u64>, value = usize, name = "by-parent-id-cached", core = crate)]
+pub struct BasicByParentIdCached;
+
+impl ViewSchema for BasicByParentIdCached {
+    type View = Self;
+
+    fn version(&self) -> u64 {
+        1
+    }
+
+    fn cache_reductions(&self) -> bool {
+        true
+    }
+
+    fn map(&self, document: &BorrowedDocument<'_>) -> ViewMapResult<Self::View> {
+        let contents = Basic::document_contents(document)?;
+        document.header.emit_key_and_value(contents.parent_id, 1)
+    }
+
+    fn reduce(
+        &self,
+        mappings: &[ViewMappedValue<Self::View>],
+        _rereduce: bool,
+    ) -> ReduceResult<Self::View> {
+        Ok(mappings.iter().map(|map| map.value).sum())
+    }
+}
+
 #[derive(Debug, Clone, View)]
 #[view(collection = Basic, key = String, value = usize, name = "by-category", core = crate)]
 pub struct BasicByCategory;
@@ -174,6 +205,21 @@ impl ViewSchema for BasicByCategory {
     }
 }
 
+#[derive(Debug)]
+pub struct BasicSearch;
+
+impl FullTextIndex for BasicSearch {
+    type Collection = Basic;
+
+    fn name(&self) -> Name {
+        Name::new("search")
+    }
+
+    fn text(&self, document: CollectionDocument<Basic>) -> Result<Vec<String>, Error> {
+        Ok(vec![document.contents.value])
+    }
+}
+
 #[derive(Debug, Clone, View)]
 #[view(collection = Basic, key = String, value = usize, name = "by-tag", core = crate)]
 pub struct BasicByTag;
@@ -474,6 +520,20 @@ pub enum HarnessTest {
     KvExpiration,
     KvDeleteExpire,
     KvTransactions,
+    PubSubWatchCollection,
+    KvScan,
+    KvBatch,
+    KvStructures,
+    KvCompareAndSwap,
+    KvExpireAndPersist,
+    KvTypedNamespace,
+    KvRateLimit,
+    FullTextSearch,
+    ViewSets,
+    Filters,
+    Projections,
+    Streaming,
+    CachedReductions,
 }
 
 impl HarnessTest {
@@ -676,6 +736,61 @@ macro_rules! define_async_connection_test_suite {
                 harness.shutdown().await
             }
 
+            #[tokio::test]
+            async fn full_text_search() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::FullTextSearch).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::full_text_search_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn view_sets() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::ViewSets).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::view_set_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn filters() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::Filters).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::filter_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn projections() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::Projections).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::projection_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn streaming() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::Streaming).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::streaming_tests(&db).await?;
+                harness.shutdown().await
+            }
+
+            #[tokio::test]
+            async fn cached_reductions() -> anyhow::Result<()> {
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::CachedReductions).await?;
+                let db = harness.connect().await?;
+
+                $crate::test_util::cached_reduction_tests(&db).await?;
+                harness.shutdown().await
+            }
+
             #[tokio::test]
             async fn named_collection() -> anyhow::Result<()> {
                 let harness =
@@ -909,6 +1024,60 @@ macro_rules! define_blocking_connection_test_suite {
                 harness.shutdown()
             }
 
+            #[test]
+            fn full_text_search() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::FullTextSearch)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_full_text_search_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn view_sets() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::ViewSets)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_view_set_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn filters() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::Filters)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_filter_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn projections() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::Projections)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_projection_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn streaming() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::Streaming)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_streaming_tests(&db)?;
+                harness.shutdown()
+            }
+
+            #[test]
+            fn cached_reductions() -> anyhow::Result<()> {
+                let harness = $harness::new($crate::test_util::HarnessTest::CachedReductions)?;
+                let db = harness.connect()?;
+
+                $crate::test_util::blocking_cached_reduction_tests(&db)?;
+                harness.shutdown()
+            }
+
             #[test]
             fn named_collection() -> anyhow::Result<()> {
                 let harness = $harness::new($crate::test_util::HarnessTest::NamedCollection)?;
@@ -1476,6 +1645,42 @@ pub async fn list_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
     assert_eq!(limited.len(), 1);
     assert_eq!(limited[0].contents.value, doc2_value.value);
 
+    // Test paging through the documents using cursors
+    let first_page = Basic::all_async(db).limit(1).next_page().await?;
+    assert_eq!(first_page.results.len(), 1);
+    assert_eq!(first_page.results[0].header.id, doc1.id);
+    let second_page = Basic::all_async(db)
+        .limit(1)
+        .after(first_page.next.expect("page was full"))
+        .next_page()
+        .await?;
+    assert_eq!(second_page.results.len(), 1);
+    assert_eq!(second_page.results[0].header.id, doc2.id);
+    let last_page = Basic::all_async(db)
+        .limit(1)
+        .after(second_page.next.expect("page was full"))
+        .next_page()
+        .await?;
+    assert!(last_page.results.is_empty());
+    assert!(last_page.next.is_none());
+
+    let descending = Basic::all_async(db)
+        .descending()
+        .after(ListCursor::after(&doc2.id)?)
+        .await?;
+    assert_eq!(descending.len(), 1);
+    assert_eq!(descending[0].header.id, doc1.id);
+    let inclusive = Basic::list_async(doc1.id..=doc2.id, db)
+        .after(ListCursor::starting_at(&doc2.id)?)
+        .headers()
+        .await?;
+    assert_eq!(inclusive.len(), 1);
+    assert_eq!(inclusive[0].id, DocumentId::from_u64(doc2.id));
+    assert!(Basic::list_async(..doc2.id, db)
+        .after(ListCursor::after(&doc2.id)?)
+        .await?
+        .is_empty());
+
     Ok(())
 }
 
@@ -1512,6 +1717,39 @@ pub fn blocking_list_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
     assert_eq!(limited.len(), 1);
     assert_eq!(limited[0].contents.value, doc2_value.value);
 
+    // Test paging through the documents using cursors
+    let first_page = Basic::all(db).limit(1).next_page()?;
+    assert_eq!(first_page.results.len(), 1);
+    assert_eq!(first_page.results[0].header.id, doc1.id);
+    let second_page = Basic::all(db)
+        .limit(1)
+        .after(first_page.next.expect("page was full"))
+        .next_page()?;
+    assert_eq!(second_page.results.len(), 1);
+    assert_eq!(second_page.results[0].header.id, doc2.id);
+    let last_page = Basic::all(db)
+        .limit(1)
+        .after(second_page.next.expect("page was full"))
+        .next_page()?;
+    assert!(last_page.results.is_empty());
+    assert!(last_page.next.is_none());
+
+    let descending = Basic::all(db)
+        .descending()
+        .after(ListCursor::after(&doc2.id)?)
+        .query()?;
+    assert_eq!(descending.len(), 1);
+    assert_eq!(descending[0].header.id, doc1.id);
+    let inclusive = Basic::list(doc1.id..=doc2.id, db)
+        .after(ListCursor::starting_at(&doc2.id)?)
+        .headers()?;
+    assert_eq!(inclusive.len(), 1);
+    assert_eq!(inclusive[0].id, DocumentId::from_u64(doc2.id));
+    assert!(Basic::list(..doc2.id, db)
+        .after(ListCursor::after(&doc2.id)?)
+        .query()?
+        .is_empty());
+
     Ok(())
 }
 
@@ -1784,6 +2022,91 @@ pub async fn view_query_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()>
     assert_eq!(last_with_parent.iter().map(|m| m.key).unique().count(), 1);
     assert_eq!(last_with_parent[0].key, has_parent[2].key);
 
+    // Test paging through the mappings one at a time, including through the
+    // mappings that share a key.
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut query = db
+            .view::<BasicByParentId>()
+            .with_key_range(Some(0)..=Some(u64::MAX))
+            .limit(1);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = query.query_with_docs_page().await?;
+        assert!(page.results.len() <= 1);
+        assert_eq!(page.results.documents.len(), page.results.len());
+        paged.extend(page.results.mappings.into_iter().map(|m| m.source.id));
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(
+        paged,
+        has_parent
+            .iter()
+            .map(|m| m.source.id.clone())
+            .collect::<Vec<_>>()
+    );
+
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut query = db
+            .view::<BasicByParentId>()
+            .with_key_range(Some(0)..=Some(u64::MAX))
+            .descending()
+            .limit(2);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = query.query_page().await?;
+        paged.extend(page.results.into_iter().map(|m| m.source.id));
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(
+        paged,
+        has_parent
+            .iter()
+            .rev()
+            .map(|m| m.source.id.clone())
+            .collect::<Vec<_>>()
+    );
+
+    // Test paging through multiple keys in descending order.
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut query = db
+            .view::<BasicByParentId>()
+            .with_keys([&Some(a.id), &Some(b.id)])
+            .descending()
+            .limit(1);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = query.query_page().await?;
+        assert!(page.results.len() <= 1);
+        paged.extend(page.results.into_iter().map(|m| m.source.id));
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(
+        paged,
+        has_parent
+            .iter()
+            .rev()
+            .map(|m| m.source.id.clone())
+            .collect::<Vec<_>>()
+    );
+
     let items_with_categories = db.view::<BasicByCategory>().query().await?;
     assert_eq!(items_with_categories.len(), 3);
 
@@ -1864,6 +2187,91 @@ pub fn blocking_view_query_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
     assert_eq!(last_with_parent.iter().map(|m| m.key).unique().count(), 1);
     assert_eq!(last_with_parent[0].key, has_parent[2].key);
 
+    // Test paging through the mappings one at a time, including through the
+    // mappings that share a key.
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut query = db
+            .view::<BasicByParentId>()
+            .with_key_range(Some(0)..=Some(u64::MAX))
+            .limit(1);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = query.query_with_docs_page()?;
+        assert!(page.results.len() <= 1);
+        assert_eq!(page.results.documents.len(), page.results.len());
+        paged.extend(page.results.mappings.into_iter().map(|m| m.source.id));
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(
+        paged,
+        has_parent
+            .iter()
+            .map(|m| m.source.id.clone())
+            .collect::<Vec<_>>()
+    );
+
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut query = db
+            .view::<BasicByParentId>()
+            .with_key_range(Some(0)..=Some(u64::MAX))
+            .descending()
+            .limit(2);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = query.query_page()?;
+        paged.extend(page.results.into_iter().map(|m| m.source.id));
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(
+        paged,
+        has_parent
+            .iter()
+            .rev()
+            .map(|m| m.source.id.clone())
+            .collect::<Vec<_>>()
+    );
+
+    // Test paging through multiple keys in descending order.
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let mut query = db
+            .view::<BasicByParentId>()
+            .with_keys(&[Some(a.id), Some(b.id)])
+            .descending()
+            .limit(1);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = query.query_page()?;
+        assert!(page.results.len() <= 1);
+        paged.extend(page.results.into_iter().map(|m| m.source.id));
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(
+        paged,
+        has_parent
+            .iter()
+            .rev()
+            .map(|m| m.source.id.clone())
+            .collect::<Vec<_>>()
+    );
+
     let items_with_categories = db.view::<BasicByCategory>().query()?;
     assert_eq!(items_with_categories.len(), 3);
 
@@ -2485,74 +2893,1157 @@ pub fn blocking_unique_view_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub async fn named_collection_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
-    Unique::new("0").push_into_async(db).await?;
-    let original_entry = Unique::entry_async("1", db)
-        .update_with(|_existing: &mut Unique| unreachable!())
-        .or_insert_with(|| Unique::new("1"))
-        .await?
-        .expect("Document not inserted");
+pub async fn full_text_search_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    let groceries = collection
+        .push(&Basic::new("Can you pick up some milk on the way home?"))
+        .await?;
+    collection
+        .push(&Basic::new("2% milk? How are our eggs?"))
+        .await?;
+    let dozen = collection
+        .push(&Basic::new("Yes. We could use another dozen eggs."))
+        .await?;
+    let eggs = collection
+        .push(&Basic::new("Eggs, eggs, and more eggs."))
+        .await?;
 
-    let updated = Unique::entry_async("1", db)
-        .update_with(|existing: &mut Unique| {
-            existing.value = String::from("2");
-        })
-        .or_insert_with(|| unreachable!())
+    // The document mentioning eggs the most is ranked first.
+    let results = BasicSearch
+        .search_async(db, "eggs")
+        .query_with_docs()
+        .await?;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].document.header.id, eggs.id);
+    assert!(results[0].score > results[1].score);
+
+    // Phrases must match in order.
+    let results = BasicSearch
+        .search_async(db, r#""dozen eggs""#)
+        .query_with_docs()
+        .await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].document.header.id, dozen.id);
+    assert!(BasicSearch
+        .search_async(db, r#""eggs dozen""#)
+        .query()
         .await?
-        .unwrap();
-    assert_eq!(original_entry.header.id, updated.header.id);
-    assert_ne!(original_entry.contents.value, updated.contents.value);
+        .is_empty());
 
-    let retrieved = Unique::entry_async("2", db).await?.unwrap();
-    assert_eq!(retrieved.contents.value, updated.contents.value);
+    // A quoted single word must also be present.
+    let results = BasicSearch
+        .search_async(db, r#"milk "dozen""#)
+        .query()
+        .await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source.id.deserialize::<u64>()?, dozen.id);
 
-    let conflict = Unique::entry_async("2", db)
-        .update_with(|existing: &mut Unique| {
-            existing.value = String::from("0");
-        })
-        .await;
-    assert!(matches!(conflict, Err(Error::UniqueKeyViolation { .. })));
+    // Queries made up of stop words match nothing.
+    assert!(BasicSearch
+        .search_async(db, "the and")
+        .query()
+        .await?
+        .is_empty());
+    assert_eq!(
+        BasicSearch
+            .search_async(db, "milk")
+            .limit(1)
+            .query()
+            .await?
+            .len(),
+        1
+    );
+
+    // Updating a document updates the index.
+    let mut groceries = Basic::get_async(&groceries.id, db).await?.unwrap();
+    groceries.contents.value = String::from("Can you pick up some bread?");
+    groceries.update_async(db).await?;
+    assert_eq!(BasicSearch.search_async(db, "milk").query().await?.len(), 1);
 
     Ok(())
 }
 
-pub fn blocking_named_collection_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
-    Unique::new("0").push_into(db)?;
-    let original_entry = Unique::entry("1", db)
-        .update_with(|_existing: &mut Unique| unreachable!())
-        .or_insert_with(|| Unique::new("1"))
-        .execute()?
-        .expect("Document not inserted");
-
-    let updated = Unique::entry("1", db)
-        .update_with(|existing: &mut Unique| {
-            existing.value = String::from("2");
-        })
-        .or_insert_with(|| unreachable!())
-        .execute()?
-        .unwrap();
-    assert_eq!(original_entry.header.id, updated.header.id);
-    assert_ne!(original_entry.contents.value, updated.contents.value);
-
-    let retrieved = Unique::entry("2", db).execute()?.unwrap();
-    assert_eq!(retrieved.contents.value, updated.contents.value);
-
-    let conflict = Unique::entry("2", db)
-        .update_with(|existing: &mut Unique| {
-            existing.value = String::from("0");
-        })
-        .execute();
-    assert!(matches!(conflict, Err(Error::UniqueKeyViolation { .. })));
+pub fn blocking_full_text_search_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    let groceries = collection.push(&Basic::new("Can you pick up some milk on the way home?"))?;
+    collection.push(&Basic::new("2% milk? How are our eggs?"))?;
+    let dozen = collection.push(&Basic::new("Yes. We could use another dozen eggs."))?;
+    let eggs = collection.push(&Basic::new("Eggs, eggs, and more eggs."))?;
+
+    // The document mentioning eggs the most is ranked first.
+    let results = BasicSearch.search(db, "eggs").query_with_docs()?;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].document.header.id, eggs.id);
+    assert!(results[0].score > results[1].score);
+
+    // Phrases must match in order.
+    let results = BasicSearch
+        .search(db, r#""dozen eggs""#)
+        .query_with_docs()?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].document.header.id, dozen.id);
+    assert!(BasicSearch
+        .search(db, r#""eggs dozen""#)
+        .query()?
+        .is_empty());
+
+    // A quoted single word must also be present.
+    let results = BasicSearch.search(db, r#"milk "dozen""#).query()?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source.id.deserialize::<u64>()?, dozen.id);
+
+    // Queries made up of stop words match nothing.
+    assert!(BasicSearch.search(db, "the and").query()?.is_empty());
+    assert_eq!(BasicSearch.search(db, "milk").limit(1).query()?.len(), 1);
+
+    // Updating a document updates the index.
+    let mut groceries = Basic::get(&groceries.id, db)?.unwrap();
+    groceries.contents.value = String::from("Can you pick up some bread?");
+    groceries.update(db)?;
+    assert_eq!(BasicSearch.search(db, "milk").query()?.len(), 1);
 
     Ok(())
 }
 
-pub async fn compaction_tests<C: AsyncConnection + AsyncKeyValue>(db: &C) -> anyhow::Result<()> {
-    let original_value = Basic::new("initial_value");
+pub async fn view_set_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
     let collection = db.collection::<Basic>();
-    collection.push(&original_value).await?;
+    let a = collection
+        .push(&Basic::new("A").with_category("Alpha").with_tag("red"))
+        .await?;
+    let b = collection
+        .push(&Basic::new("B").with_category("Alpha").with_tag("blue"))
+        .await?;
+    let c = collection
+        .push(
+            &Basic::new("C")
+                .with_category("Beta")
+                .with_tag("red")
+                .with_tag("blue"),
+        )
+        .await?;
 
-    // Test a collection compaction
+    let ids = |documents: Vec<CollectionDocument<Basic>>| {
+        documents
+            .into_iter()
+            .map(|doc| doc.header.id)
+            .collect::<Vec<_>>()
+    };
+
+    let alpha_and_red = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .intersect(db.view::<BasicByTag>().with_key("red"))
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(ids(alpha_and_red), vec![a.id]);
+
+    // Documents matched by multiple views, or emitting multiple entries, are
+    // only returned once.
+    let red_or_blue = db
+        .view::<BasicByTag>()
+        .with_key("red")
+        .union(db.view::<BasicByTag>().with_key("blue"))
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(ids(red_or_blue), vec![a.id, b.id, c.id]);
+    let first_red_or_blue = db
+        .view::<BasicByTag>()
+        .with_key("red")
+        .union(db.view::<BasicByTag>().with_key("blue"))
+        .limit(2)
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(ids(first_red_or_blue), vec![a.id, b.id]);
+
+    let alpha_and_red_or_beta = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .intersect(db.view::<BasicByTag>().with_key("red"))
+        .union(db.view::<BasicByCategory>().with_key("beta"))
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(ids(alpha_and_red_or_beta), vec![a.id, c.id]);
+
+    // Each view is queried using its own access policy.
+    let d = collection
+        .push(&Basic::new("D").with_category("Alpha").with_tag("red"))
+        .await?;
+    let stale = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .with_access_policy(AccessPolicy::NoUpdate)
+        .intersect(
+            db.view::<BasicByTag>()
+                .with_key("red")
+                .with_access_policy(AccessPolicy::NoUpdate),
+        )
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(ids(stale), vec![a.id]);
+    let updated = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .intersect(db.view::<BasicByTag>().with_key("red"))
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(ids(updated), vec![a.id, d.id]);
+
+    Ok(())
+}
+
+pub fn blocking_view_set_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    let a = collection.push(&Basic::new("A").with_category("Alpha").with_tag("red"))?;
+    let b = collection.push(&Basic::new("B").with_category("Alpha").with_tag("blue"))?;
+    let c = collection.push(
+        &Basic::new("C")
+            .with_category("Beta")
+            .with_tag("red")
+            .with_tag("blue"),
+    )?;
+
+    let ids = |documents: Vec<CollectionDocument<Basic>>| {
+        documents
+            .into_iter()
+            .map(|doc| doc.header.id)
+            .collect::<Vec<_>>()
+    };
+
+    let alpha_and_red = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .intersect(db.view::<BasicByTag>().with_key("red"))
+        .query_with_collection_docs()?;
+    assert_eq!(ids(alpha_and_red), vec![a.id]);
+
+    // Documents matched by multiple views, or emitting multiple entries, are
+    // only returned once.
+    let red_or_blue = db
+        .view::<BasicByTag>()
+        .with_key("red")
+        .union(db.view::<BasicByTag>().with_key("blue"))
+        .query_with_collection_docs()?;
+    assert_eq!(ids(red_or_blue), vec![a.id, b.id, c.id]);
+    let first_red_or_blue = db
+        .view::<BasicByTag>()
+        .with_key("red")
+        .union(db.view::<BasicByTag>().with_key("blue"))
+        .limit(2)
+        .query_with_collection_docs()?;
+    assert_eq!(ids(first_red_or_blue), vec![a.id, b.id]);
+
+    let alpha_and_red_or_beta = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .intersect(db.view::<BasicByTag>().with_key("red"))
+        .union(db.view::<BasicByCategory>().with_key("beta"))
+        .query_with_collection_docs()?;
+    assert_eq!(ids(alpha_and_red_or_beta), vec![a.id, c.id]);
+
+    // Each view is queried using its own access policy.
+    let d = collection.push(&Basic::new("D").with_category("Alpha").with_tag("red"))?;
+    let stale = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .with_access_policy(AccessPolicy::NoUpdate)
+        .intersect(
+            db.view::<BasicByTag>()
+                .with_key("red")
+                .with_access_policy(AccessPolicy::NoUpdate),
+        )
+        .query_with_collection_docs()?;
+    assert_eq!(ids(stale), vec![a.id]);
+    let updated = db
+        .view::<BasicByCategory>()
+        .with_key("alpha")
+        .intersect(db.view::<BasicByTag>().with_key("red"))
+        .query_with_collection_docs()?;
+    assert_eq!(ids(updated), vec![a.id, d.id]);
+
+    Ok(())
+}
+
+pub async fn filter_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    let a = collection
+        .push(&Basic::new("A").with_category("Alpha").with_parent_id(1))
+        .await?;
+    let b = collection
+        .push(&Basic::new("B").with_category("Beta").with_tag("red"))
+        .await?;
+    let c = collection
+        .push(
+            &Basic::new("C")
+                .with_category("Alpha")
+                .with_tag("red")
+                .with_parent_id(2),
+        )
+        .await?;
+    let d = collection
+        .push(&Basic::new("D").with_category("Alpha").with_parent_id(3))
+        .await?;
+    let alpha = Filter::field("category").equals("Alpha");
+
+    // Only matching documents count towards the limit.
+    let listed = Basic::all_async(db)
+        .with_filter(alpha.clone())
+        .limit(2)
+        .await?;
+    assert_eq!(
+        listed.iter().map(|doc| doc.header.id).collect::<Vec<_>>(),
+        vec![a.id, c.id]
+    );
+    let page = collection
+        .all()
+        .with_filter(alpha.clone())
+        .limit(2)
+        .next_page()
+        .await?;
+    let remaining = collection
+        .all()
+        .with_filter(alpha.clone())
+        .limit(2)
+        .after(page.next.expect("more documents"))
+        .await?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].header.id, DocumentId::from_u64(d.id));
+
+    let red = Filter::field("tags").contains("red");
+    assert_eq!(
+        Basic::all_async(db)
+            .with_filter(red.clone())
+            .count()
+            .await?,
+        2
+    );
+    let headers = Basic::all_async(db)
+        .with_filter(!alpha.clone())
+        .headers()
+        .await?;
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers[0].id, DocumentId::from_u64(b.id));
+
+    // Entries are ordered by parent id, with the entry for B first.
+    let mapped = db
+        .view::<BasicByParentId>()
+        .with_filter(Filter::field("parent_id").greater_than(1))
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(
+        mapped
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![c.id, d.id]
+    );
+    let mapped = db
+        .view::<BasicByParentId>()
+        .with_filter(red.or(Filter::field("parent_id").equals(3)))
+        .query_with_collection_docs()
+        .await?;
+    assert_eq!(
+        mapped
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![b.id, c.id, d.id]
+    );
+
+    // Pages are filled with matching entries, even when the entries that don't
+    // match come first.
+    let page = db
+        .view::<BasicByParentId>()
+        .with_filter(alpha.clone())
+        .limit(2)
+        .query_with_collection_docs_page()
+        .await?;
+    assert_eq!(
+        page.results
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![a.id, c.id]
+    );
+    let page = db
+        .view::<BasicByParentId>()
+        .with_filter(alpha)
+        .limit(2)
+        .after(page.next.expect("more entries"))
+        .query_with_collection_docs_page()
+        .await?;
+    assert_eq!(
+        page.results
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![d.id]
+    );
+
+    Ok(())
+}
+
+pub fn blocking_filter_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    let a = collection.push(&Basic::new("A").with_category("Alpha").with_parent_id(1))?;
+    let b = collection.push(&Basic::new("B").with_category("Beta").with_tag("red"))?;
+    let c = collection.push(
+        &Basic::new("C")
+            .with_category("Alpha")
+            .with_tag("red")
+            .with_parent_id(2),
+    )?;
+    let d = collection.push(&Basic::new("D").with_category("Alpha").with_parent_id(3))?;
+    let alpha = Filter::field("category").equals("Alpha");
+
+    // Only matching documents count towards the limit.
+    let listed = Basic::all(db).with_filter(alpha.clone()).limit(2).query()?;
+    assert_eq!(
+        listed.iter().map(|doc| doc.header.id).collect::<Vec<_>>(),
+        vec![a.id, c.id]
+    );
+    let page = collection
+        .all()
+        .with_filter(alpha.clone())
+        .limit(2)
+        .next_page()?;
+    let remaining = collection
+        .all()
+        .with_filter(alpha.clone())
+        .limit(2)
+        .after(page.next.expect("more documents"))
+        .query()?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].header.id, DocumentId::from_u64(d.id));
+
+    let red = Filter::field("tags").contains("red");
+    assert_eq!(Basic::all(db).with_filter(red.clone()).count()?, 2);
+    let headers = Basic::all(db).with_filter(!alpha.clone()).headers()?;
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers[0].id, DocumentId::from_u64(b.id));
+
+    // Entries are ordered by parent id, with the entry for B first.
+    let mapped = db
+        .view::<BasicByParentId>()
+        .with_filter(Filter::field("parent_id").greater_than(1))
+        .query_with_collection_docs()?;
+    assert_eq!(
+        mapped
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![c.id, d.id]
+    );
+    let mapped = db
+        .view::<BasicByParentId>()
+        .with_filter(red.or(Filter::field("parent_id").equals(3)))
+        .query_with_collection_docs()?;
+    assert_eq!(
+        mapped
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![b.id, c.id, d.id]
+    );
+
+    // Pages are filled with matching entries, even when the entries that don't
+    // match come first.
+    let page = db
+        .view::<BasicByParentId>()
+        .with_filter(alpha.clone())
+        .limit(2)
+        .query_with_collection_docs_page()?;
+    assert_eq!(
+        page.results
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![a.id, c.id]
+    );
+    let page = db
+        .view::<BasicByParentId>()
+        .with_filter(alpha)
+        .limit(2)
+        .after(page.next.expect("more entries"))
+        .query_with_collection_docs_page()?;
+    assert_eq!(
+        page.results
+            .iter()
+            .map(|mapping| mapping.document.header.id)
+            .collect::<Vec<_>>(),
+        vec![d.id]
+    );
+
+    Ok(())
+}
+
+pub async fn projection_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    collection
+        .push(
+            &Basic::new("A")
+                .with_category("Alpha")
+                .with_tag("red")
+                .with_parent_id(1),
+        )
+        .await?;
+    collection
+        .push(&Basic::new("B").with_category("Beta").with_parent_id(2))
+        .await?;
+
+    let full = collection.all().await?;
+    let projected = collection.all().project(["value", "tags.0"]).await?;
+    assert_eq!(projected.len(), 2);
+    for (full, projected) in full.iter().zip(&projected) {
+        assert_eq!(full.header, projected.header);
+        assert!(projected.fields.len() < full.contents.len());
+    }
+    let contents = projected[0].deserialize::<FilterValue>()?;
+    assert_eq!(contents.field("value"), Some(&FilterValue::from("A")));
+    assert_eq!(contents.field("tags.0"), Some(&FilterValue::from("red")));
+    assert_eq!(contents.field("category"), None);
+    let contents = projected[1].deserialize::<FilterValue>()?;
+    assert_eq!(
+        contents,
+        FilterValue::Map(vec![(FilterValue::from("value"), FilterValue::from("B"))])
+    );
+    // The typed list returns the same projected documents.
+    let typed = Basic::all_async(db).project(["value"]).await?;
+    assert_eq!(typed.len(), 2);
+    assert_eq!(typed[0].header, full[0].header);
+
+    // Filters are evaluated against the full contents, even when the fields
+    // they reference aren't projected.
+    let mapped = db
+        .view::<BasicByParentId>()
+        .with_filter(Filter::field("category").equals("Beta"))
+        .project(["parent_id"])
+        .await?;
+    assert_eq!(mapped.len(), 1);
+    let mapping = mapped.get(0).unwrap();
+    let contents = mapping.document.deserialize::<FilterValue>()?;
+    assert_eq!(
+        contents,
+        FilterValue::Map(vec![(
+            FilterValue::from("parent_id"),
+            FilterValue::from(2_u64)
+        )])
+    );
+
+    let mapped = db.view::<BasicByParentId>().project(["category"]).await?;
+    assert_eq!(mapped.len(), 2);
+    for mapping in &mapped {
+        let contents = mapping.document.deserialize::<FilterValue>()?;
+        assert!(contents.field("category").is_some());
+        assert_eq!(contents.field("value"), None);
+    }
+
+    // Projecting doesn't change the stored documents.
+    assert_eq!(collection.all().await?.len(), full.len());
+    for (before, after) in full.iter().zip(collection.all().await?) {
+        assert_eq!(before.header, after.header);
+        assert_eq!(before.contents, after.contents);
+    }
+
+    Ok(())
+}
+
+pub fn blocking_projection_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    collection.push(
+        &Basic::new("A")
+            .with_category("Alpha")
+            .with_tag("red")
+            .with_parent_id(1),
+    )?;
+    collection.push(&Basic::new("B").with_category("Beta").with_parent_id(2))?;
+
+    let full = collection.all().query()?;
+    let projected = collection.all().project(["value", "tags.0"])?;
+    assert_eq!(projected.len(), 2);
+    for (full, projected) in full.iter().zip(&projected) {
+        assert_eq!(full.header, projected.header);
+        assert!(projected.fields.len() < full.contents.len());
+    }
+    let contents = projected[0].deserialize::<FilterValue>()?;
+    assert_eq!(contents.field("value"), Some(&FilterValue::from("A")));
+    assert_eq!(contents.field("tags.0"), Some(&FilterValue::from("red")));
+    assert_eq!(contents.field("category"), None);
+    let contents = projected[1].deserialize::<FilterValue>()?;
+    assert_eq!(
+        contents,
+        FilterValue::Map(vec![(FilterValue::from("value"), FilterValue::from("B"))])
+    );
+    // The typed list returns the same projected documents.
+    let typed = Basic::all(db).project(["value"])?;
+    assert_eq!(typed.len(), 2);
+    assert_eq!(typed[0].header, full[0].header);
+
+    // Filters are evaluated against the full contents, even when the fields
+    // they reference aren't projected.
+    let mapped = db
+        .view::<BasicByParentId>()
+        .with_filter(Filter::field("category").equals("Beta"))
+        .project(["parent_id"])?;
+    assert_eq!(mapped.len(), 1);
+    let mapping = mapped.get(0).unwrap();
+    let contents = mapping.document.deserialize::<FilterValue>()?;
+    assert_eq!(
+        contents,
+        FilterValue::Map(vec![(
+            FilterValue::from("parent_id"),
+            FilterValue::from(2_u64)
+        )])
+    );
+
+    let mapped = db.view::<BasicByParentId>().project(["category"])?;
+    assert_eq!(mapped.len(), 2);
+    for mapping in &mapped {
+        let contents = mapping.document.deserialize::<FilterValue>()?;
+        assert!(contents.field("category").is_some());
+        assert_eq!(contents.field("value"), None);
+    }
+
+    // Projecting doesn't change the stored documents.
+    let stored = collection.all().query()?;
+    assert_eq!(stored.len(), full.len());
+    for (before, after) in full.iter().zip(stored) {
+        assert_eq!(before.header, after.header);
+        assert_eq!(before.contents, after.contents);
+    }
+
+    Ok(())
+}
+
+pub async fn streaming_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    use futures::TryStreamExt;
+
+    let collection = db.collection::<Basic>();
+    for (index, parent_id) in [1, 1, 1, 2, 2, 3, 1].into_iter().enumerate() {
+        let category = if index % 2 == 0 { "Even" } else { "Odd" };
+        collection
+            .push(
+                &Basic::new(index.to_string())
+                    .with_category(category)
+                    .with_parent_id(parent_id),
+            )
+            .await?;
+    }
+
+    // Chunks smaller than the result set are retrieved until every document
+    // has been returned.
+    let all = collection.all().await?;
+    let streamed = collection
+        .all()
+        .chunk_size(3)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert!(streamed
+        .iter()
+        .map(|doc| &doc.header)
+        .eq(all.iter().map(|doc| &doc.header)));
+    let streamed = collection
+        .all()
+        .descending()
+        .chunk_size(2)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert!(streamed
+        .iter()
+        .rev()
+        .map(|doc| &doc.header)
+        .eq(all.iter().map(|doc| &doc.header)));
+
+    // Filters only count matching documents towards each chunk.
+    let filtered = collection
+        .all()
+        .with_filter(Filter::field("category").equals("Even"))
+        .chunk_size(2)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(filtered.len(), 4);
+    let streamed = Basic::list_async(all[2].header.id.deserialize::<u64>()?.., db)
+        .chunk_size(2)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(streamed.len(), 5);
+    assert_eq!(streamed[0].contents.value, "2");
+
+    // Keys with more mappings than fit in a chunk are split across chunks.
+    let mappings = db.view::<BasicByParentId>().query().await?;
+    let streamed = db
+        .view::<BasicByParentId>()
+        .chunk_size(3)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(streamed.len(), 7);
+    assert!(streamed
+        .iter()
+        .zip(&mappings)
+        .all(|(a, b)| a.key == b.key && a.source.id == b.source.id));
+
+    let streamed = db
+        .view::<BasicByParentId>()
+        .with_key(&Some(1))
+        .chunk_size(3)
+        .stream_with_docs()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(streamed.len(), 4);
+    for (mapping, document) in &streamed {
+        assert_eq!(mapping.source.id, document.header.id);
+        assert_eq!(Basic::document_contents(document)?.parent_id, Some(1));
+    }
+
+    // Limits cap the total number of results across every chunk.
+    let limited = collection
+        .all()
+        .limit(3)
+        .chunk_size(2)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert!(limited
+        .iter()
+        .map(|doc| &doc.header)
+        .eq(all.iter().take(3).map(|doc| &doc.header)));
+    let limited = collection
+        .all()
+        .with_filter(Filter::field("category").equals("Even"))
+        .limit(3)
+        .chunk_size(2)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(limited.len(), 3);
+    let limited = db
+        .view::<BasicByParentId>()
+        .limit(5)
+        .chunk_size(2)
+        .stream()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(limited.len(), 5);
+    let limited = db
+        .view::<BasicByParentId>()
+        .with_key(&Some(1))
+        .limit(3)
+        .chunk_size(2)
+        .stream_with_docs()?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(limited.len(), 3);
+
+    // Documents can be updated while they are being streamed, as no query
+    // remains open between chunks.
+    let mut updating = collection.all().chunk_size(2).stream()?;
+    let mut updated = 0;
+    while let Some(doc) = updating.try_next().await? {
+        let mut doc = CollectionDocument::<Basic>::try_from(&doc)?;
+        doc.contents.category = Some(String::from("Streamed"));
+        doc.update_async(db).await?;
+        updated += 1;
+    }
+    assert_eq!(updated, all.len());
+    let mut updating = db
+        .view::<BasicByParentId>()
+        .chunk_size(2)
+        .stream_with_docs()?;
+    let mut updated = 0;
+    while let Some((_, doc)) = updating.try_next().await? {
+        let mut doc = CollectionDocument::<Basic>::try_from(&doc)?;
+        doc.contents.value.push('!');
+        doc.update_async(db).await?;
+        updated += 1;
+    }
+    assert_eq!(updated, mappings.len());
+
+    // Dropping a stream before it has finished doesn't affect other queries.
+    let mut partial = collection.all().chunk_size(1).stream()?;
+    assert!(partial.try_next().await?.is_some());
+    drop(partial);
+    assert_eq!(collection.all().await?.len(), all.len());
+
+    Ok(())
+}
+
+pub fn blocking_streaming_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let collection = db.collection::<Basic>();
+    for (index, parent_id) in [1, 1, 1, 2, 2, 3, 1].into_iter().enumerate() {
+        let category = if index % 2 == 0 { "Even" } else { "Odd" };
+        collection.push(
+            &Basic::new(index.to_string())
+                .with_category(category)
+                .with_parent_id(parent_id),
+        )?;
+    }
+
+    // Chunks smaller than the result set are retrieved until every document
+    // has been returned.
+    let all = collection.all().query()?;
+    let streamed = collection
+        .all()
+        .chunk_size(3)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(streamed
+        .iter()
+        .map(|doc| &doc.header)
+        .eq(all.iter().map(|doc| &doc.header)));
+    let streamed = collection
+        .all()
+        .descending()
+        .chunk_size(2)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(streamed
+        .iter()
+        .rev()
+        .map(|doc| &doc.header)
+        .eq(all.iter().map(|doc| &doc.header)));
+
+    // Filters only count matching documents towards each chunk.
+    let filtered = collection
+        .all()
+        .with_filter(Filter::field("category").equals("Even"))
+        .chunk_size(2)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(filtered.len(), 4);
+    let streamed = Basic::list(all[2].header.id.deserialize::<u64>()?.., db)
+        .chunk_size(2)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(streamed.len(), 5);
+    assert_eq!(streamed[0].contents.value, "2");
+
+    // Keys with more mappings than fit in a chunk are split across chunks.
+    let mappings = db.view::<BasicByParentId>().query()?;
+    let streamed = db
+        .view::<BasicByParentId>()
+        .chunk_size(3)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(streamed.len(), 7);
+    assert!(streamed
+        .iter()
+        .zip(&mappings)
+        .all(|(a, b)| a.key == b.key && a.source.id == b.source.id));
+
+    let streamed = db
+        .view::<BasicByParentId>()
+        .with_key(&Some(1))
+        .chunk_size(3)
+        .iter_with_docs()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(streamed.len(), 4);
+    for (mapping, document) in &streamed {
+        assert_eq!(mapping.source.id, document.header.id);
+        assert_eq!(Basic::document_contents(document)?.parent_id, Some(1));
+    }
+
+    // Limits cap the total number of results across every chunk.
+    let limited = collection
+        .all()
+        .limit(3)
+        .chunk_size(2)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(limited
+        .iter()
+        .map(|doc| &doc.header)
+        .eq(all.iter().take(3).map(|doc| &doc.header)));
+    let limited = collection
+        .all()
+        .with_filter(Filter::field("category").equals("Even"))
+        .limit(3)
+        .chunk_size(2)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(limited.len(), 3);
+    let limited = db
+        .view::<BasicByParentId>()
+        .limit(5)
+        .chunk_size(2)
+        .iter()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(limited.len(), 5);
+    let limited = db
+        .view::<BasicByParentId>()
+        .with_key(&Some(1))
+        .limit(3)
+        .chunk_size(2)
+        .iter_with_docs()?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(limited.len(), 3);
+
+    // Documents can be updated while they are being iterated, as no query
+    // remains open between chunks.
+    let mut updated = 0;
+    for doc in collection.all().chunk_size(2).iter()? {
+        let mut doc = CollectionDocument::<Basic>::try_from(&doc?)?;
+        doc.contents.category = Some(String::from("Streamed"));
+        doc.update(db)?;
+        updated += 1;
+    }
+    assert_eq!(updated, all.len());
+    let mut updated = 0;
+    for result in db
+        .view::<BasicByParentId>()
+        .chunk_size(2)
+        .iter_with_docs()?
+    {
+        let (_, doc) = result?;
+        let mut doc = CollectionDocument::<Basic>::try_from(&doc)?;
+        doc.contents.value.push('!');
+        doc.update(db)?;
+        updated += 1;
+    }
+    assert_eq!(updated, mappings.len());
+
+    // Dropping an iterator before it has finished doesn't affect other
+    // queries.
+    let mut partial = collection.all().chunk_size(1).iter()?;
+    assert!(partial.next().transpose()?.is_some());
+    drop(partial);
+    assert_eq!(collection.all().query()?.len(), all.len());
+
+    Ok(())
+}
+
+/// Keys are spaced apart so that ranges can begin and end between them.
+const CACHED_REDUCTION_KEY_SPACING: u64 = 65_537;
+
+/// Enough keys are used for the cached reductions to need several levels of
+/// nodes.
+fn cached_reduction_parent_id(index: u64) -> Option<u64> {
+    (index < 400).then_some(index % 200 * CACHED_REDUCTION_KEY_SPACING)
+}
+
+fn cached_reduction_ranges() -> [std::ops::Range<Option<u64>>; 6] {
+    let spacing = CACHED_REDUCTION_KEY_SPACING;
+    [
+        None..Some(0),
+        Some(0)..Some(spacing * 10),
+        Some(spacing * 3)..Some(spacing * 3 + 1),
+        Some(spacing * 7 + 1)..Some(spacing * 40),
+        Some(spacing * 45)..Some(spacing * 170 + 1),
+        Some(1)..Some(u64::MAX),
+    ]
+}
+
+async fn check_cached_reductions<C: AsyncConnection>(
+    db: &C,
+    parent_ids: &[Option<u64>],
+) -> anyhow::Result<()> {
+    for range in cached_reduction_ranges() {
+        let expected = parent_ids.iter().filter(|id| range.contains(*id)).count();
+        let cached = db
+            .view::<BasicByParentIdCached>()
+            .with_key_range(range.clone())
+            .reduce()
+            .await?;
+        assert_eq!(cached, expected, "{range:?}");
+        let uncached = db
+            .view::<BasicByParentId>()
+            .with_key_range(range)
+            .reduce()
+            .await?;
+        assert_eq!(cached, uncached);
+    }
+
+    let spacing = CACHED_REDUCTION_KEY_SPACING;
+    let inclusive = Some(spacing * 5)..=Some(spacing * 9);
+    let expected = parent_ids
+        .iter()
+        .filter(|id| inclusive.contains(*id))
+        .count();
+    assert_eq!(
+        db.view::<BasicByParentIdCached>()
+            .with_key_range(inclusive)
+            .reduce()
+            .await?,
+        expected
+    );
+
+    assert_eq!(
+        db.view::<BasicByParentIdCached>().reduce().await?,
+        parent_ids.len()
+    );
+
+    Ok(())
+}
+
+pub async fn cached_reduction_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    let mut documents = Vec::new();
+    let mut parent_ids = Vec::new();
+    for index in 0..410 {
+        let parent_id = cached_reduction_parent_id(index);
+        let mut basic = Basic::new(index.to_string());
+        basic.parent_id = parent_id;
+        documents.push(basic.push_into_async(db).await?);
+        parent_ids.push(parent_id);
+    }
+    check_cached_reductions(db, &parent_ids).await?;
+
+    // Removing and moving entries updates the cached reductions of both the
+    // old and new keys. Removing every document with a key removes its entry,
+    // which merges nodes that have become too small.
+    let mut remaining = Vec::new();
+    for (index, mut document) in documents.into_iter().enumerate() {
+        if index % 3 == 0 || index >= 250 {
+            document.delete_async(db).await?;
+            continue;
+        } else if index % 5 == 1 {
+            document.contents.parent_id = Some(u64::MAX - index as u64);
+            document.update_async(db).await?;
+        }
+        remaining.push(document.contents.parent_id);
+    }
+    check_cached_reductions(db, &remaining).await?;
+
+    Ok(())
+}
+
+fn blocking_check_cached_reductions<C: Connection>(
+    db: &C,
+    parent_ids: &[Option<u64>],
+) -> anyhow::Result<()> {
+    for range in cached_reduction_ranges() {
+        let expected = parent_ids.iter().filter(|id| range.contains(*id)).count();
+        let cached = db
+            .view::<BasicByParentIdCached>()
+            .with_key_range(range.clone())
+            .reduce()?;
+        assert_eq!(cached, expected, "{range:?}");
+        let uncached = db
+            .view::<BasicByParentId>()
+            .with_key_range(range)
+            .reduce()?;
+        assert_eq!(cached, uncached);
+    }
+
+    let spacing = CACHED_REDUCTION_KEY_SPACING;
+    let inclusive = Some(spacing * 5)..=Some(spacing * 9);
+    let expected = parent_ids
+        .iter()
+        .filter(|id| inclusive.contains(*id))
+        .count();
+    assert_eq!(
+        db.view::<BasicByParentIdCached>()
+            .with_key_range(inclusive)
+            .reduce()?,
+        expected
+    );
+
+    assert_eq!(
+        db.view::<BasicByParentIdCached>().reduce()?,
+        parent_ids.len()
+    );
+
+    Ok(())
+}
+
+pub fn blocking_cached_reduction_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    let mut documents = Vec::new();
+    let mut parent_ids = Vec::new();
+    for index in 0..410 {
+        let parent_id = cached_reduction_parent_id(index);
+        let mut basic = Basic::new(index.to_string());
+        basic.parent_id = parent_id;
+        documents.push(basic.push_into(db)?);
+        parent_ids.push(parent_id);
+    }
+    blocking_check_cached_reductions(db, &parent_ids)?;
+
+    let mut remaining = Vec::new();
+    for (index, mut document) in documents.into_iter().enumerate() {
+        if index % 3 == 0 || index >= 250 {
+            document.delete(db)?;
+            continue;
+        } else if index % 5 == 1 {
+            document.contents.parent_id = Some(u64::MAX - index as u64);
+            document.update(db)?;
+        }
+        remaining.push(document.contents.parent_id);
+    }
+    blocking_check_cached_reductions(db, &remaining)?;
+
+    Ok(())
+}
+
+pub async fn named_collection_tests<C: AsyncConnection>(db: &C) -> anyhow::Result<()> {
+    Unique::new("0").push_into_async(db).await?;
+    let original_entry = Unique::entry_async("1", db)
+        .update_with(|_existing: &mut Unique| unreachable!())
+        .or_insert_with(|| Unique::new("1"))
+        .await?
+        .expect("Document not inserted");
+
+    let updated = Unique::entry_async("1", db)
+        .update_with(|existing: &mut Unique| {
+            existing.value = String::from("2");
+        })
+        .or_insert_with(|| unreachable!())
+        .await?
+        .unwrap();
+    assert_eq!(original_entry.header.id, updated.header.id);
+    assert_ne!(original_entry.contents.value, updated.contents.value);
+
+    let retrieved = Unique::entry_async("2", db).await?.unwrap();
+    assert_eq!(retrieved.contents.value, updated.contents.value);
+
+    let conflict = Unique::entry_async("2", db)
+        .update_with(|existing: &mut Unique| {
+            existing.value = String::from("0");
+        })
+        .await;
+    assert!(matches!(conflict, Err(Error::UniqueKeyViolation { .. })));
+
+    Ok(())
+}
+
+pub fn blocking_named_collection_tests<C: Connection>(db: &C) -> anyhow::Result<()> {
+    Unique::new("0").push_into(db)?;
+    let original_entry = Unique::entry("1", db)
+        .update_with(|_existing: &mut Unique| unreachable!())
+        .or_insert_with(|| Unique::new("1"))
+        .execute()?
+        .expect("Document not inserted");
+
+    let updated = Unique::entry("1", db)
+        .update_with(|existing: &mut Unique| {
+            existing.value = String::from("2");
+        })
+        .or_insert_with(|| unreachable!())
+        .execute()?
+        .unwrap();
+    assert_eq!(original_entry.header.id, updated.header.id);
+    assert_ne!(original_entry.contents.value, updated.contents.value);
+
+    let retrieved = Unique::entry("2", db).execute()?.unwrap();
+    assert_eq!(retrieved.contents.value, updated.contents.value);
+
+    let conflict = Unique::entry("2", db)
+        .update_with(|existing: &mut Unique| {
+            existing.value = String::from("0");
+        })
+        .execute();
+    assert!(matches!(conflict, Err(Error::UniqueKeyViolation { .. })));
+
+    Ok(())
+}
+
+pub async fn compaction_tests<C: AsyncConnection + AsyncKeyValue>(db: &C) -> anyhow::Result<()> {
+    let original_value = Basic::new("initial_value");
+    let collection = db.collection::<Basic>();
+    collection.push(&original_value).await?;
+
+    // Test a collection compaction
     db.compact_collection::<Basic>().await?;
 
     // Test the key value store compaction
@@ -2923,6 +4414,382 @@ macro_rules! define_async_kv_test_suite {
                 Ok(())
             }
 
+            #[tokio::test]
+            async fn kv_scan_tests() -> anyhow::Result<()> {
+                use $crate::connection::{Bound, Range};
+                use $crate::keyvalue::AsyncKeyValue;
+                let harness = $harness::new($crate::test_util::HarnessTest::KvScan).await?;
+                let db = harness.connect().await?;
+                let kv = db.with_key_namespace("scan");
+                for key in ["a", "b1", "b2", "b3", "c"] {
+                    kv.set_key(key, &String::from(key)).await?;
+                }
+                // Keys in other namespaces are not listed.
+                db.set_key("b4", &String::from("b4")).await?;
+                kv.with_key_namespace("nested")
+                    .set_key("b5", &String::from("b5"))
+                    .await?;
+
+                assert_eq!(
+                    kv.scan_keys().keys().await?,
+                    vec!["a", "b1", "b2", "b3", "c"]
+                );
+                assert_eq!(
+                    kv.scan_keys().with_prefix("b").keys().await?,
+                    vec!["b1", "b2", "b3"]
+                );
+                assert_eq!(
+                    kv.scan_keys()
+                        .with_range(Range {
+                            start: Bound::Excluded(String::from("a")),
+                            end: Bound::Included(String::from("b2")),
+                        })
+                        .keys()
+                        .await?,
+                    vec!["b1", "b2"]
+                );
+                let entries = kv.scan_keys().with_prefix("c").await?;
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].0, "c");
+                assert_eq!(entries[0].1.deserialize::<String>()?, "c");
+
+                // Page through the keys two at a time.
+                let first_page = kv.scan_keys().with_prefix("b").limit(2).keys_page().await?;
+                assert_eq!(first_page.results, vec!["b1", "b2"]);
+                let second_page = kv
+                    .scan_keys()
+                    .with_prefix("b")
+                    .limit(2)
+                    .after(first_page.next.expect("more keys"))
+                    .query_page()
+                    .await?;
+                assert_eq!(second_page.results.len(), 1);
+                assert_eq!(second_page.results[0].0, "b3");
+                assert!(second_page.next.is_none());
+
+                kv.delete_key("b2").await?;
+                assert_eq!(
+                    kv.scan_keys().with_prefix("b").keys().await?,
+                    vec!["b1", "b3"]
+                );
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
+            #[tokio::test]
+            async fn kv_batch_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{AsyncKeyValue, KeyStatus, Output, Value};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvBatch).await?;
+                let db = harness.connect().await?;
+                let kv = db.with_key_namespace("batch");
+
+                let outputs = kv
+                    .batch()
+                    .set_key("flag", &true)
+                    .only_if_vacant()
+                    .increment_key_by("counter", 1_u64)
+                    .await?
+                    .expect("checks should pass");
+                assert!(matches!(
+                    outputs[0],
+                    Output::Status(KeyStatus::Inserted)
+                ));
+                assert!(matches!(
+                    &outputs[1],
+                    Output::Value(Some(Value::Numeric(value))) if value.as_u64() == Some(1)
+                ));
+
+                // The flag is already set, so the counter isn't incremented.
+                assert!(kv
+                    .batch()
+                    .set_key("flag", &true)
+                    .only_if_vacant()
+                    .increment_key_by("counter", 1_u64)
+                    .await?
+                    .is_none());
+                assert_eq!(kv.get_key("counter").into_u64().await?, Some(1));
+
+                // Incrementing the flag fails, which undoes the entire batch.
+                assert!(kv
+                    .batch()
+                    .increment_key_by("counter", 1_u64)
+                    .set_key("note", &String::from("undone"))
+                    .increment_key_by("flag", 1_u64)
+                    .await
+                    .is_err());
+                assert_eq!(kv.get_key("counter").into_u64().await?, Some(1));
+                assert_eq!(kv.get_key("note").await?, None);
+
+                kv.batch()
+                    .delete_key("flag")
+                    .only_if_exists()
+                    .decrement_key_by("counter", 1_u64)
+                    .await?
+                    .expect("checks should pass");
+                assert_eq!(kv.get_key("counter").into_u64().await?, Some(0));
+                assert_eq!(kv.get_key("flag").await?, None);
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
+            #[tokio::test]
+            async fn kv_structures_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{AsyncKeyValue, KeyStatus};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvStructures).await?;
+                let db = harness.connect().await?;
+                let kv = db.with_key_namespace("structures");
+
+                let list = kv.key_list("list");
+                assert_eq!(list.push_back(&2_u32).await?, 1);
+                assert_eq!(list.push_front(&1_u32).await?, 2);
+                assert_eq!(list.push_back(&3_u32).await?, 3);
+                assert_eq!(list.pop_front::<u32>().await?, Some(1));
+                assert_eq!(list.pop_back::<u32>().await?, Some(3));
+                assert_eq!(list.pop_back::<u32>().await?, Some(2));
+                assert_eq!(list.pop_back::<u32>().await?, None);
+                // Popping the last value deletes the key.
+                assert_eq!(kv.get_key("list").await?, None);
+
+                let set = kv.key_set("set");
+                assert!(set.add(&"a").await?);
+                assert!(!set.add(&"a").await?);
+                assert!(set.add(&"b").await?);
+                assert!(set.contains(&"a").await?);
+                assert!(!set.contains(&"c").await?);
+                assert!(set.remove(&"a").await?);
+                assert!(!set.remove(&"a").await?);
+                assert!(set.remove(&"b").await?);
+                assert_eq!(kv.get_key("set").await?, None);
+
+                let hash = kv.key_hash("hash");
+                assert_eq!(hash.set_field("name", &"alice").await?, KeyStatus::Inserted);
+                assert_eq!(hash.set_field("name", &"bob").await?, KeyStatus::Updated);
+                assert_eq!(hash.set_field("age", &30_u8).await?, KeyStatus::Inserted);
+                assert_eq!(
+                    hash.get_field::<_, String>("name").await?.as_deref(),
+                    Some("bob")
+                );
+                assert_eq!(hash.get_field::<_, u8>("missing").await?, None);
+                assert!(hash.delete_field("name").await?);
+                assert!(!hash.delete_field("name").await?);
+                assert!(hash.delete_field("age").await?);
+                assert_eq!(kv.get_key("hash").await?, None);
+
+                // Whole structures can be read, and writes can update the
+                // key's expiration.
+                let expiring = kv
+                    .key_list("expiring")
+                    .expire_in(std::time::Duration::from_secs(3_600));
+                assert_eq!(expiring.push_back(&1_u32).await?, 1);
+                assert_eq!(expiring.push_back(&2_u32).await?, 2);
+                let entry = kv
+                    .get_key("expiring")
+                    .with_metadata()
+                    .await?
+                    .expect("list missing");
+                assert!(entry.expiration.is_some());
+                assert!(matches!(
+                    &entry.value,
+                    $crate::keyvalue::Value::List(list) if list.len() == 2
+                ));
+                // Setting the key replaces the list.
+                kv.set_key("expiring", &3_u32).await?;
+                assert_eq!(kv.get_key("expiring").into::<u32>().await?, Some(3));
+
+                // Operating on a key containing another type of value fails.
+                kv.set_key("bytes", &1_u32).await?;
+                assert!(kv.key_list("bytes").push_back(&1_u32).await.is_err());
+                assert!(kv.key_set("bytes").add(&1_u32).await.is_err());
+                assert!(kv.key_hash("bytes").set_field("a", &1_u32).await.is_err());
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
+            #[tokio::test]
+            async fn kv_compare_and_swap_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{AsyncKeyValue, KeyStatus};
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::KvCompareAndSwap).await?;
+                let db = harness.connect().await?;
+                let kv = db.with_key_namespace("cas");
+
+                // A missing key never matches.
+                assert_eq!(
+                    kv.set_key("leader", &String::from("a"))
+                        .only_if_equal(&String::from("a"))
+                        .await?,
+                    KeyStatus::NotChanged
+                );
+                kv.set_key("leader", &String::from("a")).await?;
+                assert_eq!(
+                    kv.set_key("leader", &String::from("b"))
+                        .only_if_equal(&String::from("c"))
+                        .await?,
+                    KeyStatus::NotChanged
+                );
+                assert_eq!(
+                    kv.set_key("leader", &String::from("b"))
+                        .only_if_equal(&String::from("a"))
+                        .await?,
+                    KeyStatus::Updated
+                );
+                assert_eq!(
+                    kv.get_key("leader").into::<String>().await?.as_deref(),
+                    Some("b")
+                );
+
+                // Integers compare equal regardless of signedness.
+                kv.set_numeric_key("term", 1_u64).await?;
+                assert_eq!(
+                    kv.set_numeric_key("term", 2_u64)
+                        .only_if_equal_numeric(1_i64)
+                        .await?,
+                    KeyStatus::Updated
+                );
+                assert_eq!(
+                    kv.set_numeric_key("term", 3_u64)
+                        .only_if_equal_numeric(1_u64)
+                        .await?,
+                    KeyStatus::NotChanged
+                );
+                assert_eq!(kv.get_key("term").into_u64().await?, Some(2));
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
+            #[tokio::test]
+            async fn kv_expire_and_persist_tests() -> anyhow::Result<()> {
+                use std::time::Duration;
+
+                use $crate::keyvalue::{AsyncKeyValue, KeyStatus};
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::KvExpireAndPersist).await?;
+                let db = harness.connect().await?;
+                let kv = db.with_key_namespace("ttl");
+
+                // Missing keys have no metadata and can't be expired.
+                assert!(kv.get_key("session").with_metadata().await?.is_none());
+                assert_eq!(
+                    kv.expire_key("session", Duration::from_secs(60)).await?,
+                    KeyStatus::NotChanged
+                );
+                assert_eq!(kv.persist_key("session").await?, KeyStatus::NotChanged);
+
+                kv.set_key("session", &String::from("token")).await?;
+                let entry = kv.get_key("session").with_metadata().await?.unwrap();
+                assert_eq!(entry.value.deserialize::<String>()?, "token");
+                assert!(entry.expiration.is_none());
+                assert!(entry.expires_in().is_none());
+
+                assert_eq!(
+                    kv.expire_key("session", Duration::from_secs(60)).await?,
+                    KeyStatus::Updated
+                );
+                let expiring = kv.get_key("session").with_metadata().await?.unwrap();
+                assert!(expiring.expiration.is_some());
+                assert!(expiring.expires_in().unwrap() <= Duration::from_secs(60));
+                assert_eq!(expiring.last_updated, entry.last_updated);
+
+                assert_eq!(kv.persist_key("session").await?, KeyStatus::Updated);
+                let persisted = kv.get_key("session").with_metadata().await?.unwrap();
+                assert!(persisted.expiration.is_none());
+                assert_eq!(persisted.value.deserialize::<String>()?, "token");
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
+            #[tokio::test]
+            async fn kv_typed_namespace_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{AsyncKeyValue, KeyStatus, KeyValueNamespace};
+
+                struct Sessions;
+
+                impl KeyValueNamespace for Sessions {
+                    type Key = u64;
+                    type Value = String;
+
+                    const NAME: &'static str = "sessions";
+                }
+
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::KvTypedNamespace).await?;
+                let db = harness.connect().await?;
+                let sessions = db.typed_namespace::<Sessions>();
+
+                assert_eq!(sessions.get(&1).await?, None);
+                sessions.set(&1, &String::from("alice"))?.await?;
+                sessions.set(&2, &String::from("bob"))?.await?;
+                assert_eq!(sessions.get(&1).await?.as_deref(), Some("alice"));
+
+                // Keys are stored using their encoded form within the namespace.
+                let raw = db.with_key_namespace(Sessions::NAME);
+                assert_eq!(
+                    raw.get_key(Sessions::encode_key(&2)?)
+                        .into::<String>()
+                        .await?
+                        .as_deref(),
+                    Some("bob")
+                );
+
+                assert_eq!(sessions.take(&2).await?.as_deref(), Some("bob"));
+                assert_eq!(sessions.get(&2).await?, None);
+                assert_eq!(sessions.delete(&1).await?, KeyStatus::Deleted);
+                assert_eq!(sessions.delete(&1).await?, KeyStatus::NotChanged);
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
+            #[tokio::test]
+            async fn kv_rate_limit_tests() -> anyhow::Result<()> {
+                use std::time::Duration;
+
+                use $crate::keyvalue::{AsyncKeyValue, RateLimit};
+                let harness =
+                    $harness::new($crate::test_util::HarnessTest::KvRateLimit).await?;
+                let db = harness.connect().await?;
+                let kv = db.with_key_namespace("limits");
+                let limit = RateLimit::token_bucket(2, 1, Duration::from_secs(3600));
+
+                let status = kv.rate_limit("client", limit).await?;
+                assert!(status.allowed);
+                assert_eq!(status.remaining, 1);
+                assert!(kv.rate_limit("client", limit).await?.allowed);
+
+                // The bucket is empty until the next refill.
+                let status = kv.rate_limit("client", limit).await?;
+                assert!(!status.allowed);
+                assert_eq!(status.remaining, 0);
+                let retry_after = status.retry_after.unwrap();
+                assert!(retry_after > Duration::ZERO);
+                assert!(retry_after <= Duration::from_secs(3600));
+
+                // Requests costing more than the capacity are never allowed.
+                let status = kv.rate_limit("other", limit.with_cost(3)).await?;
+                assert!(!status.allowed);
+                assert_eq!(status.remaining, 2);
+                assert_eq!(status.retry_after, None);
+
+                // Rate limits can't be applied to keys storing other values.
+                kv.set_key("value", &0_u32).await?;
+                assert!(kv.rate_limit("value", limit).await.is_err());
+
+                harness.shutdown().await?;
+
+                Ok(())
+            }
+
             #[tokio::test]
             async fn kv_increment_decrement_tests() -> anyhow::Result<()> {
                 use $crate::keyvalue::{AsyncKeyValue, KeyStatus};
@@ -3400,6 +5267,368 @@ macro_rules! define_blocking_kv_test_suite {
                 Ok(())
             }
 
+            #[test]
+            fn kv_scan_tests() -> anyhow::Result<()> {
+                use $crate::connection::{Bound, Range};
+                use $crate::keyvalue::KeyValue;
+                let harness = $harness::new($crate::test_util::HarnessTest::KvScan)?;
+                let db = harness.connect()?;
+                let kv = db.with_key_namespace("scan");
+                for key in ["a", "b1", "b2", "b3", "c"] {
+                    kv.set_key(key, &String::from(key)).execute()?;
+                }
+                // Keys in other namespaces are not listed.
+                db.set_key("b4", &String::from("b4")).execute()?;
+                kv.with_key_namespace("nested")
+                    .set_key("b5", &String::from("b5"))
+                    .execute()?;
+
+                assert_eq!(kv.scan_keys().keys()?, vec!["a", "b1", "b2", "b3", "c"]);
+                assert_eq!(
+                    kv.scan_keys().with_prefix("b").keys()?,
+                    vec!["b1", "b2", "b3"]
+                );
+                assert_eq!(
+                    kv.scan_keys()
+                        .with_range(Range {
+                            start: Bound::Excluded(String::from("a")),
+                            end: Bound::Included(String::from("b2")),
+                        })
+                        .keys()?,
+                    vec!["b1", "b2"]
+                );
+                let entries = kv.scan_keys().with_prefix("c").query()?;
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].0, "c");
+                assert_eq!(entries[0].1.deserialize::<String>()?, "c");
+
+                // Page through the keys two at a time.
+                let first_page = kv.scan_keys().with_prefix("b").limit(2).keys_page()?;
+                assert_eq!(first_page.results, vec!["b1", "b2"]);
+                let second_page = kv
+                    .scan_keys()
+                    .with_prefix("b")
+                    .limit(2)
+                    .after(first_page.next.expect("more keys"))
+                    .query_page()?;
+                assert_eq!(second_page.results.len(), 1);
+                assert_eq!(second_page.results[0].0, "b3");
+                assert!(second_page.next.is_none());
+
+                kv.delete_key("b2")?;
+                assert_eq!(kv.scan_keys().with_prefix("b").keys()?, vec!["b1", "b3"]);
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
+            #[test]
+            fn kv_batch_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{KeyStatus, KeyValue, Output, Value};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvBatch)?;
+                let db = harness.connect()?;
+                let kv = db.with_key_namespace("batch");
+
+                let outputs = kv
+                    .batch()
+                    .set_key("flag", &true)
+                    .only_if_vacant()
+                    .increment_key_by("counter", 1_u64)
+                    .execute()?
+                    .expect("checks should pass");
+                assert!(matches!(
+                    outputs[0],
+                    Output::Status(KeyStatus::Inserted)
+                ));
+                assert!(matches!(
+                    &outputs[1],
+                    Output::Value(Some(Value::Numeric(value))) if value.as_u64() == Some(1)
+                ));
+
+                // The flag is already set, so the counter isn't incremented.
+                assert!(kv
+                    .batch()
+                    .set_key("flag", &true)
+                    .only_if_vacant()
+                    .increment_key_by("counter", 1_u64)
+                    .execute()?
+                    .is_none());
+                assert_eq!(kv.get_key("counter").into_u64()?, Some(1));
+
+                // Incrementing the flag fails, which undoes the entire batch.
+                assert!(kv
+                    .batch()
+                    .increment_key_by("counter", 1_u64)
+                    .set_key("note", &String::from("undone"))
+                    .increment_key_by("flag", 1_u64)
+                    .execute()
+                    .is_err());
+                assert_eq!(kv.get_key("counter").into_u64()?, Some(1));
+                assert_eq!(kv.get_key("note").query()?, None);
+
+                kv.batch()
+                    .delete_key("flag")
+                    .only_if_exists()
+                    .decrement_key_by("counter", 1_u64)
+                    .execute()?
+                    .expect("checks should pass");
+                assert_eq!(kv.get_key("counter").into_u64()?, Some(0));
+                assert_eq!(kv.get_key("flag").query()?, None);
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
+            #[test]
+            fn kv_structures_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{KeyStatus, KeyValue};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvStructures)?;
+                let db = harness.connect()?;
+                let kv = db.with_key_namespace("structures");
+
+                let list = kv.key_list("list");
+                assert_eq!(list.push_back(&2_u32)?, 1);
+                assert_eq!(list.push_front(&1_u32)?, 2);
+                assert_eq!(list.push_back(&3_u32)?, 3);
+                assert_eq!(list.pop_front::<u32>()?, Some(1));
+                assert_eq!(list.pop_back::<u32>()?, Some(3));
+                assert_eq!(list.pop_back::<u32>()?, Some(2));
+                assert_eq!(list.pop_back::<u32>()?, None);
+                // Popping the last value deletes the key.
+                assert_eq!(kv.get_key("list").query()?, None);
+
+                let set = kv.key_set("set");
+                assert!(set.add(&"a")?);
+                assert!(!set.add(&"a")?);
+                assert!(set.add(&"b")?);
+                assert!(set.contains(&"a")?);
+                assert!(!set.contains(&"c")?);
+                assert!(set.remove(&"a")?);
+                assert!(!set.remove(&"a")?);
+                assert!(set.remove(&"b")?);
+                assert_eq!(kv.get_key("set").query()?, None);
+
+                let hash = kv.key_hash("hash");
+                assert_eq!(hash.set_field("name", &"alice")?, KeyStatus::Inserted);
+                assert_eq!(hash.set_field("name", &"bob")?, KeyStatus::Updated);
+                assert_eq!(hash.set_field("age", &30_u8)?, KeyStatus::Inserted);
+                assert_eq!(
+                    hash.get_field::<_, String>("name")?.as_deref(),
+                    Some("bob")
+                );
+                assert_eq!(hash.get_field::<_, u8>("missing")?, None);
+                assert!(hash.delete_field("name")?);
+                assert!(!hash.delete_field("name")?);
+                assert!(hash.delete_field("age")?);
+                assert_eq!(kv.get_key("hash").query()?, None);
+
+                // Whole structures can be read, and writes can update the
+                // key's expiration.
+                let expiring = kv
+                    .key_list("expiring")
+                    .expire_in(std::time::Duration::from_secs(3_600));
+                assert_eq!(expiring.push_back(&1_u32)?, 1);
+                assert_eq!(expiring.push_back(&2_u32)?, 2);
+                let entry = kv
+                    .get_key("expiring")
+                    .with_metadata()?
+                    .expect("list missing");
+                assert!(entry.expiration.is_some());
+                assert!(matches!(
+                    &entry.value,
+                    $crate::keyvalue::Value::List(list) if list.len() == 2
+                ));
+                // Setting the key replaces the list.
+                kv.set_key("expiring", &3_u32).execute()?;
+                assert_eq!(kv.get_key("expiring").into::<u32>()?, Some(3));
+
+                // Operating on a key containing another type of value fails.
+                kv.set_key("bytes", &1_u32).execute()?;
+                assert!(kv.key_list("bytes").push_back(&1_u32).is_err());
+                assert!(kv.key_set("bytes").add(&1_u32).is_err());
+                assert!(kv.key_hash("bytes").set_field("a", &1_u32).is_err());
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
+            #[test]
+            fn kv_compare_and_swap_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{KeyStatus, KeyValue};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvCompareAndSwap)?;
+                let db = harness.connect()?;
+                let kv = db.with_key_namespace("cas");
+
+                // A missing key never matches.
+                assert_eq!(
+                    kv.set_key("leader", &String::from("a"))
+                        .only_if_equal(&String::from("a"))
+                        .execute()?,
+                    KeyStatus::NotChanged
+                );
+                kv.set_key("leader", &String::from("a")).execute()?;
+                assert_eq!(
+                    kv.set_key("leader", &String::from("b"))
+                        .only_if_equal(&String::from("c"))
+                        .execute()?,
+                    KeyStatus::NotChanged
+                );
+                assert_eq!(
+                    kv.set_key("leader", &String::from("b"))
+                        .only_if_equal(&String::from("a"))
+                        .execute()?,
+                    KeyStatus::Updated
+                );
+                assert_eq!(
+                    kv.get_key("leader").into::<String>()?.as_deref(),
+                    Some("b")
+                );
+
+                // Integers compare equal regardless of signedness.
+                kv.set_numeric_key("term", 1_u64).execute()?;
+                assert_eq!(
+                    kv.set_numeric_key("term", 2_u64)
+                        .only_if_equal_numeric(1_i64)
+                        .execute()?,
+                    KeyStatus::Updated
+                );
+                assert_eq!(
+                    kv.set_numeric_key("term", 3_u64)
+                        .only_if_equal_numeric(1_u64)
+                        .execute()?,
+                    KeyStatus::NotChanged
+                );
+                assert_eq!(kv.get_key("term").into_u64()?, Some(2));
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
+            #[test]
+            fn kv_expire_and_persist_tests() -> anyhow::Result<()> {
+                use std::time::Duration;
+
+                use $crate::keyvalue::{KeyStatus, KeyValue};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvExpireAndPersist)?;
+                let db = harness.connect()?;
+                let kv = db.with_key_namespace("ttl");
+
+                // Missing keys have no metadata and can't be expired.
+                assert!(kv.get_key("session").with_metadata()?.is_none());
+                assert_eq!(
+                    kv.expire_key("session", Duration::from_secs(60))?,
+                    KeyStatus::NotChanged
+                );
+                assert_eq!(kv.persist_key("session")?, KeyStatus::NotChanged);
+
+                kv.set_key("session", &String::from("token")).execute()?;
+                let entry = kv.get_key("session").with_metadata()?.unwrap();
+                assert_eq!(entry.value.deserialize::<String>()?, "token");
+                assert!(entry.expiration.is_none());
+                assert!(entry.expires_in().is_none());
+
+                assert_eq!(
+                    kv.expire_key("session", Duration::from_secs(60))?,
+                    KeyStatus::Updated
+                );
+                let expiring = kv.get_key("session").with_metadata()?.unwrap();
+                assert!(expiring.expiration.is_some());
+                assert!(expiring.expires_in().unwrap() <= Duration::from_secs(60));
+                assert_eq!(expiring.last_updated, entry.last_updated);
+
+                assert_eq!(kv.persist_key("session")?, KeyStatus::Updated);
+                let persisted = kv.get_key("session").with_metadata()?.unwrap();
+                assert!(persisted.expiration.is_none());
+                assert_eq!(persisted.value.deserialize::<String>()?, "token");
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
+            #[test]
+            fn kv_typed_namespace_tests() -> anyhow::Result<()> {
+                use $crate::keyvalue::{KeyStatus, KeyValue, KeyValueNamespace};
+
+                struct Sessions;
+
+                impl KeyValueNamespace for Sessions {
+                    type Key = u64;
+                    type Value = String;
+
+                    const NAME: &'static str = "sessions";
+                }
+
+                let harness = $harness::new($crate::test_util::HarnessTest::KvTypedNamespace)?;
+                let db = harness.connect()?;
+                let sessions = db.typed_namespace::<Sessions>();
+
+                assert_eq!(sessions.get(&1)?, None);
+                sessions.set(&1, &String::from("alice"))?.execute()?;
+                sessions.set(&2, &String::from("bob"))?.execute()?;
+                assert_eq!(sessions.get(&1)?.as_deref(), Some("alice"));
+
+                // Keys are stored using their encoded form within the namespace.
+                let raw = db.with_key_namespace(Sessions::NAME);
+                assert_eq!(
+                    raw.get_key(Sessions::encode_key(&2)?)
+                        .into::<String>()?
+                        .as_deref(),
+                    Some("bob")
+                );
+
+                assert_eq!(sessions.take(&2)?.as_deref(), Some("bob"));
+                assert_eq!(sessions.get(&2)?, None);
+                assert_eq!(sessions.delete(&1)?, KeyStatus::Deleted);
+                assert_eq!(sessions.delete(&1)?, KeyStatus::NotChanged);
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
+            #[test]
+            fn kv_rate_limit_tests() -> anyhow::Result<()> {
+                use std::time::Duration;
+
+                use $crate::keyvalue::{KeyValue, RateLimit};
+                let harness = $harness::new($crate::test_util::HarnessTest::KvRateLimit)?;
+                let db = harness.connect()?;
+                let kv = db.with_key_namespace("limits");
+                let limit = RateLimit::token_bucket(2, 1, Duration::from_secs(3600));
+
+                let status = kv.rate_limit("client", limit)?;
+                assert!(status.allowed);
+                assert_eq!(status.remaining, 1);
+                assert!(kv.rate_limit("client", limit)?.allowed);
+
+                // The bucket is empty until the next refill.
+                let status = kv.rate_limit("client", limit)?;
+                assert!(!status.allowed);
+                assert_eq!(status.remaining, 0);
+                let retry_after = status.retry_after.unwrap();
+                assert!(retry_after > Duration::ZERO);
+                assert!(retry_after <= Duration::from_secs(3600));
+
+                // Requests costing more than the capacity are never allowed.
+                let status = kv.rate_limit("other", limit.with_cost(3))?;
+                assert!(!status.allowed);
+                assert_eq!(status.remaining, 2);
+                assert_eq!(status.retry_after, None);
+
+                // Rate limits can't be applied to keys storing other values.
+                kv.set_key("value", &0_u32).execute()?;
+                assert!(kv.rate_limit("value", limit).is_err());
+
+                harness.shutdown()?;
+
+                Ok(())
+            }
+
             #[test]
             fn kv_increment_decrement_tests() -> anyhow::Result<()> {
                 use $crate::keyvalue::{KeyStatus, KeyValue};
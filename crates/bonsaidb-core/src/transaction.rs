@@ -415,6 +415,35 @@ pub struct Executed {
     pub changes: Changes,
 }
 
+impl Executed {
+    /// Returns the changes this transaction made to `collection`, or None if
+    /// no documents in `collection` were changed.
+    #[must_use]
+    pub fn collection_changes(&self, collection: &CollectionName) -> Option<CollectionChanges> {
+        let documents = self.changes.documents()?;
+        let index = documents
+            .collections
+            .iter()
+            .position(|changed| changed == collection)?;
+        let index = u16::try_from(index).ok()?;
+        let changes = documents
+            .documents
+            .iter()
+            .filter(|doc| doc.collection == index)
+            .map(|doc| DocumentChange {
+                id: doc.id.clone(),
+                deleted: doc.deleted,
+            })
+            .collect::<Vec<_>>();
+
+        (!changes.is_empty()).then(|| CollectionChanges {
+            transaction_id: self.id,
+            collection: collection.clone(),
+            changes,
+        })
+    }
+}
+
 /// A list of changes.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Changes {
@@ -606,6 +635,57 @@ fn document_changes_iter() {
     assert_eq!(b_changes, 1);
 }
 
+#[test]
+fn executed_collection_changes() {
+    use crate::schema::Qualified;
+
+    let executed = Executed {
+        id: 42,
+        changes: Changes::Documents(DocumentChanges {
+            collections: vec![CollectionName::private("a"), CollectionName::private("b")],
+            documents: vec![
+                ChangedDocument {
+                    collection: 1,
+                    id: DocumentId::from_u64(0),
+                    deleted: false,
+                },
+                ChangedDocument {
+                    collection: 0,
+                    id: DocumentId::from_u64(1),
+                    deleted: true,
+                },
+                ChangedDocument {
+                    collection: 1,
+                    id: DocumentId::from_u64(2),
+                    deleted: true,
+                },
+            ],
+        }),
+    };
+
+    let b_changes = executed
+        .collection_changes(&CollectionName::private("b"))
+        .unwrap();
+    assert_eq!(b_changes.transaction_id, 42);
+    assert_eq!(b_changes.collection, CollectionName::private("b"));
+    assert_eq!(
+        b_changes.changes,
+        vec![
+            DocumentChange {
+                id: DocumentId::from_u64(0),
+                deleted: false,
+            },
+            DocumentChange {
+                id: DocumentId::from_u64(2),
+                deleted: true,
+            },
+        ]
+    );
+    assert!(executed
+        .collection_changes(&CollectionName::private("c"))
+        .is_none());
+}
+
 /// A record of a changed document.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangedDocument {
@@ -631,3 +711,26 @@ pub struct ChangedKey {
     /// True if the key was deleted.
     pub deleted: bool,
 }
+
+/// The changes a single transaction made to a single collection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CollectionChanges {
+    /// The id of the transaction that made these changes.
+    pub transaction_id: u64,
+
+    /// The collection that was changed.
+    pub collection: CollectionName,
+
+    /// The documents changed, in the order they were changed.
+    pub changes: Vec<DocumentChange>,
+}
+
+/// A record of a changed document within a [`CollectionChanges`].
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DocumentChange {
+    /// The id of the changed `Document`.
+    pub id: DocumentId,
+
+    /// If the `Document` has been deleted, this will be `true`.
+    pub deleted: bool,
+}
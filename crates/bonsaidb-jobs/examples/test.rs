@@ -1,4 +1,5 @@
-use bonsaidb_core::schema::{Qualified, Schema, SchemaName, Schematic};
+use bonsaidb_core::schema::{Schema, SchemaName, Schematic};
+use bonsaidb_jobs::job::Progress;
 use bonsaidb_jobs::queue::{Queue, QueueOwner};
 use bonsaidb_local::{
     config::{Builder, StorageConfiguration},
@@ -20,6 +21,20 @@ impl Schema for TestSchema {
 
 fn main() {
     let db = Database::open::<TestSchema>(StorageConfiguration::new("jobs-test.bonsaidb")).unwrap();
-    let queue = Queue::create(QueueOwner::Backend, "hello-world", &db).unwrap();
+    let queue = match Queue::find(QueueOwner::Backend, "hello-world", &db).unwrap() {
+        Some(queue) => queue,
+        None => Queue::create(QueueOwner::Backend, "hello-world", &db).unwrap(),
+    };
     println!("Queue: {queue:?}");
+
+    queue.enqueue(b"hello, world!".to_vec(), &db).unwrap();
+
+    while let Some(mut job) = queue.lease_next(&db).unwrap() {
+        println!("Leased job {}: {:?}", job.id(), job.payload());
+        job.report_progress(Progress::new(1, 1), &db).unwrap();
+        job.ack(&db).unwrap();
+    }
+
+    let removed = queue.remove_completed(&db).unwrap();
+    println!("Removed {removed} completed jobs");
 }
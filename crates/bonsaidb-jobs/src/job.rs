@@ -0,0 +1,370 @@
+use std::fmt::Display;
+use std::ops::Deref;
+use std::time::Duration;
+
+use bonsaidb_core::connection::{AsyncConnection, Connection};
+use bonsaidb_core::document::CollectionDocument;
+use bonsaidb_core::key::time::TimestampAsNanoseconds;
+use bonsaidb_core::key::Key;
+use bonsaidb_core::schema::SerializedCollection;
+use serde::{Deserialize, Serialize};
+
+use crate::queue::QueueConfig;
+use crate::schema::job::JobRecord;
+use crate::Error;
+
+/// The state of a [`Job`].
+///
+/// The order of the variants is part of the on-disk format of the queue's
+/// views, and must not be changed.
+#[derive(Key, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[key(core = bonsaidb_core)]
+pub enum JobState {
+    /// The job is waiting to be leased by a worker.
+    Pending,
+    /// The job has been leased by a worker and is not visible to other
+    /// workers until the lease expires.
+    Leased,
+    /// The job has been acknowledged as completed.
+    Completed,
+    /// The job has exhausted its attempts or was explicitly moved to the dead
+    /// letters.
+    DeadLettered,
+}
+
+/// Progress reported by a worker while executing a job.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct Progress {
+    /// The number of units of work completed.
+    pub completed: u64,
+    /// The total number of units of work, if known.
+    pub total: Option<u64>,
+    /// An optional description of the current step.
+    pub message: Option<String>,
+}
+
+impl Progress {
+    /// Returns a new progress report with `completed` out of `total` units of
+    /// work done.
+    #[must_use]
+    pub const fn new(completed: u64, total: u64) -> Self {
+        Self {
+            completed,
+            total: Some(total),
+            message: None,
+        }
+    }
+
+    /// Sets the message of this progress report and returns self.
+    #[must_use]
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// A job stored in a [`Queue`](crate::queue::Queue).
+#[derive(Debug, Clone)]
+pub struct Job(pub(crate) CollectionDocument<JobRecord>);
+
+impl Job {
+    /// Returns the job with the unique `id` given, if found.
+    pub fn get<Database: Connection>(id: u64, database: &Database) -> Result<Option<Self>, Error> {
+        Ok(JobRecord::get(&id, database)?.map(Self))
+    }
+
+    /// Returns the job with the unique `id` given, if found.
+    pub async fn get_async<Database: AsyncConnection>(
+        id: u64,
+        database: &Database,
+    ) -> Result<Option<Self>, Error> {
+        Ok(JobRecord::get_async(&id, database).await?.map(Self))
+    }
+
+    /// Returns the unique id of this job.
+    #[must_use]
+    pub const fn id(&self) -> u64 {
+        self.0.header.id
+    }
+
+    /// Returns the id of the queue this job belongs to.
+    #[must_use]
+    pub const fn queue_id(&self) -> u64 {
+        self.0.contents.queue_id
+    }
+
+    /// Returns the payload of this job.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.0.contents.payload
+    }
+
+    /// Returns the current state of this job.
+    #[must_use]
+    pub const fn state(&self) -> JobState {
+        self.0.contents.state
+    }
+
+    /// Returns the number of times this job has been leased since it was
+    /// enqueued or last retried.
+    #[must_use]
+    pub const fn attempts(&self) -> u32 {
+        self.0.contents.attempts
+    }
+
+    /// Returns the time this job was enqueued.
+    #[must_use]
+    pub const fn enqueued_at(&self) -> TimestampAsNanoseconds {
+        self.0.contents.enqueued_at
+    }
+
+    /// Returns the earliest time this job can be leased while it is pending.
+    #[must_use]
+    pub const fn available_at(&self) -> TimestampAsNanoseconds {
+        self.0.contents.available_at
+    }
+
+    /// Returns the time the current lease expires, if this job is leased.
+    #[must_use]
+    pub const fn leased_until(&self) -> Option<TimestampAsNanoseconds> {
+        self.0.contents.leased_until
+    }
+
+    /// Returns the time this job was completed or moved to the dead letters.
+    #[must_use]
+    pub const fn finished_at(&self) -> Option<TimestampAsNanoseconds> {
+        self.0.contents.finished_at
+    }
+
+    /// Returns the last progress reported for this job.
+    #[must_use]
+    pub const fn progress(&self) -> Option<&Progress> {
+        self.0.contents.progress.as_ref()
+    }
+
+    /// Returns the error recorded by the last failed attempt, if any.
+    #[must_use]
+    pub fn last_error(&self) -> Option<&str> {
+        self.0.contents.last_error.as_deref()
+    }
+
+    /// Returns this job to the pending state with its attempts reset. This is
+    /// typically used to retry a job from the dead letters.
+    pub fn retry<Database: Connection>(&mut self, database: &Database) -> Result<(), Error> {
+        self.0.contents.reset(TimestampAsNanoseconds::now());
+        self.0.update(database)?;
+        Ok(())
+    }
+
+    /// Returns this job to the pending state with its attempts reset. This is
+    /// typically used to retry a job from the dead letters.
+    pub async fn retry_async<Database: AsyncConnection>(
+        &mut self,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.0.contents.reset(TimestampAsNanoseconds::now());
+        self.0.update_async(database).await?;
+        Ok(())
+    }
+
+    /// Permanently removes this job.
+    pub fn delete<Database: Connection>(self, database: &Database) -> Result<(), Error> {
+        self.0.delete(database)?;
+        Ok(())
+    }
+
+    /// Permanently removes this job.
+    pub async fn delete_async<Database: AsyncConnection>(
+        self,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.0.delete_async(database).await?;
+        Ok(())
+    }
+}
+
+/// A [`Job`] that has been leased by a worker.
+///
+/// While a job is leased, it will not be returned to other workers until the
+/// lease expires. Each method that changes the job verifies that the job has
+/// not been changed since it was leased. If another worker has leased the job
+/// after the lease expired, [`Error::LeaseLost`] is returned.
+#[derive(Debug)]
+pub struct LeasedJob {
+    job: Job,
+    config: QueueConfig,
+}
+
+impl LeasedJob {
+    pub(crate) const fn new(document: CollectionDocument<JobRecord>, config: QueueConfig) -> Self {
+        Self {
+            job: Job(document),
+            config,
+        }
+    }
+
+    /// Marks this job as completed.
+    pub fn ack<Database: Connection>(mut self, database: &Database) -> Result<Job, Error> {
+        self.job.0.contents.complete(TimestampAsNanoseconds::now());
+        lease_result(self.job.0.update(database))?;
+        Ok(self.job)
+    }
+
+    /// Marks this job as completed.
+    pub async fn ack_async<Database: AsyncConnection>(
+        mut self,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.job.0.contents.complete(TimestampAsNanoseconds::now());
+        lease_result(self.job.0.update_async(database).await)?;
+        Ok(self.job)
+    }
+
+    /// Records that this attempt failed with `error`.
+    ///
+    /// If the job has attempts remaining, it will become available again after
+    /// the queue's [`Backoff`](crate::queue::Backoff) delay. Otherwise, it is
+    /// moved to the dead letters. The returned job reflects the new state.
+    pub fn nack<Database: Connection>(
+        mut self,
+        error: impl Display,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.job.0.contents.fail(
+            error.to_string(),
+            &self.config,
+            TimestampAsNanoseconds::now(),
+        );
+        lease_result(self.job.0.update(database))?;
+        Ok(self.job)
+    }
+
+    /// Records that this attempt failed with `error`.
+    ///
+    /// If the job has attempts remaining, it will become available again after
+    /// the queue's [`Backoff`](crate::queue::Backoff) delay. Otherwise, it is
+    /// moved to the dead letters. The returned job reflects the new state.
+    pub async fn nack_async<Database: AsyncConnection>(
+        mut self,
+        error: impl Display + Send,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.job.0.contents.fail(
+            error.to_string(),
+            &self.config,
+            TimestampAsNanoseconds::now(),
+        );
+        lease_result(self.job.0.update_async(database).await)?;
+        Ok(self.job)
+    }
+
+    /// Moves this job to the dead letters without retrying it, regardless of
+    /// how many attempts remain.
+    pub fn dead_letter<Database: Connection>(
+        mut self,
+        error: impl Display,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.job
+            .0
+            .contents
+            .dead_letter(Some(error.to_string()), TimestampAsNanoseconds::now());
+        lease_result(self.job.0.update(database))?;
+        Ok(self.job)
+    }
+
+    /// Moves this job to the dead letters without retrying it, regardless of
+    /// how many attempts remain.
+    pub async fn dead_letter_async<Database: AsyncConnection>(
+        mut self,
+        error: impl Display + Send,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.job
+            .0
+            .contents
+            .dead_letter(Some(error.to_string()), TimestampAsNanoseconds::now());
+        lease_result(self.job.0.update_async(database).await)?;
+        Ok(self.job)
+    }
+
+    /// Stores `progress` for this job.
+    pub fn report_progress<Database: Connection>(
+        &mut self,
+        progress: Progress,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.job.0.contents.progress = Some(progress);
+        lease_result(self.job.0.update(database))
+    }
+
+    /// Stores `progress` for this job.
+    pub async fn report_progress_async<Database: AsyncConnection>(
+        &mut self,
+        progress: Progress,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.job.0.contents.progress = Some(progress);
+        lease_result(self.job.0.update_async(database).await)
+    }
+
+    /// Extends the lease on this job by the queue's visibility timeout,
+    /// starting from now.
+    pub fn extend_lease<Database: Connection>(&mut self, database: &Database) -> Result<(), Error> {
+        self.job.0.contents.leased_until = Some(offset_timestamp(
+            TimestampAsNanoseconds::now(),
+            self.config.visibility_timeout,
+        ));
+        lease_result(self.job.0.update(database))
+    }
+
+    /// Extends the lease on this job by the queue's visibility timeout,
+    /// starting from now.
+    pub async fn extend_lease_async<Database: AsyncConnection>(
+        &mut self,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.job.0.contents.leased_until = Some(offset_timestamp(
+            TimestampAsNanoseconds::now(),
+            self.config.visibility_timeout,
+        ));
+        lease_result(self.job.0.update_async(database).await)
+    }
+}
+
+impl Deref for LeasedJob {
+    type Target = Job;
+
+    fn deref(&self) -> &Self::Target {
+        &self.job
+    }
+}
+
+/// Converts the result of updating a leased job, translating conflicts into
+/// [`Error::LeaseLost`].
+pub(crate) fn lease_result(result: Result<(), bonsaidb_core::Error>) -> Result<(), Error> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) if is_lease_conflict(&err) => Err(Error::LeaseLost),
+        Err(other) => Err(Error::from(other)),
+    }
+}
+
+/// Returns true if `error` indicates that the job was modified or removed by
+/// someone else.
+pub(crate) const fn is_lease_conflict(error: &bonsaidb_core::Error) -> bool {
+    matches!(
+        error,
+        bonsaidb_core::Error::DocumentConflict(..) | bonsaidb_core::Error::DocumentNotFound(..)
+    )
+}
+
+/// Returns `timestamp` advanced by `duration`, saturating at the maximum
+/// representable timestamp.
+pub(crate) fn offset_timestamp(
+    timestamp: TimestampAsNanoseconds,
+    duration: Duration,
+) -> TimestampAsNanoseconds {
+    let nanos = i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX);
+    TimestampAsNanoseconds::from_representation(timestamp.representation().saturating_add(nanos))
+}
@@ -0,0 +1,107 @@
+//! Persistent job queues for BonsaiDb.
+//!
+//! This crate stores background jobs in [BonsaiDb](https://bonsaidb.io/)
+//! collections. Each [`Queue`](queue::Queue) is identified by its
+//! [`QueueOwner`](queue::QueueOwner) and a name, and jobs pushed into it are
+//! durable until they are acknowledged.
+//!
+//! # Job lifecycle
+//!
+//! - [`Queue::enqueue`](queue::Queue::enqueue) stores a new pending job.
+//! - [`Queue::lease`](queue::Queue::lease) hands pending jobs to a worker for
+//!   the queue's visibility timeout. If the worker does not acknowledge the job
+//!   before the lease expires, the job becomes available to other workers
+//!   again.
+//! - [`LeasedJob::ack`](job::LeasedJob::ack) marks a job as completed.
+//! - [`LeasedJob::nack`](job::LeasedJob::nack) records a failure. The job is
+//!   retried after the queue's [`Backoff`](queue::Backoff) delay, or moved to
+//!   the dead letters once it has been attempted
+//!   [`max_attempts`](queue::QueueConfig::max_attempts) times.
+//! - [`LeasedJob::report_progress`](job::LeasedJob::report_progress) persists
+//!   progress information that can be observed while the job is running.
+//!
+//! Every operation has an `_async` counterpart that accepts an
+//! [`AsyncConnection`](bonsaidb_core::connection::AsyncConnection).
+//!
+//! # Registering the collections
+//!
+//! The queues and jobs are stored in collections that must be registered in
+//! your schema using [`define_collections()`].
+
+#![forbid(unsafe_code)]
+#![warn(
+    clippy::cargo,
+    missing_docs,
+    // clippy::missing_docs_in_private_items,
+    clippy::pedantic,
+    future_incompatible,
+    rust_2018_idioms,
+)]
+#![allow(
+    clippy::missing_errors_doc, // TODO clippy::missing_errors_doc
+    clippy::option_if_let_else,
+    clippy::module_name_repetitions,
+)]
+
+use bonsaidb_core::schema::{InsertError, Schematic};
+
+/// Types for leased and stored jobs.
+pub mod job;
+/// Types for creating and interacting with job queues.
+pub mod queue;
+
+mod schema;
+
+/// Registers the collections this crate uses into `schema`.
+pub fn define_collections(schema: &mut Schematic) -> Result<(), bonsaidb_core::Error> {
+    schema.define_collection::<schema::queue::QueueRecord>()?;
+    schema.define_collection::<schema::job::JobRecord>()?;
+
+    Ok(())
+}
+
+/// Errors that can be returned when interacting with job queues.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// An underlying database error was returned.
+    #[error("database error: {0}")]
+    Database(bonsaidb_core::Error),
+    /// A queue with the same owner and name already exists.
+    #[error("a queue already exists with the owner and name provided")]
+    QueueAlreadyExists,
+    /// The queue was not found.
+    #[error("queue not found")]
+    QueueNotFound,
+    /// The job was not found.
+    #[error("job not found")]
+    JobNotFound,
+    /// The job was modified by another worker since it was leased. This
+    /// happens when a lease expires and the job is leased again, or when the
+    /// job is removed.
+    #[error("the lease on the job has been lost")]
+    LeaseLost,
+}
+
+impl<T> From<InsertError<T>> for Error {
+    fn from(err: InsertError<T>) -> Self {
+        Self::from(err.error)
+    }
+}
+
+impl From<bonsaidb_core::Error> for Error {
+    fn from(err: bonsaidb_core::Error) -> Self {
+        Self::Database(err)
+    }
+}
+
+impl From<Error> for bonsaidb_core::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Database(err) => err,
+            other => Self::other("bonsaidb-jobs", other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,633 @@
+use std::time::Duration;
+
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::connection::{AsyncConnection, Connection, HasSchema};
+use bonsaidb_core::document::CollectionDocument;
+use bonsaidb_core::key::time::TimestampAsNanoseconds;
+use bonsaidb_core::key::Key;
+use bonsaidb_core::schema::view::map::MappedDocuments;
+use bonsaidb_core::schema::SerializedCollection;
+use serde::{Deserialize, Serialize};
+
+use crate::job::{is_lease_conflict, offset_timestamp, Job, JobState, LeasedJob};
+use crate::schema::job::{state_range, ByQueueAndState, JobKey, JobRecord};
+use crate::schema::queue::{ByOwnerAndName, QueueRecord};
+use crate::Error;
+
+/// The owner of a [`Queue`]. Queue names are unique per owner.
+#[derive(Key, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[key(core = bonsaidb_core)]
+pub enum QueueOwner {
+    /// The queue belongs to the application's backend.
+    Backend,
+    /// The queue belongs to the user with the given id.
+    User(u64),
+    /// The queue belongs to the role with the given id.
+    Role(u64),
+}
+
+/// Settings that control how jobs in a [`Queue`] are leased and retried.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct QueueConfig {
+    /// The amount of time a leased job is hidden from other workers. If the
+    /// job is not acknowledged within this duration, it will be leased again.
+    pub visibility_timeout: Duration,
+    /// The number of times a job is leased before it is moved to the dead
+    /// letters.
+    pub max_attempts: u32,
+    /// The delay between failed attempts.
+    pub backoff: Backoff,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            visibility_timeout: Duration::from_secs(30),
+            max_attempts: 5,
+            backoff: Backoff::default(),
+        }
+    }
+}
+
+/// An exponential backoff applied between failed attempts of a job.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Backoff {
+    /// The delay after the first failed attempt. Each subsequent failure
+    /// doubles the delay.
+    pub initial: Duration,
+    /// The largest delay between attempts.
+    pub maximum: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            maximum: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns a backoff that immediately retries failed jobs.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            initial: Duration::ZERO,
+            maximum: Duration::ZERO,
+        }
+    }
+
+    /// Returns the delay before retrying a job that has failed `attempts`
+    /// times.
+    #[must_use]
+    pub fn delay_for(&self, attempts: u32) -> Duration {
+        let multiplier = 2_u32.saturating_pow(attempts.saturating_sub(1));
+        self.initial.saturating_mul(multiplier).min(self.maximum)
+    }
+}
+
+/// A persistent job queue.
+#[derive(Debug, Clone)]
+pub struct Queue(CollectionDocument<QueueRecord>);
+
+impl Queue {
+    /// Creates a new queue named `name` for `owner` using the default
+    /// [`QueueConfig`].
+    ///
+    /// Returns [`Error::QueueAlreadyExists`] if `owner` already has a queue
+    /// named `name`.
+    pub fn create<Database: Connection>(
+        owner: QueueOwner,
+        name: impl Into<String>,
+        database: &Database,
+    ) -> Result<Self, Error> {
+        Self::create_with_config(owner, name, QueueConfig::default(), database)
+    }
+
+    /// Creates a new queue named `name` for `owner` using `config`.
+    ///
+    /// Returns [`Error::QueueAlreadyExists`] if `owner` already has a queue
+    /// named `name`.
+    pub fn create_with_config<Database: Connection>(
+        owner: QueueOwner,
+        name: impl Into<String>,
+        config: QueueConfig,
+        database: &Database,
+    ) -> Result<Self, Error> {
+        let queue = QueueRecord {
+            owner,
+            name: name.into(),
+            config,
+        }
+        .push_into(database)
+        .map_err(|err| queue_creation_error(err.error, database))?;
+        Ok(Self(queue))
+    }
+
+    /// Creates a new queue named `name` for `owner` using the default
+    /// [`QueueConfig`].
+    ///
+    /// Returns [`Error::QueueAlreadyExists`] if `owner` already has a queue
+    /// named `name`.
+    pub async fn create_async<Database: AsyncConnection>(
+        owner: QueueOwner,
+        name: impl Into<String> + Send,
+        database: &Database,
+    ) -> Result<Self, Error> {
+        Self::create_with_config_async(owner, name, QueueConfig::default(), database).await
+    }
+
+    /// Creates a new queue named `name` for `owner` using `config`.
+    ///
+    /// Returns [`Error::QueueAlreadyExists`] if `owner` already has a queue
+    /// named `name`.
+    pub async fn create_with_config_async<Database: AsyncConnection>(
+        owner: QueueOwner,
+        name: impl Into<String> + Send,
+        config: QueueConfig,
+        database: &Database,
+    ) -> Result<Self, Error> {
+        let queue = QueueRecord {
+            owner,
+            name: name.into(),
+            config,
+        }
+        .push_into_async(database)
+        .await
+        .map_err(|err| queue_creation_error(err.error, database))?;
+        Ok(Self(queue))
+    }
+
+    /// Returns the queue with the unique `id` given, if found.
+    pub fn get<Database: Connection>(id: u64, database: &Database) -> Result<Option<Self>, Error> {
+        Ok(QueueRecord::get(&id, database)?.map(Self))
+    }
+
+    /// Returns the queue with the unique `id` given, if found.
+    pub async fn get_async<Database: AsyncConnection>(
+        id: u64,
+        database: &Database,
+    ) -> Result<Option<Self>, Error> {
+        Ok(QueueRecord::get_async(&id, database).await?.map(Self))
+    }
+
+    /// Returns the queue named `name` owned by `owner`, if found.
+    pub fn find<Database: Connection>(
+        owner: QueueOwner,
+        name: &str,
+        database: &Database,
+    ) -> Result<Option<Self>, Error> {
+        let key = (owner, name.to_string());
+        let mapped = database
+            .view::<ByOwnerAndName>()
+            .with_key(&key)
+            .query_with_collection_docs()?;
+        Ok(mapped.documents.into_values().next().map(Self))
+    }
+
+    /// Returns the queue named `name` owned by `owner`, if found.
+    pub async fn find_async<Database: AsyncConnection>(
+        owner: QueueOwner,
+        name: &str,
+        database: &Database,
+    ) -> Result<Option<Self>, Error> {
+        let key = (owner, name.to_string());
+        let mapped = database
+            .view::<ByOwnerAndName>()
+            .with_key(&key)
+            .query_with_collection_docs()
+            .await?;
+        Ok(mapped.documents.into_values().next().map(Self))
+    }
+
+    /// Returns the unique id of this queue.
+    #[must_use]
+    pub const fn id(&self) -> u64 {
+        self.0.header.id
+    }
+
+    /// Returns the owner of this queue.
+    #[must_use]
+    pub const fn owner(&self) -> QueueOwner {
+        self.0.contents.owner
+    }
+
+    /// Returns the name of this queue.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.0.contents.name
+    }
+
+    /// Returns the configuration of this queue.
+    #[must_use]
+    pub const fn config(&self) -> &QueueConfig {
+        &self.0.contents.config
+    }
+
+    /// Replaces the configuration of this queue. Jobs that are currently
+    /// leased keep the configuration they were leased with.
+    pub fn set_config<Database: Connection>(
+        &mut self,
+        config: QueueConfig,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.0.contents.config = config;
+        self.0.update(database)?;
+        Ok(())
+    }
+
+    /// Replaces the configuration of this queue. Jobs that are currently
+    /// leased keep the configuration they were leased with.
+    pub async fn set_config_async<Database: AsyncConnection>(
+        &mut self,
+        config: QueueConfig,
+        database: &Database,
+    ) -> Result<(), Error> {
+        self.0.contents.config = config;
+        self.0.update_async(database).await?;
+        Ok(())
+    }
+
+    /// Deletes this queue and all of its jobs.
+    pub fn delete<Database: Connection>(self, database: &Database) -> Result<(), Error> {
+        database
+            .view::<ByQueueAndState>()
+            .with_key_range::<JobKey, _>(self.all_jobs_range())
+            .delete_docs()?;
+        self.0.delete(database)?;
+        Ok(())
+    }
+
+    /// Deletes this queue and all of its jobs.
+    pub async fn delete_async<Database: AsyncConnection>(
+        self,
+        database: &Database,
+    ) -> Result<(), Error> {
+        database
+            .view::<ByQueueAndState>()
+            .with_key_range::<JobKey, _>(self.all_jobs_range())
+            .delete_docs()
+            .await?;
+        self.0.delete_async(database).await?;
+        Ok(())
+    }
+
+    /// Pushes a new job with `payload` into this queue. The job is available
+    /// to be leased immediately.
+    pub fn enqueue<Database: Connection>(
+        &self,
+        payload: impl Into<Vec<u8>>,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.enqueue_delayed(payload, Duration::ZERO, database)
+    }
+
+    /// Pushes a new job with `payload` into this queue. The job will not be
+    /// leased until `delay` has elapsed.
+    pub fn enqueue_delayed<Database: Connection>(
+        &self,
+        payload: impl Into<Vec<u8>>,
+        delay: Duration,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        let job = self.new_job(payload.into(), delay).push_into(database)?;
+        Ok(Job(job))
+    }
+
+    /// Pushes a new job with `payload` into this queue. The job is available
+    /// to be leased immediately.
+    pub async fn enqueue_async<Database: AsyncConnection>(
+        &self,
+        payload: impl Into<Vec<u8>> + Send,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        self.enqueue_delayed_async(payload, Duration::ZERO, database)
+            .await
+    }
+
+    /// Pushes a new job with `payload` into this queue. The job will not be
+    /// leased until `delay` has elapsed.
+    pub async fn enqueue_delayed_async<Database: AsyncConnection>(
+        &self,
+        payload: impl Into<Vec<u8>> + Send,
+        delay: Duration,
+        database: &Database,
+    ) -> Result<Job, Error> {
+        let job = self
+            .new_job(payload.into(), delay)
+            .push_into_async(database)
+            .await?;
+        Ok(Job(job))
+    }
+
+    /// Leases up to `max_jobs` jobs that are ready to be executed, oldest
+    /// first. Jobs whose lease has expired are eligible to be leased again.
+    ///
+    /// Jobs that were leased by another worker while this call was executing
+    /// are skipped. Jobs whose lease expired after their final attempt are
+    /// moved to the dead letters instead of being returned.
+    pub fn lease<Database: Connection>(
+        &self,
+        max_jobs: u32,
+        database: &Database,
+    ) -> Result<Vec<LeasedJob>, Error> {
+        let now = TimestampAsNanoseconds::now();
+        let config = *self.config();
+        let mut leased = Vec::new();
+        let mut remaining = max_jobs;
+        // Candidates that are moved to the dead letters or leased by another
+        // worker don't count toward `max_jobs`, so candidates are queried until
+        // enough jobs have been leased or none remain.
+        while remaining > 0 {
+            let candidates = self.lease_candidates(remaining, now, database)?;
+            if candidates.is_empty() {
+                break;
+            }
+
+            for mut job in candidates {
+                if remaining == 0 {
+                    break;
+                }
+                let leasing = prepare_lease(&mut job.contents, &config, now);
+                match job.update(database) {
+                    Ok(()) if leasing => {
+                        leased.push(LeasedJob::new(job, config));
+                        remaining -= 1;
+                    }
+                    Ok(()) => {}
+                    Err(err) if is_lease_conflict(&err) => {}
+                    Err(other) => return Err(Error::from(other)),
+                }
+            }
+        }
+
+        Ok(leased)
+    }
+
+    /// Leases up to `max_jobs` jobs that are ready to be executed, oldest
+    /// first. Jobs whose lease has expired are eligible to be leased again.
+    ///
+    /// Jobs that were leased by another worker while this call was executing
+    /// are skipped. Jobs whose lease expired after their final attempt are
+    /// moved to the dead letters instead of being returned.
+    pub async fn lease_async<Database: AsyncConnection>(
+        &self,
+        max_jobs: u32,
+        database: &Database,
+    ) -> Result<Vec<LeasedJob>, Error> {
+        let now = TimestampAsNanoseconds::now();
+        let config = *self.config();
+        let mut leased = Vec::new();
+        let mut remaining = max_jobs;
+        // Candidates that are moved to the dead letters or leased by another
+        // worker don't count toward `max_jobs`, so candidates are queried until
+        // enough jobs have been leased or none remain.
+        while remaining > 0 {
+            let candidates = self
+                .lease_candidates_async(remaining, now, database)
+                .await?;
+            if candidates.is_empty() {
+                break;
+            }
+
+            for mut job in candidates {
+                if remaining == 0 {
+                    break;
+                }
+                let leasing = prepare_lease(&mut job.contents, &config, now);
+                match job.update_async(database).await {
+                    Ok(()) if leasing => {
+                        leased.push(LeasedJob::new(job, config));
+                        remaining -= 1;
+                    }
+                    Ok(()) => {}
+                    Err(err) if is_lease_conflict(&err) => {}
+                    Err(other) => return Err(Error::from(other)),
+                }
+            }
+        }
+
+        Ok(leased)
+    }
+
+    /// Leases the oldest job that is ready to be executed, if one is
+    /// available.
+    pub fn lease_next<Database: Connection>(
+        &self,
+        database: &Database,
+    ) -> Result<Option<LeasedJob>, Error> {
+        Ok(self.lease(1, database)?.into_iter().next())
+    }
+
+    /// Leases the oldest job that is ready to be executed, if one is
+    /// available.
+    pub async fn lease_next_async<Database: AsyncConnection>(
+        &self,
+        database: &Database,
+    ) -> Result<Option<LeasedJob>, Error> {
+        Ok(self.lease_async(1, database).await?.into_iter().next())
+    }
+
+    /// Returns all jobs in this queue that are in `state`.
+    pub fn jobs<Database: Connection>(
+        &self,
+        state: JobState,
+        database: &Database,
+    ) -> Result<Vec<Job>, Error> {
+        Ok(documents_in_key_order(
+            database
+                .view::<ByQueueAndState>()
+                .with_key_range::<JobKey, _>(state_range(self.id(), state, max_timestamp()))
+                .query_with_collection_docs()?,
+        )
+        .into_iter()
+        .map(Job)
+        .collect())
+    }
+
+    /// Returns all jobs in this queue that are in `state`.
+    pub async fn jobs_async<Database: AsyncConnection>(
+        &self,
+        state: JobState,
+        database: &Database,
+    ) -> Result<Vec<Job>, Error> {
+        Ok(documents_in_key_order(
+            database
+                .view::<ByQueueAndState>()
+                .with_key_range::<JobKey, _>(state_range(self.id(), state, max_timestamp()))
+                .query_with_collection_docs()
+                .await?,
+        )
+        .into_iter()
+        .map(Job)
+        .collect())
+    }
+
+    /// Returns all jobs in this queue that have been moved to the dead
+    /// letters. Use [`Job::retry`] to make a dead-lettered job pending again.
+    pub fn dead_letters<Database: Connection>(
+        &self,
+        database: &Database,
+    ) -> Result<Vec<Job>, Error> {
+        self.jobs(JobState::DeadLettered, database)
+    }
+
+    /// Returns all jobs in this queue that have been moved to the dead
+    /// letters. Use [`Job::retry_async`] to make a dead-lettered job pending
+    /// again.
+    pub async fn dead_letters_async<Database: AsyncConnection>(
+        &self,
+        database: &Database,
+    ) -> Result<Vec<Job>, Error> {
+        self.jobs_async(JobState::DeadLettered, database).await
+    }
+
+    /// Removes all completed jobs from this queue, returning the number of
+    /// jobs removed.
+    pub fn remove_completed<Database: Connection>(
+        &self,
+        database: &Database,
+    ) -> Result<u64, Error> {
+        Ok(database
+            .view::<ByQueueAndState>()
+            .with_key_range::<JobKey, _>(state_range(
+                self.id(),
+                JobState::Completed,
+                max_timestamp(),
+            ))
+            .delete_docs()?)
+    }
+
+    /// Removes all completed jobs from this queue, returning the number of
+    /// jobs removed.
+    pub async fn remove_completed_async<Database: AsyncConnection>(
+        &self,
+        database: &Database,
+    ) -> Result<u64, Error> {
+        Ok(database
+            .view::<ByQueueAndState>()
+            .with_key_range::<JobKey, _>(state_range(
+                self.id(),
+                JobState::Completed,
+                max_timestamp(),
+            ))
+            .delete_docs()
+            .await?)
+    }
+
+    /// Returns up to `limit` jobs that are ready to be leased, oldest first.
+    /// Both pending jobs and jobs whose lease has expired are returned.
+    fn lease_candidates<Database: Connection>(
+        &self,
+        limit: u32,
+        now: TimestampAsNanoseconds,
+        database: &Database,
+    ) -> Result<Vec<CollectionDocument<JobRecord>>, Error> {
+        let mut candidates = Vec::new();
+        for state in [JobState::Pending, JobState::Leased] {
+            candidates.extend(documents_in_key_order(
+                database
+                    .view::<ByQueueAndState>()
+                    .with_key_range::<JobKey, _>(state_range(self.id(), state, now))
+                    .limit(limit)
+                    .query_with_collection_docs()?,
+            ));
+        }
+        candidates.sort_by_key(|job| job.contents.state_timestamp());
+        Ok(candidates)
+    }
+
+    /// Returns up to `limit` jobs that are ready to be leased, oldest first.
+    /// Both pending jobs and jobs whose lease has expired are returned.
+    async fn lease_candidates_async<Database: AsyncConnection>(
+        &self,
+        limit: u32,
+        now: TimestampAsNanoseconds,
+        database: &Database,
+    ) -> Result<Vec<CollectionDocument<JobRecord>>, Error> {
+        let mut candidates = Vec::new();
+        for state in [JobState::Pending, JobState::Leased] {
+            candidates.extend(documents_in_key_order(
+                database
+                    .view::<ByQueueAndState>()
+                    .with_key_range::<JobKey, _>(state_range(self.id(), state, now))
+                    .limit(limit)
+                    .query_with_collection_docs()
+                    .await?,
+            ));
+        }
+        candidates.sort_by_key(|job| job.contents.state_timestamp());
+        Ok(candidates)
+    }
+
+    fn new_job(&self, payload: Vec<u8>, delay: Duration) -> JobRecord {
+        let now = TimestampAsNanoseconds::now();
+        JobRecord {
+            queue_id: self.id(),
+            payload: Bytes::from(payload),
+            state: JobState::Pending,
+            attempts: 0,
+            enqueued_at: now,
+            available_at: offset_timestamp(now, delay),
+            leased_until: None,
+            finished_at: None,
+            progress: None,
+            last_error: None,
+        }
+    }
+
+    fn all_jobs_range(&self) -> std::ops::RangeInclusive<JobKey> {
+        let (start, _) = state_range(self.id(), JobState::Pending, max_timestamp()).into_inner();
+        let (_, end) = state_range(self.id(), JobState::DeadLettered, max_timestamp()).into_inner();
+        start..=end
+    }
+}
+
+/// Converts an error from inserting a [`QueueRecord`] into
+/// [`Error::QueueAlreadyExists`] if the queue's name is already in use.
+fn queue_creation_error<Database: HasSchema>(
+    error: bonsaidb_core::Error,
+    database: &Database,
+) -> Error {
+    if error.is_unique_key_error::<ByOwnerAndName, _>(database) {
+        Error::QueueAlreadyExists
+    } else {
+        Error::from(error)
+    }
+}
+
+/// Updates `job` to be leased, returning true if the job should be returned to
+/// the worker. If the job has no attempts remaining, it is moved to the dead
+/// letters instead and false is returned.
+fn prepare_lease(job: &mut JobRecord, config: &QueueConfig, now: TimestampAsNanoseconds) -> bool {
+    if job.state == JobState::Leased {
+        job.last_error = Some(String::from(
+            "the lease expired before the job was acknowledged",
+        ));
+    }
+
+    if job.attempts >= config.max_attempts {
+        job.dead_letter(None, now);
+        false
+    } else {
+        job.lease(now, config.visibility_timeout);
+        true
+    }
+}
+
+fn documents_in_key_order(
+    mut mapped: MappedDocuments<CollectionDocument<JobRecord>, ByQueueAndState>,
+) -> Vec<CollectionDocument<JobRecord>> {
+    let mut documents = Vec::with_capacity(mapped.documents.len());
+    for mapping in &mapped.mappings {
+        if let Some(document) = mapped.documents.remove(&mapping.source.id) {
+            documents.push(document);
+        }
+    }
+    documents
+}
+
+fn max_timestamp() -> TimestampAsNanoseconds {
+    TimestampAsNanoseconds::from_representation(i64::MAX)
+}
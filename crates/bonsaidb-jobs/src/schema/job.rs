@@ -0,0 +1,126 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::document::{CollectionDocument, Emit};
+use bonsaidb_core::key::time::TimestampAsNanoseconds;
+use bonsaidb_core::schema::{Collection, CollectionViewSchema, View, ViewMapResult};
+use serde::{Deserialize, Serialize};
+
+use crate::job::{offset_timestamp, JobState, Progress};
+use crate::queue::QueueConfig;
+
+/// The key emitted by [`ByQueueAndState`]: the queue id, the job's state, and
+/// the job's [state timestamp](JobRecord::state_timestamp).
+pub type JobKey = (u64, JobState, TimestampAsNanoseconds);
+
+#[derive(Debug, Clone, Serialize, Deserialize, Collection)]
+#[collection(name = "jobs", authority = "bonsaidb", views = [ByQueueAndState], core = bonsaidb_core)]
+pub struct JobRecord {
+    pub queue_id: u64,
+    pub payload: Bytes,
+    pub state: JobState,
+    pub attempts: u32,
+    pub enqueued_at: TimestampAsNanoseconds,
+    /// The earliest time this job can be leased while it is pending.
+    pub available_at: TimestampAsNanoseconds,
+    /// The time the current lease expires while the job is leased.
+    pub leased_until: Option<TimestampAsNanoseconds>,
+    /// The time the job was completed or moved to the dead letters.
+    pub finished_at: Option<TimestampAsNanoseconds>,
+    pub progress: Option<Progress>,
+    pub last_error: Option<String>,
+}
+
+impl JobRecord {
+    /// Returns the timestamp this job is ordered by within its current state.
+    pub fn state_timestamp(&self) -> TimestampAsNanoseconds {
+        match self.state {
+            JobState::Pending => self.available_at,
+            JobState::Leased => self.leased_until.unwrap_or(self.available_at),
+            JobState::Completed | JobState::DeadLettered => {
+                self.finished_at.unwrap_or(self.available_at)
+            }
+        }
+    }
+
+    /// Leases this job until `now + visibility_timeout`.
+    pub fn lease(&mut self, now: TimestampAsNanoseconds, visibility_timeout: Duration) {
+        self.state = JobState::Leased;
+        self.attempts = self.attempts.saturating_add(1);
+        self.leased_until = Some(offset_timestamp(now, visibility_timeout));
+    }
+
+    /// Marks this job as completed.
+    pub fn complete(&mut self, now: TimestampAsNanoseconds) {
+        self.state = JobState::Completed;
+        self.leased_until = None;
+        self.finished_at = Some(now);
+    }
+
+    /// Records a failed attempt. The job is scheduled for another attempt
+    /// after the queue's backoff, or moved to the dead letters if no attempts
+    /// remain.
+    pub fn fail(&mut self, error: String, config: &QueueConfig, now: TimestampAsNanoseconds) {
+        if self.attempts >= config.max_attempts {
+            self.dead_letter(Some(error), now);
+        } else {
+            self.state = JobState::Pending;
+            self.leased_until = None;
+            self.available_at = offset_timestamp(now, config.backoff.delay_for(self.attempts));
+            self.last_error = Some(error);
+        }
+    }
+
+    /// Moves this job to the dead letters.
+    pub fn dead_letter(&mut self, error: Option<String>, now: TimestampAsNanoseconds) {
+        self.state = JobState::DeadLettered;
+        self.leased_until = None;
+        self.finished_at = Some(now);
+        if error.is_some() {
+            self.last_error = error;
+        }
+    }
+
+    /// Returns this job to the pending state with no attempts recorded.
+    pub fn reset(&mut self, now: TimestampAsNanoseconds) {
+        self.state = JobState::Pending;
+        self.attempts = 0;
+        self.available_at = now;
+        self.leased_until = None;
+        self.finished_at = None;
+    }
+}
+
+/// Returns the range of keys in [`ByQueueAndState`] for jobs in `queue_id`
+/// with `state` whose state timestamp is no later than `until`.
+pub fn state_range(
+    queue_id: u64,
+    state: JobState,
+    until: TimestampAsNanoseconds,
+) -> RangeInclusive<JobKey> {
+    (
+        queue_id,
+        state,
+        TimestampAsNanoseconds::from_representation(i64::MIN),
+    )..=(queue_id, state, until)
+}
+
+#[derive(Debug, Clone, View)]
+#[view(name = "by-queue-and-state", collection = JobRecord, key = JobKey)]
+#[view(core = bonsaidb_core)]
+pub struct ByQueueAndState;
+
+impl CollectionViewSchema for ByQueueAndState {
+    type View = Self;
+
+    fn lazy(&self) -> bool {
+        false
+    }
+
+    fn map(&self, doc: CollectionDocument<JobRecord>) -> ViewMapResult<Self::View> {
+        let timestamp = doc.contents.state_timestamp();
+        doc.header
+            .emit_key((doc.contents.queue_id, doc.contents.state, timestamp))
+    }
+}
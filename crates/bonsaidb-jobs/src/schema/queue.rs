@@ -0,0 +1,30 @@
+use bonsaidb_core::document::{CollectionDocument, Emit};
+use bonsaidb_core::schema::{Collection, CollectionViewSchema, View, ViewMapResult};
+use serde::{Deserialize, Serialize};
+
+use crate::queue::{QueueConfig, QueueOwner};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Collection)]
+#[collection(name = "queues", authority = "bonsaidb", views = [ByOwnerAndName], core = bonsaidb_core)]
+pub struct QueueRecord {
+    pub owner: QueueOwner,
+    pub name: String,
+    pub config: QueueConfig,
+}
+
+#[derive(Debug, Clone, View)]
+#[view(name = "by-owner-and-name", collection = QueueRecord, key = (QueueOwner, String))]
+#[view(core = bonsaidb_core)]
+pub struct ByOwnerAndName;
+
+impl CollectionViewSchema for ByOwnerAndName {
+    type View = Self;
+
+    fn unique(&self) -> bool {
+        true
+    }
+
+    fn map(&self, doc: CollectionDocument<QueueRecord>) -> ViewMapResult<Self::View> {
+        doc.header.emit_key((doc.contents.owner, doc.contents.name))
+    }
+}
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use bonsaidb_core::schema::{Schema, SchemaName, Schematic};
+use bonsaidb_core::test_util::TestDirectory;
+use bonsaidb_local::config::{Builder, StorageConfiguration};
+use bonsaidb_local::{AsyncDatabase, Database};
+
+use crate::job::{Job, JobState, Progress};
+use crate::queue::{Backoff, Queue, QueueConfig, QueueOwner};
+use crate::Error;
+
+#[derive(Debug)]
+struct JobsSchema;
+
+impl Schema for JobsSchema {
+    fn schema_name() -> SchemaName {
+        SchemaName::private("jobs")
+    }
+
+    fn define_collections(schema: &mut Schematic) -> Result<(), bonsaidb_core::Error> {
+        crate::define_collections(schema)
+    }
+}
+
+fn immediate_retries(max_attempts: u32) -> QueueConfig {
+    QueueConfig {
+        max_attempts,
+        backoff: Backoff::none(),
+        ..QueueConfig::default()
+    }
+}
+
+#[test]
+fn queue_names_are_unique_per_owner() {
+    let directory = TestDirectory::new("jobs-unique-queues");
+    let database = Database::open::<JobsSchema>(StorageConfiguration::new(&directory)).unwrap();
+
+    let queue = Queue::create(QueueOwner::Backend, "emails", &database).unwrap();
+    assert!(matches!(
+        Queue::create(QueueOwner::Backend, "emails", &database),
+        Err(Error::QueueAlreadyExists)
+    ));
+    let user_queue = Queue::create(QueueOwner::User(1), "emails", &database).unwrap();
+    assert_ne!(queue.id(), user_queue.id());
+
+    let found = Queue::find(QueueOwner::Backend, "emails", &database)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id(), queue.id());
+    assert!(Queue::find(QueueOwner::Role(1), "emails", &database)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn lease_and_ack() {
+    let directory = TestDirectory::new("jobs-lease-and-ack");
+    let database = Database::open::<JobsSchema>(StorageConfiguration::new(&directory)).unwrap();
+    let queue = Queue::create(QueueOwner::Backend, "work", &database).unwrap();
+
+    let first = queue.enqueue(b"first".to_vec(), &database).unwrap();
+    let second = queue.enqueue(b"second".to_vec(), &database).unwrap();
+
+    let mut leased = queue.lease(1, &database).unwrap();
+    assert_eq!(leased.len(), 1);
+    let mut job = leased.pop().unwrap();
+    assert_eq!(job.id(), first.id());
+    assert_eq!(job.payload(), b"first");
+    assert_eq!(job.attempts(), 1);
+    assert_eq!(job.state(), JobState::Leased);
+
+    // The leased job is hidden from other workers.
+    let other = queue.lease_next(&database).unwrap().unwrap();
+    assert_eq!(other.id(), second.id());
+    assert!(queue.lease_next(&database).unwrap().is_none());
+
+    job.report_progress(Progress::new(1, 2).with_message("halfway"), &database)
+        .unwrap();
+    let stored = Job::get(job.id(), &database).unwrap().unwrap();
+    assert_eq!(
+        stored.progress().unwrap().message.as_deref(),
+        Some("halfway")
+    );
+
+    let completed = job.ack(&database).unwrap();
+    assert_eq!(completed.state(), JobState::Completed);
+    assert!(completed.finished_at().is_some());
+    other.ack(&database).unwrap();
+
+    assert_eq!(queue.jobs(JobState::Completed, &database).unwrap().len(), 2);
+    assert_eq!(queue.remove_completed(&database).unwrap(), 2);
+    assert!(Job::get(first.id(), &database).unwrap().is_none());
+}
+
+#[test]
+fn nack_retries_then_dead_letters() {
+    let directory = TestDirectory::new("jobs-nack");
+    let database = Database::open::<JobsSchema>(StorageConfiguration::new(&directory)).unwrap();
+    let queue = Queue::create_with_config(
+        QueueOwner::Backend,
+        "flaky",
+        immediate_retries(2),
+        &database,
+    )
+    .unwrap();
+    let enqueued = queue.enqueue(b"flaky".to_vec(), &database).unwrap();
+
+    let job = queue.lease_next(&database).unwrap().unwrap();
+    let job = job.nack("first failure", &database).unwrap();
+    assert_eq!(job.state(), JobState::Pending);
+    assert_eq!(job.last_error(), Some("first failure"));
+
+    let job = queue.lease_next(&database).unwrap().unwrap();
+    assert_eq!(job.attempts(), 2);
+    let job = job.nack("second failure", &database).unwrap();
+    assert_eq!(job.state(), JobState::DeadLettered);
+    assert!(queue.lease_next(&database).unwrap().is_none());
+
+    let mut dead_letters = queue.dead_letters(&database).unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    let mut dead = dead_letters.pop().unwrap();
+    assert_eq!(dead.id(), enqueued.id());
+    assert_eq!(dead.last_error(), Some("second failure"));
+
+    dead.retry(&database).unwrap();
+    assert!(queue.dead_letters(&database).unwrap().is_empty());
+    let job = queue.lease_next(&database).unwrap().unwrap();
+    assert_eq!(job.attempts(), 1);
+}
+
+#[test]
+fn expired_leases_are_released() {
+    let directory = TestDirectory::new("jobs-expired-lease");
+    let database = Database::open::<JobsSchema>(StorageConfiguration::new(&directory)).unwrap();
+    let queue = Queue::create_with_config(
+        QueueOwner::Backend,
+        "slow",
+        QueueConfig {
+            visibility_timeout: Duration::from_millis(10),
+            ..immediate_retries(2)
+        },
+        &database,
+    )
+    .unwrap();
+    queue.enqueue(b"slow".to_vec(), &database).unwrap();
+
+    let mut abandoned = queue.lease_next(&database).unwrap().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+
+    // The lease expired, so another worker can take over the job.
+    let job = queue.lease_next(&database).unwrap().unwrap();
+    assert_eq!(job.id(), abandoned.id());
+    assert_eq!(job.attempts(), 2);
+    assert!(matches!(
+        abandoned.report_progress(Progress::default(), &database),
+        Err(Error::LeaseLost)
+    ));
+
+    // Letting the final attempt expire moves the job to the dead letters.
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(queue.lease_next(&database).unwrap().is_none());
+    assert!(matches!(job.ack(&database), Err(Error::LeaseLost)));
+    let dead_letters = queue.dead_letters(&database).unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(
+        dead_letters[0].last_error(),
+        Some("the lease expired before the job was acknowledged")
+    );
+}
+
+#[test]
+fn dead_lettered_leases_do_not_limit_leasing() {
+    let directory = TestDirectory::new("jobs-dead-lettered-leases");
+    let database = Database::open::<JobsSchema>(StorageConfiguration::new(&directory)).unwrap();
+    let queue = Queue::create_with_config(
+        QueueOwner::Backend,
+        "flaky",
+        QueueConfig {
+            visibility_timeout: Duration::from_millis(10),
+            ..immediate_retries(2)
+        },
+        &database,
+    )
+    .unwrap();
+    queue.enqueue(b"first".to_vec(), &database).unwrap();
+    queue.enqueue(b"second".to_vec(), &database).unwrap();
+    assert_eq!(queue.lease(2, &database).unwrap().len(), 2);
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(queue.lease(2, &database).unwrap().len(), 2);
+    let third = queue.enqueue(b"third".to_vec(), &database).unwrap();
+    assert_eq!(queue.lease(1, &database).unwrap()[0].id(), third.id());
+    std::thread::sleep(Duration::from_millis(20));
+
+    // The first two jobs' leases expire first and are moved to the dead
+    // letters, which must not prevent the third job from being leased.
+    let leased = queue.lease(2, &database).unwrap();
+    assert_eq!(leased.len(), 1);
+    assert_eq!(leased[0].id(), third.id());
+    assert_eq!(leased[0].attempts(), 2);
+    assert_eq!(queue.dead_letters(&database).unwrap().len(), 2);
+}
+
+#[test]
+fn delayed_jobs() {
+    let directory = TestDirectory::new("jobs-delayed");
+    let database = Database::open::<JobsSchema>(StorageConfiguration::new(&directory)).unwrap();
+    let queue = Queue::create(QueueOwner::Backend, "delayed", &database).unwrap();
+
+    queue
+        .enqueue_delayed(b"later".to_vec(), Duration::from_millis(50), &database)
+        .unwrap();
+    assert!(queue.lease_next(&database).unwrap().is_none());
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(queue.lease_next(&database).unwrap().is_some());
+
+    queue.delete(&database).unwrap();
+    assert!(Queue::find(QueueOwner::Backend, "delayed", &database)
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn async_lease_and_ack() {
+    let directory = TestDirectory::new("jobs-lease-and-ack-async");
+    let database = AsyncDatabase::open::<JobsSchema>(StorageConfiguration::new(&directory))
+        .await
+        .unwrap();
+    let queue = Queue::create_with_config_async(
+        QueueOwner::Backend,
+        "work",
+        immediate_retries(1),
+        &database,
+    )
+    .await
+    .unwrap();
+
+    let enqueued = queue
+        .enqueue_async(b"async".to_vec(), &database)
+        .await
+        .unwrap();
+    let mut job = queue.lease_next_async(&database).await.unwrap().unwrap();
+    assert_eq!(job.id(), enqueued.id());
+    job.report_progress_async(Progress::new(1, 1), &database)
+        .await
+        .unwrap();
+    job.ack_async(&database).await.unwrap();
+
+    queue
+        .enqueue_async(b"fails".to_vec(), &database)
+        .await
+        .unwrap();
+    let job = queue.lease_next_async(&database).await.unwrap().unwrap();
+    let job = job.nack_async("failed", &database).await.unwrap();
+    assert_eq!(job.state(), JobState::DeadLettered);
+    assert_eq!(queue.dead_letters_async(&database).await.unwrap().len(), 1);
+}
@@ -4,9 +4,11 @@ use async_trait::async_trait;
 use bonsaidb_core::connection::{
     self, AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncStorageConnection,
     Connection, HasSchema, HasSession, IdentityReference, LowLevelConnection, Range,
-    SerializedQueryKey, Session, Sort, StorageConnection,
+    SerializedQueryKey, Session, Sort, StorageConnection, ViewCursor, ViewSetQuery,
+};
+use bonsaidb_core::document::{
+    DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection,
 };
-use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::keyvalue::{AsyncKeyValue, KeyOperation, KeyValue, Output};
 use bonsaidb_core::permissions::Permissions;
 use bonsaidb_core::pubsub::{self, AsyncPubSub, AsyncSubscriber, PubSub, Receiver};
@@ -19,7 +21,7 @@ use bonsaidb_core::transaction::{self, OperationResult, Transaction};
 use crate::config::StorageConfiguration;
 use crate::database::DatabaseNonBlocking;
 use crate::storage::{AnyBackupLocation, StorageNonBlocking};
-use crate::{Database, Error, Storage, Subscriber};
+use crate::{Database, Error, RestorePoint, Storage, Subscriber};
 
 /// A file-based, multi-database, multi-user database engine. This type is
 /// designed for use with [Tokio](https://tokio.rs). For blocking
@@ -135,7 +137,9 @@ impl AsyncStorage {
             .map(Storage::into_async)
     }
 
-    /// Restores all data from a previously stored backup `location`.
+    /// Restores all data from a previously stored backup `location`. If
+    /// `location` contains incremental backups, they are restored after the
+    /// full backup in the order they were taken.
     pub async fn restore<L: AnyBackupLocation + 'static>(&self, location: L) -> Result<(), Error> {
         let task_self = self.clone();
         self.runtime
@@ -143,6 +147,27 @@ impl AsyncStorage {
             .await?
     }
 
+    /// Restores the database named `database_name` from a previously stored
+    /// backup `location` to the state it was in at `point`. See
+    /// [`Storage::restore_to()`] for more information.
+    pub async fn restore_to<L: AnyBackupLocation + 'static>(
+        &self,
+        location: L,
+        database_name: &str,
+        point: impl Into<RestorePoint>,
+    ) -> Result<(), Error> {
+        let task_self = self.clone();
+        let database_name = database_name.to_string();
+        let point = point.into();
+        self.runtime
+            .spawn_blocking(move || {
+                task_self
+                    .storage
+                    .restore_to(&location, &database_name, point)
+            })
+            .await?
+    }
+
     /// Stores a copy of all data in this instance to `location`.
     pub async fn backup<L: AnyBackupLocation + 'static>(&self, location: L) -> Result<(), Error> {
         let task_self = self.clone();
@@ -151,6 +176,18 @@ impl AsyncStorage {
             .await?
     }
 
+    /// Stores the changes made since the last backup stored in `location`.
+    /// See [`Storage::backup_incremental()`] for more information.
+    pub async fn backup_incremental<L: AnyBackupLocation + 'static>(
+        &self,
+        location: L,
+    ) -> Result<(), Error> {
+        let task_self = self.clone();
+        self.runtime
+            .spawn_blocking(move || task_self.storage.backup_incremental(&location))
+            .await?
+    }
+
     /// Restricts an unauthenticated instance to having `effective_permissions`.
     /// Returns `None` if a session has already been established.
     #[must_use]
@@ -301,6 +338,20 @@ impl AsyncDatabase {
             })
     }
 
+    /// Applies `transaction` as a bulk import, deferring eager view updates
+    /// until every operation has been executed. See
+    /// [`Database::bulk_import()`] for more information.
+    pub async fn bulk_import(
+        &self,
+        transaction: Transaction,
+    ) -> Result<Vec<OperationResult>, bonsaidb_core::Error> {
+        let task_self = self.clone();
+        self.runtime
+            .spawn_blocking(move || task_self.database.bulk_import(transaction))
+            .await
+            .map_err(Error::from)?
+    }
+
     /// Converts this instance into its blocking version, which is able to be
     /// used without async.
     #[must_use]
@@ -690,6 +741,20 @@ impl AsyncSubscriber for Subscriber {
         pubsub::Subscriber::unsubscribe_from_bytes(self, topic)
     }
 
+    async fn subscribe_to_collection_changes(
+        &self,
+        collection: &CollectionName,
+    ) -> Result<(), bonsaidb_core::Error> {
+        pubsub::Subscriber::subscribe_to_collection_changes(self, collection)
+    }
+
+    async fn subscribe_to_key_changes(
+        &self,
+        namespace: Option<&str>,
+    ) -> Result<(), bonsaidb_core::Error> {
+        pubsub::Subscriber::subscribe_to_key_changes(self, namespace)
+    }
+
     fn receiver(&self) -> &Receiver {
         pubsub::Subscriber::receiver(self)
     }
@@ -732,6 +797,7 @@ impl AsyncLowLevelConnection for AsyncDatabase {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
         let task_self = self.clone();
@@ -740,7 +806,33 @@ impl AsyncLowLevelConnection for AsyncDatabase {
             .spawn_blocking(move || {
                 task_self
                     .database
-                    .list_from_collection(ids, order, limit, &collection)
+                    .list_from_collection(ids, order, limit, filter, &collection)
+            })
+            .await
+            .map_err(Error::from)?
+    }
+
+    async fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, bonsaidb_core::Error> {
+        let task_self = self.clone();
+        let collection = collection.clone();
+        self.runtime
+            .spawn_blocking(move || {
+                task_self.database.list_projected_from_collection(
+                    ids,
+                    order,
+                    limit,
+                    filter,
+                    projection,
+                    &collection,
+                )
             })
             .await
             .map_err(Error::from)?
@@ -751,15 +843,20 @@ impl AsyncLowLevelConnection for AsyncDatabase {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, bonsaidb_core::Error> {
         let task_self = self.clone();
         let collection = collection.clone();
         self.runtime
             .spawn_blocking(move || {
-                task_self
-                    .database
-                    .list_headers_from_collection(ids, order, limit, &collection)
+                task_self.database.list_headers_from_collection(
+                    ids,
+                    order,
+                    limit,
+                    filter,
+                    &collection,
+                )
             })
             .await
             .map_err(Error::from)?
@@ -814,6 +911,7 @@ impl AsyncLowLevelConnection for AsyncDatabase {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, bonsaidb_core::Error> {
         let task_self = self.clone();
@@ -822,7 +920,7 @@ impl AsyncLowLevelConnection for AsyncDatabase {
             .spawn_blocking(move || {
                 task_self
                     .database
-                    .query_by_name(&view, key, order, limit, access_policy)
+                    .query_by_name(&view, key, order, limit, cursor, access_policy)
             })
             .await
             .map_err(Error::from)?
@@ -834,20 +932,71 @@ impl AsyncLowLevelConnection for AsyncDatabase {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
         let task_self = self.clone();
         let view = view.clone();
         self.runtime
             .spawn_blocking(move || {
-                task_self
-                    .database
-                    .query_by_name_with_docs(&view, key, order, limit, access_policy)
+                task_self.database.query_by_name_with_docs(
+                    &view,
+                    key,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                    filter,
+                )
+            })
+            .await
+            .map_err(Error::from)?
+    }
+
+    async fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<schema::view::map::MappedSerializedDocuments<ProjectedDocument>, bonsaidb_core::Error>
+    {
+        let task_self = self.clone();
+        let view = view.clone();
+        self.runtime
+            .spawn_blocking(move || {
+                task_self.database.query_by_name_with_projected_docs(
+                    &view,
+                    key,
+                    order,
+                    limit,
+                    cursor,
+                    access_policy,
+                    filter,
+                    projection,
+                )
             })
             .await
             .map_err(Error::from)?
     }
 
+    async fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        let task_self = self.clone();
+        self.runtime
+            .spawn_blocking(move || task_self.database.query_view_set(query, limit))
+            .await
+            .map_err(Error::from)?
+    }
+
     async fn reduce_by_name(
         &self,
         view: &ViewName,
@@ -65,6 +65,20 @@ pub struct StorageConfiguration {
     /// Controls how the key-value store persists keys, on a per-database basis.
     pub key_value_persistence: KeyValuePersistence,
 
+    /// If true, changes made to keys in the key-value store, including keys
+    /// expiring, are published as
+    /// [`KeyChange`](bonsaidb_core::keyvalue::KeyChange)s to the reserved
+    /// topic returned by
+    /// [`key_changes_topic()`](bonsaidb_core::pubsub::key_changes_topic).
+    /// Defaults to false.
+    pub key_value_notifications: bool,
+
+    /// Limits how many keys, or how many bytes of keys and values, each
+    /// database's key-value store can hold. When a limit is exceeded, keys are
+    /// evicted until the store is within its limits again. Defaults to `None`,
+    /// which never evicts keys.
+    pub key_value_eviction: Option<KeyValueEviction>,
+
     /// Sets the default compression algorithm.
     #[cfg(feature = "compression")]
     pub default_compression: Option<Compression>,
@@ -99,6 +113,8 @@ impl Default for StorageConfiguration {
             workers: Tasks::default_for(&system),
             views: Views::default(),
             key_value_persistence: KeyValuePersistence::default(),
+            key_value_notifications: false,
+            key_value_eviction: None,
             authenticated_permissions: Permissions::default(),
             #[cfg(feature = "password-hashing")]
             argon: ArgonConfiguration::default_for(&system),
@@ -310,6 +326,84 @@ impl PersistenceThreshold {
     }
 }
 
+/// Limits on the size of a database's key-value store, and how keys are chosen
+/// to be evicted when a limit is exceeded.
+///
+/// ```rust
+/// # use bonsaidb_local::config::{EvictionPolicy, KeyValueEviction};
+/// let eviction = KeyValueEviction::least_recently_used()
+///     .with_max_entries(10_000)
+///     .with_max_bytes(64 * 1024 * 1024);
+/// assert_eq!(eviction.policy, EvictionPolicy::LeastRecentlyUsed);
+/// assert_eq!(eviction.max_entries, Some(10_000));
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[must_use]
+pub struct KeyValueEviction {
+    /// The maximum number of keys the store may contain.
+    pub max_entries: Option<usize>,
+    /// The maximum number of bytes the store's keys and values may occupy.
+    /// Numeric values are counted as 8 bytes, and lists, sets, and hashes are
+    /// counted as the sum of their contents.
+    pub max_bytes: Option<usize>,
+    /// The policy used to choose which keys to evict.
+    pub policy: EvictionPolicy,
+}
+
+impl KeyValueEviction {
+    /// Returns an unlimited configuration that evicts the least recently used
+    /// keys once a limit is set.
+    pub const fn least_recently_used() -> Self {
+        Self {
+            max_entries: None,
+            max_bytes: None,
+            policy: EvictionPolicy::LeastRecentlyUsed,
+        }
+    }
+
+    /// Returns an unlimited configuration that evicts the least frequently
+    /// used keys once a limit is set.
+    pub const fn least_frequently_used() -> Self {
+        Self {
+            max_entries: None,
+            max_bytes: None,
+            policy: EvictionPolicy::LeastFrequentlyUsed,
+        }
+    }
+
+    /// Sets the maximum number of keys to `max_entries` and returns self.
+    pub const fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Sets the maximum number of bytes to `max_bytes` and returns self.
+    pub const fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Returns true if a store containing `entries` keys totalling `bytes`
+    /// exceeds these limits.
+    #[must_use]
+    pub fn is_exceeded(&self, entries: usize, bytes: usize) -> bool {
+        self.max_entries.map_or(false, |max| entries > max)
+            || self.max_bytes.map_or(false, |max| bytes > max)
+    }
+}
+
+/// Determines which keys are evicted from a key-value store that has exceeded
+/// its [`KeyValueEviction`] limits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EvictionPolicy {
+    /// Evicts the keys that have gone the longest without being read or
+    /// written.
+    LeastRecentlyUsed,
+    /// Evicts the keys that have been read or written the fewest times. Ties
+    /// are broken by evicting the least recently used key.
+    LeastFrequentlyUsed,
+}
+
 /// Storage configuration builder methods.
 pub trait Builder: Sized {
     /// Creates a default configuration with `path` set.
@@ -359,6 +453,12 @@ pub trait Builder: Sized {
     /// Sets [`StorageConfiguration::key_value_persistence`](StorageConfiguration#structfield.key_value_persistence) to `persistence` and returns self.
     #[must_use]
     fn key_value_persistence(self, persistence: KeyValuePersistence) -> Self;
+    /// Sets [`StorageConfiguration::key_value_notifications`](StorageConfiguration#structfield.key_value_notifications) to `enabled` and returns self.
+    #[must_use]
+    fn key_value_notifications(self, enabled: bool) -> Self;
+    /// Sets [`StorageConfiguration::key_value_eviction`](StorageConfiguration#structfield.key_value_eviction) to `eviction` and returns self.
+    #[must_use]
+    fn key_value_eviction(self, eviction: KeyValueEviction) -> Self;
     /// Sets [`Self::authenticated_permissions`](Self#structfield.authenticated_permissions) to `authenticated_permissions` and returns self.
     #[must_use]
     fn authenticated_permissions<P: Into<Permissions>>(self, authenticated_permissions: P) -> Self;
@@ -430,6 +530,16 @@ impl Builder for StorageConfiguration {
         self
     }
 
+    fn key_value_notifications(mut self, enabled: bool) -> Self {
+        self.key_value_notifications = enabled;
+        self
+    }
+
+    fn key_value_eviction(mut self, eviction: KeyValueEviction) -> Self {
+        self.key_value_eviction = Some(eviction);
+        self
+    }
+
     fn authenticated_permissions<P: Into<Permissions>>(
         mut self,
         authenticated_permissions: P,
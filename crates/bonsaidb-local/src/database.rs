@@ -1,19 +1,22 @@
 use std::borrow::{Borrow, Cow};
-use std::collections::{BTreeMap, HashMap, HashSet};
-use std::convert::Infallible;
-use std::ops::{self, Deref};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::{self, Deref, RangeBounds};
 use std::sync::Arc;
 use std::u8;
 
-use bonsaidb_core::arc_bytes::serde::CowBytes;
+use bonsaidb_core::arc_bytes::serde::{Bytes, CowBytes};
 use bonsaidb_core::arc_bytes::ArcBytes;
 use bonsaidb_core::connection::{
     self, AccessPolicy, Connection, HasSchema, HasSession, LowLevelConnection, Range,
-    SerializedQueryKey, Session, Sort, StorageConnection,
+    SerializedQueryKey, Session, Sort, StorageConnection, ViewCursor, ViewSetQuery,
 };
 #[cfg(any(feature = "encryption", feature = "compression"))]
 use bonsaidb_core::document::KeyId;
-use bonsaidb_core::document::{BorrowedDocument, DocumentId, Header, OwnedDocument, Revision};
+use bonsaidb_core::document::{
+    BorrowedDocument, DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection,
+    Revision,
+};
 use bonsaidb_core::keyvalue::{KeyOperation, Output, Timestamp};
 use bonsaidb_core::limits::{
     LIST_TRANSACTIONS_DEFAULT_RESULT_COUNT, LIST_TRANSACTIONS_MAX_RESULTS,
@@ -24,6 +27,7 @@ use bonsaidb_core::permissions::bonsai::{
     ViewAction,
 };
 use bonsaidb_core::permissions::Permissions;
+use bonsaidb_core::pubsub::{collection_changes_topic, database_topic};
 use bonsaidb_core::schema::view::map::MappedSerializedValue;
 use bonsaidb_core::schema::view::{self};
 use bonsaidb_core::schema::{self, CollectionName, Schema, Schematic, ViewName};
@@ -31,6 +35,7 @@ use bonsaidb_core::transaction::{
     self, ChangedDocument, Changes, Command, DocumentChanges, Operation, OperationResult,
     Transaction,
 };
+use easy_parallel::Parallel;
 use itertools::Itertools;
 use nebari::io::any::AnyFile;
 use nebari::tree::{
@@ -42,7 +47,7 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use watchable::Watchable;
 
-use crate::config::{Builder, KeyValuePersistence, StorageConfiguration};
+use crate::config::{Builder, KeyValueEviction, KeyValuePersistence, StorageConfiguration};
 use crate::database::keyvalue::BackgroundWorkerProcessTarget;
 use crate::error::Error;
 use crate::open_trees::OpenTrees;
@@ -50,8 +55,8 @@ use crate::storage::StorageLock;
 #[cfg(feature = "encryption")]
 use crate::storage::TreeVault;
 use crate::views::{
-    mapper, view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
-    ViewEntry,
+    mapper, reductions, view_document_map_tree_name, view_entries_tree_name,
+    view_invalidated_docs_tree_name, view_reductions_tree_name, ViewEntry,
 };
 use crate::Storage;
 
@@ -60,6 +65,9 @@ pub mod keyvalue;
 pub(crate) mod compat;
 pub mod pubsub;
 
+/// The name of the tree that stores the migration state of each collection.
+pub(crate) const COLLECTION_VERSIONS_TREE: &str = "collection-versions";
+
 /// A database stored in BonsaiDb. This type blocks the current thread when
 /// used. See [`AsyncDatabase`](crate::AsyncDatabase) for this type's async counterpart.
 ///
@@ -123,6 +131,16 @@ pub struct Data {
     pub(crate) schema: Arc<Schematic>,
 }
 
+/// Controls when eager views are updated while applying a transaction.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum EagerViewUpdates {
+    /// Each document is mapped as the operation changing it is executed.
+    Inline,
+    /// All changed documents are mapped after every operation has been
+    /// executed.
+    Deferred,
+}
+
 impl Database {
     /// Opens a local file as a bonsaidb.
     pub(crate) fn new<DB: Schema, S: Into<Cow<'static, str>> + Send>(
@@ -193,6 +211,83 @@ impl Database {
         &self.data.schema
     }
 
+    /// Applies `transaction` as a bulk import. This is intended for loading
+    /// large numbers of documents, such as when using
+    /// [`Operation::push_serialized()`] to build a transaction containing many
+    /// documents.
+    ///
+    /// When applying a transaction normally, each document is mapped into every
+    /// eager view as the operation that changed it is executed. During a bulk
+    /// import, eager view updates and their unique key checks are deferred
+    /// until every operation has been executed. The affected views are then
+    /// updated in parallel using batches of documents. Just like
+    /// [`Transaction::apply()`], the import is atomic: if any view returns an
+    /// error, including a [`UniqueKeyViolation`](bonsaidb_core::Error::UniqueKeyViolation),
+    /// none of the transaction's changes are persisted.
+    pub fn bulk_import(
+        &self,
+        transaction: Transaction,
+    ) -> Result<Vec<OperationResult>, bonsaidb_core::Error> {
+        self.apply_transaction_with_mode(transaction, EagerViewUpdates::Deferred, None)
+    }
+
+    /// Applies a page of a collection migration. The documents changed by
+    /// `transaction` and `version`, the serialized migration state of
+    /// `collection`, are persisted in the same transaction.
+    pub(crate) fn apply_migration(
+        &self,
+        transaction: Transaction,
+        collection: &CollectionName,
+        version: Vec<u8>,
+    ) -> Result<(), bonsaidb_core::Error> {
+        self.apply_transaction_with_mode(
+            transaction,
+            EagerViewUpdates::Inline,
+            Some((collection, version)),
+        )?;
+        Ok(())
+    }
+
+    /// Returns the serialized migration state of `collection`, if one has been
+    /// stored by [`Self::apply_migration()`].
+    pub(crate) fn collection_version(
+        &self,
+        collection: &CollectionName,
+    ) -> Result<Option<ArcBytes<'static>>, Error> {
+        self.roots()
+            .tree(Unversioned::tree(COLLECTION_VERSIONS_TREE))?
+            .get(collection.to_string().as_bytes())
+            .map_err(Error::from)
+    }
+
+    /// Invokes `snapshot` with the id of the last transaction executed by
+    /// this database while preventing any other transaction from modifying
+    /// its documents or persisted keys. Everything read by `snapshot` reflects
+    /// the state of the database as of that transaction.
+    pub(crate) fn with_consistent_snapshot<T>(
+        &self,
+        snapshot: impl FnOnce(Option<u64>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut trees = Vec::<Box<dyn AnyTreeRoot<AnyFile>>>::new();
+        for collection in self.data.schema.collections() {
+            trees.push(Box::new(self.collection_tree::<Versioned, _>(
+                collection,
+                document_tree_name(collection),
+            )?));
+        }
+        trees.push(Box::new(Unversioned::tree(keyvalue::KEY_TREE)));
+        trees.push(Box::new(Unversioned::tree(keyvalue::STRUCTURE_TREE)));
+
+        // This transaction is never committed. It only holds the trees' write
+        // locks until `snapshot` returns.
+        let _locks = self
+            .data
+            .context
+            .roots
+            .transaction::<_, dyn AnyTreeRoot<AnyFile>>(&trees)?;
+        snapshot(self.roots().transactions().current_transaction_id())
+    }
+
     pub(crate) fn roots(&self) -> &'_ nebari::Roots<AnyFile> {
         &self.data.context.roots
     }
@@ -203,9 +298,65 @@ impl Database {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
         mut callback: F,
     ) -> Result<(), bonsaidb_core::Error> {
+        self.with_view_entries(view, access_policy, |view_entries| {
+            Self::scan_view_entries(
+                view_entries,
+                key,
+                order,
+                limit,
+                cursor.as_ref(),
+                &mut callback,
+            )
+        })
+    }
+
+    /// Invokes `callback` with each mapping of the entries of the named `view`
+    /// that match the query, after checking that the view may be queried.
+    #[allow(clippy::too_many_arguments)]
+    fn for_each_mapping<
+        F: FnMut(schema::view::map::Serialized) -> Result<(), bonsaidb_core::Error> + Send + Sync,
+    >(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        mut callback: F,
+    ) -> Result<(), bonsaidb_core::Error> {
+        let view = self.schematic().view_by_name(view)?;
+        self.check_permission(
+            view_resource_name(self.name(), &view.view_name()),
+            &BonsaiAction::Database(DatabaseAction::View(ViewAction::Query)),
+        )?;
+        self.for_each_in_view(view, key, order, limit, cursor, access_policy, |entry| {
+            for mapping in entry.mappings {
+                callback(schema::view::map::Serialized {
+                    source: mapping.source,
+                    key: entry.key.clone(),
+                    value: mapping.value,
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Invokes `callback` with the entries tree of `view`, updating the view
+    /// as requested by `access_policy`.
+    fn with_view_entries<
+        T,
+        F: FnOnce(&Tree<Unversioned, AnyFile>) -> Result<T, bonsaidb_core::Error>,
+    >(
+        &self,
+        view: &dyn view::Serialized,
+        access_policy: AccessPolicy,
+        callback: F,
+    ) -> Result<T, bonsaidb_core::Error> {
         if matches!(access_policy, AccessPolicy::UpdateBefore) {
             self.storage
                 .instance
@@ -231,11 +382,7 @@ impl Database {
             )?)
             .map_err(Error::from)?;
 
-        {
-            for entry in Self::create_view_iterator(&view_entries, key, order, limit)? {
-                callback(entry)?;
-            }
-        }
+        let result = callback(&view_entries)?;
 
         if matches!(access_policy, AccessPolicy::UpdateAfter) {
             let db = self.clone();
@@ -251,6 +398,260 @@ impl Database {
                 .update_view_if_needed(view, &db, false)?;
         }
 
+        Ok(result)
+    }
+
+    /// Reduces the entries of `view` whose keys are contained within `range`
+    /// using the view's cached reductions.
+    fn reduce_cached(
+        &self,
+        view: &dyn view::Serialized,
+        range: &Range<Bytes>,
+        access_policy: AccessPolicy,
+    ) -> Result<Vec<u8>, bonsaidb_core::Error> {
+        self.check_permission(
+            view_resource_name(self.name(), &view.view_name()),
+            &BonsaiAction::Database(DatabaseAction::View(ViewAction::Reduce)),
+        )?;
+        self.with_view_entries(view, access_policy, |view_entries| {
+            let view_reductions = self
+                .roots()
+                .tree(self.collection_tree(
+                    &view.collection(),
+                    view_reductions_tree_name(&view.view_name()),
+                )?)
+                .map_err(Error::from)?;
+            let reduced = reductions::reduce(
+                view,
+                view_entries,
+                &view_reductions,
+                &range.map_ref(|bytes| &bytes[..]),
+            )?;
+            Ok(reduced)
+        })
+    }
+
+    /// Returns the ids of the documents matched by `query`. If `candidates`
+    /// is provided, only ids contained in it are returned, which allows
+    /// intersections to avoid collecting ids that can't be part of the result.
+    fn view_set_document_ids(
+        &self,
+        query: &ViewSetQuery,
+        candidates: Option<&BTreeSet<DocumentId>>,
+    ) -> Result<BTreeSet<DocumentId>, bonsaidb_core::Error> {
+        match query {
+            ViewSetQuery::View(query) => {
+                let view = self.schematic().view_by_name(&query.view)?;
+                self.check_permission(
+                    view_resource_name(self.name(), &view.view_name()),
+                    &BonsaiAction::Database(DatabaseAction::View(ViewAction::Query)),
+                )?;
+
+                let mut ids = BTreeSet::new();
+                self.for_each_in_view(
+                    view,
+                    query.key.clone(),
+                    Sort::Ascending,
+                    None,
+                    None,
+                    query.access_policy,
+                    |entry| {
+                        ids.extend(
+                            entry
+                                .mappings
+                                .into_iter()
+                                .map(|mapping| mapping.source.id)
+                                .filter(|id| candidates.map_or(true, |ids| ids.contains(id))),
+                        );
+                        Ok(())
+                    },
+                )?;
+                Ok(ids)
+            }
+            ViewSetQuery::Intersection(queries) => {
+                let mut queries = queries.iter();
+                let mut ids = match queries.next() {
+                    Some(query) => self.view_set_document_ids(query, candidates)?,
+                    None => return Ok(BTreeSet::new()),
+                };
+                for query in queries {
+                    if ids.is_empty() {
+                        break;
+                    }
+                    ids = self.view_set_document_ids(query, Some(&ids))?;
+                }
+                Ok(ids)
+            }
+            ViewSetQuery::Union(queries) => {
+                let mut ids = BTreeSet::new();
+                for query in queries {
+                    ids.extend(self.view_set_document_ids(query, candidates)?);
+                }
+                Ok(ids)
+            }
+        }
+    }
+
+    /// Queries `view` with the source documents of each mapping, returning
+    /// only the mappings whose source document matches `filter`. Pages of
+    /// `limit` entries are queried until `limit` matching mappings have been
+    /// found or no more entries remain, and at most `limit` mappings are
+    /// returned.
+    #[allow(clippy::too_many_arguments)]
+    fn query_by_name_with_filtered_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        mut cursor: Option<ViewCursor>,
+        mut access_policy: AccessPolicy,
+        filter: &Filter,
+    ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
+        let collection = self.schematic().view_by_name(view)?.collection();
+        let mut mappings = Vec::new();
+        let mut documents = BTreeMap::new();
+        loop {
+            let results = self.query_by_name(
+                view,
+                key.clone(),
+                order,
+                limit,
+                cursor.take(),
+                access_policy,
+            )?;
+            let ids = results
+                .iter()
+                .map(|mapping| mapping.source.id.clone())
+                .filter(|id| !documents.contains_key(id))
+                .collect::<Vec<_>>();
+            for document in self.get_multiple_from_collection(&ids, &collection)? {
+                if filter.matches_contents(&document.contents)? {
+                    documents.insert(document.header.id.clone(), document);
+                }
+            }
+
+            let page_is_full = limit.map_or(false, |limit| results.len() >= limit as usize);
+            cursor = results.last().map(ViewCursor::after_serialized);
+            mappings.extend(
+                results
+                    .into_iter()
+                    .filter(|mapping| documents.contains_key(&mapping.source.id)),
+            );
+            match limit {
+                Some(limit) if page_is_full && mappings.len() < limit as usize => {
+                    // The first query already applied the access policy.
+                    access_policy = AccessPolicy::NoUpdate;
+                }
+                _ => break,
+            }
+        }
+
+        if let Some(limit) = limit {
+            if mappings.len() > limit as usize {
+                mappings.truncate(limit as usize);
+                let referenced = mappings
+                    .iter()
+                    .map(|mapping| &mapping.source.id)
+                    .collect::<BTreeSet<_>>();
+                documents.retain(|id, _| referenced.contains(id));
+            }
+        }
+
+        Ok(schema::view::map::MappedSerializedDocuments {
+            mappings,
+            documents,
+        })
+    }
+
+    /// Scans the documents of `collection` within `ids`, returning the result
+    /// of `map` for each document whose contents match `filter`. At most
+    /// `limit` documents are returned.
+    fn scan_documents<T>(
+        &self,
+        ids: Range<DocumentId>,
+        sort: Sort,
+        limit: Option<u32>,
+        filter: Option<&Filter>,
+        collection: &CollectionName,
+        mut map: impl FnMut(BorrowedDocument<'_>) -> Result<T, bonsaidb_core::Error>,
+    ) -> Result<Vec<T>, bonsaidb_core::Error> {
+        let mut found = Vec::new();
+        self.for_each_document(ids, sort, limit, filter, collection, |doc| {
+            found.push(map(doc)?);
+            Ok(())
+        })?;
+        Ok(found)
+    }
+
+    /// Scans the documents of `collection` within `ids`, invoking `callback`
+    /// with each document whose contents match `filter`. At most `limit`
+    /// documents are passed to `callback`.
+    fn for_each_document(
+        &self,
+        ids: Range<DocumentId>,
+        sort: Sort,
+        limit: Option<u32>,
+        filter: Option<&Filter>,
+        collection: &CollectionName,
+        mut callback: impl FnMut(BorrowedDocument<'_>) -> Result<(), bonsaidb_core::Error>,
+    ) -> Result<(), bonsaidb_core::Error> {
+        let tree = self
+            .data
+            .context
+            .roots
+            .tree(self.collection_tree::<Versioned, _>(collection, document_tree_name(collection))?)
+            .map_err(Error::from)?;
+        let mut keys_read = 0;
+        // Without a filter, every document read is returned, which allows the
+        // limit to be enforced before reading a document's data.
+        let matched = Cell::new(0);
+        let ids = DocumentIdRange(ids);
+        tree.scan(
+            &ids.borrow_as_bytes(),
+            match sort {
+                Sort::Ascending => true,
+                Sort::Descending => false,
+            },
+            |_, _, _| ScanEvaluation::ReadData,
+            |_, _| {
+                if let Some(limit) = limit {
+                    let returned = if filter.is_some() {
+                        matched.get()
+                    } else {
+                        keys_read
+                    };
+                    if returned >= limit {
+                        return ScanEvaluation::Stop;
+                    }
+
+                    keys_read += 1;
+                }
+                ScanEvaluation::ReadData
+            },
+            |_, _, doc| {
+                if limit.map_or(false, |limit| matched.get() >= limit) {
+                    return Ok(());
+                }
+
+                let doc = deserialize_document(&doc).map_err(AbortError::Other)?;
+                if let Some(filter) = filter {
+                    if !filter
+                        .matches_contents(&doc.contents)
+                        .map_err(|err| AbortError::Other(Error::from(err)))?
+                    {
+                        return Ok(());
+                    }
+                }
+                matched.set(matched.get() + 1);
+                callback(doc).map_err(|err| AbortError::Other(Error::from(err)))
+            },
+        )
+        .map_err(|err| match err {
+            AbortError::Other(err) => err,
+            AbortError::Nebari(err) => crate::Error::from(err),
+        })?;
+
         Ok(())
     }
 
@@ -303,11 +704,89 @@ impl Database {
         Ok(open_trees)
     }
 
+    fn apply_transaction_with_mode(
+        &self,
+        transaction: Transaction,
+        mode: EagerViewUpdates,
+        collection_version: Option<(&CollectionName, Vec<u8>)>,
+    ) -> Result<Vec<OperationResult>, bonsaidb_core::Error> {
+        for op in &transaction.operations {
+            let (resource, action) = match &op.command {
+                Command::Insert { .. } => (
+                    collection_resource_name(self.name(), &op.collection),
+                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Insert)),
+                ),
+                Command::Update { header, .. } => (
+                    document_resource_name(self.name(), &op.collection, &header.id),
+                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Update)),
+                ),
+                Command::Overwrite { id, .. } => (
+                    document_resource_name(self.name(), &op.collection, id),
+                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Overwrite)),
+                ),
+                Command::Delete { header } => (
+                    document_resource_name(self.name(), &op.collection, &header.id),
+                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Delete)),
+                ),
+                Command::Check { id, .. } => (
+                    document_resource_name(self.name(), &op.collection, id),
+                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Get)),
+                ),
+            };
+            self.check_permission(resource, &action)?;
+        }
+
+        let mut eager_view_tasks = Vec::new();
+        for collection_name in transaction
+            .operations
+            .iter()
+            .map(|op| &op.collection)
+            .collect::<HashSet<_>>()
+        {
+            for view in self.data.schema.eager_views_in_collection(collection_name) {
+                if let Some(task) = self
+                    .storage
+                    .instance
+                    .tasks()
+                    .spawn_integrity_check(view, self)
+                {
+                    eager_view_tasks.push(task);
+                }
+            }
+        }
+
+        let mut eager_view_mapping_tasks = Vec::new();
+        for task in eager_view_tasks {
+            if let Some(spawned_task) = task.receive().map_err(Error::from)?.map_err(Error::from)? {
+                eager_view_mapping_tasks.push(spawned_task);
+            }
+        }
+
+        for task in eager_view_mapping_tasks {
+            let mut task = task.lock();
+            if let Some(task) = task.take() {
+                task.receive().map_err(Error::from)?.map_err(Error::from)?;
+            }
+        }
+
+        self.apply_transaction_to_roots(&transaction, mode, collection_version)
+            .map_err(bonsaidb_core::Error::from)
+    }
+
     fn apply_transaction_to_roots(
         &self,
         transaction: &Transaction,
+        mode: EagerViewUpdates,
+        collection_version: Option<(&CollectionName, Vec<u8>)>,
     ) -> Result<Vec<OperationResult>, Error> {
-        let open_trees = self.open_trees_for_transaction(transaction)?;
+        let mut open_trees = self.open_trees_for_transaction(transaction)?;
+        if collection_version.is_some() {
+            open_trees.open_tree::<Unversioned>(
+                COLLECTION_VERSIONS_TREE,
+                #[cfg(any(feature = "encryption", feature = "compression"))]
+                None,
+            );
+        }
 
         let mut roots_transaction = self
             .data
@@ -324,6 +803,7 @@ impl Database {
                 op,
                 &mut roots_transaction,
                 &open_trees.trees_index_by_name,
+                mode,
             )?;
 
             if let Some((collection, id, deleted)) = match &result {
@@ -356,6 +836,15 @@ impl Database {
             results.push(result);
         }
 
+        if mode == EagerViewUpdates::Deferred {
+            self.update_deferred_eager_views(
+                &roots_transaction,
+                &open_trees,
+                &collections,
+                &changed_documents,
+            )?;
+        }
+
         self.invalidate_changed_documents(
             &mut roots_transaction,
             &open_trees,
@@ -363,20 +852,54 @@ impl Database {
             &changed_documents,
         )?;
 
+        let executed = transaction::Executed {
+            id: roots_transaction.entry().id,
+            changes: Changes::Documents(DocumentChanges {
+                collections,
+                documents: changed_documents,
+            }),
+        };
+
+        if let Some((collection, version)) = collection_version {
+            roots_transaction
+                .tree::<Unversioned>(open_trees.trees_index_by_name[COLLECTION_VERSIONS_TREE])
+                .unwrap()
+                .set(collection.to_string().into_bytes(), version)?;
+        }
+
         roots_transaction
             .entry_mut()
             .set_data(compat::serialize_executed_transaction_changes(
-                &Changes::Documents(DocumentChanges {
-                    collections,
-                    documents: changed_documents,
-                }),
+                &executed.changes,
             )?)?;
 
         roots_transaction.commit()?;
 
+        self.publish_collection_changes(&executed);
+
         Ok(results)
     }
 
+    /// Notifies all watchers of the collections changed by `executed`.
+    fn publish_collection_changes(&self, executed: &transaction::Executed) {
+        let Some(documents) = executed.changes.documents() else {
+            return;
+        };
+        for collection in &documents.collections {
+            if let Some(changes) = executed.collection_changes(collection) {
+                match pot::to_vec(&changes) {
+                    Ok(payload) => self.storage.instance.relay().publish_raw(
+                        database_topic(&self.data.name, &collection_changes_topic(collection)),
+                        payload,
+                    ),
+                    Err(err) => {
+                        log::error!("Error serializing changes for {collection}: {err}");
+                    }
+                }
+            }
+        }
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
     fn invalidate_changed_documents(
         &self,
@@ -412,29 +935,131 @@ impl Database {
         Ok(())
     }
 
+    /// Maps the documents changed by a bulk import into the eager views of
+    /// their collections. The views of each collection are updated in
+    /// parallel, and any unique key violation aborts the entire transaction.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+    fn update_deferred_eager_views(
+        &self,
+        roots_transaction: &ExecutingTransaction<AnyFile>,
+        open_trees: &OpenTrees,
+        collections: &[CollectionName],
+        changed_documents: &[ChangedDocument],
+    ) -> Result<(), Error> {
+        // The mapper expects each batch of document ids to be sorted and
+        // unique.
+        let mut document_ids_by_collection = BTreeMap::<u16, BTreeSet<ArcBytes<'static>>>::new();
+        for changed_document in changed_documents {
+            document_ids_by_collection
+                .entry(changed_document.collection)
+                .or_default()
+                .insert(ArcBytes::from(changed_document.id.to_vec()));
+        }
+
+        for (collection, document_ids) in document_ids_by_collection {
+            let collection = &collections[usize::from(collection)];
+            let views = self
+                .data
+                .schema
+                .eager_views_in_collection(collection)
+                .map(|view| {
+                    let view_name = view.view_name();
+                    let document_map = roots_transaction
+                        .unlocked_tree(
+                            open_trees.trees_index_by_name
+                                [&view_document_map_tree_name(&view_name)],
+                        )
+                        .unwrap();
+                    let view_entries = roots_transaction
+                        .unlocked_tree(
+                            open_trees.trees_index_by_name[&view_entries_tree_name(&view_name)],
+                        )
+                        .unwrap();
+                    let view_reductions = view.cache_reductions().then(|| {
+                        roots_transaction
+                            .unlocked_tree(
+                                open_trees.trees_index_by_name
+                                    [&view_reductions_tree_name(&view_name)],
+                            )
+                            .unwrap()
+                    });
+                    (view, view_name, document_map, view_entries, view_reductions)
+                })
+                .collect::<Vec<_>>();
+            if views.is_empty() {
+                continue;
+            }
+
+            let documents = roots_transaction
+                .unlocked_tree(open_trees.trees_index_by_name[&document_tree_name(collection)])
+                .unwrap();
+            let document_ids = document_ids.into_iter().collect::<Vec<_>>();
+            for result in Parallel::new()
+                .each(
+                    views,
+                    |(view, view_name, document_map, view_entries, view_reductions)| -> Result<(), Error> {
+                        mapper::DocumentRequest {
+                            database: self,
+                            document_ids: document_ids.clone(),
+                            map_request: &mapper::Map {
+                                database: self.data.name.clone(),
+                                collection: collection.clone(),
+                                view_name,
+                            },
+                            document_map,
+                            documents,
+                            view_entries,
+                            view_reductions,
+                            view,
+                        }
+                        .map()
+                    },
+                )
+                .run()
+            {
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn execute_operation(
         &self,
         operation: &Operation,
         transaction: &mut ExecutingTransaction<AnyFile>,
         tree_index_map: &HashMap<String, usize>,
+        mode: EagerViewUpdates,
     ) -> Result<OperationResult, Error> {
         match &operation.command {
-            Command::Insert { id, contents } => {
-                self.execute_insert(operation, transaction, tree_index_map, id.clone(), contents)
-            }
+            Command::Insert { id, contents } => self.execute_insert(
+                operation,
+                transaction,
+                tree_index_map,
+                mode,
+                id.clone(),
+                contents,
+            ),
             Command::Update { header, contents } => self.execute_update(
                 operation,
                 transaction,
                 tree_index_map,
+                mode,
                 &header.id,
                 Some(&header.revision),
                 contents,
             ),
-            Command::Overwrite { id, contents } => {
-                self.execute_update(operation, transaction, tree_index_map, id, None, contents)
-            }
+            Command::Overwrite { id, contents } => self.execute_update(
+                operation,
+                transaction,
+                tree_index_map,
+                mode,
+                id,
+                None,
+                contents,
+            ),
             Command::Delete { header } => {
-                self.execute_delete(operation, transaction, tree_index_map, header)
+                self.execute_delete(operation, transaction, tree_index_map, mode, header)
             }
             Command::Check { id, revision } => Self::execute_check(
                 operation,
@@ -458,11 +1083,13 @@ impl Database {
             )
         )
     )]
+    #[allow(clippy::too_many_arguments)]
     fn execute_update(
         &self,
         operation: &Operation,
         transaction: &mut ExecutingTransaction<AnyFile>,
         tree_index_map: &HashMap<String, usize>,
+        mode: EagerViewUpdates,
         id: &DocumentId,
         check_revision: Option<&Revision>,
         contents: &[u8],
@@ -552,7 +1179,7 @@ impl Database {
         )?;
         drop(documents);
 
-        if updated {
+        if updated && mode == EagerViewUpdates::Inline {
             self.update_eager_views(&document_id, operation, transaction, tree_index_map)?;
         }
 
@@ -576,6 +1203,7 @@ impl Database {
         operation: &Operation,
         transaction: &mut ExecutingTransaction<AnyFile>,
         tree_index_map: &HashMap<String, usize>,
+        mode: EagerViewUpdates,
         id: Option<DocumentId>,
         contents: &[u8],
     ) -> Result<OperationResult, Error> {
@@ -606,7 +1234,9 @@ impl Database {
             )))
         } else {
             drop(documents);
-            self.update_eager_views(&document_id, operation, transaction, tree_index_map)?;
+            if mode == EagerViewUpdates::Inline {
+                self.update_eager_views(&document_id, operation, transaction, tree_index_map)?;
+            }
 
             Ok(OperationResult::DocumentUpdated {
                 collection: operation.collection.clone(),
@@ -629,6 +1259,7 @@ impl Database {
         operation: &Operation,
         transaction: &mut ExecutingTransaction<AnyFile>,
         tree_index_map: &HashMap<String, usize>,
+        mode: EagerViewUpdates,
         header: &Header,
     ) -> Result<OperationResult, Error> {
         let mut documents = transaction
@@ -638,12 +1269,14 @@ impl Database {
             drop(documents);
             let doc = deserialize_document(&vec)?;
             if &doc.header == header {
-                self.update_eager_views(
-                    &ArcBytes::from(doc.header.id.to_vec()),
-                    operation,
-                    transaction,
-                    tree_index_map,
-                )?;
+                if mode == EagerViewUpdates::Inline {
+                    self.update_eager_views(
+                        &ArcBytes::from(doc.header.id.to_vec()),
+                        operation,
+                        transaction,
+                        tree_index_map,
+                    )?;
+                }
 
                 Ok(OperationResult::DocumentDeleted {
                     collection: operation.collection.clone(),
@@ -696,6 +1329,11 @@ impl Database {
                 let view_entries = transaction
                     .unlocked_tree(tree_index_map[&view_entries_tree_name(&name)])
                     .unwrap();
+                let view_reductions = view.cache_reductions().then(|| {
+                    transaction
+                        .unlocked_tree(tree_index_map[&view_reductions_tree_name(&name)])
+                        .unwrap()
+                });
                 mapper::DocumentRequest {
                     database: self,
                     document_ids: vec![document_id.clone()],
@@ -707,6 +1345,7 @@ impl Database {
                     document_map,
                     documents,
                     view_entries,
+                    view_reductions,
                     view,
                 }
                 .map()?;
@@ -756,56 +1395,109 @@ impl Database {
         }
     }
 
-    fn create_view_iterator(
+    /// Invokes `callback` with each entry of `view_entries` matching `key`.
+    /// Entries are deserialized and passed to `callback` as they are scanned,
+    /// rather than being collected first.
+    fn scan_view_entries<F: FnMut(ViewEntry) -> Result<(), bonsaidb_core::Error>>(
         view_entries: &Tree<Unversioned, AnyFile>,
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
-    ) -> Result<Vec<ViewEntry>, Error> {
-        let mut values = Vec::new();
+        cursor: Option<&ViewCursor>,
+        mut callback: F,
+    ) -> Result<(), bonsaidb_core::Error> {
         let forwards = match order {
             Sort::Ascending => true,
             Sort::Descending => false,
         };
+        let key = match cursor {
+            Some(cursor) => match Self::resume_query_key_after(key, cursor, forwards) {
+                Some(key) => Some(key),
+                None => return Ok(()),
+            },
+            None => key,
+        };
+        let mut emit = |value: &[u8]| -> Result<(), Error> {
+            let mut entry = bincode::deserialize::<ViewEntry>(value)?;
+
+            // Mappings that share a key are returned in document id order,
+            // which allows a cursor to resume partway through an entry.
+            entry.mappings.sort_by(|a, b| a.source.id.cmp(&b.source.id));
+            if !forwards {
+                entry.mappings.reverse();
+            }
+
+            if let Some(cursor) = cursor {
+                if entry.key[..] == *cursor.key() {
+                    entry.mappings.retain(|mapping| {
+                        if forwards {
+                            &mapping.source.id > cursor.document_id()
+                        } else {
+                            &mapping.source.id < cursor.document_id()
+                        }
+                    });
+                }
+            }
+
+            if entry.mappings.is_empty() {
+                Ok(())
+            } else {
+                callback(entry).map_err(Error::from)
+            }
+        };
+        // The entry at the cursor's key may only be partially returned, so it
+        // does not count towards the limit.
+        let is_cursor_key = |key: &[u8]| cursor.map_or(false, |cursor| cursor.key() == key);
         let mut values_read = 0;
-        if let Some(key) = key {
-            match key {
-                SerializedQueryKey::Range(range) => {
-                    view_entries.scan::<Infallible, _, _, _, _>(
-                        &range.map_ref(|bytes| &bytes[..]),
-                        forwards,
-                        |_, _, _| ScanEvaluation::ReadData,
-                        |_, _| {
-                            if let Some(limit) = limit {
-                                if values_read >= limit {
-                                    return ScanEvaluation::Stop;
-                                }
-                                values_read += 1;
-                            }
-                            ScanEvaluation::ReadData
-                        },
-                        |_key, _index, value| {
-                            values.push(value);
-                            Ok(())
-                        },
-                    )?;
+        let scanned = match key {
+            Some(SerializedQueryKey::Range(range)) => view_entries.scan(
+                &range.map_ref(|bytes| &bytes[..]),
+                forwards,
+                |_, _, _| ScanEvaluation::ReadData,
+                |key, _| {
+                    if let Some(limit) = limit {
+                        if values_read >= limit {
+                            return ScanEvaluation::Stop;
+                        }
+                        if !is_cursor_key(&key[..]) {
+                            values_read += 1;
+                        }
+                    }
+                    ScanEvaluation::ReadData
+                },
+                |_key, _index, value| emit(&value).map_err(AbortError::Other),
+            ),
+            Some(SerializedQueryKey::Matches(key)) => {
+                if let Some(value) = view_entries.get(&key).map_err(Error::from)? {
+                    emit(&value)?;
                 }
-                SerializedQueryKey::Matches(key) => {
-                    values.extend(view_entries.get(&key)?);
+                Ok(())
+            }
+            Some(SerializedQueryKey::Multiple(mut list)) => {
+                list.sort();
+                list.dedup();
+
+                let mut entries = view_entries
+                    .get_multiple(list.iter().map(|bytes| bytes.as_slice()))
+                    .map_err(Error::from)?;
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                if !forwards {
+                    entries.reverse();
                 }
-                SerializedQueryKey::Multiple(mut list) => {
-                    list.sort();
-
-                    values.extend(
-                        view_entries
-                            .get_multiple(list.iter().map(|bytes| bytes.as_slice()))?
-                            .into_iter()
-                            .map(|(_, value)| value),
-                    );
+                for (key, value) in entries {
+                    if let Some(limit) = limit {
+                        if values_read >= limit {
+                            break;
+                        }
+                        if !is_cursor_key(&key[..]) {
+                            values_read += 1;
+                        }
+                    }
+                    emit(&value)?;
                 }
+                Ok(())
             }
-        } else {
-            view_entries.scan::<Infallible, _, _, _, _>(
+            None => view_entries.scan(
                 &(..),
                 forwards,
                 |_, _, _| ScanEvaluation::ReadData,
@@ -818,17 +1510,75 @@ impl Database {
                     }
                     ScanEvaluation::ReadData
                 },
-                |_, _, value| {
-                    values.push(value);
-                    Ok(())
+                |_, _, value| emit(&value).map_err(AbortError::Other),
+            ),
+        };
+        scanned.map_err(|err| match err {
+            AbortError::Other(err) => err,
+            AbortError::Nebari(err) => crate::Error::from(err),
+        })?;
+
+        Ok(())
+    }
+
+    /// Narrows `key` to the keys at or after `cursor`'s key in the sort
+    /// order. Returns `None` if no keys can match.
+    fn resume_query_key_after(
+        key: Option<SerializedQueryKey>,
+        cursor: &ViewCursor,
+        forwards: bool,
+    ) -> Option<SerializedQueryKey> {
+        let is_at_or_after_cursor = |key: &[u8]| {
+            if forwards {
+                key >= cursor.key()
+            } else {
+                key <= cursor.key()
+            }
+        };
+        match key {
+            Some(SerializedQueryKey::Matches(key)) => {
+                is_at_or_after_cursor(&key).then_some(SerializedQueryKey::Matches(key))
+            }
+            Some(SerializedQueryKey::Multiple(mut keys)) => {
+                keys.retain(|key| is_at_or_after_cursor(key));
+                (!keys.is_empty()).then_some(SerializedQueryKey::Multiple(keys))
+            }
+            Some(SerializedQueryKey::Range(mut range)) => {
+                let bound = if forwards {
+                    &mut range.start
+                } else {
+                    &mut range.end
+                };
+                let narrows = match bound {
+                    connection::Bound::Unbounded => true,
+                    connection::Bound::Included(key) | connection::Bound::Excluded(key) => {
+                        !is_at_or_after_cursor(key)
+                    }
+                };
+                if narrows {
+                    let cursor_key = Bytes::from(cursor.key().to_vec());
+                    // If the other end of the range is before the cursor, the
+                    // narrowed range is empty.
+                    if !range.contains(&cursor_key) {
+                        return None;
+                    }
+                    *bound = connection::Bound::Included(cursor_key);
+                }
+                Some(SerializedQueryKey::Range(range))
+            }
+            None => Some(SerializedQueryKey::Range(Range {
+                start: if forwards {
+                    connection::Bound::Included(Bytes::from(cursor.key().to_vec()))
+                } else {
+                    connection::Bound::Unbounded
+                },
+                end: if forwards {
+                    connection::Bound::Unbounded
+                } else {
+                    connection::Bound::Included(Bytes::from(cursor.key().to_vec()))
                 },
-            )?;
+            })),
         }
-
-        values
-            .into_iter()
-            .map(|value| bincode::deserialize(&value).map_err(Error::from))
-            .collect::<Result<Vec<_>, Error>>()
     }
 
     #[cfg(any(feature = "encryption", feature = "compression"))]
@@ -906,6 +1656,10 @@ impl Database {
             .update_key_expiration(tree_key, expiration);
     }
 
+    pub(crate) fn track_existing_keys(&self, keys: impl IntoIterator<Item = (String, usize)>) {
+        self.data.context.track_existing_keys(keys);
+    }
+
     /// Converts this instance into its blocking version, which is able to be
     /// used without async. The returned instance uses the current Tokio runtime
     /// handle to spawn blocking tasks.
@@ -1129,67 +1883,7 @@ impl LowLevelConnection for Database {
         &self,
         transaction: Transaction,
     ) -> Result<Vec<OperationResult>, bonsaidb_core::Error> {
-        for op in &transaction.operations {
-            let (resource, action) = match &op.command {
-                Command::Insert { .. } => (
-                    collection_resource_name(self.name(), &op.collection),
-                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Insert)),
-                ),
-                Command::Update { header, .. } => (
-                    document_resource_name(self.name(), &op.collection, &header.id),
-                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Update)),
-                ),
-                Command::Overwrite { id, .. } => (
-                    document_resource_name(self.name(), &op.collection, id),
-                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Overwrite)),
-                ),
-                Command::Delete { header } => (
-                    document_resource_name(self.name(), &op.collection, &header.id),
-                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Delete)),
-                ),
-                Command::Check { id, .. } => (
-                    document_resource_name(self.name(), &op.collection, id),
-                    BonsaiAction::Database(DatabaseAction::Document(DocumentAction::Get)),
-                ),
-            };
-            self.check_permission(resource, &action)?;
-        }
-
-        let mut eager_view_tasks = Vec::new();
-        for collection_name in transaction
-            .operations
-            .iter()
-            .map(|op| &op.collection)
-            .collect::<HashSet<_>>()
-        {
-            for view in self.data.schema.eager_views_in_collection(collection_name) {
-                if let Some(task) = self
-                    .storage
-                    .instance
-                    .tasks()
-                    .spawn_integrity_check(view, self)
-                {
-                    eager_view_tasks.push(task);
-                }
-            }
-        }
-
-        let mut eager_view_mapping_tasks = Vec::new();
-        for task in eager_view_tasks {
-            if let Some(spawned_task) = task.receive().map_err(Error::from)?.map_err(Error::from)? {
-                eager_view_mapping_tasks.push(spawned_task);
-            }
-        }
-
-        for task in eager_view_mapping_tasks {
-            let mut task = task.lock();
-            if let Some(task) = task.take() {
-                task.receive().map_err(Error::from)?.map_err(Error::from)?;
-            }
-        }
-
-        self.apply_transaction_to_roots(&transaction)
-            .map_err(bonsaidb_core::Error::from)
+        self.apply_transaction_with_mode(transaction, EagerViewUpdates::Inline, None)
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1237,53 +1931,45 @@ impl LowLevelConnection for Database {
         ids: Range<DocumentId>,
         sort: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
         self.check_permission(
             collection_resource_name(self.name(), collection),
             &BonsaiAction::Database(DatabaseAction::Document(DocumentAction::List)),
         )?;
-        let tree = self
-            .data
-            .context
-            .roots
-            .tree(self.collection_tree::<Versioned, _>(collection, document_tree_name(collection))?)
-            .map_err(Error::from)?;
-        let mut found_docs = Vec::new();
-        let mut keys_read = 0;
-        let ids = DocumentIdRange(ids);
-        tree.scan(
-            &ids.borrow_as_bytes(),
-            match sort {
-                Sort::Ascending => true,
-                Sort::Descending => false,
-            },
-            |_, _, _| ScanEvaluation::ReadData,
-            |_, _| {
-                if let Some(limit) = limit {
-                    if keys_read >= limit {
-                        return ScanEvaluation::Stop;
-                    }
+        self.scan_documents(ids, sort, limit, filter.as_ref(), collection, |doc| {
+            Ok(doc.into_owned())
+        })
+    }
 
-                    keys_read += 1;
-                }
-                ScanEvaluation::ReadData
-            },
-            |_, _, doc| {
-                found_docs.push(
-                    deserialize_document(&doc)
-                        .map(BorrowedDocument::into_owned)
-                        .map_err(AbortError::Other)?,
-                );
-                Ok(())
-            },
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = "trace",
+        skip(self, collection, projection),
+        fields(
+            database = self.name(),
+            collection.name = collection.name.as_ref(),
+            collection.authority = collection.authority.as_ref(),
         )
-        .map_err(|err| match err {
-            AbortError::Other(err) => err,
-            AbortError::Nebari(err) => crate::Error::from(err),
-        })?;
-
-        Ok(found_docs)
+    ))]
+    fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        sort: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, bonsaidb_core::Error> {
+        self.check_permission(
+            collection_resource_name(self.name(), collection),
+            &BonsaiAction::Database(DatabaseAction::Document(DocumentAction::List)),
+        )?;
+        // Projections are applied after filtering, as filters may reference
+        // fields that aren't projected.
+        self.scan_documents(ids, sort, limit, filter.as_ref(), collection, |doc| {
+            projection.project_document(doc.header, &doc.contents)
+        })
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1300,53 +1986,16 @@ impl LowLevelConnection for Database {
         ids: Range<DocumentId>,
         sort: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, bonsaidb_core::Error> {
         self.check_permission(
             collection_resource_name(self.name(), collection),
             &BonsaiAction::Database(DatabaseAction::Document(DocumentAction::ListHeaders)),
         )?;
-        let tree = self
-            .data
-            .context
-            .roots
-            .tree(self.collection_tree::<Versioned, _>(collection, document_tree_name(collection))?)
-            .map_err(Error::from)?;
-        let mut found_headers = Vec::new();
-        let mut keys_read = 0;
-        let ids = DocumentIdRange(ids);
-        tree.scan(
-            &ids.borrow_as_bytes(),
-            match sort {
-                Sort::Ascending => true,
-                Sort::Descending => false,
-            },
-            |_, _, _| ScanEvaluation::ReadData,
-            |_, _| {
-                if let Some(limit) = limit {
-                    if keys_read >= limit {
-                        return ScanEvaluation::Stop;
-                    }
-
-                    keys_read += 1;
-                }
-                ScanEvaluation::ReadData
-            },
-            |_, _, doc| {
-                found_headers.push(
-                    deserialize_document(&doc)
-                        .map(|doc| doc.header)
-                        .map_err(AbortError::Other)?,
-                );
-                Ok(())
-            },
-        )
-        .map_err(|err| match err {
-            AbortError::Other(err) => err,
-            AbortError::Nebari(err) => crate::Error::from(err),
-        })?;
-
-        Ok(found_headers)
+        self.scan_documents(ids, sort, limit, filter.as_ref(), collection, |doc| {
+            Ok(doc.header)
+        })
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(
@@ -1461,22 +2110,12 @@ impl LowLevelConnection for Database {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, bonsaidb_core::Error> {
-        let view = self.schematic().view_by_name(view)?;
-        self.check_permission(
-            view_resource_name(self.name(), &view.view_name()),
-            &BonsaiAction::Database(DatabaseAction::View(ViewAction::Query)),
-        )?;
         let mut results = Vec::new();
-        self.for_each_in_view(view, key, order, limit, access_policy, |entry| {
-            for mapping in entry.mappings {
-                results.push(bonsaidb_core::schema::view::map::Serialized {
-                    source: mapping.source,
-                    key: entry.key.clone(),
-                    value: mapping.value,
-                });
-            }
+        self.for_each_mapping(view, key, order, limit, cursor, access_policy, |mapping| {
+            results.push(mapping);
             Ok(())
         })?;
 
@@ -1499,9 +2138,23 @@ impl LowLevelConnection for Database {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
-        let results = self.query_by_name(view, key, order, limit, access_policy)?;
+        if let Some(filter) = filter {
+            return self.query_by_name_with_filtered_docs(
+                view,
+                key,
+                order,
+                limit,
+                cursor,
+                access_policy,
+                &filter,
+            );
+        }
+
+        let results = self.query_by_name(view, key, order, limit, cursor, access_policy)?;
         let view = self.schematic().view_by_name(view).unwrap(); // query() will fail if it's not present
 
         let documents = self
@@ -1524,6 +2177,35 @@ impl LowLevelConnection for Database {
         )
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = "trace",
+        skip(self, query),
+        fields(
+            database = self.name(),
+        )
+    ))]
+    fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        let Some(collection) = query.collection(self.schematic())? else {
+            return Ok(Vec::new());
+        };
+        let limit = limit.map_or(usize::MAX, |limit| {
+            usize::try_from(limit).unwrap_or(usize::MAX)
+        });
+        let ids = self
+            .view_set_document_ids(&query, None)?
+            .into_iter()
+            .take(limit)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.get_multiple_from_collection(&ids, &collection)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level = "trace",
         skip(self, view_name),
@@ -1540,12 +2222,24 @@ impl LowLevelConnection for Database {
         key: Option<SerializedQueryKey>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<u8>, bonsaidb_core::Error> {
+        let view = self.data.schema.view_by_name(view_name)?;
+        if view.cache_reductions() {
+            // Ranged reductions can be computed from the cached reductions,
+            // while individual keys already store their reduced values.
+            match &key {
+                None => return self.reduce_cached(view, &Range::default(), access_policy),
+                Some(SerializedQueryKey::Range(range)) => {
+                    return self.reduce_cached(view, range, access_policy)
+                }
+                Some(SerializedQueryKey::Matches(_) | SerializedQueryKey::Multiple(_)) => {}
+            }
+        }
+
         let mut mappings = self.reduce_grouped_by_name(view_name, key, access_policy)?;
 
         let result = if mappings.len() == 1 {
             mappings.pop().unwrap().value.into_vec()
         } else {
-            let view = self.data.schema.view_by_name(view_name)?;
             view.reduce(
                 &mappings
                     .iter()
@@ -1581,13 +2275,21 @@ impl LowLevelConnection for Database {
             &BonsaiAction::Database(DatabaseAction::View(ViewAction::Reduce)),
         )?;
         let mut mappings = Vec::new();
-        self.for_each_in_view(view, key, Sort::Ascending, None, access_policy, |entry| {
-            mappings.push(MappedSerializedValue {
-                key: entry.key,
-                value: entry.reduced_value,
-            });
-            Ok(())
-        })?;
+        self.for_each_in_view(
+            view,
+            key,
+            Sort::Ascending,
+            None,
+            None,
+            access_policy,
+            |entry| {
+                mappings.push(MappedSerializedValue {
+                    key: entry.key,
+                    value: entry.reduced_value,
+                });
+                Ok(())
+            },
+        )?;
 
         Ok(mappings)
     }
@@ -1611,13 +2313,21 @@ impl LowLevelConnection for Database {
         let view = self.data.schema.view_by_name(view)?;
         let collection = view.collection();
         let mut transaction = Transaction::default();
-        self.for_each_in_view(view, key, Sort::Ascending, None, access_policy, |entry| {
-            for mapping in entry.mappings {
-                transaction.push(Operation::delete(collection.clone(), mapping.source));
-            }
+        self.for_each_in_view(
+            view,
+            key,
+            Sort::Ascending,
+            None,
+            None,
+            access_policy,
+            |entry| {
+                for mapping in entry.mappings {
+                    transaction.push(Operation::delete(collection.clone(), mapping.source));
+                }
 
-            Ok(())
-        })?;
+                Ok(())
+            },
+        )?;
 
         let results = LowLevelConnection::apply_transaction(self, transaction)?;
 
@@ -1678,14 +2388,18 @@ impl Context {
     pub(crate) fn new(
         roots: Roots<AnyFile>,
         key_value_persistence: KeyValuePersistence,
+        key_value_eviction: Option<KeyValueEviction>,
+        key_change_notifier: Option<keyvalue::KeyChangeNotifier>,
         storage_lock: Option<StorageLock>,
     ) -> Self {
         let background_worker_target = Watchable::new(BackgroundWorkerProcessTarget::Never);
         let mut background_worker_target_watcher = background_worker_target.watch();
         let key_value_state = Arc::new(Mutex::new(keyvalue::KeyValueState::new(
             key_value_persistence,
+            key_value_eviction,
             roots.clone(),
             background_worker_target,
+            key_change_notifier,
         )));
         let background_worker_state = Arc::downgrade(&key_value_state);
         let context = Self {
@@ -1724,6 +2438,11 @@ impl Context {
         state.update_key_expiration(tree_key, expiration);
     }
 
+    pub(crate) fn track_existing_keys(&self, keys: impl IntoIterator<Item = (String, usize)>) {
+        let mut state = self.data.key_value_state.lock();
+        state.track_existing_keys(keys);
+    }
+
     #[cfg(test)]
     pub(crate) fn kv_persistence_watcher(&self) -> watchable::Watcher<Timestamp> {
         let state = self.data.key_value_state.lock();
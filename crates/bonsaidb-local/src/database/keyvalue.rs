@@ -1,16 +1,20 @@
 use std::borrow::Cow;
-use std::collections::{btree_map, BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 
-use bonsaidb_core::connection::{Connection, HasSession};
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::connection::{Bound, Connection, HasSession, Range};
 use bonsaidb_core::keyvalue::{
-    Command, KeyCheck, KeyOperation, KeyStatus, KeyValue, Numeric, Output, SetCommand, Timestamp,
-    Value,
+    BatchOperation, Command, HashCommand, KeyChange, KeyChangeKind, KeyCheck, KeyEntry,
+    KeyOperation, KeyStatus, KeyValue, ListCommand, ListEnd, Numeric, Output, RateLimit,
+    RateLimitStatus, ScanCommand, ScannedKey, SetCommand, SetMembersCommand, Timestamp, Value,
 };
 use bonsaidb_core::permissions::bonsai::{
-    keyvalue_key_resource_name, BonsaiAction, DatabaseAction, KeyValueAction,
+    keyvalue_key_resource_name, keyvalue_namespace_resource_name, BonsaiAction, DatabaseAction,
+    KeyValueAction,
 };
+use bonsaidb_core::pubsub::{database_topic, key_changes_topic};
 use bonsaidb_core::transaction::{ChangedKey, Changes};
 use nebari::io::any::AnyFile;
 use nebari::tree::{CompareSwap, Operation, Root, ScanEvaluation, Unversioned};
@@ -19,8 +23,9 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use watchable::{Watchable, Watcher};
 
-use crate::config::KeyValuePersistence;
+use crate::config::{EvictionPolicy, KeyValueEviction, KeyValuePersistence};
 use crate::database::compat;
+use crate::database::pubsub::Relay;
 use crate::storage::StorageLock;
 use crate::tasks::{Job, Keyed, Task};
 use crate::{Database, DatabaseNonBlocking, Error};
@@ -57,15 +62,35 @@ impl Entry {
 
 impl KeyValue for Database {
     fn execute_key_operation(&self, op: KeyOperation) -> Result<Output, bonsaidb_core::Error> {
-        self.check_permission(
-            keyvalue_key_resource_name(self.name(), op.namespace.as_deref(), &op.key),
-            &BonsaiAction::Database(DatabaseAction::KeyValue(KeyValueAction::ExecuteOperation)),
-        )?;
+        self.check_key_operation_permission(&op)?;
         self.data.context.perform_kv_operation(op)
     }
 }
 
 impl Database {
+    fn check_key_operation_permission(
+        &self,
+        op: &KeyOperation,
+    ) -> Result<(), bonsaidb_core::Error> {
+        let resource_name = match &op.command {
+            Command::Scan(_) => {
+                keyvalue_namespace_resource_name(self.name(), op.namespace.as_deref())
+            }
+            Command::Batch(operations) => {
+                for operation in operations {
+                    self.check_key_operation_permission(&operation.operation)?;
+                }
+                return Ok(());
+            }
+            _ => keyvalue_key_resource_name(self.name(), op.namespace.as_deref(), &op.key),
+        };
+        self.check_permission(
+            resource_name,
+            &BonsaiAction::Database(DatabaseAction::KeyValue(KeyValueAction::ExecuteOperation)),
+        )?;
+        Ok(())
+    }
+
     pub(crate) fn all_key_value_entries(
         &self,
     ) -> Result<BTreeMap<(Option<String>, String), Entry>, Error> {
@@ -118,11 +143,37 @@ impl Database {
             }
         }
 
+        for ((namespace, key), entry) in &mut all_entries {
+            state.load_elements(&full_key(namespace.as_deref(), key), &mut entry.value)?;
+        }
+
         Ok(all_entries)
     }
+
+    /// Returns the keys that have been modified but whose changes have not
+    /// been persisted yet.
+    pub(crate) fn unpersisted_keys(&self) -> BTreeSet<(Option<String>, String)> {
+        let state = self.data.context.key_value_state.lock();
+        state
+            .keys_being_persisted
+            .iter()
+            .flat_map(|keys| keys.keys())
+            .chain(state.dirty_keys.keys())
+            .filter_map(|key| split_key(key))
+            .collect()
+    }
 }
 
 pub(crate) const KEY_TREE: &str = "kv";
+/// The tree containing the elements of lists, sets, and hashes. Each element
+/// is stored in its own key, allowing operations to only read and write the
+/// elements they affect. The key's entry in [`KEY_TREE`] stores an empty
+/// value of the structure's type.
+pub(crate) const STRUCTURE_TREE: &str = "kv-structures";
+
+/// The position of the first value pushed onto a list. Lists can grow in both
+/// directions from this position.
+const LIST_ORIGIN: u64 = u64::MAX / 2;
 
 fn full_key(namespace: Option<&str>, key: &str) -> String {
     let full_length = namespace.map_or_else(|| 0, str::len) + key.len() + 1;
@@ -148,6 +199,229 @@ fn split_key(full_key: &str) -> Option<(Option<String>, String)> {
     }
 }
 
+/// Returns the range of full keys on disk that may contain keys matching
+/// `scan`, or `None` if no keys can match.
+fn stored_key_range(
+    namespace: Option<&str>,
+    namespace_prefix: &str,
+    prefix: &str,
+    scan: &ScanCommand,
+) -> Option<Range<Vec<u8>>> {
+    let start = match &scan.range.start {
+        Bound::Included(key) if key.as_str() > prefix => {
+            Bound::Included(full_key(namespace, key).into_bytes())
+        }
+        Bound::Excluded(key) if key.as_str() >= prefix => {
+            Bound::Excluded(full_key(namespace, key).into_bytes())
+        }
+        _ => Bound::Included(full_key(namespace, prefix).into_bytes()),
+    };
+    let end = match &scan.range.end {
+        Bound::Included(key) => Bound::Included(full_key(namespace, key).into_bytes()),
+        Bound::Excluded(key) => Bound::Excluded(full_key(namespace, key).into_bytes()),
+        Bound::Unbounded => {
+            // The namespace separator is the final byte of the prefix.
+            let mut namespace_end = namespace_prefix.as_bytes().to_vec();
+            *namespace_end.last_mut().expect("namespace separator") += 1;
+            Bound::Excluded(namespace_end)
+        }
+    };
+
+    let is_empty = match (&start, &end) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (Bound::Included(start) | Bound::Excluded(start), Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end)) => start >= end,
+        _ => false,
+    };
+    (!is_empty).then_some(Range { start, end })
+}
+
+/// Returns true if `check` allows an operation on a key whose current entry
+/// is `existing`.
+fn check_passes(check: &KeyCheck, existing: Option<&Entry>) -> bool {
+    match check {
+        KeyCheck::OnlyIfPresent => existing.is_some(),
+        KeyCheck::OnlyIfVacant => existing.is_none(),
+        KeyCheck::OnlyIfEqual(expected) => {
+            existing.map_or(false, |existing| values_equal(&existing.value, expected))
+        }
+    }
+}
+
+/// Compares values for [`KeyCheck::OnlyIfEqual`]. Integers are compared by
+/// their numeric value regardless of whether they are signed.
+fn values_equal(existing: &Value, expected: &Value) -> bool {
+    match (existing, expected) {
+        (
+            Value::Numeric(Numeric::Integer(signed)),
+            Value::Numeric(Numeric::UnsignedInteger(unsigned)),
+        )
+        | (
+            Value::Numeric(Numeric::UnsignedInteger(unsigned)),
+            Value::Numeric(Numeric::Integer(signed)),
+        ) => u64::try_from(*signed).map_or(false, |signed| signed == *unsigned),
+        _ => existing == expected,
+    }
+}
+
+/// Returns true if `value` is a list, set, or hash, whose elements are stored
+/// in [`STRUCTURE_TREE`].
+const fn is_structure(value: &Value) -> bool {
+    matches!(value, Value::List(_) | Value::Set(_) | Value::Hash(_))
+}
+
+/// Returns the prefix of the keys in [`STRUCTURE_TREE`] belonging to the
+/// structure stored in `full_key`. The key's length is written first so that
+/// no structure's prefix begins with another structure's prefix.
+fn structure_prefix(full_key: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(8 + full_key.len());
+    prefix.extend_from_slice(&(full_key.len() as u64).to_be_bytes());
+    prefix.extend_from_slice(full_key.as_bytes());
+    prefix
+}
+
+/// Returns the key of `element` within the structure with `prefix`. The
+/// structure's [`StructureMetadata`] is stored in `prefix` itself, which sorts
+/// before every element.
+fn element_key(prefix: &[u8], element: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(prefix.len() + 1 + element.len());
+    key.extend_from_slice(prefix);
+    key.push(0);
+    key.extend_from_slice(element);
+    key
+}
+
+/// Information about a list, set, or hash that is updated along with its
+/// elements.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct StructureMetadata {
+    /// The number of elements in the structure.
+    length: u64,
+    /// The number of bytes the elements count against
+    /// [`KeyValueEviction::max_bytes`].
+    bytes: u64,
+    /// The position of the first value of a list.
+    head: u64,
+}
+
+impl Default for StructureMetadata {
+    fn default() -> Self {
+        Self {
+            length: 0,
+            bytes: 0,
+            head: LIST_ORIGIN,
+        }
+    }
+}
+
+/// Changes to the elements in [`STRUCTURE_TREE`] that have not been
+/// persisted.
+#[derive(Debug, Default)]
+struct StructureChanges {
+    /// Elements that have been set or removed.
+    elements: BTreeMap<Vec<u8>, Option<Bytes>>,
+    /// The prefixes of structures whose previously stored elements have all
+    /// been removed. Elements set after the structure was cleared are kept in
+    /// `elements`.
+    cleared: BTreeSet<Vec<u8>>,
+}
+
+impl StructureChanges {
+    /// Returns the change made to `element_key`, or `None` if these changes do
+    /// not affect it.
+    fn get(&self, prefix: &[u8], element_key: &[u8]) -> Option<Option<&Bytes>> {
+        if let Some(value) = self.elements.get(element_key) {
+            Some(value.as_ref())
+        } else if self.cleared.contains(prefix) {
+            Some(None)
+        } else {
+            None
+        }
+    }
+
+    fn elements_of<'a>(
+        &'a self,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = (&'a Vec<u8>, &'a Option<Bytes>)> + 'a {
+        self.elements
+            .range(prefix.to_vec()..)
+            .take_while(move |(key, _)| key.starts_with(prefix))
+    }
+
+    /// Returns a copy of the changes made to the structure with `prefix`.
+    fn structure(&self, prefix: &[u8]) -> Self {
+        Self {
+            elements: self
+                .elements_of(prefix)
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            cleared: self.cleared.get(prefix).cloned().into_iter().collect(),
+        }
+    }
+
+    /// Discards the changes made to the structure with `prefix`.
+    fn remove_structure(&mut self, prefix: &[u8]) {
+        let keys = self
+            .elements_of(prefix)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in keys {
+            self.elements.remove(&key);
+        }
+        self.cleared.remove(prefix);
+    }
+
+    /// Replaces the changes made to the structure with `prefix` with a copy
+    /// previously returned from [`Self::structure()`].
+    fn restore_structure(&mut self, prefix: &[u8], changes: Self) {
+        self.remove_structure(prefix);
+        self.elements.extend(changes.elements);
+        self.cleared.extend(changes.cleared);
+    }
+}
+
+/// Returns the elements stored on disk for the structure with `prefix`, keyed
+/// by their key in [`STRUCTURE_TREE`]. Values are only read if `read_values`
+/// is true.
+fn stored_elements(
+    roots: &Roots<AnyFile>,
+    prefix: &[u8],
+    read_values: bool,
+) -> Result<BTreeMap<Vec<u8>, Option<Bytes>>, Error> {
+    let mut elements = BTreeMap::new();
+    let mut unread_keys = Vec::new();
+    roots
+        .tree(Unversioned::tree(STRUCTURE_TREE))?
+        .scan::<Error, _, _, _, _>(
+            &(prefix..),
+            true,
+            |_, _, _| ScanEvaluation::ReadData,
+            |key, _| {
+                if !key.starts_with(prefix) {
+                    ScanEvaluation::Stop
+                } else if read_values {
+                    ScanEvaluation::ReadData
+                } else {
+                    unread_keys.push(key.to_vec());
+                    ScanEvaluation::Skip
+                }
+            },
+            |key, _, value: ArcBytes<'static>| {
+                elements.insert(key.to_vec(), Some(Bytes::from(value.to_vec())));
+                Ok(())
+            },
+        )?;
+    elements.extend(unread_keys.into_iter().map(|key| (key, None)));
+    Ok(elements)
+}
+
+fn mismatched_structure(expected: &str) -> bonsaidb_core::Error {
+    bonsaidb_core::Error::other(
+        "bonsaidb-local",
+        format!("type of stored `Value` is not `{expected}`"),
+    )
+}
+
 fn increment(existing: &Numeric, amount: &Numeric, saturating: bool) -> Numeric {
     match amount {
         Numeric::Integer(amount) => {
@@ -204,6 +478,235 @@ fn decrement(existing: &Numeric, amount: &Numeric, saturating: bool) -> Numeric
     }
 }
 
+/// The state of a token bucket stored by [`Command::RateLimit`].
+#[derive(Serialize, Deserialize, Debug)]
+struct TokenBucket {
+    tokens: u64,
+    refilled_at: Timestamp,
+}
+
+impl TokenBucket {
+    /// Adds the tokens that have been refilled since `refilled_at`.
+    fn refill(&mut self, limit: &RateLimit, now: Timestamp) {
+        let elapsed = (now - self.refilled_at).unwrap_or_default();
+        let intervals = elapsed.as_nanos() / limit.refill_interval.as_nanos();
+        let tokens = u128::from(self.tokens)
+            .saturating_add(intervals.saturating_mul(u128::from(limit.refill_amount)));
+        if tokens >= u128::from(limit.capacity) {
+            self.tokens = limit.capacity;
+            self.refilled_at = now;
+        } else {
+            self.tokens = u64::try_from(tokens).unwrap_or(limit.capacity);
+            // Partial intervals continue counting towards the next refill.
+            self.refilled_at = self.refilled_at
+                + duration_from_nanos(intervals * limit.refill_interval.as_nanos());
+        }
+    }
+
+    /// Returns the amount of time until the bucket contains `tokens`, or
+    /// `None` if the bucket can never contain that many tokens.
+    fn duration_until(&self, tokens: u64, limit: &RateLimit, now: Timestamp) -> Option<Duration> {
+        if tokens > limit.capacity {
+            return None;
+        }
+
+        let needed = u128::from(tokens.saturating_sub(self.tokens));
+        let refill_amount = u128::from(limit.refill_amount);
+        let intervals = (needed + refill_amount - 1) / refill_amount;
+        let available_at =
+            self.refilled_at + duration_from_nanos(intervals * limit.refill_interval.as_nanos());
+        Some((available_at - now).unwrap_or_default())
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+/// Publishes the [`KeyChange`]s made to a database's keys to the storage's
+/// [`Relay`].
+#[derive(Debug)]
+pub struct KeyChangeNotifier {
+    relay: Relay,
+    database: String,
+    pending: Vec<KeyChange>,
+}
+
+impl KeyChangeNotifier {
+    pub fn new(relay: Relay, database: &str) -> Self {
+        Self {
+            relay,
+            database: database.to_string(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, full_key: &str, kind: KeyChangeKind) {
+        if let Some((namespace, key)) = split_key(full_key) {
+            self.pending.push(KeyChange {
+                namespace,
+                key,
+                kind,
+            });
+        }
+    }
+
+    /// Publishes all recorded changes.
+    fn publish(&mut self) {
+        for change in self.pending.drain(..) {
+            match pot::to_vec(&change) {
+                Ok(payload) => self.relay.publish_raw(
+                    database_topic(
+                        &self.database,
+                        &key_changes_topic(change.namespace.as_deref()),
+                    ),
+                    payload,
+                ),
+                Err(err) => log::error!("Error serializing key change: {err}"),
+            }
+        }
+    }
+
+    /// Discards all recorded changes without publishing them.
+    fn discard(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Tracks the size and usage of each key so that keys can be evicted once the
+/// store exceeds its configured [`KeyValueEviction`] limits.
+#[derive(Debug)]
+struct EvictionTracker {
+    limits: KeyValueEviction,
+    keys: BTreeMap<String, TrackedKey>,
+    /// Keys ordered by their eviction priority, with the next key to evict
+    /// first.
+    order: BTreeSet<(u64, u64, String)>,
+    total_bytes: usize,
+    clock: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedKey {
+    bytes: usize,
+    uses: u64,
+    last_used: u64,
+}
+
+impl EvictionTracker {
+    fn new(limits: KeyValueEviction) -> Self {
+        Self {
+            limits,
+            keys: BTreeMap::new(),
+            order: BTreeSet::new(),
+            total_bytes: 0,
+            clock: 0,
+        }
+    }
+
+    fn priority(&self, tracked: &TrackedKey) -> (u64, u64) {
+        match self.limits.policy {
+            EvictionPolicy::LeastRecentlyUsed => (0, tracked.last_used),
+            EvictionPolicy::LeastFrequentlyUsed => (tracked.uses, tracked.last_used),
+        }
+    }
+
+    fn insert(&mut self, key: String, tracked: TrackedKey) {
+        let (first, second) = self.priority(&tracked);
+        self.order.insert((first, second, key.clone()));
+        self.total_bytes += tracked.bytes;
+        self.keys.insert(key, tracked);
+    }
+
+    fn forget(&mut self, key: &str) -> Option<TrackedKey> {
+        let tracked = self.keys.remove(key)?;
+        let (first, second) = self.priority(&tracked);
+        self.order.remove(&(first, second, key.to_string()));
+        self.total_bytes -= tracked.bytes;
+        Some(tracked)
+    }
+
+    /// Records a use of `key`, which now occupies `bytes`.
+    fn used(&mut self, key: String, bytes: usize) {
+        self.clock += 1;
+        let uses = self.forget(&key).map_or(0, |tracked| tracked.uses);
+        self.insert(
+            key,
+            TrackedKey {
+                bytes,
+                uses: uses.saturating_add(1),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Records a use of `key` if it is being tracked, leaving its size
+    /// unchanged.
+    fn touched(&mut self, key: String) {
+        if let Some(tracked) = self.keys.get(&key) {
+            let bytes = tracked.bytes;
+            self.used(key, bytes);
+        }
+    }
+
+    /// Tracks a key that has not been used since the store was opened. These
+    /// keys are evicted before any keys that have been used.
+    fn loaded(&mut self, key: String, bytes: usize) {
+        if !self.keys.contains_key(&key) {
+            self.insert(
+                key,
+                TrackedKey {
+                    bytes,
+                    uses: 0,
+                    last_used: 0,
+                },
+            );
+        }
+    }
+
+    /// Returns the next key to evict, if the store exceeds its limits.
+    fn next_eviction(&mut self) -> Option<String> {
+        if self.limits.is_exceeded(self.keys.len(), self.total_bytes) {
+            let next = self.order.iter().next()?.clone();
+            self.order.remove(&next);
+            let (_, _, key) = next;
+            let tracked = self.keys.remove(&key).unwrap();
+            self.total_bytes -= tracked.bytes;
+            Some(key)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the number of bytes counted against
+/// [`KeyValueEviction::max_bytes`] for a key.
+fn entry_size(full_key: &str, value: &Value) -> usize {
+    full_key.len()
+        + match value {
+            Value::Bytes(bytes) => bytes.len(),
+            Value::Numeric(_) => std::mem::size_of::<u64>(),
+            Value::List(values) => values.iter().map(|value| value.len()).sum(),
+            Value::Set(members) => members.iter().map(|member| member.len()).sum(),
+            Value::Hash(fields) => fields
+                .iter()
+                .map(|(field, value)| field.len() + value.len())
+                .sum(),
+        }
+}
+
+/// Returns the full keys an operation reads or writes.
+fn touched_keys(op: &KeyOperation) -> Vec<String> {
+    match &op.command {
+        Command::Scan(_) => Vec::new(),
+        Command::Batch(operations) => operations
+            .iter()
+            .flat_map(|operation| touched_keys(&operation.operation))
+            .collect(),
+        _ => vec![full_key(op.namespace.as_deref(), &op.key)],
+    }
+}
+
 #[derive(Debug)]
 pub struct KeyValueState {
     roots: Roots<AnyFile>,
@@ -214,15 +717,21 @@ pub struct KeyValueState {
     expiration_order: VecDeque<String>,
     dirty_keys: BTreeMap<String, Option<Entry>>,
     keys_being_persisted: Option<Arc<BTreeMap<String, Option<Entry>>>>,
+    dirty_structures: StructureChanges,
+    structures_being_persisted: Option<Arc<StructureChanges>>,
     last_persistence: Watchable<Timestamp>,
     shutdown: Option<flume::Sender<()>>,
+    key_change_notifier: Option<KeyChangeNotifier>,
+    eviction: Option<EvictionTracker>,
 }
 
 impl KeyValueState {
     pub fn new(
         persistence: KeyValuePersistence,
+        eviction: Option<KeyValueEviction>,
         roots: Roots<AnyFile>,
         background_worker_target: Watchable<BackgroundWorkerProcessTarget>,
+        key_change_notifier: Option<KeyChangeNotifier>,
     ) -> Self {
         Self {
             roots,
@@ -233,8 +742,12 @@ impl KeyValueState {
             expiration_order: VecDeque::new(),
             dirty_keys: BTreeMap::new(),
             keys_being_persisted: None,
+            dirty_structures: StructureChanges::default(),
+            structures_being_persisted: None,
             last_persistence: Watchable::new(Timestamp::MIN),
             shutdown: None,
+            key_change_notifier,
+            eviction: eviction.map(EvictionTracker::new),
         }
     }
 
@@ -256,12 +769,55 @@ impl KeyValueState {
         let now = Timestamp::now();
         // If there are any keys that have expired, clear them before executing any operations.
         self.remove_expired_keys(now);
-        let result = match op.command {
+        let touched_keys = if self.eviction.is_some() {
+            touched_keys(&op)
+        } else {
+            Vec::new()
+        };
+        let result = self.execute_operation(op, now);
+        if result.is_ok() {
+            self.record_key_usage(touched_keys);
+        }
+        if let Some(notifier) = &mut self.key_change_notifier {
+            // Changes made by a failed operation have been undone.
+            if result.is_ok() {
+                notifier.publish();
+            } else {
+                notifier.discard();
+            }
+        }
+        if result.is_ok() {
+            if self.needs_commit(now) {
+                self.commit_dirty_keys(state);
+            }
+            self.update_background_worker_target();
+        }
+        result
+    }
+
+    fn execute_operation(
+        &mut self,
+        op: KeyOperation,
+        now: Timestamp,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        match op.command {
             Command::Set(command) => {
                 self.execute_set_operation(op.namespace.as_deref(), &op.key, command, now)
             }
-            Command::Get { delete } => {
-                self.execute_get_operation(op.namespace.as_deref(), &op.key, delete)
+            Command::Get { delete } => Ok(Output::Value(
+                self.execute_get_operation(op.namespace.as_deref(), &op.key, delete)?
+                    .map(|entry| entry.value),
+            )),
+            Command::GetEntry { delete } => Ok(Output::Entry(
+                self.execute_get_operation(op.namespace.as_deref(), &op.key, delete)?
+                    .map(|entry| KeyEntry {
+                        value: entry.value,
+                        expiration: entry.expiration,
+                        last_updated: entry.last_updated,
+                    }),
+            )),
+            Command::Expire { expiration } => {
+                self.execute_expire_operation(op.namespace.as_deref(), &op.key, expiration)
             }
             Command::Delete => self.execute_delete_operation(op.namespace.as_deref(), &op.key),
             Command::Increment { amount, saturating } => self.execute_increment_operation(
@@ -278,14 +834,87 @@ impl KeyValueState {
                 saturating,
                 now,
             ),
-        };
-        if result.is_ok() {
-            if self.needs_commit(now) {
-                self.commit_dirty_keys(state);
+            Command::Scan(scan) => self.execute_scan_operation(op.namespace.as_deref(), &scan),
+            Command::List(command) => {
+                self.execute_list_operation(op.namespace.as_deref(), &op.key, command, now)
             }
-            self.update_background_worker_target();
+            Command::SetMembers(command) => {
+                self.execute_set_members_operation(op.namespace.as_deref(), &op.key, command, now)
+            }
+            Command::Hash(command) => {
+                self.execute_hash_operation(op.namespace.as_deref(), &op.key, command, now)
+            }
+            Command::RateLimit(limit) => {
+                self.execute_rate_limit_operation(op.namespace.as_deref(), &op.key, &limit, now)
+            }
+            Command::Batch(operations) => self.execute_batch_operation(operations, now),
         }
-        result
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, operations, now))
+    )]
+    fn execute_batch_operation(
+        &mut self,
+        operations: Vec<BatchOperation>,
+        now: Timestamp,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        // Verify every check before performing any operations, and remember
+        // the unpersisted state of each key so that the batch can be undone if
+        // an operation fails.
+        let mut previous_states = BTreeMap::new();
+        for operation in &operations {
+            if matches!(operation.operation.command, Command::Batch(_)) {
+                return Err(bonsaidb_core::Error::other(
+                    "bonsaidb-local",
+                    "batches cannot contain other batches",
+                ));
+            }
+
+            let full_key = full_key(
+                operation.operation.namespace.as_deref(),
+                &operation.operation.key,
+            );
+            if let Some(check) = &operation.check {
+                let existing = self.get_for_check(&full_key, check)?;
+                if !check_passes(check, existing.as_ref()) {
+                    return Ok(Output::Batch(None));
+                }
+            }
+
+            if !previous_states.contains_key(&full_key) {
+                let previous_state = (
+                    self.dirty_keys.get(&full_key).cloned(),
+                    self.expiring_keys.get(&full_key).copied(),
+                    self.dirty_structures
+                        .structure(&structure_prefix(&full_key)),
+                );
+                previous_states.insert(full_key, previous_state);
+            }
+        }
+
+        let mut outputs = Vec::with_capacity(operations.len());
+        for operation in operations {
+            match self.execute_operation(operation.operation, now) {
+                Ok(output) => outputs.push(output),
+                Err(err) => {
+                    for (full_key, (dirty_entry, expiration, structure)) in previous_states {
+                        self.update_key_expiration(&full_key, expiration);
+                        self.dirty_structures
+                            .restore_structure(&structure_prefix(&full_key), structure);
+                        if let Some(dirty_entry) = dirty_entry {
+                            self.dirty_keys.insert(full_key, dirty_entry);
+                        } else {
+                            self.dirty_keys.remove(&full_key);
+                        }
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(Output::Batch(Some(outputs)))
     }
 
     #[cfg_attr(
@@ -305,19 +934,21 @@ impl KeyValueState {
             last_updated: now,
         };
         let full_key = full_key(namespace, key);
-        let possible_existing_value =
-            if set.check.is_some() || set.return_previous_value || set.keep_existing_expiration {
-                Some(self.get(&full_key).map_err(Error::from)?)
-            } else {
-                None
-            };
+        let possible_existing_value = if set.return_previous_value {
+            Some(self.load(&full_key)?)
+        } else if let Some(check) = &set.check {
+            Some(self.get_for_check(&full_key, check)?)
+        } else if set.keep_existing_expiration {
+            Some(self.get(&full_key).map_err(Error::from)?)
+        } else {
+            None
+        };
         let existing_value_ref = possible_existing_value.as_ref().and_then(Option::as_ref);
 
-        let updating = match set.check {
-            Some(KeyCheck::OnlyIfPresent) => existing_value_ref.is_some(),
-            Some(KeyCheck::OnlyIfVacant) => existing_value_ref.is_none(),
-            None => true,
-        };
+        let updating = set
+            .check
+            .as_ref()
+            .map_or(true, |check| check_passes(check, existing_value_ref));
         if updating {
             if set.keep_existing_expiration {
                 if let Some(existing_value) = existing_value_ref {
@@ -328,11 +959,18 @@ impl KeyValueState {
 
             let previous_value = if let Some(existing_value) = possible_existing_value {
                 // we already fetched, no need to ask for the existing value back
-                self.set(full_key, entry);
                 existing_value
             } else {
-                self.replace(full_key, entry).map_err(Error::from)?
+                self.get(&full_key).map_err(Error::from)?
             };
+            if previous_value
+                .as_ref()
+                .map_or(false, |previous| is_structure(&previous.value))
+            {
+                self.clear_structure(&full_key);
+            }
+            entry.value = self.store_elements(&full_key, entry.value);
+            self.set(full_key, entry);
             if set.return_previous_value {
                 Ok(Output::Value(previous_value.map(|entry| entry.value)))
             } else if previous_value.is_none() {
@@ -425,15 +1063,43 @@ impl KeyValueState {
         namespace: Option<&str>,
         key: &str,
         delete: bool,
+    ) -> Result<Option<Entry>, bonsaidb_core::Error> {
+        let full_key = full_key(namespace, key);
+        let entry = self.load(&full_key)?;
+        if delete && entry.is_some() {
+            self.remove(full_key).map_err(Error::from)?;
+        }
+
+        Ok(entry)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn execute_expire_operation(
+        &mut self,
+        namespace: Option<&str>,
+        key: &str,
+        expiration: Option<Timestamp>,
     ) -> Result<Output, bonsaidb_core::Error> {
         let full_key = full_key(namespace, key);
-        let entry = if delete {
-            self.remove(full_key).map_err(Error::from)?
+        if let Some(entry) = self.get(&full_key).map_err(Error::from)? {
+            self.set_expiration(full_key, entry, expiration);
+            Ok(Output::Status(KeyStatus::Updated))
         } else {
-            self.get(&full_key).map_err(Error::from)?
-        };
+            Ok(Output::Status(KeyStatus::NotChanged))
+        }
+    }
 
-        Ok(Output::Value(entry.map(|e| e.value)))
+    fn set_expiration(
+        &mut self,
+        full_key: String,
+        mut entry: Entry,
+        expiration: Option<Timestamp>,
+    ) {
+        // Only the expiration changes, so no change notification is published
+        // and the last updated timestamp is left untouched.
+        entry.expiration = expiration;
+        self.update_key_expiration(&full_key, expiration);
+        self.dirty_keys.insert(full_key, Some(entry));
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
@@ -506,32 +1172,469 @@ impl KeyValueState {
                 self.set(full_key, entry);
                 Ok(Output::Value(Some(value)))
             }
-            Value::Bytes(_) => Err(bonsaidb_core::Error::other(
+            _ => Err(bonsaidb_core::Error::other(
                 "bonsaidb-local",
                 "type of stored `Value` is not `Numeric`",
             )),
         }
     }
 
+    /// Returns the entry of the list, set, or hash stored in `full_key`, or
+    /// `None` if the key is vacant. Returns an error if the key contains a
+    /// different type of value than `empty`.
+    fn structure_entry(
+        &self,
+        full_key: &str,
+        empty: &Value,
+        expected: &str,
+    ) -> Result<Option<Entry>, bonsaidb_core::Error> {
+        match self.get(full_key).map_err(Error::from)? {
+            Some(entry)
+                if std::mem::discriminant(&entry.value) == std::mem::discriminant(empty) =>
+            {
+                Ok(Some(entry))
+            }
+            Some(_) => Err(mismatched_structure(expected)),
+            None => Ok(None),
+        }
+    }
+
+    /// Records a change to the elements of the structure stored in
+    /// `full_key`. Structures without any elements are removed.
+    fn write_structure(
+        &mut self,
+        full_key: String,
+        mut entry: Entry,
+        metadata: &StructureMetadata,
+        expiration: Option<Timestamp>,
+        now: Timestamp,
+    ) -> Result<(), Error> {
+        if metadata.length == 0 {
+            self.remove(full_key)?;
+        } else {
+            if expiration.is_some() {
+                entry.expiration = expiration;
+                self.update_key_expiration(&full_key, expiration);
+            }
+            entry.last_updated = now;
+            self.set_structure_metadata(&structure_prefix(&full_key), metadata);
+            self.set(full_key, entry);
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, command, now))
+    )]
+    fn execute_list_operation(
+        &mut self,
+        namespace: Option<&str>,
+        key: &str,
+        command: ListCommand,
+        now: Timestamp,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        let full_key = full_key(namespace, key);
+        let empty = Value::List(VecDeque::new());
+        let existing = self.structure_entry(&full_key, &empty, "List")?;
+        let prefix = structure_prefix(&full_key);
+        match command {
+            ListCommand::Push {
+                value,
+                end,
+                expiration,
+            } => {
+                let mut metadata = if existing.is_some() {
+                    self.structure_metadata(&prefix)?
+                } else {
+                    StructureMetadata::default()
+                };
+                let position = match end {
+                    ListEnd::Front => {
+                        metadata.head -= 1;
+                        metadata.head
+                    }
+                    ListEnd::Back => metadata.head + metadata.length,
+                };
+                metadata.length += 1;
+                metadata.bytes += value.len() as u64;
+                self.set_element(element_key(&prefix, &position.to_be_bytes()), Some(value));
+
+                let entry = existing.unwrap_or(Entry {
+                    value: empty,
+                    expiration: None,
+                    last_updated: now,
+                });
+                self.write_structure(full_key, entry, &metadata, expiration, now)?;
+                Ok(Output::Value(Some(Value::Numeric(
+                    Numeric::UnsignedInteger(metadata.length),
+                ))))
+            }
+            ListCommand::Pop { end } => {
+                let Some(entry) = existing else {
+                    return Ok(Output::Value(None));
+                };
+                let mut metadata = self.structure_metadata(&prefix)?;
+                if metadata.length == 0 {
+                    return Ok(Output::Value(None));
+                }
+
+                let position = match end {
+                    ListEnd::Front => {
+                        metadata.head += 1;
+                        metadata.head - 1
+                    }
+                    ListEnd::Back => metadata.head + metadata.length - 1,
+                };
+                metadata.length -= 1;
+                let element_key = element_key(&prefix, &position.to_be_bytes());
+                let popped = self.get_element(&prefix, &element_key)?;
+                if let Some(popped) = &popped {
+                    metadata.bytes -= popped.len() as u64;
+                }
+                self.set_element(element_key, None);
+
+                self.write_structure(full_key, entry, &metadata, None, now)?;
+                Ok(Output::Value(popped.map(Value::Bytes)))
+            }
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, command, now))
+    )]
+    fn execute_set_members_operation(
+        &mut self,
+        namespace: Option<&str>,
+        key: &str,
+        command: SetMembersCommand,
+        now: Timestamp,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        let full_key = full_key(namespace, key);
+        let empty = Value::Set(BTreeSet::new());
+        let existing = self.structure_entry(&full_key, &empty, "Set")?;
+        let prefix = structure_prefix(&full_key);
+        match command {
+            SetMembersCommand::Add { member, expiration } => {
+                let element_key = element_key(&prefix, &member);
+                let mut metadata = if let Some(entry) = &existing {
+                    if self.get_element(&prefix, &element_key)?.is_some() {
+                        if expiration.is_some() {
+                            self.set_expiration(full_key, entry.clone(), expiration);
+                        }
+                        return Ok(Output::Status(KeyStatus::NotChanged));
+                    }
+                    self.structure_metadata(&prefix)?
+                } else {
+                    StructureMetadata::default()
+                };
+                metadata.length += 1;
+                metadata.bytes += member.len() as u64;
+                self.set_element(element_key, Some(Bytes::default()));
+
+                let entry = existing.unwrap_or(Entry {
+                    value: empty,
+                    expiration: None,
+                    last_updated: now,
+                });
+                self.write_structure(full_key, entry, &metadata, expiration, now)?;
+                Ok(Output::Status(KeyStatus::Inserted))
+            }
+            SetMembersCommand::Remove(member) => {
+                let element_key = element_key(&prefix, &member);
+                let Some(entry) = existing else {
+                    return Ok(Output::Status(KeyStatus::NotChanged));
+                };
+                if self.get_element(&prefix, &element_key)?.is_none() {
+                    return Ok(Output::Status(KeyStatus::NotChanged));
+                }
+
+                let mut metadata = self.structure_metadata(&prefix)?;
+                metadata.length -= 1;
+                metadata.bytes -= member.len() as u64;
+                self.set_element(element_key, None);
+                self.write_structure(full_key, entry, &metadata, None, now)?;
+                Ok(Output::Status(KeyStatus::Deleted))
+            }
+            SetMembersCommand::Contains(member) => {
+                let contains = existing.is_some()
+                    && self
+                        .get_element(&prefix, &element_key(&prefix, &member))?
+                        .is_some();
+                Ok(Output::Contains(contains))
+            }
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, command, now))
+    )]
+    fn execute_hash_operation(
+        &mut self,
+        namespace: Option<&str>,
+        key: &str,
+        command: HashCommand,
+        now: Timestamp,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        let full_key = full_key(namespace, key);
+        let empty = Value::Hash(BTreeMap::new());
+        let existing = self.structure_entry(&full_key, &empty, "Hash")?;
+        let prefix = structure_prefix(&full_key);
+        match command {
+            HashCommand::SetField {
+                field,
+                value,
+                expiration,
+            } => {
+                let element_key = element_key(&prefix, field.as_bytes());
+                let (mut metadata, previous) = if existing.is_some() {
+                    (
+                        self.structure_metadata(&prefix)?,
+                        self.get_element(&prefix, &element_key)?,
+                    )
+                } else {
+                    (StructureMetadata::default(), None)
+                };
+                let status = if let Some(previous) = previous {
+                    metadata.bytes -= previous.len() as u64;
+                    KeyStatus::Updated
+                } else {
+                    metadata.length += 1;
+                    metadata.bytes += field.len() as u64;
+                    KeyStatus::Inserted
+                };
+                metadata.bytes += value.len() as u64;
+                self.set_element(element_key, Some(value));
+
+                let entry = existing.unwrap_or(Entry {
+                    value: empty,
+                    expiration: None,
+                    last_updated: now,
+                });
+                self.write_structure(full_key, entry, &metadata, expiration, now)?;
+                Ok(Output::Status(status))
+            }
+            HashCommand::GetField { field } => {
+                let value = if existing.is_some() {
+                    self.get_element(&prefix, &element_key(&prefix, field.as_bytes()))?
+                } else {
+                    None
+                };
+                Ok(Output::Value(value.map(Value::Bytes)))
+            }
+            HashCommand::DeleteField { field } => {
+                let element_key = element_key(&prefix, field.as_bytes());
+                let Some(entry) = existing else {
+                    return Ok(Output::Status(KeyStatus::NotChanged));
+                };
+                let Some(previous) = self.get_element(&prefix, &element_key)? else {
+                    return Ok(Output::Status(KeyStatus::NotChanged));
+                };
+
+                let mut metadata = self.structure_metadata(&prefix)?;
+                metadata.length -= 1;
+                metadata.bytes -= (field.len() + previous.len()) as u64;
+                self.set_element(element_key, None);
+                self.write_structure(full_key, entry, &metadata, None, now)?;
+                Ok(Output::Status(KeyStatus::Deleted))
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn execute_rate_limit_operation(
+        &mut self,
+        namespace: Option<&str>,
+        key: &str,
+        limit: &RateLimit,
+        now: Timestamp,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        if limit.capacity == 0 || limit.refill_amount == 0 || limit.refill_interval.is_zero() {
+            return Err(bonsaidb_core::Error::other(
+                "bonsaidb-local",
+                "rate limits require a non-zero capacity, refill amount, and refill interval",
+            ));
+        }
+
+        let full_key = full_key(namespace, key);
+        // A missing bucket is a full bucket.
+        let mut bucket = match self.get(&full_key).map_err(Error::from)? {
+            Some(Entry {
+                value: Value::Bytes(bytes),
+                ..
+            }) => pot::from_slice::<TokenBucket>(&bytes)
+                .map_err(|_| mismatched_structure("TokenBucket"))?,
+            Some(_) => return Err(mismatched_structure("TokenBucket")),
+            None => TokenBucket {
+                tokens: limit.capacity,
+                refilled_at: now,
+            },
+        };
+        bucket.refill(limit, now);
+
+        let allowed = bucket.tokens >= limit.cost;
+        let retry_after = if allowed {
+            bucket.tokens -= limit.cost;
+            None
+        } else {
+            bucket.duration_until(limit.cost, limit, now)
+        };
+
+        // Once the bucket would be full again, it no longer needs to be
+        // stored.
+        let expiration = bucket
+            .duration_until(limit.capacity, limit, now)
+            .map(|duration| now + duration);
+        let value = Value::Bytes(Bytes::from(pot::to_vec(&bucket)?));
+        self.update_key_expiration(&full_key, expiration);
+        self.set(
+            full_key,
+            Entry {
+                value,
+                expiration,
+                last_updated: now,
+            },
+        );
+
+        Ok(Output::RateLimit(RateLimitStatus {
+            allowed,
+            remaining: bucket.tokens,
+            retry_after,
+        }))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, scan))
+    )]
+    fn execute_scan_operation(
+        &self,
+        namespace: Option<&str>,
+        scan: &ScanCommand,
+    ) -> Result<Output, bonsaidb_core::Error> {
+        let namespace_prefix = full_key(namespace, "");
+        let prefix = scan.prefix.as_deref().unwrap_or("");
+        let key_range = scan.range.map_ref(String::as_str);
+        // Keys containing a null byte belong to a nested namespace.
+        let is_listed =
+            |key: &str| !key.contains('\0') && key.starts_with(prefix) && key_range.contains(&key);
+
+        // Keys that have not been persisted yet take precedence over the keys
+        // stored on disk.
+        let unpersisted = self
+            .keys_being_persisted
+            .iter()
+            .flat_map(|keys| keys.iter())
+            .chain(self.dirty_keys.iter())
+            .map(|(key, entry)| (key.as_str(), entry.as_ref()))
+            .collect::<BTreeMap<_, _>>();
+        let mut listed = unpersisted
+            .range(namespace_prefix.as_str()..)
+            .map_while(|(full_key, entry)| {
+                full_key
+                    .strip_prefix(&namespace_prefix)
+                    .map(|key| (key, entry))
+            })
+            .filter_map(|(key, entry)| {
+                entry
+                    .filter(|_| is_listed(key))
+                    .map(|entry| (key.to_string(), Some(entry.value.clone())))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let limit = scan.limit.map_or(usize::MAX, |limit| limit as usize);
+        if let Some(stored_keys) = stored_key_range(namespace, &namespace_prefix, prefix, scan) {
+            let mut keys_read = 0;
+            let mut stored_keys_without_values = Vec::new();
+            self.roots
+                .tree(Unversioned::tree(KEY_TREE))
+                .map_err(Error::from)?
+                .scan::<Error, _, _, _, _>(
+                    &stored_keys.map_ref(|key| &key[..]),
+                    true,
+                    |_, _, _| ScanEvaluation::ReadData,
+                    |full_key, _| {
+                        if keys_read >= limit {
+                            return ScanEvaluation::Stop;
+                        }
+                        let full_key = std::str::from_utf8(full_key).unwrap_or_default();
+                        let key = match full_key.strip_prefix(&namespace_prefix) {
+                            Some(key) => key,
+                            None => return ScanEvaluation::Skip,
+                        };
+                        if !key.starts_with(prefix) && key > prefix {
+                            // All remaining keys are after the prefix.
+                            ScanEvaluation::Stop
+                        } else if unpersisted.contains_key(full_key) || !is_listed(key) {
+                            ScanEvaluation::Skip
+                        } else if scan.include_values {
+                            keys_read += 1;
+                            ScanEvaluation::ReadData
+                        } else {
+                            keys_read += 1;
+                            stored_keys_without_values.push(key.to_string());
+                            ScanEvaluation::Skip
+                        }
+                    },
+                    |full_key, _, entry| {
+                        let entry = bincode::deserialize::<Entry>(&entry)
+                            .map_err(|err| AbortError::Other(Error::from(err)))?;
+                        let full_key = std::str::from_utf8(&full_key)
+                            .map_err(|err| AbortError::Other(Error::from(err)))?;
+                        listed.insert(
+                            full_key[namespace_prefix.len()..].to_string(),
+                            Some(entry.value),
+                        );
+                        Ok(())
+                    },
+                )?;
+            listed.extend(
+                stored_keys_without_values
+                    .into_iter()
+                    .map(|key| (key, None)),
+            );
+        }
+
+        let mut keys = Vec::with_capacity(listed.len().min(limit));
+        for (key, value) in listed.into_iter().take(limit) {
+            let mut value = value.filter(|_| scan.include_values);
+            if let Some(value) = &mut value {
+                self.load_elements(&full_key(namespace, &key), value)?;
+            }
+            keys.push(ScannedKey { key, value });
+        }
+        Ok(Output::Keys(keys))
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn remove(&mut self, key: String) -> Result<Option<Entry>, nebari::Error> {
         self.update_key_expiration(&key, None);
 
-        if let Some(dirty_entry) = self.dirty_keys.get_mut(&key) {
-            Ok(dirty_entry.take())
+        let previous_value = if let Some(dirty_entry) = self.dirty_keys.get_mut(&key) {
+            dirty_entry.take()
         } else if let Some(persisting_entry) = self
             .keys_being_persisted
             .as_ref()
             .and_then(|keys| keys.get(&key))
         {
-            self.dirty_keys.insert(key, None);
-            Ok(persisting_entry.clone())
+            let persisting_entry = persisting_entry.clone();
+            self.dirty_keys.insert(key.clone(), None);
+            persisting_entry
         } else {
             // There might be a value on-disk we need to remove.
             let previous_value = Self::retrieve_key_from_disk(&self.roots, &key)?;
-            self.dirty_keys.insert(key, None);
-            Ok(previous_value)
+            self.dirty_keys.insert(key.clone(), None);
+            previous_value
+        };
+        if let Some(previous_value) = &previous_value {
+            if is_structure(&previous_value.value) {
+                self.clear_structure(&key);
+            }
+            self.notify(&key, KeyChangeKind::Deleted);
         }
+        Ok(previous_value)
     }
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
@@ -550,33 +1653,171 @@ impl KeyValueState {
     }
 
     fn set(&mut self, key: String, value: Entry) {
+        self.notify(&key, KeyChangeKind::Set);
         self.dirty_keys.insert(key, Some(value));
     }
 
-    fn replace(&mut self, key: String, value: Entry) -> Result<Option<Entry>, nebari::Error> {
-        let mut value = Some(value);
-        let map_entry = self.dirty_keys.entry(key);
-        if matches!(map_entry, btree_map::Entry::Vacant(_)) {
-            // This key is clean, and the caller is expecting the previous
-            // value.
-            let stored_value = if let Some(persisting_entry) = self
-                .keys_being_persisted
+    /// Returns the entry stored in `key`, including the elements of lists,
+    /// sets, and hashes.
+    fn load(&self, key: &str) -> Result<Option<Entry>, Error> {
+        let Some(mut entry) = self.get(key)? else {
+            return Ok(None);
+        };
+        self.load_elements(key, &mut entry.value)?;
+        Ok(Some(entry))
+    }
+
+    /// Returns the entry stored in `key` for evaluating `check`. Only
+    /// [`KeyCheck::OnlyIfEqual`] requires the elements of structures.
+    fn get_for_check(&self, key: &str, check: &KeyCheck) -> Result<Option<Entry>, Error> {
+        if matches!(check, KeyCheck::OnlyIfEqual(_)) {
+            self.load(key)
+        } else {
+            Ok(self.get(key)?)
+        }
+    }
+
+    /// Fills `value` with the elements of the structure stored in `full_key`
+    /// if `value` is a list, set, or hash.
+    fn load_elements(&self, full_key: &str, value: &mut Value) -> Result<(), Error> {
+        if !is_structure(value) {
+            return Ok(());
+        }
+
+        let prefix = structure_prefix(full_key);
+        let layers = [
+            self.structures_being_persisted.as_deref(),
+            Some(&self.dirty_structures),
+        ];
+        let mut elements = if layers
+            .iter()
+            .flatten()
+            .any(|changes| changes.cleared.contains(&prefix))
+        {
+            BTreeMap::new()
+        } else {
+            stored_elements(&self.roots, &prefix, true)?
+        };
+        for changes in layers.into_iter().flatten() {
+            if changes.cleared.contains(&prefix) {
+                elements.clear();
+            }
+            for (key, element) in changes.elements_of(&prefix) {
+                elements.insert(key.clone(), element.clone());
+            }
+        }
+
+        // Skip the structure's metadata, which is stored in the prefix itself.
+        let element_start = prefix.len() + 1;
+        let elements = elements
+            .into_iter()
+            .filter(|(key, _)| key.len() >= element_start)
+            .filter_map(|(key, element)| element.map(|element| (key, element)));
+        match value {
+            Value::List(list) => list.extend(elements.map(|(_, element)| element)),
+            Value::Set(set) => {
+                set.extend(elements.map(|(key, _)| Bytes::from(key[element_start..].to_vec())))
+            }
+            Value::Hash(hash) => {
+                for (key, element) in elements {
+                    let field = std::str::from_utf8(&key[element_start..])?;
+                    hash.insert(field.to_string(), element);
+                }
+            }
+            Value::Bytes(_) | Value::Numeric(_) => unreachable!("not a structure"),
+        }
+        Ok(())
+    }
+
+    /// Returns the value of an element of the structure with `prefix`.
+    fn get_element(&self, prefix: &[u8], element_key: &[u8]) -> Result<Option<Bytes>, Error> {
+        let change = self.dirty_structures.get(prefix, element_key).or_else(|| {
+            self.structures_being_persisted
                 .as_ref()
-                .and_then(|keys| keys.get(map_entry.key()))
-            {
-                persisting_entry.clone()
-            } else {
-                Self::retrieve_key_from_disk(&self.roots, map_entry.key())?
-            };
-            map_entry.or_insert(value);
-            Ok(stored_value)
+                .and_then(|changes| changes.get(prefix, element_key))
+        });
+        if let Some(change) = change {
+            Ok(change.cloned())
         } else {
-            // This key is already dirty, we can just replace the value and
-            // return the old value.
-            map_entry.and_modify(|map_entry| {
-                std::mem::swap(&mut value, map_entry);
-            });
-            Ok(value)
+            Ok(self
+                .roots
+                .tree(Unversioned::tree(STRUCTURE_TREE))?
+                .get(element_key)?
+                .map(|element| Bytes::from(element.to_vec())))
+        }
+    }
+
+    fn set_element(&mut self, element_key: Vec<u8>, value: Option<Bytes>) {
+        self.dirty_structures.elements.insert(element_key, value);
+    }
+
+    fn structure_metadata(&self, prefix: &[u8]) -> Result<StructureMetadata, Error> {
+        self.get_element(prefix, prefix)?
+            .map_or_else(
+                || Ok(StructureMetadata::default()),
+                |metadata| bincode::deserialize(&metadata),
+            )
+            .map_err(Error::from)
+    }
+
+    fn set_structure_metadata(&mut self, prefix: &[u8], metadata: &StructureMetadata) {
+        let metadata = bincode::serialize(metadata).unwrap();
+        self.set_element(prefix.to_vec(), Some(Bytes::from(metadata)));
+    }
+
+    /// Removes every element of the structure stored in `full_key`.
+    fn clear_structure(&mut self, full_key: &str) {
+        let prefix = structure_prefix(full_key);
+        self.dirty_structures.remove_structure(&prefix);
+        self.dirty_structures.cleared.insert(prefix);
+    }
+
+    /// Stores the elements of `value` if it is a list, set, or hash, returning
+    /// the value to store in the key's entry. The key must not contain any
+    /// elements.
+    fn store_elements(&mut self, full_key: &str, value: Value) -> Value {
+        let prefix = structure_prefix(full_key);
+        let mut metadata = StructureMetadata::default();
+        let empty = match value {
+            Value::List(list) => {
+                for value in list {
+                    let position = metadata.head + metadata.length;
+                    metadata.length += 1;
+                    metadata.bytes += value.len() as u64;
+                    self.set_element(element_key(&prefix, &position.to_be_bytes()), Some(value));
+                }
+                Value::List(VecDeque::new())
+            }
+            Value::Set(set) => {
+                for member in set {
+                    metadata.length += 1;
+                    metadata.bytes += member.len() as u64;
+                    self.set_element(element_key(&prefix, &member), Some(Bytes::default()));
+                }
+                Value::Set(BTreeSet::new())
+            }
+            Value::Hash(hash) => {
+                for (field, value) in hash {
+                    metadata.length += 1;
+                    metadata.bytes += (field.len() + value.len()) as u64;
+                    self.set_element(element_key(&prefix, field.as_bytes()), Some(value));
+                }
+                Value::Hash(BTreeMap::new())
+            }
+            other => return other,
+        };
+        self.set_structure_metadata(&prefix, &metadata);
+        empty
+    }
+
+    /// Returns the number of bytes counted against
+    /// [`KeyValueEviction::max_bytes`] for the entry stored in `full_key`.
+    fn stored_size(&self, full_key: &str, value: &Value) -> Result<usize, Error> {
+        if is_structure(value) {
+            let metadata = self.structure_metadata(&structure_prefix(full_key))?;
+            Ok(full_key.len() + usize::try_from(metadata.bytes).unwrap_or(usize::MAX))
+        } else {
+            Ok(entry_size(full_key, value))
         }
     }
 
@@ -636,10 +1877,85 @@ impl KeyValueState {
         {
             let key = self.expiration_order.pop_front().unwrap();
             self.expiring_keys.remove(&key);
+            if let Some(tracker) = &mut self.eviction {
+                tracker.forget(&key);
+            }
+            self.notify(&key, KeyChangeKind::Expired);
+            self.clear_structure(&key);
+            self.dirty_keys.insert(key, None);
+        }
+
+        if let Some(notifier) = &mut self.key_change_notifier {
+            notifier.publish();
+        }
+    }
+
+    /// Updates the eviction tracker for keys touched by a successful
+    /// operation, and evicts keys if the store now exceeds its limits.
+    fn record_key_usage(&mut self, keys: Vec<String>) {
+        // The tracker is taken while measuring keys, which requires borrowing
+        // the rest of the state.
+        if let Some(mut tracker) = self.eviction.take() {
+            for key in keys {
+                // Keys that have been modified are dirty. Clean keys still have
+                // the size they were tracked with.
+                let modified = self.dirty_keys.get(&key).or_else(|| {
+                    self.keys_being_persisted
+                        .as_ref()
+                        .and_then(|keys| keys.get(&key))
+                });
+                match modified {
+                    Some(Some(entry)) => match self.stored_size(&key, &entry.value) {
+                        Ok(bytes) => tracker.used(key, bytes),
+                        Err(err) => {
+                            log::error!("Error measuring key-value entry: {err}");
+                            tracker.touched(key);
+                        }
+                    },
+                    Some(None) => {
+                        tracker.forget(&key);
+                    }
+                    None => tracker.touched(key),
+                }
+            }
+            self.eviction = Some(tracker);
+            self.evict_keys();
+        }
+    }
+
+    /// Tracks keys that were stored before this database was opened.
+    pub fn track_existing_keys(&mut self, keys: impl IntoIterator<Item = (String, usize)>) {
+        if let Some(tracker) = &mut self.eviction {
+            for (key, bytes) in keys {
+                tracker.loaded(key, bytes);
+            }
+            self.evict_keys();
+            if let Some(notifier) = &mut self.key_change_notifier {
+                notifier.publish();
+            }
+            self.update_background_worker_target();
+        }
+    }
+
+    fn evict_keys(&mut self) {
+        while let Some(key) = self
+            .eviction
+            .as_mut()
+            .and_then(EvictionTracker::next_eviction)
+        {
+            self.update_key_expiration(&key, None);
+            self.notify(&key, KeyChangeKind::Evicted);
+            self.clear_structure(&key);
             self.dirty_keys.insert(key, None);
         }
     }
 
+    fn notify(&mut self, full_key: &str, kind: KeyChangeKind) {
+        if let Some(notifier) = &mut self.key_change_notifier {
+            notifier.record(full_key, kind);
+        }
+    }
+
     fn needs_commit(&mut self, now: Timestamp) -> bool {
         if self.keys_being_persisted.is_some() {
             false
@@ -650,23 +1966,28 @@ impl KeyValueState {
         }
     }
 
-    fn stage_dirty_keys(&mut self) -> Option<Arc<BTreeMap<String, Option<Entry>>>> {
+    fn stage_dirty_keys(
+        &mut self,
+    ) -> Option<(Arc<BTreeMap<String, Option<Entry>>>, Arc<StructureChanges>)> {
+        // Every change to a structure's elements also dirties its key.
         if !self.dirty_keys.is_empty() && self.keys_being_persisted.is_none() {
             let keys = Arc::new(std::mem::take(&mut self.dirty_keys));
+            let structures = Arc::new(std::mem::take(&mut self.dirty_structures));
             self.keys_being_persisted = Some(keys.clone());
-            Some(keys)
+            self.structures_being_persisted = Some(structures.clone());
+            Some((keys, structures))
         } else {
             None
         }
     }
 
     pub fn commit_dirty_keys(&mut self, state: &Arc<Mutex<KeyValueState>>) -> bool {
-        if let Some(keys) = self.stage_dirty_keys() {
+        if let Some((keys, structures)) = self.stage_dirty_keys() {
             let roots = self.roots.clone();
             let state = state.clone();
             std::thread::Builder::new()
                 .name(String::from("keyvalue-persist"))
-                .spawn(move || Self::persist_keys(&state, &roots, &keys))
+                .spawn(move || Self::persist_keys(&state, &roots, &keys, &structures))
                 .unwrap();
             self.last_commit = Timestamp::now();
             true
@@ -685,9 +2006,27 @@ impl KeyValueState {
         key_value_state: &Arc<Mutex<KeyValueState>>,
         roots: &Roots<AnyFile>,
         keys: &BTreeMap<String, Option<Entry>>,
+        structures: &StructureChanges,
     ) -> Result<(), bonsaidb_core::Error> {
+        // The elements of cleared structures are removed, unless they have
+        // been set again. Only this thread writes to the structure tree, so
+        // the stored elements can be gathered before the transaction begins.
+        let mut elements = BTreeMap::new();
+        for prefix in &structures.cleared {
+            elements.extend(stored_elements(roots, prefix, false)?);
+        }
+        elements.extend(
+            structures
+                .elements
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+
         let mut transaction = roots
-            .transaction(&[Unversioned::tree(KEY_TREE)])
+            .transaction(&[
+                Unversioned::tree(KEY_TREE),
+                Unversioned::tree(STRUCTURE_TREE),
+            ])
             .map_err(Error::from)?;
         let all_keys = keys
             .keys()
@@ -725,7 +2064,33 @@ impl KeyValueState {
             )
             .map_err(Error::from)?;
 
-        if !changed_keys.is_empty() {
+        let mut changed_elements = false;
+        if !elements.is_empty() {
+            let element_keys = elements
+                .keys()
+                .map(|key| ArcBytes::from(key.clone()))
+                .collect();
+            transaction
+                .tree::<Unversioned>(1)
+                .unwrap()
+                .modify(
+                    element_keys,
+                    Operation::CompareSwap(CompareSwap::new(&mut |key, existing_value| {
+                        if let Some(new_value) = elements.get(&key[..]).unwrap() {
+                            changed_elements = true;
+                            nebari::tree::KeyOperation::Set(ArcBytes::from(new_value.to_vec()))
+                        } else if existing_value.is_some() {
+                            changed_elements = true;
+                            nebari::tree::KeyOperation::Remove
+                        } else {
+                            nebari::tree::KeyOperation::Skip
+                        }
+                    })),
+                )
+                .map_err(Error::from)?;
+        }
+
+        if !changed_keys.is_empty() || changed_elements {
             transaction
                 .entry_mut()
                 .set_data(compat::serialize_executed_transaction_changes(
@@ -740,6 +2105,7 @@ impl KeyValueState {
             let mut state = key_value_state.lock();
             state.last_persistence.replace(Timestamp::now());
             state.keys_being_persisted = None;
+            state.structures_being_persisted = None;
             state.update_background_worker_target();
             // This block is a little ugly to avoid having to acquire the lock
             // twice. If we're shutting down and have no dirty keys, we notify
@@ -757,8 +2123,8 @@ impl KeyValueState {
                 None
             }
         };
-        if let Some(final_keys) = final_keys {
-            Self::persist_keys(key_value_state, roots, &final_keys)?;
+        if let Some((final_keys, final_structures)) = final_keys {
+            Self::persist_keys(key_value_state, roots, &final_keys, &final_structures)?;
         }
         Ok(())
     }
@@ -850,13 +2216,25 @@ impl Job for ExpirationLoader {
         let database = self.database.clone();
         let launched_at = self.launched_at;
 
-        for ((namespace, key), entry) in database.all_key_value_entries()? {
+        let entries = database.all_key_value_entries()?;
+        for ((namespace, key), entry) in &entries {
             if entry.last_updated < launched_at && entry.expiration.is_some() {
                 self.database
-                    .update_key_expiration(full_key(namespace.as_deref(), &key), entry.expiration);
+                    .update_key_expiration(full_key(namespace.as_deref(), key), entry.expiration);
             }
         }
 
+        self.database.track_existing_keys(
+            entries
+                .into_iter()
+                .filter(|(_, entry)| entry.last_updated < launched_at)
+                .map(|((namespace, key), entry)| {
+                    let full_key = full_key(namespace.as_deref(), &key);
+                    let bytes = entry_size(&full_key, &entry.value);
+                    (full_key, bytes)
+                }),
+        );
+
         self.database
             .storage()
             .instance
@@ -891,7 +2269,7 @@ mod tests {
             .file_manager(AnyFileManager::std())
             .open()?;
 
-        let context = Context::new(sled.clone(), persistence, None);
+        let context = Context::new(sled.clone(), persistence, None, None, None);
 
         test_contents(context, sled)?;
 
@@ -1164,6 +2542,174 @@ mod tests {
         )
     }
 
+    #[test]
+    fn scan_merges_unpersisted_keys() -> anyhow::Result<()> {
+        run_test_with_persistence(
+            "kv-scan-unpersisted",
+            KeyValuePersistence::lazy([PersistenceThreshold::after_changes(3)]),
+            &|context, _roots| {
+                let mut persistence_watcher = context.kv_persistence_watcher();
+                let set = |namespace: Option<&str>, key: &str| {
+                    context.perform_kv_operation(KeyOperation {
+                        namespace: namespace.map(String::from),
+                        key: String::from(key),
+                        command: Command::Set(SetCommand {
+                            value: Value::Numeric(Numeric::UnsignedInteger(key.len() as u64)),
+                            expiration: None,
+                            keep_existing_expiration: false,
+                            check: None,
+                            return_previous_value: false,
+                        }),
+                    })
+                };
+                let scan = |scan: ScanCommand| match context.perform_kv_operation(KeyOperation {
+                    namespace: None,
+                    key: String::new(),
+                    command: Command::Scan(scan),
+                })? {
+                    Output::Keys(keys) => Ok::<_, bonsaidb_core::Error>(keys),
+                    other => unreachable!("unexpected output {other:?}"),
+                };
+
+                // Persist the first three keys.
+                set(None, "a")?;
+                set(None, "bb")?;
+                set(Some("ns"), "ignored")?;
+                persistence_watcher.next_value()?;
+
+                // Delete a persisted key and add a new one without persisting.
+                set(None, "ccc")?;
+                context.perform_kv_operation(KeyOperation {
+                    namespace: None,
+                    key: String::from("a"),
+                    command: Command::Delete,
+                })?;
+
+                let keys = scan(ScanCommand {
+                    include_values: true,
+                    ..ScanCommand::default()
+                })?;
+                assert_eq!(
+                    keys,
+                    vec![
+                        ScannedKey {
+                            key: String::from("bb"),
+                            value: Some(Value::Numeric(Numeric::UnsignedInteger(2))),
+                        },
+                        ScannedKey {
+                            key: String::from("ccc"),
+                            value: Some(Value::Numeric(Numeric::UnsignedInteger(3))),
+                        },
+                    ]
+                );
+
+                let keys = scan(ScanCommand {
+                    limit: Some(1),
+                    ..ScanCommand::default()
+                })?;
+                assert_eq!(
+                    keys,
+                    vec![ScannedKey {
+                        key: String::from("bb"),
+                        value: None,
+                    }]
+                );
+
+                let keys = scan(ScanCommand {
+                    prefix: Some(String::from("c")),
+                    ..ScanCommand::default()
+                })?;
+                assert_eq!(keys.len(), 1);
+                assert_eq!(keys[0].key, "ccc");
+
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn structure_elements_are_stored_individually() -> anyhow::Result<()> {
+        run_test_with_persistence(
+            "kv-structure-elements",
+            KeyValuePersistence::lazy([PersistenceThreshold::after_changes(2)]),
+            &|context, roots| {
+                let mut persistence_watcher = context.kv_persistence_watcher();
+                let perform = |key: &str, command: Command| {
+                    context.perform_kv_operation(KeyOperation {
+                        namespace: None,
+                        key: String::from(key),
+                        command,
+                    })
+                };
+                // Changing the list only dirties one key, so setting a second
+                // key causes the changes to be persisted.
+                let flush = || {
+                    perform(
+                        "flush",
+                        Command::Set(SetCommand {
+                            value: Value::Bytes(Bytes::default()),
+                            expiration: None,
+                            keep_existing_expiration: false,
+                            check: None,
+                            return_previous_value: false,
+                        }),
+                    )
+                };
+                let prefix = structure_prefix(&full_key(None, "list"));
+                let stored_count =
+                    || stored_elements(&roots, &prefix, false).map(|elements| elements.len());
+
+                for value in 0_u8..3 {
+                    perform(
+                        "list",
+                        Command::List(ListCommand::Push {
+                            value: Bytes::from(vec![value]),
+                            end: ListEnd::Back,
+                            expiration: None,
+                        }),
+                    )?;
+                }
+                flush()?;
+                persistence_watcher.next_value()?;
+
+                // The key stores an empty list, and each value is stored
+                // alongside the list's metadata.
+                let entry = roots
+                    .tree(Unversioned::tree(KEY_TREE))?
+                    .get(b"\0list")?
+                    .expect("list not persisted");
+                let entry = bincode::deserialize::<Entry>(&entry)?;
+                assert_eq!(entry.value, Value::List(VecDeque::new()));
+                assert_eq!(stored_count()?, 4);
+
+                perform(
+                    "list",
+                    Command::List(ListCommand::Pop {
+                        end: ListEnd::Front,
+                    }),
+                )?;
+                flush()?;
+                persistence_watcher.next_value()?;
+                assert_eq!(stored_count()?, 3);
+                match perform("list", Command::Get { delete: false })? {
+                    Output::Value(Some(Value::List(list))) => assert_eq!(
+                        list,
+                        VecDeque::from([Bytes::from(vec![1]), Bytes::from(vec![2])])
+                    ),
+                    other => unreachable!("unexpected output {other:?}"),
+                }
+
+                // Deleting the key removes all of its elements.
+                perform("list", Command::Delete)?;
+                flush()?;
+                persistence_watcher.next_value()?;
+                assert_eq!(stored_count()?, 0);
+
+                Ok(())
+            },
+        )
+    }
+
     #[test]
     fn saves_on_drop() -> anyhow::Result<()> {
         let dir = TestDirectory::new("saves-on-drop.bonsaidb");
@@ -1176,6 +2722,8 @@ mod tests {
             sled,
             KeyValuePersistence::lazy([PersistenceThreshold::after_changes(2)]),
             None,
+            None,
+            None,
         );
         context
             .perform_kv_operation(KeyOperation {
@@ -1199,4 +2747,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn token_bucket_refills() {
+        let limit = RateLimit::token_bucket(5, 2, Duration::from_secs(1));
+        let start = Timestamp::now();
+        let mut bucket = TokenBucket {
+            tokens: 0,
+            refilled_at: start,
+        };
+
+        // Only whole intervals refill the bucket, and the partial interval
+        // continues counting towards the next refill.
+        let now = start + Duration::from_millis(1_500);
+        bucket.refill(&limit, now);
+        assert_eq!(bucket.tokens, 2);
+        assert_eq!(bucket.refilled_at, start + Duration::from_secs(1));
+        assert_eq!(
+            bucket.duration_until(3, &limit, now),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            bucket.duration_until(5, &limit, now),
+            Some(Duration::from_millis(1_500))
+        );
+        assert_eq!(bucket.duration_until(6, &limit, now), None);
+
+        // Refilling never exceeds the capacity.
+        let now = start + Duration::from_secs(60);
+        bucket.refill(&limit, now);
+        assert_eq!(bucket.tokens, 5);
+        assert_eq!(bucket.refilled_at, now);
+    }
 }
@@ -2,9 +2,12 @@ use bonsaidb_core::arc_bytes::OwnedBytes;
 pub use bonsaidb_core::circulate::Relay;
 use bonsaidb_core::connection::{Connection, HasSession};
 use bonsaidb_core::permissions::bonsai::{
-    database_resource_name, pubsub_topic_resource_name, BonsaiAction, DatabaseAction, PubSubAction,
+    collection_resource_name, database_resource_name, keyvalue_namespace_resource_name,
+    pubsub_topic_resource_name, BonsaiAction, DatabaseAction, DocumentAction, KeyValueAction,
+    PubSubAction,
 };
 use bonsaidb_core::pubsub::{self, database_topic, PubSub, Receiver};
+use bonsaidb_core::schema::CollectionName;
 use bonsaidb_core::{circulate, Error};
 
 use crate::{Database, DatabaseNonBlocking};
@@ -24,6 +27,9 @@ impl PubSub for super::Database {
     }
 
     fn publish_bytes(&self, topic: Vec<u8>, payload: Vec<u8>) -> Result<(), bonsaidb_core::Error> {
+        if pubsub::is_reserved_topic(&topic) {
+            return Err(Error::ReservedTopic);
+        }
         self.check_permission(
             pubsub_topic_resource_name(self.name(), &topic),
             &BonsaiAction::Database(DatabaseAction::PubSub(PubSubAction::Publish)),
@@ -44,6 +50,9 @@ impl PubSub for super::Database {
             topics
                 .into_iter()
                 .map(|topic| {
+                    if pubsub::is_reserved_topic(&topic) {
+                        return Err(Error::ReservedTopic);
+                    }
                     self.check_permission(
                         pubsub_topic_resource_name(self.name(), &topic),
                         &BonsaiAction::Database(DatabaseAction::PubSub(PubSubAction::Publish)),
@@ -82,6 +91,12 @@ impl Drop for Subscriber {
 
 impl pubsub::Subscriber for Subscriber {
     fn subscribe_to_bytes(&self, topic: Vec<u8>) -> Result<(), Error> {
+        // Collection and key changes are only delivered to subscribers that can
+        // read the collection or namespace, which is checked by
+        // subscribe_to_collection_changes and subscribe_to_key_changes.
+        if pubsub::is_reserved_topic(&topic) {
+            return Err(Error::ReservedTopic);
+        }
         self.database.check_permission(
             pubsub_topic_resource_name(self.database.name(), &topic),
             &BonsaiAction::Database(DatabaseAction::PubSub(PubSubAction::SubscribeTo)),
@@ -101,6 +116,32 @@ impl pubsub::Subscriber for Subscriber {
         Ok(())
     }
 
+    fn subscribe_to_collection_changes(&self, collection: &CollectionName) -> Result<(), Error> {
+        for action in [DocumentAction::List, DocumentAction::Get] {
+            self.database.check_permission(
+                collection_resource_name(self.database.name(), collection),
+                &BonsaiAction::Database(DatabaseAction::Document(action)),
+            )?;
+        }
+        self.subscriber.subscribe_to_raw(database_topic(
+            self.database.name(),
+            &pubsub::collection_changes_topic(collection),
+        ));
+        Ok(())
+    }
+
+    fn subscribe_to_key_changes(&self, namespace: Option<&str>) -> Result<(), Error> {
+        self.database.check_permission(
+            keyvalue_namespace_resource_name(self.database.name(), namespace),
+            &BonsaiAction::Database(DatabaseAction::KeyValue(KeyValueAction::ExecuteOperation)),
+        )?;
+        self.subscriber.subscribe_to_raw(database_topic(
+            self.database.name(),
+            &pubsub::key_changes_topic(namespace),
+        ));
+        Ok(())
+    }
+
     fn receiver(&self) -> &Receiver {
         &self.receiver
     }
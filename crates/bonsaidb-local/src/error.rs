@@ -71,6 +71,25 @@ pub enum Error {
     #[error("a backup error: {0}")]
     Backup(Box<dyn AnyError>),
 
+    /// An incremental backup of `database` could not be restored because the
+    /// backup of the transactions executed after `after` is missing.
+    #[error("backup of {database} is missing the transactions after {after:?}")]
+    IncompleteBackup {
+        /// The name of the database being restored.
+        database: String,
+        /// The id of the last transaction that was restored.
+        after: Option<u64>,
+    },
+
+    /// `database` could not be restored to the requested
+    /// [`RestorePoint`](crate::RestorePoint) because no available backup
+    /// captures the state of the database at that point.
+    #[error("no backup of {database} captures the requested restore point")]
+    RestorePointUnavailable {
+        /// The name of the database being restored.
+        database: String,
+    },
+
     /// An error occurred with a password hash.
     #[cfg(all(feature = "password-hashing", feature = "cli"))]
     #[error("error reading password: {0}")]
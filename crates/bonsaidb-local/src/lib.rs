@@ -37,7 +37,9 @@ pub use bonsaidb_core as core;
 pub use self::database::pubsub::Subscriber;
 pub use self::database::{Database, DatabaseNonBlocking};
 pub use self::error::Error;
-pub use self::storage::{BackupLocation, Storage, StorageId, StorageNonBlocking};
+pub use self::storage::{
+    BackupArchive, BackupLocation, RestorePoint, Storage, StorageId, StorageNonBlocking,
+};
 
 #[cfg(feature = "async")]
 mod r#async;
@@ -9,6 +9,7 @@ use crate::database::document_tree_name;
 use crate::storage::TreeVault;
 use crate::views::{
     view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
+    view_reductions_tree_name,
 };
 
 #[derive(Default)]
@@ -65,6 +66,13 @@ impl OpenTrees {
                     #[cfg(any(feature = "encryption", feature = "compression"))]
                     vault.clone(),
                 );
+                if view.cache_reductions() {
+                    self.open_tree::<Unversioned>(
+                        &view_reductions_tree_name(&view_name),
+                        #[cfg(any(feature = "encryption", feature = "compression"))]
+                        vault.clone(),
+                    );
+                }
             } else {
                 self.open_tree::<Unversioned>(
                     &view_invalidated_docs_tree_name(&view_name),
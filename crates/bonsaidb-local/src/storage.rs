@@ -37,7 +37,8 @@ use rand::{thread_rng, Rng};
 
 #[cfg(feature = "compression")]
 use crate::config::Compression;
-use crate::config::{KeyValuePersistence, StorageConfiguration};
+use crate::config::{KeyValueEviction, KeyValuePersistence, StorageConfiguration};
+use crate::database::keyvalue::KeyChangeNotifier;
 use crate::database::Context;
 use crate::tasks::manager::Manager;
 use crate::tasks::TaskManager;
@@ -51,8 +52,9 @@ mod argon;
 mod token_authentication;
 
 mod backup;
+mod migrations;
 mod pubsub;
-pub use backup::{AnyBackupLocation, BackupLocation};
+pub use backup::{AnyBackupLocation, BackupArchive, BackupLocation, RestorePoint};
 
 /// A file-based, multi-database, multi-user database engine. This type blocks
 /// the current thread when used. See [`AsyncStorage`](crate::AsyncStorage) for
@@ -244,6 +246,7 @@ struct Data {
     schemas: RwLock<HashMap<SchemaName, Arc<dyn DatabaseOpener>>>,
     available_databases: RwLock<HashMap<String, SchemaName>>,
     open_roots: Mutex<HashMap<String, Context>>,
+    migration_lock: Mutex<()>,
     // cfg check matches `Connection::authenticate`
     authenticated_permissions: Permissions,
     sessions: RwLock<AuthenticatedSessions>,
@@ -257,6 +260,8 @@ struct Data {
     #[cfg(any(feature = "compression", feature = "encryption"))]
     tree_vault: Option<TreeVault>,
     pub(crate) key_value_persistence: KeyValuePersistence,
+    key_value_notifications: bool,
+    key_value_eviction: Option<KeyValueEviction>,
     chunk_cache: ChunkCache,
     pub(crate) check_view_integrity_on_database_open: bool,
     relay: Relay,
@@ -305,6 +310,8 @@ impl Storage {
         let parallelization = configuration.workers.parallelization;
         let check_view_integrity_on_database_open = configuration.views.check_integrity_on_open;
         let key_value_persistence = configuration.key_value_persistence;
+        let key_value_notifications = configuration.key_value_notifications;
+        let key_value_eviction = configuration.key_value_eviction;
         #[cfg(feature = "password-hashing")]
         let argon = argon::Hasher::new(configuration.argon);
         #[cfg(feature = "encryption")]
@@ -346,7 +353,10 @@ impl Storage {
                     schemas: RwLock::new(configuration.initial_schemas),
                     available_databases: RwLock::default(),
                     open_roots: Mutex::default(),
+                    migration_lock: Mutex::default(),
                     key_value_persistence,
+                    key_value_notifications,
+                    key_value_eviction,
                     check_view_integrity_on_database_open,
                     relay: Relay::default(),
                 }),
@@ -359,6 +369,8 @@ impl Storage {
 
         storage.create_admin_database_if_needed()?;
 
+        storage.instance.migrate_databases()?;
+
         Ok(storage)
     }
 
@@ -613,9 +625,15 @@ impl StorageInstance {
             }
 
             let roots = config.open().map_err(Error::from)?;
+            let key_change_notifier = self
+                .data
+                .key_value_notifications
+                .then(|| KeyChangeNotifier::new(self.relay().clone(), name));
             let context = Context::new(
                 roots,
                 self.data.key_value_persistence.clone(),
+                self.data.key_value_eviction,
+                key_change_notifier,
                 Some(self.data.lock.clone()),
             );
 
@@ -940,12 +958,13 @@ impl StorageConnection for StorageInstance {
     ) -> Result<(), bonsaidb_core::Error> {
         Storage::validate_name(name)?;
 
-        {
+        let collection_versions = {
             let schemas = self.data.schemas.read();
-            if !schemas.contains_key(&schema) {
+            let Some(opener) = schemas.get(&schema) else {
                 return Err(bonsaidb_core::Error::SchemaNotRegistered(schema));
-            }
-        }
+            };
+            migrations::collection_versions(opener.schematic())
+        };
 
         let mut available_databases = self.data.available_databases.write();
         let admin = self.admin();
@@ -955,9 +974,13 @@ impl StorageConnection for StorageInstance {
                 .push(&admin::Database {
                     name: name.to_string(),
                     schema: schema.clone(),
+                    collection_versions,
                 })?;
             available_databases.insert(name.to_string(), schema);
-        } else if !only_if_needed {
+        } else if only_if_needed {
+            drop(available_databases);
+            self.migrate_database(name)?;
+        } else {
             return Err(bonsaidb_core::Error::DatabaseNameAlreadyTaken(
                 name.to_string(),
             ));
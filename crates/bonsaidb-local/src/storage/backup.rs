@@ -1,17 +1,26 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::DirEntry;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
-use bonsaidb_core::connection::{LowLevelConnection, Range, Sort, StorageConnection};
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::connection::{Connection, LowLevelConnection, Range, Sort, StorageConnection};
 use bonsaidb_core::document::DocumentId;
-use bonsaidb_core::schema::{Collection, Qualified, SchemaName};
-use bonsaidb_core::transaction::{Operation, Transaction};
+use bonsaidb_core::keyvalue::{Command as KeyCommand, KeyOperation, KeyValue, Timestamp};
+use bonsaidb_core::limits::LIST_TRANSACTIONS_MAX_RESULTS;
+use bonsaidb_core::schema::{Collection, CollectionName, Qualified, SchemaName};
+use bonsaidb_core::transaction::{Changes, Operation, Transaction};
 use bonsaidb_core::{admin, AnyError};
+use serde::{Deserialize, Serialize};
 
 use crate::database::keyvalue::Entry;
 use crate::database::DatabaseNonBlocking;
 use crate::{Database, Error, Storage};
 
+mod archive;
+
+pub use self::archive::BackupArchive;
+
 /// A location to store and restore a database from.
 pub trait BackupLocation: Send + Sync {
     /// The error type for the backup location.
@@ -51,20 +60,54 @@ pub trait BackupLocation: Send + Sync {
     ) -> Result<Vec<u8>, Self::Error>;
 }
 
+/// The container used to store the transaction log information of a backup.
+const TRANSACTIONS_CONTAINER: &str = "_transactions";
+/// The name of the object storing the [`BackupManifest`] of the full backup.
+const FULL_BACKUP_MANIFEST: &str = "full";
+/// The container used to store the key-value entries of a full backup.
+const KEY_VALUE_CONTAINER: &str = "_kv";
+
+/// A point in time to restore a backup to using
+/// [`Storage::restore_to()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RestorePoint {
+    /// Restores the state of the database from before the transaction with
+    /// this id was executed.
+    BeforeTransaction(u64),
+    /// Restores the state of the database at this time.
+    At(Timestamp),
+}
+
+impl RestorePoint {
+    /// Returns true if a backup containing the transactions up to
+    /// `transaction_id` that was taken at `timestamp` can be restored without
+    /// going past this restore point.
+    fn includes(self, transaction_id: Option<u64>, timestamp: Timestamp) -> bool {
+        match self {
+            Self::BeforeTransaction(before) => transaction_id.map_or(true, |id| id < before),
+            Self::At(at) => timestamp <= at,
+        }
+    }
+}
+
+impl From<Timestamp> for RestorePoint {
+    fn from(timestamp: Timestamp) -> Self {
+        Self::At(timestamp)
+    }
+}
+
 impl Storage {
     /// Stores a copy of all data in this instance to `location`.
+    ///
+    /// Each database is copied as of a single transaction: transactions that
+    /// modify a database's documents wait until its backup has been stored.
+    ///
+    /// If `location` already contains a backup of a database, the new backup
+    /// is stored separately from the previous one and replaces it once it has
+    /// been completely stored. Restoring never combines the contents of two
+    /// full backups.
     pub fn backup<L: AnyBackupLocation>(&self, location: &L) -> Result<(), Error> {
-        let databases = {
-            self.instance
-                .data
-                .available_databases
-                .read()
-                .keys()
-                .cloned()
-                .collect::<Vec<_>>()
-        };
-
-        for name in databases {
+        for name in self.available_database_names() {
             let database = self
                 .instance
                 .database_without_schema(&name, Some(self), None)?;
@@ -74,7 +117,55 @@ impl Storage {
         Ok(())
     }
 
-    /// Restores all data from a previously stored backup `location`.
+    /// Stores the changes made since the last backup stored in `location`.
+    ///
+    /// The id of the last transaction included in each backup is recorded in
+    /// `location`. Incremental backups use the transaction log to only store
+    /// the documents and keys changed by the transactions executed since the
+    /// previous backup. If `location` does not contain a backup of a
+    /// database, a full backup of that database is stored instead.
+    ///
+    /// Key-value changes that have not been persisted yet are included in
+    /// the incremental backup if any transactions have been executed since the
+    /// previous backup. Otherwise, they are included once they are persisted.
+    ///
+    /// Just like [`backup()`](Self::backup), transactions that modify a
+    /// database's documents wait until its incremental backup has been read.
+    ///
+    /// [`restore()`](Self::restore) restores the full backup followed by each
+    /// incremental backup in the order they were taken.
+    pub fn backup_incremental<L: AnyBackupLocation>(&self, location: &L) -> Result<(), Error> {
+        for name in self.available_database_names() {
+            let database = self
+                .instance
+                .database_without_schema(&name, Some(self), None)?;
+            let schema = database.schematic().name.clone();
+            match last_backed_up_transaction(&schema, &name, location)? {
+                Some(after) => Self::backup_database_incrementally(&database, after, location)?,
+                None => Self::backup_database(&database, location)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn available_database_names(&self) -> Vec<String> {
+        self.instance
+            .data
+            .available_databases
+            .read()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Restores all data from a previously stored backup `location`. If
+    /// `location` contains incremental backups, they are restored after the
+    /// full backup in the order they were taken.
+    ///
+    /// An error is returned if a database being restored already contains a
+    /// document stored in the backup. Use [`restore_to()`](Self::restore_to)
+    /// to replace the contents of an existing database.
     pub fn restore<L: AnyBackupLocation>(&self, location: &L) -> Result<(), Error> {
         for schema in location
             .list_schemas()
@@ -84,59 +175,263 @@ impl Storage {
                 .list_databases(&schema)
                 .map_err(|err| Error::Backup(Box::new(err)))?
             {
-                // The admin database is already going to be created by the process of creating a database.
-                self.create_database_with_schema(&database, schema.clone(), true)?;
-
-                let database =
-                    self.instance
-                        .database_without_schema(&database, Some(self), None)?;
-                Self::restore_database(&database, location)?;
+                self.restore_database_from(location, schema.clone(), &database, None)?;
             }
         }
 
         Ok(())
     }
 
+    /// Restores the database named `database_name` from a previously stored
+    /// backup `location` to the state it was in at `point`. Because each
+    /// database has its own transaction log, a
+    /// [`RestorePoint::BeforeTransaction`] refers to a transaction executed
+    /// by this database.
+    ///
+    /// The full backup is restored followed by each incremental backup that
+    /// does not go past `point`. If the database already exists, its
+    /// documents and keys are replaced with the restored state.
+    ///
+    /// Backups only capture the state of the database after their last
+    /// transaction. A [`RestorePoint::BeforeTransaction`] is only available
+    /// if no transactions were executed between the last transaction of a
+    /// backup and the requested transaction. A [`RestorePoint::At`] restores
+    /// the state captured by the most recent backup taken at or before the
+    /// timestamp. Because a full backup replaces the previous one, only
+    /// restore points after the most recent full backup stored in `location`
+    /// are available. [`Error::RestorePointUnavailable`] is returned if the
+    /// backups can't restore `point`, and the database is left unmodified.
+    ///
+    /// ```rust
+    /// # use bonsaidb_local::{RestorePoint, Storage};
+    /// # fn test(storage: &Storage, location: &std::path::PathBuf) -> Result<(), bonsaidb_local::Error> {
+    /// // Restore the state from before transaction 42 was executed.
+    /// storage.restore_to(location, "my-database", RestorePoint::BeforeTransaction(42))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_to<L: AnyBackupLocation>(
+        &self,
+        location: &L,
+        database_name: &str,
+        point: impl Into<RestorePoint>,
+    ) -> Result<(), Error> {
+        for schema in location
+            .list_schemas()
+            .map_err(|err| Error::Backup(Box::new(err)))?
+        {
+            if location
+                .list_databases(&schema)
+                .map_err(|err| Error::Backup(Box::new(err)))?
+                .iter()
+                .any(|database| database == database_name)
+            {
+                return self.restore_database_from(
+                    location,
+                    schema,
+                    database_name,
+                    Some(point.into()),
+                );
+            }
+        }
+
+        Err(Error::Core(bonsaidb_core::Error::DatabaseNotFound(
+            database_name.to_string(),
+        )))
+    }
+
+    fn restore_database_from(
+        &self,
+        location: &dyn AnyBackupLocation,
+        schema: SchemaName,
+        database_name: &str,
+        point: Option<RestorePoint>,
+    ) -> Result<(), Error> {
+        // Every backup that will be restored is loaded and verified before
+        // the database is modified.
+        let plan = RestorePlan::load(&schema, database_name, location, point)?;
+
+        // The admin database is already going to be created by the process of creating a database.
+        self.create_database_with_schema(database_name, schema, true)?;
+
+        let database = self
+            .instance
+            .database_without_schema(database_name, Some(self), None)?;
+        // Only restoring to a point in time replaces the existing contents of
+        // the database.
+        Self::restore_database(&database, location, plan, point.is_some())
+    }
+
     pub(crate) fn backup_database(
         database: &Database,
         location: &dyn AnyBackupLocation,
     ) -> Result<(), Error> {
         let schema = database.schematic().name.clone();
-        for collection in database.schematic().collections() {
-            let documents = database.list_from_collection(
-                Range::from(..),
-                Sort::Ascending,
-                None,
-                collection,
-            )?;
-            let collection_name = collection.encoded();
-            // TODO consider how to best parallelize -- perhaps a location can opt into parallelization?
-            for document in documents {
-                location.store(
-                    &schema,
-                    database.name(),
-                    &collection_name,
-                    &document.header.id.to_string(),
-                    &document.contents,
+        // Each full backup is stored in its own containers. Previous full
+        // backups are left in place, but they are no longer restored once the
+        // manifest pointing to this backup has been stored.
+        let generation = full_backup_manifest(&schema, database.name(), location)?
+            .map_or(0, |manifest| manifest.generation + 1);
+        // The documents are copied while no transactions can modify them,
+        // ensuring the backup contains exactly the changes made by the
+        // transactions up to the one recorded in the manifest. Keys that have
+        // not been persisted yet have no transaction id. Their current values
+        // are stored, and they are included again by the first incremental
+        // backup taken after they are persisted.
+        let manifest = database.with_consistent_snapshot(|transaction_id| {
+            let manifest = BackupManifest {
+                generation,
+                transaction_id,
+                timestamp: Timestamp::now(),
+            };
+            for collection in database.schematic().collections() {
+                let documents = database.list_from_collection(
+                    Range::from(..),
+                    Sort::Ascending,
+                    None,
+                    None,
+                    collection,
                 )?;
+                let container = full_backup_container(Some(generation), &collection.encoded());
+                // TODO consider how to best parallelize -- perhaps a location can opt into parallelization?
+                for document in documents {
+                    location.store(
+                        &schema,
+                        database.name(),
+                        &container,
+                        &document.header.id.to_string(),
+                        &document.contents,
+                    )?;
+                }
             }
+            let key_value_container = full_backup_container(Some(generation), KEY_VALUE_CONTAINER);
             for ((namespace, key), entry) in database.all_key_value_entries()? {
                 let full_name = format!("{}._key._{key}", namespace.as_deref().unwrap_or(""));
                 location.store(
                     &schema,
                     database.name(),
-                    "_kv",
+                    &key_value_container,
                     &full_name,
                     &pot::to_vec(&entry)?,
                 )?;
             }
+            Ok(manifest)
+        })?;
+        location.store(
+            &schema,
+            database.name(),
+            TRANSACTIONS_CONTAINER,
+            FULL_BACKUP_MANIFEST,
+            &pot::to_vec(&manifest)?,
+        )?;
+        Ok(())
+    }
+
+    fn backup_database_incrementally(
+        database: &Database,
+        after: Option<u64>,
+        location: &dyn AnyBackupLocation,
+    ) -> Result<(), Error> {
+        let timestamp = Timestamp::now();
+        // The transaction log is read and the changed documents are copied
+        // while no transactions can modify them, ensuring each copied
+        // document is the revision written by the last transaction included
+        // in this backup.
+        let backup = database.with_consistent_snapshot(|_| {
+            let mut changed_keys = database.unpersisted_keys();
+            let mut changed_documents = BTreeSet::new();
+            let mut first_transaction_id = None;
+            let mut last_transaction_id = None;
+            loop {
+                let starting_id = last_transaction_id.or(after).map_or(0, |id| id + 1);
+                let executed = database.list_executed_transactions(
+                    Some(starting_id),
+                    Some(LIST_TRANSACTIONS_MAX_RESULTS),
+                )?;
+                let Some(last) = executed.last() else {
+                    break;
+                };
+                last_transaction_id = Some(last.id);
+                first_transaction_id =
+                    first_transaction_id.or_else(|| executed.first().map(|first| first.id));
+                for transaction in executed {
+                    match transaction.changes {
+                        Changes::Documents(changes) => {
+                            for document in changes.documents {
+                                changed_documents.insert((
+                                    changes.collections[usize::from(document.collection)].clone(),
+                                    document.id,
+                                ));
+                            }
+                        }
+                        Changes::Keys(keys) => {
+                            changed_keys
+                                .extend(keys.into_iter().map(|key| (key.namespace, key.key)));
+                        }
+                    }
+                }
+            }
+
+            let (Some(first_transaction_id), Some(transaction_id)) =
+                (first_transaction_id, last_transaction_id)
+            else {
+                // No transactions have been executed since the last backup.
+                return Ok(None);
+            };
+
+            let mut documents = Vec::with_capacity(changed_documents.len());
+            for (collection, id) in changed_documents {
+                let contents = database
+                    .get_from_collection(id.clone(), &collection)?
+                    .map(|document| document.contents);
+                documents.push(BackedUpDocument {
+                    collection,
+                    id,
+                    contents,
+                });
+            }
+
+            let mut keys = Vec::with_capacity(changed_keys.len());
+            if !changed_keys.is_empty() {
+                let mut entries = database.all_key_value_entries()?;
+                for (namespace, key) in changed_keys {
+                    let entry = entries.remove(&(namespace.clone(), key.clone()));
+                    keys.push(BackedUpKey {
+                        namespace,
+                        key,
+                        entry,
+                    });
+                }
+            }
+
+            Ok(Some(IncrementalBackup {
+                after,
+                first_transaction_id,
+                transaction_id,
+                timestamp,
+                documents,
+                keys,
+            }))
+        })?;
+
+        if let Some(backup) = backup {
+            location.store(
+                &database.schematic().name,
+                database.name(),
+                TRANSACTIONS_CONTAINER,
+                &incremental_backup_name(backup.transaction_id),
+                &pot::to_vec(&backup)?,
+            )?;
         }
+
         Ok(())
     }
 
     pub(crate) fn restore_database(
         database: &Database,
         location: &dyn AnyBackupLocation,
+        plan: RestorePlan,
+        overwrite: bool,
     ) -> Result<(), Error> {
         let schema = database.schematic().name.clone();
         let mut transaction = Transaction::new();
@@ -149,9 +444,28 @@ impl Storage {
             .collections()
             .filter(|c| *c != &database_collection)
         {
-            let collection_name = collection.encoded();
+            // When overwriting, documents that aren't in the backup are
+            // deleted, and the backed up documents overwrite any existing
+            // documents. Otherwise, the backed up documents are inserted,
+            // which fails if the database already contains them.
+            let mut existing = if overwrite {
+                database
+                    .list_headers_from_collection(
+                        Range::from(..),
+                        Sort::Ascending,
+                        None,
+                        None,
+                        collection,
+                    )?
+                    .into_iter()
+                    .map(|header| (header.id.clone(), header))
+                    .collect::<BTreeMap<_, _>>()
+            } else {
+                BTreeMap::new()
+            };
+            let container = full_backup_container(plan.generation, &collection.encoded());
             for (id, id_string) in location
-                .list_stored(&schema, database.name(), &collection_name)?
+                .list_stored(&schema, database.name(), &container)?
                 .into_iter()
                 .filter_map(|id_string| {
                     id_string
@@ -160,30 +474,315 @@ impl Storage {
                         .map(|id| (id, id_string))
                 })
             {
-                let contents =
-                    location.load(&schema, database.name(), &collection_name, &id_string)?;
-                transaction.push(Operation::insert(collection.clone(), Some(id), contents));
+                let contents = location.load(&schema, database.name(), &container, &id_string)?;
+                if overwrite {
+                    existing.remove(&id);
+                    transaction.push(Operation::overwrite(collection.clone(), id, contents));
+                } else {
+                    transaction.push(Operation::insert(collection.clone(), Some(id), contents));
+                }
+            }
+            for header in existing.into_values() {
+                transaction.push(Operation::delete(collection.clone(), header));
             }
         }
-        database.apply_transaction(transaction)?;
+        if !transaction.operations.is_empty() {
+            database.apply_transaction(transaction)?;
+        }
 
-        for full_key in location.list_stored(&schema, database.name(), "_kv")? {
+        let key_value_container = full_backup_container(plan.generation, KEY_VALUE_CONTAINER);
+        let mut existing_keys = if overwrite {
+            database.all_key_value_entries()?
+        } else {
+            BTreeMap::new()
+        };
+        for full_key in location.list_stored(&schema, database.name(), &key_value_container)? {
             if let Some((namespace, key)) = full_key.split_once("._key._") {
-                let entry = location.load(&schema, database.name(), "_kv", &full_key)?;
+                let entry =
+                    location.load(&schema, database.name(), &key_value_container, &full_key)?;
                 let entry = pot::from_slice::<Entry>(&entry)?;
                 let namespace = if namespace.is_empty() {
                     None
                 } else {
                     Some(namespace.to_string())
                 };
+                existing_keys.remove(&(namespace.clone(), key.to_string()));
                 entry.restore(namespace, key.to_string(), database)?;
             }
         }
+        for (namespace, key) in existing_keys.into_keys() {
+            database.execute_key_operation(KeyOperation {
+                namespace,
+                key,
+                command: KeyCommand::Delete,
+            })?;
+        }
+
+        Self::restore_incremental_backups(database, plan.incremental)
+    }
+
+    fn restore_incremental_backups(
+        database: &Database,
+        backups: Vec<IncrementalBackup>,
+    ) -> Result<(), Error> {
+        let database_collection = admin::Database::collection_name();
+        for backup in backups {
+            let mut transaction = Transaction::new();
+            for document in backup.documents {
+                if document.collection == database_collection {
+                    continue;
+                }
+
+                match document.contents {
+                    Some(contents) => transaction.push(Operation::overwrite(
+                        document.collection,
+                        document.id,
+                        contents,
+                    )),
+                    None => {
+                        if let Some(existing) =
+                            database.get_from_collection(document.id, &document.collection)?
+                        {
+                            transaction
+                                .push(Operation::delete(document.collection, existing.header));
+                        }
+                    }
+                }
+            }
+            if !transaction.operations.is_empty() {
+                database.apply_transaction(transaction)?;
+            }
+
+            for key in backup.keys {
+                match key.entry {
+                    Some(entry) => entry.restore(key.namespace, key.key, database)?,
+                    None => {
+                        database.execute_key_operation(KeyOperation {
+                            namespace: key.namespace,
+                            key: key.key,
+                            command: KeyCommand::Delete,
+                        })?;
+                    }
+                }
+            }
+        }
 
         Ok(())
     }
 }
 
+/// Information about a full backup.
+#[derive(Serialize, Deserialize)]
+struct BackupManifest {
+    /// Identifies the containers the backup is stored in. Each full backup of
+    /// a database uses the next generation.
+    generation: u64,
+    /// The id of the last transaction included in the backup, or `None` if
+    /// the database had not executed any transactions.
+    transaction_id: Option<u64>,
+    /// When the backup was taken.
+    timestamp: Timestamp,
+}
+
+/// The changes made to a database by the transactions executed after the
+/// previous backup.
+#[derive(Serialize, Deserialize)]
+struct IncrementalBackup {
+    /// The id of the last transaction included in the previous backup.
+    after: Option<u64>,
+    /// The id of the first transaction included in this backup.
+    first_transaction_id: u64,
+    /// The id of the last transaction included in this backup.
+    transaction_id: u64,
+    /// When the backup was taken.
+    timestamp: Timestamp,
+    documents: Vec<BackedUpDocument>,
+    keys: Vec<BackedUpKey>,
+}
+
+/// The backups that restore a database to a [`RestorePoint`].
+struct RestorePlan {
+    /// The generation of the full backup to restore, or `None` if the backup
+    /// was stored before manifests were recorded.
+    generation: Option<u64>,
+    /// The incremental backups to restore after the full backup, in the
+    /// order they were taken.
+    incremental: Vec<IncrementalBackup>,
+}
+
+impl RestorePlan {
+    fn load(
+        schema: &SchemaName,
+        database_name: &str,
+        location: &dyn AnyBackupLocation,
+        point: Option<RestorePoint>,
+    ) -> Result<Self, Error> {
+        let unavailable = || Error::RestorePointUnavailable {
+            database: database_name.to_string(),
+        };
+        let Some(manifest) = full_backup_manifest(schema, database_name, location)? else {
+            if point.is_some() {
+                return Err(unavailable());
+            }
+            // Backups stored before manifests were recorded only contain a
+            // full backup, which is stored without a generation.
+            return Ok(Self {
+                generation: None,
+                incremental: Vec::new(),
+            });
+        };
+        if let Some(point) = point {
+            if !point.includes(manifest.transaction_id, manifest.timestamp) {
+                return Err(unavailable());
+            }
+        }
+
+        let mut incremental = Vec::new();
+        let mut last_transaction_id = manifest.transaction_id;
+        let mut next_transaction_id = None;
+        for transaction_id in incremental_backup_ids(schema, database_name, location)? {
+            if last_transaction_id.map_or(false, |last| transaction_id <= last) {
+                // This incremental backup was superseded by a newer full
+                // backup.
+                continue;
+            }
+
+            let backup = pot::from_slice::<IncrementalBackup>(&location.load(
+                schema,
+                database_name,
+                TRANSACTIONS_CONTAINER,
+                &incremental_backup_name(transaction_id),
+            )?)?;
+            let included = point.map_or(true, |point| {
+                point.includes(Some(backup.transaction_id), backup.timestamp)
+            });
+            if backup.after != last_transaction_id {
+                if !included {
+                    // The restore point can't be verified to precede this
+                    // backup's first transaction.
+                    break;
+                }
+                return Err(Error::IncompleteBackup {
+                    database: database_name.to_string(),
+                    after: last_transaction_id,
+                });
+            }
+            if !included {
+                next_transaction_id = Some(backup.first_transaction_id);
+                break;
+            }
+
+            last_transaction_id = Some(backup.transaction_id);
+            incremental.push(backup);
+        }
+
+        if let Some(RestorePoint::BeforeTransaction(before)) = point {
+            // The restored state is the state after the last restored
+            // transaction. It is only the state before `before` if no other
+            // transactions were executed in between.
+            let captured = next_transaction_id == Some(before)
+                || last_transaction_id.map_or(false, |last| last + 1 == before);
+            if !captured {
+                return Err(unavailable());
+            }
+        }
+
+        Ok(Self {
+            generation: Some(manifest.generation),
+            incremental,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackedUpDocument {
+    collection: CollectionName,
+    id: DocumentId,
+    /// The contents of the document, or `None` if it was deleted.
+    contents: Option<Bytes>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackedUpKey {
+    namespace: Option<String>,
+    key: String,
+    /// The stored entry, or `None` if the key was deleted.
+    entry: Option<Entry>,
+}
+
+/// Returns the name of the container storing `container` of the full backup
+/// with `generation`.
+fn full_backup_container(generation: Option<u64>, container: &str) -> String {
+    match generation {
+        Some(generation) => format!("_full.{generation}.{container}"),
+        None => container.to_string(),
+    }
+}
+
+fn incremental_backup_name(transaction_id: u64) -> String {
+    // Zero-padding keeps the names sorted in the order the backups were taken.
+    format!("{transaction_id:020}")
+}
+
+/// Returns the transaction ids of all incremental backups of `database_name`
+/// stored in `location`, in ascending order.
+fn incremental_backup_ids(
+    schema: &SchemaName,
+    database_name: &str,
+    location: &dyn AnyBackupLocation,
+) -> Result<Vec<u64>, Error> {
+    let mut ids = location
+        .list_stored(schema, database_name, TRANSACTIONS_CONTAINER)?
+        .into_iter()
+        .filter_map(|name| name.parse::<u64>().ok())
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Returns the manifest of the full backup of `database_name`, if one is
+/// present.
+fn full_backup_manifest(
+    schema: &SchemaName,
+    database_name: &str,
+    location: &dyn AnyBackupLocation,
+) -> Result<Option<BackupManifest>, Error> {
+    if location
+        .list_stored(schema, database_name, TRANSACTIONS_CONTAINER)?
+        .iter()
+        .any(|name| name == FULL_BACKUP_MANIFEST)
+    {
+        let manifest = location.load(
+            schema,
+            database_name,
+            TRANSACTIONS_CONTAINER,
+            FULL_BACKUP_MANIFEST,
+        )?;
+        Ok(Some(pot::from_slice(&manifest)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the id of the last transaction stored in any backup of
+/// `database_name`. Returns `None` if no full backup is present, and
+/// `Some(None)` if the database had not executed any transactions when the
+/// backups were taken.
+#[allow(clippy::option_option)]
+fn last_backed_up_transaction(
+    schema: &SchemaName,
+    database_name: &str,
+    location: &dyn AnyBackupLocation,
+) -> Result<Option<Option<u64>>, Error> {
+    let Some(manifest) = full_backup_manifest(schema, database_name, location)? else {
+        return Ok(None);
+    };
+    let last_incremental = incremental_backup_ids(schema, database_name, location)?
+        .last()
+        .copied();
+    Ok(Some(manifest.transaction_id.max(last_incremental)))
+}
+
 pub trait AnyBackupLocation: Send + Sync {
     fn store(
         &self,
@@ -441,12 +1040,12 @@ fn container_folder(
 #[cfg(test)]
 mod tests {
     use bonsaidb_core::connection::{Connection as _, StorageConnection as _};
-    use bonsaidb_core::keyvalue::KeyValue;
+    use bonsaidb_core::keyvalue::{KeyValue, Timestamp};
     use bonsaidb_core::schema::SerializedCollection;
     use bonsaidb_core::test_util::{Basic, TestDirectory};
 
     use crate::config::{Builder, KeyValuePersistence, PersistenceThreshold, StorageConfiguration};
-    use crate::Storage;
+    use crate::{RestorePoint, Storage};
 
     #[test]
     fn backup_restore() -> anyhow::Result<()> {
@@ -495,4 +1094,169 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn repeated_full_backup_restore() -> anyhow::Result<()> {
+        let backup_destination = TestDirectory::new("repeated-full-backup-restore.bonsaidb.backup");
+
+        let (kept, deleted) = {
+            let database_directory = TestDirectory::new("repeated-full-backup-restore.bonsaidb");
+            let storage = Storage::open(
+                StorageConfiguration::new(&database_directory).with_schema::<Basic>()?,
+            )?;
+            let db = storage.create_database::<Basic>("basic", false)?;
+
+            let kept = Basic::new("kept").push_into(&db)?;
+            let deleted = Basic::new("deleted").push_into(&db)?;
+            db.set_numeric_key("deleted", 1_u64).execute()?;
+            storage.backup(&backup_destination.0)?;
+
+            // The second full backup must not include the documents and keys
+            // deleted since the first full backup.
+            deleted.delete(&db)?;
+            db.delete_key("deleted")?;
+            storage.backup(&backup_destination.0)?;
+
+            (kept, deleted)
+        };
+
+        let database_directory = TestDirectory::new("repeated-full-backup-restore.bonsaidb");
+        let restored_storage =
+            Storage::open(StorageConfiguration::new(&database_directory).with_schema::<Basic>()?)?;
+        restored_storage.restore(&backup_destination.0)?;
+
+        let db = restored_storage.database::<Basic>("basic")?;
+        let doc = Basic::get(&kept.header.id, &db)?.expect("kept document not found");
+        assert_eq!(doc.contents.value, "kept");
+        assert!(Basic::get(&deleted.header.id, &db)?.is_none());
+        assert_eq!(db.get_key("deleted").into_u64()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn incremental_backup_restore() -> anyhow::Result<()> {
+        let backup_destination = TestDirectory::new("incremental-backup-restore.bonsaidb.backup");
+
+        let (updated, deleted, added) = {
+            let database_directory = TestDirectory::new("incremental-backup-restore.bonsaidb");
+            let storage = Storage::open(
+                StorageConfiguration::new(&database_directory).with_schema::<Basic>()?,
+            )?;
+            let db = storage.create_database::<Basic>("basic", false)?;
+
+            // Without an existing backup, a full backup is stored.
+            let mut updated = Basic::new("original").push_into(&db)?;
+            let deleted = Basic::new("deleted").push_into(&db)?;
+            db.set_numeric_key("deleted", 1_u64).execute()?;
+            storage.backup_incremental(&backup_destination.0)?;
+
+            updated.contents.value = String::from("updated");
+            updated.update(&db)?;
+            db.set_numeric_key("added", 2_u64).execute()?;
+            storage.backup_incremental(&backup_destination.0)?;
+
+            deleted.delete(&db)?;
+            db.delete_key("deleted")?;
+            let added = Basic::new("added").push_into(&db)?;
+            storage.backup_incremental(&backup_destination.0)?;
+            // Backing up again only stores the changes made since the
+            // previous backup.
+            storage.backup_incremental(&backup_destination.0)?;
+
+            (updated, deleted, added)
+        };
+
+        let database_directory = TestDirectory::new("incremental-backup-restore.bonsaidb");
+        let restored_storage =
+            Storage::open(StorageConfiguration::new(&database_directory).with_schema::<Basic>()?)?;
+        restored_storage.restore(&backup_destination.0)?;
+
+        let db = restored_storage.database::<Basic>("basic")?;
+        let doc = Basic::get(&updated.header.id, &db)?.expect("updated document not found");
+        assert_eq!(doc.contents.value, "updated");
+        assert!(Basic::get(&deleted.header.id, &db)?.is_none());
+        let doc = Basic::get(&added.header.id, &db)?.expect("added document not found");
+        assert_eq!(doc.contents.value, "added");
+        assert_eq!(db.get_key("deleted").into_u64()?, None);
+        assert_eq!(db.get_key("added").into_u64()?, Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_in_time_restore() -> anyhow::Result<()> {
+        let backup_destination = TestDirectory::new("point-in-time-restore.bonsaidb.backup");
+
+        let (first, second, first_delete) = {
+            let database_directory = TestDirectory::new("point-in-time-restore.bonsaidb");
+            let storage = Storage::open(
+                StorageConfiguration::new(&database_directory).with_schema::<Basic>()?,
+            )?;
+            let db = storage.create_database::<Basic>("basic", false)?;
+
+            let first = Basic::new("first").push_into(&db)?;
+            storage.backup_incremental(&backup_destination.0)?;
+            let second = Basic::new("second").push_into(&db)?;
+            storage.backup_incremental(&backup_destination.0)?;
+
+            // Accidentally delete everything.
+            first.delete(&db)?;
+            let first_delete = db.last_transaction_id()?.unwrap();
+            second.delete(&db)?;
+            let second_delete = db.last_transaction_id()?.unwrap();
+            storage.backup_incremental(&backup_destination.0)?;
+
+            // Restoring into the existing database overwrites the current
+            // state of the database.
+            let third = Basic::new("third").push_into(&db)?;
+            storage.restore_to(
+                &backup_destination.0,
+                "basic",
+                RestorePoint::BeforeTransaction(first_delete),
+            )?;
+            let doc = Basic::get(&first.header.id, &db)?.expect("first document not found");
+            assert_eq!(doc.contents.value, "first");
+            let doc = Basic::get(&second.header.id, &db)?.expect("second document not found");
+            assert_eq!(doc.contents.value, "second");
+            assert!(Basic::get(&third.header.id, &db)?.is_none());
+
+            // The state before the second deletion was never captured by a
+            // backup, and the database is left unmodified.
+            assert!(matches!(
+                storage.restore_to(
+                    &backup_destination.0,
+                    "basic",
+                    RestorePoint::BeforeTransaction(second_delete),
+                ),
+                Err(crate::Error::RestorePointUnavailable { .. })
+            ));
+            assert!(Basic::get(&first.header.id, &db)?.is_some());
+            assert!(Basic::get(&second.header.id, &db)?.is_some());
+
+            (first, second, first_delete)
+        };
+
+        let database_directory = TestDirectory::new("point-in-time-restore.bonsaidb");
+        let restored_storage =
+            Storage::open(StorageConfiguration::new(&database_directory).with_schema::<Basic>()?)?;
+        // No backup was taken before the beginning of time.
+        assert!(matches!(
+            restored_storage.restore_to(&backup_destination.0, "basic", Timestamp::MIN),
+            Err(crate::Error::RestorePointUnavailable { .. })
+        ));
+
+        restored_storage.restore_to(
+            &backup_destination.0,
+            "basic",
+            RestorePoint::BeforeTransaction(first_delete),
+        )?;
+        let db = restored_storage.database::<Basic>("basic")?;
+        let doc = Basic::get(&first.header.id, &db)?.expect("first document not found");
+        assert_eq!(doc.contents.value, "first");
+        let doc = Basic::get(&second.header.id, &db)?.expect("second document not found");
+        assert_eq!(doc.contents.value, "second");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,885 @@
+#[cfg(feature = "compression")]
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "encryption")]
+use std::sync::Arc;
+
+#[cfg(feature = "encryption")]
+use bonsaidb_core::document::KeyId;
+use bonsaidb_core::schema::SchemaName;
+use itertools::Itertools;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "compression")]
+use crate::config::Compression;
+use crate::storage::BackupLocation;
+#[cfg(feature = "encryption")]
+use crate::vault::Vault;
+use crate::Error;
+#[cfg(feature = "encryption")]
+use crate::Storage;
+
+/// The bytes every archive begins with.
+const MAGIC: &[u8; 8] = b"bonsaiar";
+const FORMAT_VERSION: u8 = 1;
+/// The length of the archive's header: its magic bytes and format version.
+const ARCHIVE_HEADER_LENGTH: u64 = MAGIC.len() as u64 + 1;
+/// Set in an entry's flags when its contents are encrypted. The remaining bits
+/// contain the compression algorithm used, if any.
+const ENCRYPTED: u8 = 0b1000_0000;
+/// Set in an entry's flags when the entry contains the archive's index rather
+/// than a stored object.
+const INDEX: u8 = 0b0100_0000;
+/// The length of the trailer of an index entry: the length of the entire
+/// entry, which allows the index to be found from the end of the archive.
+const INDEX_TRAILER_LENGTH: usize = 8;
+/// The length of an entry's frame: the length of its body and the checksum
+/// of its body.
+const FRAME_LENGTH: u64 = 8 + 4;
+/// The length of the fixed-size fields of an entry's body: its flags and the
+/// length of its name.
+const BODY_HEADER_LENGTH: usize = 1 + 4;
+
+/// A [`BackupLocation`] that stores an entire backup in a single file.
+///
+/// Each object is appended to the archive as an entry containing its name and
+/// contents. Each entry is prefixed by its length and a CRC-32C checksum of
+/// its contents. Storing an object with the same name as an existing entry
+/// replaces it. If the file does not exist, it is created.
+///
+/// When the archive is compacted or dropped, an index of the current entries
+/// is appended to the archive. When the archive is first accessed, the index
+/// is read if it is the last entry of the archive. If the archive has no
+/// index, or its index fails verification, every entry is read and verified
+/// to rebuild it instead.
+///
+/// If the process stops while an entry is being written, the archive ends
+/// with an incomplete entry. The incomplete entry is ignored and removed when
+/// the archive is next opened. Any other entry that fails verification
+/// returns an error.
+///
+/// Because entries are only ever appended, replaced entries continue to use
+/// space in the file. [`BackupArchive::compact()`] rewrites the archive
+/// with only the current entries.
+///
+/// Entries can optionally be compressed and encrypted. Each entry records how
+/// it was stored, which allows archives containing a mix of entries to be
+/// restored.
+///
+/// ```rust
+/// # use bonsaidb_local::{BackupArchive, Storage};
+/// # fn test(storage: &Storage) -> Result<(), bonsaidb_local::Error> {
+/// storage.backup(&BackupArchive::new("backup.bonsaidb-archive"))?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BackupArchive {
+    path: PathBuf,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
+    #[cfg(feature = "encryption")]
+    encryption: Option<ArchiveEncryption>,
+    state: Mutex<Option<OpenArchive>>,
+}
+
+#[cfg(feature = "encryption")]
+struct ArchiveEncryption {
+    key: KeyId,
+    vault: Arc<Vault>,
+}
+
+struct OpenArchive {
+    file: File,
+    manifest: BTreeMap<EntryName, EntryLocation>,
+    /// True if the archive ends with an index of `manifest`.
+    indexed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct EntryName {
+    schema: SchemaName,
+    database: String,
+    container: String,
+    name: String,
+}
+
+/// The location of an entry's body within the archive.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct EntryLocation {
+    flags: u8,
+    offset: u64,
+    length: u64,
+    checksum: u32,
+    /// The offset of the contents within the body.
+    contents_offset: usize,
+}
+
+impl BackupArchive {
+    /// Returns an archive stored at `path`.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            #[cfg(feature = "compression")]
+            compression: None,
+            #[cfg(feature = "encryption")]
+            encryption: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Compresses newly stored entries using `compression`.
+    #[must_use]
+    #[cfg(feature = "compression")]
+    pub const fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Encrypts newly stored entries with `key` using the vault of `storage`.
+    /// Encrypted entries can only be read using the vault that encrypted
+    /// them, which requires restoring with access to the same master keys as
+    /// `storage`.
+    #[must_use]
+    #[cfg(feature = "encryption")]
+    pub fn encryption(mut self, key: KeyId, storage: &Storage) -> Self {
+        self.encryption = Some(ArchiveEncryption {
+            key,
+            vault: storage.vault().clone(),
+        });
+        self
+    }
+
+    /// Returns the path of the archive.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Rewrites the archive so that it only contains the current entry for
+    /// each name, reclaiming the space used by replaced entries.
+    ///
+    /// The compacted archive is written next to the archive and synchronized
+    /// to disk before it replaces the archive. If the process stops before
+    /// compaction completes, the original archive is left untouched.
+    pub fn compact(&self) -> Result<(), Error> {
+        let mut state = self.state.lock();
+        let mut archive = match state.take() {
+            Some(archive) => archive,
+            None => self.open()?,
+        };
+
+        let mut compacted_path = self.path.clone().into_os_string();
+        compacted_path.push(".compacting");
+        let compacted_path = PathBuf::from(compacted_path);
+        let mut compacted = BufWriter::new(File::create(&compacted_path)?);
+        compacted.write_all(MAGIC)?;
+        compacted.write_all(&[FORMAT_VERSION])?;
+
+        let mut manifest = BTreeMap::new();
+        let mut offset = ARCHIVE_HEADER_LENGTH;
+        for (name, location) in &archive.manifest {
+            // Entries are copied without being decoded, which allows archives
+            // containing entries stored with other settings to be compacted.
+            let body = read_body(&mut archive.file, location)?;
+            if crc32c::crc32c(&body) != location.checksum {
+                return Err(corrupt_entry());
+            }
+            compacted.write_all(&location.length.to_le_bytes())?;
+            compacted.write_all(&location.checksum.to_le_bytes())?;
+            compacted.write_all(&body)?;
+            manifest.insert(
+                name.clone(),
+                EntryLocation {
+                    offset: offset + FRAME_LENGTH,
+                    ..*location
+                },
+            );
+            offset += FRAME_LENGTH + location.length;
+        }
+        compacted.write_all(&self.index_entry(&manifest)?)?;
+        let compacted = compacted
+            .into_inner()
+            .map_err(std::io::IntoInnerError::into_error)?;
+        compacted.sync_all()?;
+        drop(compacted);
+        drop(archive);
+
+        std::fs::rename(&compacted_path, &self.path)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        *state = Some(OpenArchive {
+            file,
+            manifest,
+            indexed: true,
+        });
+        Ok(())
+    }
+
+    fn with_archive<R>(
+        &self,
+        callback: impl FnOnce(&mut OpenArchive) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        let mut state = self.state.lock();
+        if state.is_none() {
+            *state = Some(self.open()?);
+        }
+        callback(state.as_mut().expect("just opened"))
+    }
+
+    fn open(&self) -> Result<OpenArchive, Error> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+        let length = file.metadata()?.len();
+        let mut manifest = BTreeMap::new();
+        if length == 0 {
+            file.write_all(MAGIC)?;
+            file.write_all(&[FORMAT_VERSION])?;
+            return Ok(OpenArchive {
+                file,
+                manifest,
+                indexed: false,
+            });
+        }
+
+        let mut header = [0; MAGIC.len() + 1];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        if &header[..MAGIC.len()] != MAGIC || header[MAGIC.len()] != FORMAT_VERSION {
+            return Err(Error::other(
+                "backup archive",
+                "file is not a supported backup archive",
+            ));
+        }
+
+        if let Some(manifest) = self.read_index(&mut file, length)? {
+            return Ok(OpenArchive {
+                file,
+                manifest,
+                indexed: true,
+            });
+        }
+
+        file.seek(SeekFrom::Start(ARCHIVE_HEADER_LENGTH))?;
+        let mut reader = BufReader::new(&mut file);
+        let mut offset = ARCHIVE_HEADER_LENGTH;
+        while offset < length {
+            let remaining = length - offset;
+            let mut frame = [0; FRAME_LENGTH as usize];
+            let body_length = if remaining < FRAME_LENGTH {
+                None
+            } else {
+                reader.read_exact(&mut frame)?;
+                let body_length = u64::from_le_bytes(frame[..8].try_into().expect("8 bytes"));
+                Some(body_length).filter(|body_length| {
+                    FRAME_LENGTH
+                        .checked_add(*body_length)
+                        .map_or(false, |entry_length| entry_length <= remaining)
+                })
+            };
+            let Some(body_length) = body_length else {
+                // The last entry was not completely written.
+                break;
+            };
+            let checksum = u32::from_le_bytes(frame[8..].try_into().expect("4 bytes"));
+
+            let mut body = vec![
+                0;
+                usize::try_from(body_length)
+                    .map_err(|err| Error::other("backup archive", err))?
+            ];
+            reader.read_exact(&mut body)?;
+            if crc32c::crc32c(&body) != checksum {
+                if FRAME_LENGTH + body_length == remaining {
+                    // The last entry was not completely written.
+                    break;
+                }
+                return Err(corrupt_entry());
+            }
+
+            // Indexes appended when the archive was previously closed are
+            // superseded by the entries being read.
+            if body.first().map_or(true, |flags| flags & INDEX == 0) {
+                let (name, location) = self.parse_body(&body, offset + FRAME_LENGTH, checksum)?;
+                manifest.insert(name, location);
+            }
+            offset += FRAME_LENGTH + body_length;
+        }
+        drop(reader);
+
+        if offset < length {
+            // Remove the incomplete entry so that new entries are appended
+            // after the last complete entry.
+            file.set_len(offset)?;
+            file.sync_all()?;
+        }
+
+        Ok(OpenArchive {
+            file,
+            manifest,
+            indexed: false,
+        })
+    }
+
+    /// Reads the index at the end of the archive. Returns `None` if the
+    /// archive doesn't end with an index or the index fails verification.
+    fn read_index(
+        &self,
+        file: &mut File,
+        length: u64,
+    ) -> Result<Option<BTreeMap<EntryName, EntryLocation>>, Error> {
+        let minimum_length = FRAME_LENGTH + (BODY_HEADER_LENGTH + INDEX_TRAILER_LENGTH) as u64;
+        if length < ARCHIVE_HEADER_LENGTH + minimum_length {
+            return Ok(None);
+        }
+
+        let mut trailer = [0; INDEX_TRAILER_LENGTH];
+        file.seek(SeekFrom::Start(length - INDEX_TRAILER_LENGTH as u64))?;
+        file.read_exact(&mut trailer)?;
+        let entry_length = u64::from_le_bytes(trailer);
+        if entry_length < minimum_length || entry_length > length - ARCHIVE_HEADER_LENGTH {
+            return Ok(None);
+        }
+
+        let mut frame = [0; FRAME_LENGTH as usize];
+        file.seek(SeekFrom::Start(length - entry_length))?;
+        file.read_exact(&mut frame)?;
+        let body_length = u64::from_le_bytes(frame[..8].try_into().expect("8 bytes"));
+        let checksum = u32::from_le_bytes(frame[8..].try_into().expect("4 bytes"));
+        if body_length != entry_length - FRAME_LENGTH {
+            return Ok(None);
+        }
+        let location = EntryLocation {
+            flags: 0,
+            offset: length - body_length,
+            length: body_length,
+            checksum,
+            contents_offset: BODY_HEADER_LENGTH,
+        };
+        let body = read_body(file, &location)?;
+        if crc32c::crc32c(&body) != checksum || body[0] & INDEX == 0 {
+            return Ok(None);
+        }
+
+        let index = self.decode(
+            body[0] & !INDEX,
+            body[BODY_HEADER_LENGTH..body.len() - INDEX_TRAILER_LENGTH].to_vec(),
+        )?;
+        let index = pot::from_slice::<Vec<(EntryName, EntryLocation)>>(&index)?;
+        Ok(Some(index.into_iter().collect()))
+    }
+
+    /// Returns an entry containing the index of `manifest`.
+    fn index_entry(&self, manifest: &BTreeMap<EntryName, EntryLocation>) -> Result<Vec<u8>, Error> {
+        let index = self.encode(&pot::to_vec(&manifest.iter().collect::<Vec<_>>())?)?;
+        let entry_length =
+            FRAME_LENGTH as usize + BODY_HEADER_LENGTH + index.len() + INDEX_TRAILER_LENGTH;
+
+        let mut entry = Vec::with_capacity(entry_length);
+        entry.extend([0; FRAME_LENGTH as usize]);
+        entry.push(self.flags() | INDEX);
+        entry.extend(0_u32.to_le_bytes());
+        entry.extend(index);
+        entry.extend((entry_length as u64).to_le_bytes());
+        let body_length = (entry.len() - FRAME_LENGTH as usize) as u64;
+        let checksum = crc32c::crc32c(&entry[FRAME_LENGTH as usize..]);
+        entry[..8].copy_from_slice(&body_length.to_le_bytes());
+        entry[8..FRAME_LENGTH as usize].copy_from_slice(&checksum.to_le_bytes());
+        Ok(entry)
+    }
+
+    /// Appends an index of the archive's current entries.
+    fn write_index(&self, archive: &mut OpenArchive) -> Result<(), Error> {
+        let entry = self.index_entry(&archive.manifest)?;
+        archive.file.seek(SeekFrom::End(0))?;
+        archive.file.write_all(&entry)?;
+        archive.indexed = true;
+        Ok(())
+    }
+
+    fn parse_body(
+        &self,
+        body: &[u8],
+        offset: u64,
+        checksum: u32,
+    ) -> Result<(EntryName, EntryLocation), Error> {
+        if body.len() < BODY_HEADER_LENGTH {
+            return Err(corrupt_entry());
+        }
+        let flags = body[0];
+        let name_length =
+            u32::from_le_bytes(body[1..BODY_HEADER_LENGTH].try_into().expect("4 bytes"));
+        let contents_offset = usize::try_from(name_length)
+            .ok()
+            .and_then(|name_length| name_length.checked_add(BODY_HEADER_LENGTH))
+            .filter(|contents_offset| *contents_offset <= body.len())
+            .ok_or_else(corrupt_entry)?;
+        let name = pot::from_slice::<EntryName>(
+            &self.decode(flags, body[BODY_HEADER_LENGTH..contents_offset].to_vec())?,
+        )?;
+        Ok((
+            name,
+            EntryLocation {
+                flags,
+                offset,
+                length: body.len() as u64,
+                checksum,
+                contents_offset,
+            },
+        ))
+    }
+
+    #[cfg_attr(
+        not(any(feature = "compression", feature = "encryption")),
+        allow(clippy::unused_self)
+    )]
+    fn flags(&self) -> u8 {
+        #[cfg_attr(
+            not(any(feature = "compression", feature = "encryption")),
+            allow(unused_mut)
+        )]
+        let mut flags = 0;
+        #[cfg(feature = "compression")]
+        if let Some(compression) = self.compression {
+            flags |= compression as u8;
+        }
+        #[cfg(feature = "encryption")]
+        if self.encryption.is_some() {
+            flags |= ENCRYPTED;
+        }
+        flags
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    #[cfg_attr(
+        not(any(feature = "compression", feature = "encryption")),
+        allow(clippy::unused_self)
+    )]
+    fn encode(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "compression")]
+        let payload = match self.compression {
+            Some(Compression::Lz4) => Cow::Owned(lz4_flex::block::compress_prepend_size(payload)),
+            None => Cow::Borrowed(payload),
+        };
+
+        #[cfg(feature = "encryption")]
+        if let Some(encryption) = &self.encryption {
+            return encryption
+                .vault
+                .encrypt_payload(&encryption.key, &payload, None);
+        }
+
+        Ok(payload.to_vec())
+    }
+
+    fn decode(&self, flags: u8, payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let payload = if flags & ENCRYPTED == 0 {
+            payload
+        } else {
+            self.decrypt(&payload)?
+        };
+
+        let compression = flags & !ENCRYPTED;
+        if compression == 0 {
+            return Ok(payload);
+        }
+        #[cfg(feature = "compression")]
+        if let Some(Compression::Lz4) = Compression::from_u8(compression) {
+            return Ok(lz4_flex::block::decompress_size_prepended(&payload)?);
+        }
+
+        Err(Error::other(
+            "backup archive",
+            "entry is compressed with an unsupported algorithm",
+        ))
+    }
+
+    #[cfg(feature = "encryption")]
+    fn decrypt(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        let encryption = self.encryption.as_ref().ok_or_else(|| {
+            Error::other(
+                "backup archive",
+                "entry is encrypted, but no encryption was configured",
+            )
+        })?;
+        encryption.vault.decrypt_payload(payload, None)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[allow(clippy::unused_self)]
+    fn decrypt(&self, _payload: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::EncryptionDisabled)
+    }
+}
+
+impl Debug for BackupArchive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackupArchive")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for BackupArchive {
+    fn drop(&mut self) {
+        if let Some(mut archive) = self.state.get_mut().take() {
+            if !archive.indexed {
+                // Without an index, the next open reads every entry instead.
+                drop(self.write_index(&mut archive));
+            }
+        }
+    }
+}
+
+impl BackupLocation for BackupArchive {
+    type Error = Error;
+
+    fn store(
+        &self,
+        schema: &SchemaName,
+        database_name: &str,
+        container: &str,
+        name: &str,
+        object: &[u8],
+    ) -> Result<(), Self::Error> {
+        let entry_name = EntryName {
+            schema: schema.clone(),
+            database: database_name.to_string(),
+            container: container.to_string(),
+            name: name.to_string(),
+        };
+        let flags = self.flags();
+        let encoded_name = self.encode(&pot::to_vec(&entry_name)?)?;
+        let encoded_contents = self.encode(object)?;
+        let name_length =
+            u32::try_from(encoded_name.len()).map_err(|err| Error::other("backup archive", err))?;
+        let contents_offset = BODY_HEADER_LENGTH + encoded_name.len();
+
+        let mut entry =
+            Vec::with_capacity(FRAME_LENGTH as usize + contents_offset + encoded_contents.len());
+        entry.extend([0; FRAME_LENGTH as usize]);
+        entry.push(flags);
+        entry.extend(name_length.to_le_bytes());
+        entry.extend(encoded_name);
+        entry.extend(encoded_contents);
+        let body_length = (entry.len() - FRAME_LENGTH as usize) as u64;
+        let checksum = crc32c::crc32c(&entry[FRAME_LENGTH as usize..]);
+        entry[..8].copy_from_slice(&body_length.to_le_bytes());
+        entry[8..FRAME_LENGTH as usize].copy_from_slice(&checksum.to_le_bytes());
+
+        self.with_archive(|archive| {
+            let offset = archive.file.seek(SeekFrom::End(0))?;
+            archive.file.write_all(&entry)?;
+            archive.indexed = false;
+            archive.manifest.insert(
+                entry_name,
+                EntryLocation {
+                    flags,
+                    offset: offset + FRAME_LENGTH,
+                    length: body_length,
+                    checksum,
+                    contents_offset,
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn list_schemas(&self) -> Result<Vec<SchemaName>, Self::Error> {
+        self.with_archive(|archive| {
+            Ok(archive
+                .manifest
+                .keys()
+                .map(|entry| &entry.schema)
+                .dedup()
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn list_databases(&self, schema: &SchemaName) -> Result<Vec<String>, Self::Error> {
+        self.with_archive(|archive| {
+            Ok(archive
+                .manifest
+                .keys()
+                .filter(|entry| &entry.schema == schema)
+                .map(|entry| &entry.database)
+                .dedup()
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn list_stored(
+        &self,
+        schema: &SchemaName,
+        database_name: &str,
+        container: &str,
+    ) -> Result<Vec<String>, Self::Error> {
+        self.with_archive(|archive| {
+            Ok(archive
+                .manifest
+                .keys()
+                .filter(|entry| {
+                    &entry.schema == schema
+                        && entry.database == database_name
+                        && entry.container == container
+                })
+                .map(|entry| entry.name.clone())
+                .collect())
+        })
+    }
+
+    fn load(
+        &self,
+        schema: &SchemaName,
+        database_name: &str,
+        container: &str,
+        name: &str,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let entry_name = EntryName {
+            schema: schema.clone(),
+            database: database_name.to_string(),
+            container: container.to_string(),
+            name: name.to_string(),
+        };
+        self.with_archive(|archive| {
+            let location = archive.manifest.get(&entry_name).copied().ok_or_else(|| {
+                std::io::Error::new(ErrorKind::NotFound, format!("{name} not found in archive"))
+            })?;
+            let mut body = read_body(&mut archive.file, &location)?;
+            if crc32c::crc32c(&body) != location.checksum {
+                return Err(corrupt_entry());
+            }
+            self.decode(location.flags, body.split_off(location.contents_offset))
+        })
+    }
+}
+
+fn read_body(file: &mut File, location: &EntryLocation) -> Result<Vec<u8>, Error> {
+    let mut body = vec![
+        0;
+        usize::try_from(location.length)
+            .map_err(|err| Error::other("backup archive", err))?
+    ];
+    file.seek(SeekFrom::Start(location.offset))?;
+    file.read_exact(&mut body)?;
+    Ok(body)
+}
+
+fn corrupt_entry() -> Error {
+    Error::other("backup archive", "entry failed checksum verification")
+}
+
+#[cfg(all(test, feature = "compression", feature = "encryption"))]
+mod tests {
+    use bonsaidb_core::connection::{Connection as _, StorageConnection as _};
+    use bonsaidb_core::document::KeyId;
+    use bonsaidb_core::keyvalue::KeyValue;
+    use bonsaidb_core::schema::{Qualified, SchemaName, SerializedCollection};
+    use bonsaidb_core::test_util::{Basic, TestDirectory};
+
+    use super::BackupArchive;
+    use crate::config::{Builder, Compression, StorageConfiguration};
+    use crate::storage::BackupLocation;
+    use crate::Storage;
+
+    #[test]
+    fn archive_backup_restore() -> anyhow::Result<()> {
+        let archive_directory = TestDirectory::new("archive-backup-restore.bonsaidb.backup");
+        std::fs::create_dir_all(&archive_directory)?;
+        let archive_path = archive_directory.join("backup.archive");
+
+        let (test_doc, deleted_doc) = {
+            let database_directory = TestDirectory::new("archive-backup-restore.bonsaidb");
+            let storage = Storage::open(
+                StorageConfiguration::new(&database_directory).with_schema::<Basic>()?,
+            )?;
+
+            let db = storage.create_database::<Basic>("basic", false)?;
+            let test_doc = Basic::new("somevalue").push_into(&db)?;
+            let deleted_doc = Basic::new("deleted").push_into(&db)?;
+            db.set_numeric_key("key1", 1_u64).execute()?;
+
+            storage.backup(&BackupArchive::new(&archive_path).compression(Compression::Lz4))?;
+            // A second full backup replaces the first one, including the
+            // documents it contained.
+            deleted_doc.delete(&db)?;
+            storage.backup(&BackupArchive::new(&archive_path))?;
+            // Adding another entry after the archive has been closed appends
+            // to the existing archive.
+            let updated_doc = Basic::new("updated").overwrite_into(&test_doc.header.id, &db)?;
+            storage.backup_incremental(&BackupArchive::new(&archive_path))?;
+
+            (updated_doc, deleted_doc)
+        };
+
+        let database_directory = TestDirectory::new("archive-backup-restore.bonsaidb");
+        let restored_storage =
+            Storage::open(StorageConfiguration::new(&database_directory).with_schema::<Basic>()?)?;
+        restored_storage.restore(&BackupArchive::new(&archive_path))?;
+
+        let db = restored_storage.database::<Basic>("basic")?;
+        let doc = Basic::get(&test_doc.header.id, &db)?.expect("Backed up document not found");
+        assert_eq!(doc.contents.value, "updated");
+        assert!(Basic::get(&deleted_doc.header.id, &db)?.is_none());
+        assert_eq!(db.get_key("key1").into_u64()?, Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn encrypted_archive() -> anyhow::Result<()> {
+        let database_directory = TestDirectory::new("encrypted-archive.bonsaidb");
+        let storage = Storage::open(StorageConfiguration::new(&database_directory))?;
+        let archive_path = database_directory.join("backup.archive");
+        let schema = SchemaName::new("khonsulabs", "archive-test");
+
+        let archive = BackupArchive::new(&archive_path)
+            .compression(Compression::Lz4)
+            .encryption(KeyId::Master, &storage);
+        archive.store(
+            &schema,
+            "basic",
+            "container",
+            "secret-name",
+            b"secret value",
+        )?;
+        drop(archive);
+
+        let contents = std::fs::read(&archive_path)?;
+        assert!(!contents.windows(6).any(|window| window == b"secret"));
+
+        // Reading the archive without encryption fails.
+        assert!(BackupArchive::new(&archive_path).list_schemas().is_err());
+
+        let archive = BackupArchive::new(&archive_path).encryption(KeyId::Master, &storage);
+        assert_eq!(archive.list_schemas()?, vec![schema.clone()]);
+        assert_eq!(
+            archive.list_stored(&schema, "basic", "container")?,
+            vec![String::from("secret-name")]
+        );
+        assert_eq!(
+            archive.load(&schema, "basic", "container", "secret-name")?,
+            b"secret value"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn incomplete_entries_and_compaction() -> anyhow::Result<()> {
+        let archive_directory = TestDirectory::new("archive-incomplete-entries.bonsaidb.backup");
+        std::fs::create_dir_all(&archive_directory)?;
+        let archive_path = archive_directory.join("backup.archive");
+        let schema = SchemaName::new("khonsulabs", "archive-test");
+
+        let archive = BackupArchive::new(&archive_path);
+        archive.store(&schema, "basic", "container", "first", b"first value")?;
+        archive.store(&schema, "basic", "container", "first", b"replaced value")?;
+        archive.store(&schema, "basic", "container", "second", b"second value")?;
+        let length = std::fs::metadata(&archive_path)?.len();
+        drop(archive);
+
+        // Simulate the process stopping while the last entry was written,
+        // before the archive's index was written.
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&archive_path)?
+            .set_len(length - 4)?;
+
+        let archive = BackupArchive::new(&archive_path);
+        assert_eq!(
+            archive.list_stored(&schema, "basic", "container")?,
+            vec![String::from("first")]
+        );
+        assert_eq!(
+            archive.load(&schema, "basic", "container", "first")?,
+            b"replaced value"
+        );
+        // New entries are appended after the last complete entry.
+        archive.store(&schema, "basic", "container", "second", b"second value")?;
+        drop(archive);
+
+        let archive = BackupArchive::new(&archive_path);
+        let uncompacted_length = std::fs::metadata(&archive_path)?.len();
+        archive.compact()?;
+        assert!(std::fs::metadata(&archive_path)?.len() < uncompacted_length);
+        assert_eq!(
+            archive.load(&schema, "basic", "container", "second")?,
+            b"second value"
+        );
+        drop(archive);
+
+        let archive = BackupArchive::new(&archive_path);
+        assert_eq!(
+            archive.list_stored(&schema, "basic", "container")?,
+            vec![String::from("first"), String::from("second")]
+        );
+        assert_eq!(
+            archive.load(&schema, "basic", "container", "first")?,
+            b"replaced value"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn index() -> anyhow::Result<()> {
+        let archive_directory = TestDirectory::new("archive-index.bonsaidb.backup");
+        std::fs::create_dir_all(&archive_directory)?;
+        let archive_path = archive_directory.join("backup.archive");
+        let schema = SchemaName::new("khonsulabs", "archive-test");
+
+        let archive = BackupArchive::new(&archive_path);
+        archive.store(&schema, "basic", "container", "first", b"first value")?;
+        archive.store(&schema, "basic", "container", "second", b"second value")?;
+        drop(archive);
+
+        // An index that fails verification is ignored, and the entries are
+        // read instead.
+        let mut contents = std::fs::read(&archive_path)?;
+        let index_byte = contents.len() - 9;
+        contents[index_byte] ^= 0xFF;
+        std::fs::write(&archive_path, &contents)?;
+        let archive = BackupArchive::new(&archive_path);
+        assert_eq!(
+            archive.list_stored(&schema, "basic", "container")?,
+            vec![String::from("first"), String::from("second")]
+        );
+        drop(archive);
+
+        // Dropping the archive wrote a new index. Corrupting an entry is no
+        // longer noticed until the entry is loaded, which shows the entries
+        // weren't read when the archive was opened.
+        let mut contents = std::fs::read(&archive_path)?;
+        let first_entry_byte = super::ARCHIVE_HEADER_LENGTH as usize
+            + super::FRAME_LENGTH as usize
+            + super::BODY_HEADER_LENGTH;
+        contents[first_entry_byte] ^= 0xFF;
+        std::fs::write(&archive_path, &contents)?;
+        let archive = BackupArchive::new(&archive_path);
+        assert_eq!(
+            archive.list_stored(&schema, "basic", "container")?,
+            vec![String::from("first"), String::from("second")]
+        );
+        assert_eq!(
+            archive.load(&schema, "basic", "container", "second")?,
+            b"second value"
+        );
+        assert!(archive
+            .load(&schema, "basic", "container", "first")
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use bonsaidb_core::admin::database::Database as DatabaseRecord;
+use bonsaidb_core::connection::{Bound, LowLevelConnection, Range, Sort, StorageConnection};
+use bonsaidb_core::document::DocumentId;
+use bonsaidb_core::schema::{CollectionName, NamedCollection, Schematic};
+use bonsaidb_core::transaction::{Operation, Transaction};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::StorageInstance;
+use crate::{Database, Error};
+
+/// The number of documents migrated in each transaction.
+const MIGRATION_PAGE_SIZE: u32 = 1_000;
+
+impl StorageInstance {
+    /// Upgrades every database with a registered schema to the collection
+    /// versions defined by its schema.
+    pub(crate) fn migrate_databases(&self) -> Result<(), Error> {
+        let database_names = {
+            let schemas = self.data.schemas.read();
+            let available_databases = self.data.available_databases.read();
+            available_databases
+                .iter()
+                .filter(|(_, schema)| schemas.contains_key(*schema))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        for name in database_names {
+            self.migrate_database(&name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Upgrades the documents stored in the database named `name` to the
+    /// collection versions defined by its schema.
+    ///
+    /// Each collection is migrated in pages of `MIGRATION_PAGE_SIZE`
+    /// documents. Every page is applied in the same transaction that records
+    /// the collection's migration progress in the migrated database, so an
+    /// interrupted migration resumes after the last migrated document rather
+    /// than migrating any document twice. Once complete, the new versions are
+    /// also recorded in the admin database.
+    pub(crate) fn migrate_database(&self, name: &str) -> Result<(), Error> {
+        let _guard = self.data.migration_lock.lock();
+
+        let admin = self.admin();
+        let mut record = DatabaseRecord::load(name.to_ascii_lowercase(), &admin)?
+            .ok_or_else(|| Error::Core(bonsaidb_core::Error::DatabaseNotFound(name.to_string())))?;
+        let database = self.database_without_schema(name, None, None)?;
+        let schematic = database.schematic();
+
+        for collection in schematic.collections() {
+            let recorded_version = record
+                .contents
+                .collection_versions
+                .get(collection)
+                .copied()
+                .unwrap_or_default();
+            migrate_collection(&database, schematic, collection, recorded_version)?;
+        }
+
+        let collection_versions = collection_versions(schematic);
+        if collection_versions != record.contents.collection_versions {
+            record.contents.collection_versions = collection_versions;
+            record.update(&admin)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the versions of all collections in `schematic` that should be
+/// recorded in the admin database.
+pub(crate) fn collection_versions(schematic: &Schematic) -> BTreeMap<CollectionName, u64> {
+    schematic
+        .collections()
+        .filter_map(|collection| {
+            schematic
+                .collection_version(collection)
+                .filter(|version| *version > 0)
+                .map(|version| (collection.clone(), version))
+        })
+        .collect()
+}
+
+/// The migration state of a collection, stored in the collection versions tree
+/// of the database containing it.
+#[derive(Debug, Serialize, Deserialize)]
+struct CollectionVersion {
+    /// The version of every document not covered by `migrating`.
+    version: u64,
+    /// While a migration is in progress, the version being migrated to and the
+    /// id of the last document that has been migrated.
+    migrating: Option<(u64, DocumentId)>,
+}
+
+fn migrate_collection(
+    database: &Database,
+    schematic: &Schematic,
+    collection: &CollectionName,
+    recorded_version: u64,
+) -> Result<(), Error> {
+    let version = schematic.collection_version(collection).unwrap_or_default();
+    // The database's own record is authoritative. The admin database's record
+    // is only used for databases that have never been migrated.
+    let mut state = match database.collection_version(collection)? {
+        Some(state) => bincode::deserialize::<CollectionVersion>(&state)?,
+        None => CollectionVersion {
+            version: recorded_version,
+            migrating: None,
+        },
+    };
+
+    loop {
+        // A migration that was interrupted is finished before migrating any
+        // further.
+        let (target, start) = match &state.migrating {
+            Some((target, last_migrated)) => (*target, Bound::Excluded(last_migrated.clone())),
+            None if state.version == version => return Ok(()),
+            None => (version, Bound::Unbounded),
+        };
+        let unavailable = || {
+            Error::Core(bonsaidb_core::Error::MigrationUnavailable {
+                collection: collection.clone(),
+                stored_version: state.version,
+                version: target,
+            })
+        };
+        if state.version > target {
+            return Err(unavailable());
+        }
+
+        let documents = database.list_from_collection(
+            Range {
+                start,
+                end: Bound::Unbounded,
+            },
+            Sort::Ascending,
+            Some(MIGRATION_PAGE_SIZE),
+            None,
+            collection,
+        )?;
+        let last_migrated = documents
+            .last()
+            .filter(|_| documents.len() == MIGRATION_PAGE_SIZE as usize)
+            .map(|document| document.header.id.clone());
+
+        let mut transaction = Transaction::new();
+        for mut document in documents {
+            for from_version in state.version..target {
+                let migration = schematic
+                    .migration(collection, from_version)
+                    .ok_or_else(unavailable)?;
+                migration(&mut document)?;
+            }
+
+            transaction.push(Operation::update(
+                collection.clone(),
+                document.header,
+                document.contents,
+            ));
+        }
+
+        state = match last_migrated {
+            Some(last_migrated) => CollectionVersion {
+                version: state.version,
+                migrating: Some((target, last_migrated)),
+            },
+            None => CollectionVersion {
+                version: target,
+                migrating: None,
+            },
+        };
+        database.apply_migration(transaction, collection, bincode::serialize(&state)?)?;
+    }
+}
@@ -143,6 +143,7 @@ impl TaskManager {
                 scan: IntegrityScan {
                     database: database.data.name.clone(),
                     view_version: view.version(),
+                    cache_reductions: view.cache_reductions(),
                     collection: view.collection(),
                     view_name,
                 },
@@ -4,12 +4,12 @@ use bonsaidb_core::connection::Connection;
 use bonsaidb_core::schema::CollectionName;
 use nebari::tree::{Root, Unversioned, Versioned};
 
-use crate::database::keyvalue::KEY_TREE;
+use crate::database::keyvalue::{KEY_TREE, STRUCTURE_TREE};
 use crate::database::{document_tree_name, DatabaseNonBlocking};
 use crate::tasks::{Job, Keyed, Task};
 use crate::views::{
     view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
-    view_versions_tree_name,
+    view_reductions_tree_name, view_versions_tree_name,
 };
 use crate::{Database, Error};
 
@@ -68,7 +68,10 @@ impl Target {
                 gather_collection_trees(database, &collection, &mut trees);
                 compact_trees(database, trees)
             }
-            Target::KeyValue => compact_tree::<Unversioned, _>(database, KEY_TREE),
+            Target::KeyValue => {
+                compact_tree::<Unversioned, _>(database, KEY_TREE)?;
+                compact_tree::<Unversioned, _>(database, STRUCTURE_TREE)
+            }
             Target::Database => {
                 let mut trees = Vec::new();
                 for collection in database.schematic().collections() {
@@ -112,6 +115,9 @@ fn gather_collection_trees(
         trees.push(Target::UnversionedTree(view_invalidated_docs_tree_name(
             &name,
         )));
+        if view.cache_reductions() {
+            trees.push(Target::UnversionedTree(view_reductions_tree_name(&name)));
+        }
     }
 }
 
@@ -224,6 +224,243 @@ fn integrity_checks() -> anyhow::Result<()> {
     unreachable!("Integrity checker didn't run in the allocated time")
 }
 
+#[test]
+fn watching_collections_requires_document_permissions() -> anyhow::Result<()> {
+    use bonsaidb_core::permissions::bonsai::{
+        BonsaiAction, DatabaseAction, DocumentAction, PubSubAction,
+    };
+    use bonsaidb_core::pubsub::{collection_changes_topic, PubSub, Subscriber};
+    use bonsaidb_core::schema::{Collection, SerializedCollection};
+
+    let path = TestDirectory::new("watch-collection-permissions");
+    let db = Database::open::<BasicSchema>(StorageConfiguration::new(&path))?;
+    let pubsub = Statement::for_any()
+        .allowing(&BonsaiAction::Database(DatabaseAction::PubSub(
+            PubSubAction::CreateSuscriber,
+        )))
+        .allowing(&BonsaiAction::Database(DatabaseAction::PubSub(
+            PubSubAction::SubscribeTo,
+        )));
+
+    // Permission to subscribe to topics doesn't allow receiving the ids of
+    // changed documents.
+    let subscriber_only = db
+        .with_effective_permissions(Permissions::from(pubsub.clone()))
+        .unwrap();
+    assert!(matches!(
+        subscriber_only.watch_collection::<Basic>(),
+        Err(bonsaidb_core::Error::PermissionDenied(_))
+    ));
+    let subscriber = subscriber_only.create_subscriber()?;
+    assert!(matches!(
+        subscriber.subscribe_to_bytes(collection_changes_topic(&Basic::collection_name())),
+        Err(bonsaidb_core::Error::ReservedTopic)
+    ));
+
+    let reader = db
+        .with_effective_permissions(Permissions::from(vec![
+            pubsub,
+            Statement::for_any()
+                .allowing(&BonsaiAction::Database(DatabaseAction::Document(
+                    DocumentAction::List,
+                )))
+                .allowing(&BonsaiAction::Database(DatabaseAction::Document(
+                    DocumentAction::Get,
+                ))),
+        ]))
+        .unwrap();
+    let mut watcher = reader.watch_collection::<Basic>()?;
+    let doc = Basic::new("watched").push_into(&db)?;
+    let changes = watcher.next_changes()?.expect("watcher disconnected");
+    assert_eq!(changes.changes[0].id.deserialize::<u64>()?, doc.header.id);
+
+    Ok(())
+}
+
+#[test]
+fn collection_migrations() -> anyhow::Result<()> {
+    use bonsaidb_core::admin::database::Database as DatabaseRecord;
+    use bonsaidb_core::connection::StorageConnection;
+    use bonsaidb_core::document::OwnedDocument;
+    use bonsaidb_core::schema::{Collection, NamedCollection, SerializedCollection};
+    use bonsaidb_core::transaction::{Operation, Transaction};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, Collection)]
+    #[collection(name = "migrating", core = bonsaidb_core)]
+    struct Original {
+        value: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Collection)]
+    #[collection(name = "migrating", version = 1, migrations = [stringify_value], core = bonsaidb_core)]
+    struct Migrated {
+        value: String,
+    }
+
+    fn stringify_value(document: &mut OwnedDocument) -> Result<(), bonsaidb_core::Error> {
+        let original = Original::deserialize(&document.contents)?;
+        document.contents = Migrated::serialize(&Migrated {
+            value: original.value.to_string(),
+        })?
+        .into();
+        Ok(())
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Collection)]
+    #[collection(name = "migrating", version = 2, core = bonsaidb_core)]
+    struct MissingMigration;
+
+    let path = TestDirectory::new("collection-migrations");
+    {
+        // Store more documents than are migrated in a single transaction.
+        let db = Database::open::<Original>(StorageConfiguration::new(&path))?;
+        let mut transaction = Transaction::new();
+        for value in 0..2_500 {
+            transaction.push(Operation::push_serialized::<Original>(&Original { value })?);
+        }
+        transaction.apply(&db)?;
+    }
+
+    for _ in 0..2 {
+        // The first open migrates the documents, and the second open should
+        // leave the already migrated documents untouched.
+        let db = Database::open::<Migrated>(StorageConfiguration::new(&path))?;
+        let documents = Migrated::all(&db).query()?;
+        assert_eq!(documents.len(), 2_500);
+        for (value, document) in documents.iter().enumerate() {
+            assert_eq!(document.contents.value, value.to_string());
+        }
+
+        let record = DatabaseRecord::load("default", &db.storage().admin())?.unwrap();
+        assert_eq!(
+            record
+                .contents
+                .collection_versions
+                .get(&Migrated::collection_name()),
+            Some(&1)
+        );
+    }
+
+    assert!(matches!(
+        Database::open::<MissingMigration>(StorageConfiguration::new(&path)),
+        Err(crate::Error::Core(
+            bonsaidb_core::Error::MigrationUnavailable {
+                stored_version: 1,
+                version: 2,
+                ..
+            }
+        ))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn bulk_import() -> anyhow::Result<()> {
+    use bonsaidb_core::schema::SerializedCollection;
+    use bonsaidb_core::test_util::{BasicByParentIdEager, Unique, UniqueValue};
+    use bonsaidb_core::transaction::{Operation, Transaction};
+
+    let path = TestDirectory::new("bulk-import");
+    let db = Database::open::<BasicSchema>(StorageConfiguration::new(&path))?;
+    let parent = Basic::new("parent").push_into(&db)?;
+
+    let mut transaction = Transaction::new();
+    for index in 0..1_000 {
+        transaction.push(Operation::push_serialized::<Basic>(
+            &Basic::new(index.to_string()).with_parent_id(parent.header.id),
+        )?);
+        transaction.push(Operation::push_serialized::<Unique>(&Unique::new(index))?);
+    }
+    let results = db.bulk_import(transaction)?;
+    assert_eq!(results.len(), 2_000);
+
+    // Eager views must be up-to-date without needing to update the views.
+    let children = db
+        .view::<BasicByParentIdEager>()
+        .with_access_policy(AccessPolicy::NoUpdate)
+        .with_key(&Some(parent.header.id))
+        .query()?;
+    assert_eq!(children.len(), 1_000);
+    let unique = db
+        .view::<UniqueValue>()
+        .with_access_policy(AccessPolicy::NoUpdate)
+        .with_key("999")
+        .query()?;
+    assert_eq!(unique.len(), 1);
+
+    // A unique key violation within the import aborts the entire import.
+    let mut transaction = Transaction::new();
+    transaction.push(Operation::push_serialized::<Basic>(&Basic::new(
+        "not imported",
+    ))?);
+    transaction.push(Operation::push_serialized::<Unique>(&Unique::new(
+        "duplicate",
+    ))?);
+    transaction.push(Operation::push_serialized::<Unique>(&Unique::new(
+        "duplicate",
+    ))?);
+    let err = db.bulk_import(transaction).unwrap_err();
+    assert!(err.is_unique_key_error::<UniqueValue, _>(&db));
+    assert_eq!(Basic::all(&db).count()?, 1_001);
+    assert_eq!(Unique::all(&db).count()?, 1_000);
+
+    Ok(())
+}
+
+#[test]
+fn multi_field_unique_constraints() -> anyhow::Result<()> {
+    use bonsaidb_core::document::DocumentId;
+    use bonsaidb_core::schema::{Collection, SerializedCollection};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, Collection)]
+    #[collection(name = "users", unique = [(tenant_id, email)], core = bonsaidb_core)]
+    struct User {
+        tenant_id: u32,
+        email: String,
+    }
+
+    let path = TestDirectory::new("multi-field-unique-constraints");
+    let db = Database::open::<User>(StorageConfiguration::new(&path))?;
+    let existing = User {
+        tenant_id: 1,
+        email: String::from("user@example.com"),
+    }
+    .push_into(&db)?;
+    // The same email in another tenant doesn't violate the constraint.
+    User {
+        tenant_id: 2,
+        email: String::from("user@example.com"),
+    }
+    .push_into(&db)?;
+
+    let err = User {
+        tenant_id: 1,
+        email: String::from("user@example.com"),
+    }
+    .push_into(&db)
+    .unwrap_err()
+    .error;
+    assert!(err.is_unique_key_error::<UserUniqueTenantIdAndEmail, _>(&db));
+    let bonsaidb_core::Error::UniqueKeyViolation {
+        view,
+        existing_document,
+        ..
+    } = &err
+    else {
+        unreachable!("unexpected error: {err:?}")
+    };
+    assert_eq!(view.name.as_ref(), "unique-tenant_id-email");
+    assert_eq!(
+        existing_document.id,
+        DocumentId::from_u64(existing.header.id)
+    );
+
+    Ok(())
+}
+
 #[test]
 #[cfg(feature = "encryption")]
 fn encryption() -> anyhow::Result<()> {
@@ -318,3 +555,184 @@ fn expiration_after_close() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn key_value_notifications() -> anyhow::Result<()> {
+    use bonsaidb_core::keyvalue::{KeyChange, KeyChangeKind, KeyValue};
+    use bonsaidb_core::pubsub::{PubSub, Subscriber};
+
+    let path = TestDirectory::new("key-value-notifications");
+    let db = Database::open::<()>(StorageConfiguration::new(&path).key_value_notifications(true))?;
+    let subscriber = db.create_subscriber()?;
+    subscriber.subscribe_to_key_changes(None)?;
+    subscriber.subscribe_to_key_changes(Some("sessions"))?;
+    let next_change = || -> anyhow::Result<KeyChange> {
+        Ok(subscriber.receiver().receive()?.payload::<KeyChange>()?)
+    };
+
+    db.set_key("a", &0_u32).execute()?;
+    assert_eq!(
+        next_change()?,
+        KeyChange {
+            namespace: None,
+            key: String::from("a"),
+            kind: KeyChangeKind::Set,
+        }
+    );
+    db.delete_key("a")?;
+    assert_eq!(next_change()?.kind, KeyChangeKind::Deleted);
+    // Deleting a key that doesn't exist doesn't publish a change.
+    db.delete_key("a")?;
+
+    // Changes in other namespaces are only delivered if subscribed to.
+    db.with_key_namespace("other")
+        .set_key("b", &0_u32)
+        .execute()?;
+    db.with_key_namespace("sessions")
+        .set_key("token", &0_u32)
+        .expire_in(Duration::from_millis(100))
+        .execute()?;
+    assert_eq!(
+        next_change()?,
+        KeyChange {
+            namespace: Some(String::from("sessions")),
+            key: String::from("token"),
+            kind: KeyChangeKind::Set,
+        }
+    );
+    assert_eq!(
+        next_change()?,
+        KeyChange {
+            namespace: Some(String::from("sessions")),
+            key: String::from("token"),
+            kind: KeyChangeKind::Expired,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn key_changes_require_key_value_permissions() -> anyhow::Result<()> {
+    use bonsaidb_core::keyvalue::{KeyChange, KeyValue};
+    use bonsaidb_core::permissions::bonsai::{
+        BonsaiAction, DatabaseAction, KeyValueAction, PubSubAction,
+    };
+    use bonsaidb_core::pubsub::{key_changes_topic, PubSub, Subscriber};
+
+    let path = TestDirectory::new("key-changes-permissions");
+    let db = Database::open::<()>(StorageConfiguration::new(&path).key_value_notifications(true))?;
+    let pubsub = Statement::for_any()
+        .allowing(&BonsaiAction::Database(DatabaseAction::PubSub(
+            PubSubAction::CreateSuscriber,
+        )))
+        .allowing(&BonsaiAction::Database(DatabaseAction::PubSub(
+            PubSubAction::SubscribeTo,
+        )));
+
+    // Permission to subscribe to topics doesn't allow receiving the names of
+    // changed keys.
+    let subscriber_only = db
+        .with_effective_permissions(Permissions::from(pubsub.clone()))
+        .unwrap();
+    let subscriber = subscriber_only.create_subscriber()?;
+    assert!(matches!(
+        subscriber.subscribe_to_key_changes(Some("secret")),
+        Err(bonsaidb_core::Error::PermissionDenied(_))
+    ));
+    assert!(matches!(
+        subscriber.subscribe_to_bytes(key_changes_topic(Some("secret"))),
+        Err(bonsaidb_core::Error::ReservedTopic)
+    ));
+
+    let kv_user = db
+        .with_effective_permissions(Permissions::from(vec![
+            pubsub,
+            Statement::for_any().allowing(&BonsaiAction::Database(DatabaseAction::KeyValue(
+                KeyValueAction::ExecuteOperation,
+            ))),
+        ]))
+        .unwrap();
+    let subscriber = kv_user.create_subscriber()?;
+    subscriber.subscribe_to_key_changes(Some("secret"))?;
+    db.set_key("public", &0_u32).execute()?;
+    db.with_key_namespace("secret")
+        .set_key("token", &0_u32)
+        .execute()?;
+    let change = subscriber.receiver().receive()?.payload::<KeyChange>()?;
+    assert_eq!(change.namespace.as_deref(), Some("secret"));
+    assert_eq!(change.key, "token");
+
+    Ok(())
+}
+
+#[test]
+fn key_value_eviction() -> anyhow::Result<()> {
+    use bonsaidb_core::keyvalue::{KeyChange, KeyChangeKind, KeyValue};
+    use bonsaidb_core::pubsub::{PubSub, Subscriber};
+
+    use crate::config::KeyValueEviction;
+
+    let path = TestDirectory::new("key-value-eviction-lru");
+    let db = Database::open::<()>(
+        StorageConfiguration::new(&path)
+            .key_value_notifications(true)
+            .key_value_eviction(KeyValueEviction::least_recently_used().with_max_entries(2)),
+    )?;
+    let subscriber = db.create_subscriber()?;
+    subscriber.subscribe_to_key_changes(None)?;
+
+    db.set_key("a", &0_u32).execute()?;
+    db.set_key("b", &0_u32).execute()?;
+    // Reading "a" makes "b" the least recently used key.
+    assert!(db.get_key("a").query()?.is_some());
+    db.set_key("c", &0_u32).execute()?;
+    assert!(db.get_key("a").query()?.is_some());
+    assert!(db.get_key("b").query()?.is_none());
+    assert!(db.get_key("c").query()?.is_some());
+
+    let next_change = || -> anyhow::Result<KeyChange> {
+        Ok(subscriber.receiver().receive()?.payload::<KeyChange>()?)
+    };
+    for _ in 0..3 {
+        assert_eq!(next_change()?.kind, KeyChangeKind::Set);
+    }
+    assert_eq!(
+        next_change()?,
+        KeyChange {
+            namespace: None,
+            key: String::from("b"),
+            kind: KeyChangeKind::Evicted,
+        }
+    );
+    drop(db);
+
+    let path = TestDirectory::new("key-value-eviction-lfu");
+    let db = Database::open::<()>(
+        StorageConfiguration::new(&path)
+            .key_value_eviction(KeyValueEviction::least_frequently_used().with_max_entries(2)),
+    )?;
+    db.set_key("a", &0_u32).execute()?;
+    db.set_key("b", &0_u32).execute()?;
+    for _ in 0..3 {
+        assert!(db.get_key("a").query()?.is_some());
+    }
+    // "b" was used the fewest times.
+    db.set_key("c", &0_u32).execute()?;
+    assert!(db.get_key("a").query()?.is_some());
+    assert!(db.get_key("b").query()?.is_none());
+    drop(db);
+
+    let path = TestDirectory::new("key-value-eviction-bytes");
+    let db = Database::open::<()>(
+        StorageConfiguration::new(&path)
+            .key_value_eviction(KeyValueEviction::least_recently_used().with_max_bytes(64)),
+    )?;
+    // Each key counts 6 bytes for its name and 8 or 48 bytes for its value.
+    db.set_binary_key("small", &[0; 8]).execute()?;
+    db.set_binary_key("large", &[0; 48]).execute()?;
+    assert!(db.get_key("small").query()?.is_none());
+    assert!(db.get_key("large").query()?.is_some());
+
+    Ok(())
+}
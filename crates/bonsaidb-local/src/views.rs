@@ -22,6 +22,7 @@ pub struct EntryMapping {
 
 pub mod integrity_scanner;
 pub mod mapper;
+pub mod reductions;
 
 pub fn view_entries_tree_name(view_name: &impl Display) -> String {
     format!("view.{view_name:#}")
@@ -32,6 +33,11 @@ pub fn view_document_map_tree_name(view_name: &impl Display) -> String {
     format!("view.{view_name:#}.document-map")
 }
 
+/// Used to store the cached partial reductions of views that opt in.
+pub fn view_reductions_tree_name(view_name: &impl Display) -> String {
+    format!("view.{view_name:#}.reductions")
+}
+
 pub fn view_invalidated_docs_tree_name(view_name: &impl Display) -> String {
     format!("view.{view_name:#}.invalidated")
 }
@@ -13,7 +13,7 @@ use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
 use super::mapper::{Map, Mapper};
-use super::{view_invalidated_docs_tree_name, view_versions_tree_name};
+use super::{view_invalidated_docs_tree_name, view_reductions_tree_name, view_versions_tree_name};
 use crate::database::{document_tree_name, Database};
 use crate::tasks::handle::Handle;
 use crate::tasks::{Job, Keyed, Task};
@@ -29,6 +29,7 @@ pub struct IntegrityScanner {
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub struct IntegrityScan {
     pub view_version: u64,
+    pub cache_reductions: bool,
     pub database: Arc<Cow<'static, str>>,
     pub collection: CollectionName,
     pub view_name: ViewName,
@@ -59,6 +60,7 @@ impl Job for IntegrityScanner {
 
         let view_name = self.scan.view_name.clone();
         let view_version = self.scan.view_version;
+        let cache_reductions = self.scan.cache_reductions;
         let roots = self.database.roots().clone();
         let version = view_versions
             .get(view_name.to_string().as_bytes())?
@@ -68,7 +70,7 @@ impl Job for IntegrityScanner {
         // Remove any old files that are no longer used.
         version.cleanup(&roots, &view_name)?;
 
-        let task = if version.is_current(view_version) {
+        let task = if version.is_current(view_version, cache_reductions) {
             None
         } else {
             // The view isn't the current version, queue up all documents.
@@ -79,6 +81,7 @@ impl Job for IntegrityScanner {
             roots.delete_tree(view_invalidated_docs_tree_name(&self.scan.view_name))?;
             roots.delete_tree(view_entries_tree_name(&self.scan.view_name))?;
             roots.delete_tree(view_document_map_tree_name(&self.scan.view_name))?;
+            roots.delete_tree(view_reductions_tree_name(&self.scan.view_name))?;
             // Add all missing entries to the invalidated list. The view
             // mapping job will update them on the next pass.
             let invalidated_entries_tree = self.database.collection_tree::<Unversioned, _>(
@@ -91,7 +94,7 @@ impl Job for IntegrityScanner {
                 let mut view_versions = transaction.tree::<Unversioned>(1).unwrap();
                 view_versions.set(
                     view_name.to_string().as_bytes().to_vec(),
-                    ViewVersion::current_for(view_version).to_vec()?,
+                    ViewVersion::current_for(view_version, cache_reductions).to_vec()?,
                 )?;
                 let mut invalidated_entries = transaction.tree::<Unversioned>(0).unwrap();
                 let mut missing_entries = missing_entries
@@ -138,6 +141,9 @@ impl Job for IntegrityScanner {
 pub struct ViewVersion {
     internal_version: u8,
     schema_version: u64,
+    /// Whether the view's reductions were cached when it was built.
+    #[serde(default)]
+    cache_reductions: bool,
 }
 
 impl ViewVersion {
@@ -153,6 +159,7 @@ impl ViewVersion {
                 Ok(Self {
                     internal_version: 0,
                     schema_version,
+                    cache_reductions: false,
                 })
             }
             Err(err) => Err(crate::Error::from(err)),
@@ -163,15 +170,18 @@ impl ViewVersion {
         pot::to_vec(self).map_err(crate::Error::from)
     }
 
-    pub fn current_for(schema_version: u64) -> Self {
+    pub fn current_for(schema_version: u64, cache_reductions: bool) -> Self {
         Self {
             internal_version: Self::CURRENT_VERSION,
             schema_version,
+            cache_reductions,
         }
     }
 
-    pub fn is_current(&self, schema_version: u64) -> bool {
-        self.internal_version == Self::CURRENT_VERSION && self.schema_version == schema_version
+    pub fn is_current(&self, schema_version: u64, cache_reductions: bool) -> bool {
+        self.internal_version == Self::CURRENT_VERSION
+            && self.schema_version == schema_version
+            && self.cache_reductions == cache_reductions
     }
 
     pub fn cleanup(&self, roots: &Roots<AnyFile>, view: &ViewName) -> Result<(), crate::Error> {
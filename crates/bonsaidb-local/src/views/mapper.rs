@@ -16,8 +16,8 @@ use nebari::{LockedTransactionTree, Tree, UnlockedTransactionTree};
 use crate::database::{deserialize_document, document_tree_name, Database};
 use crate::tasks::{Job, Keyed, Task};
 use crate::views::{
-    view_document_map_tree_name, view_entries_tree_name, view_invalidated_docs_tree_name,
-    EntryMapping, ViewEntry,
+    reductions, view_document_map_tree_name, view_entries_tree_name,
+    view_invalidated_docs_tree_name, view_reductions_tree_name, EntryMapping, ViewEntry,
 };
 use crate::Error;
 
@@ -73,6 +73,21 @@ impl Job for Mapper {
                     view_invalidated_docs_tree_name(&self.map.view_name),
                 )?)?;
 
+        let view = self
+            .database
+            .data
+            .schema
+            .view_by_name(&self.map.view_name)?;
+        let view_reductions = if view.cache_reductions() {
+            let tree = self.database.collection_tree::<Unversioned, _>(
+                &self.map.collection,
+                view_reductions_tree_name(&self.map.view_name),
+            )?;
+            Some(self.database.roots().tree(tree)?)
+        } else {
+            None
+        };
+
         let transaction_id = self
             .database
             .last_transaction_id()?
@@ -86,6 +101,7 @@ impl Job for Mapper {
             &document_map,
             &documents,
             &view_entries,
+            view_reductions.as_ref(),
             &storage,
             &map_request,
         )?;
@@ -106,6 +122,7 @@ fn map_view(
     document_map: &Tree<Unversioned, AnyFile>,
     documents: &Tree<Versioned, AnyFile>,
     view_entries: &Tree<Unversioned, AnyFile>,
+    view_reductions: Option<&Tree<Unversioned, AnyFile>>,
     database: &Database,
     map_request: &Map,
 ) -> Result<(), Error> {
@@ -117,14 +134,18 @@ fn map_view(
         .map(|(key, _)| key)
         .collect::<Vec<_>>();
     while !invalidated_ids.is_empty() {
+        let mut trees = vec![
+            Box::new(invalidated_entries.clone()) as Box<dyn AnyTreeRoot<AnyFile>>,
+            Box::new(document_map.clone()),
+            Box::new(documents.clone()),
+            Box::new(view_entries.clone()),
+        ];
+        if let Some(view_reductions) = view_reductions {
+            trees.push(Box::new(view_reductions.clone()));
+        }
         let transaction = database
             .roots()
-            .transaction::<_, dyn AnyTreeRoot<AnyFile>>(&[
-                Box::new(invalidated_entries.clone()) as Box<dyn AnyTreeRoot<AnyFile>>,
-                Box::new(document_map.clone()),
-                Box::new(documents.clone()),
-                Box::new(view_entries.clone()),
-            ])?;
+            .transaction::<_, dyn AnyTreeRoot<AnyFile>>(&trees)?;
         {
             let view = database
                 .data
@@ -138,6 +159,7 @@ fn map_view(
             let document_map = transaction.unlocked_tree(1).unwrap();
             let documents = transaction.unlocked_tree(2).unwrap();
             let view_entries = transaction.unlocked_tree(3).unwrap();
+            let view_reductions = view_reductions.map(|_| transaction.unlocked_tree(4).unwrap());
             DocumentRequest {
                 document_ids: document_ids.clone(),
                 map_request,
@@ -145,6 +167,7 @@ fn map_view(
                 document_map,
                 documents,
                 view_entries,
+                view_reductions,
                 view,
             }
             .map()?;
@@ -166,6 +189,8 @@ pub struct DocumentRequest<'a> {
     pub document_map: &'a UnlockedTransactionTree<AnyFile>,
     pub documents: &'a UnlockedTransactionTree<AnyFile>,
     pub view_entries: &'a UnlockedTransactionTree<AnyFile>,
+    /// The tree of cached reductions, if the view caches reductions.
+    pub view_reductions: Option<&'a UnlockedTransactionTree<AnyFile>>,
     pub view: &'a dyn Serialized,
 }
 
@@ -302,7 +327,7 @@ impl<'a> DocumentRequest<'a> {
         view: &dyn Serialized,
         map_request: &Map,
         view_entries: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
-        all_keys: BTreeSet<ArcBytes<'static>>,
+        all_keys: &BTreeSet<ArcBytes<'static>>,
         view_entries_to_clean: BTreeMap<ArcBytes<'static>, HashSet<ArcBytes<'static>>>,
         new_mappings: BTreeMap<ArcBytes<'static>, Vec<map::Serialized>>,
     ) -> Result<(), Error> {
@@ -316,7 +341,7 @@ impl<'a> DocumentRequest<'a> {
         };
         view_entries
             .modify(
-                all_keys.into_iter().collect(),
+                all_keys.iter().cloned().collect(),
                 Operation::CompareSwap(CompareSwap::new(&mut |key, view_entries| {
                     updater.compare_swap_view_entry(key, view_entries)
                 })),
@@ -331,6 +356,7 @@ impl<'a> DocumentRequest<'a> {
         map_request: &Map,
         document_map: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
         view_entries: &mut LockedTransactionTree<'_, Unversioned, AnyFile>,
+        view_reductions: &mut Option<LockedTransactionTree<'_, Unversioned, AnyFile>>,
     ) -> Result<(), Error> {
         while let Ok(Batch {
             document_ids,
@@ -352,10 +378,14 @@ impl<'a> DocumentRequest<'a> {
                 view,
                 map_request,
                 view_entries,
-                all_keys,
+                &all_keys,
                 view_entries_to_clean,
                 new_mappings,
             )?;
+
+            if let Some(view_reductions) = view_reductions {
+                reductions::update(view, view_entries, view_reductions, &all_keys)?;
+            }
         }
         Ok(())
     }
@@ -377,12 +407,16 @@ impl<'a> DocumentRequest<'a> {
             .add(|| {
                 let mut document_map = self.document_map.lock();
                 let mut view_entries = self.view_entries.lock();
+                let mut view_reductions = self
+                    .view_reductions
+                    .map(UnlockedTransactionTree::lock::<Unversioned>);
                 Self::save_mappings(
                     &mapped_receiver,
                     self.view,
                     self.map_request,
                     &mut document_map,
                     &mut view_entries,
+                    &mut view_reductions,
                 )
             })
             .run()
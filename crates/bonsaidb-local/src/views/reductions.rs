@@ -0,0 +1,610 @@
+//! Cached partial reductions for views that opt in using
+//! [`ViewSchema::cache_reductions()`](bonsaidb_core::schema::ViewSchema::cache_reductions).
+//!
+//! The reductions are stored as a B+tree over the view's entries in key order.
+//! Each leaf covers a contiguous range of view entries, and each branch covers
+//! a contiguous range of the nodes one level below it. A node is split once it
+//! has more than [`MAX_CHILDREN`] children and is merged with a sibling once it
+//! has fewer than [`MIN_CHILDREN`], which keeps the tree's height logarithmic
+//! in the number of entries regardless of how the keys are distributed.
+//!
+//! Each node contains the reduced value of every view entry it covers, and is
+//! computed by re-reducing the values of its children. Reducing a range of
+//! keys only needs to visit the nodes along the range's bounds, while every
+//! node contained within the range contributes its cached value.
+//!
+//! Nodes are stored under their level followed by the first key they cover,
+//! and cover every key up to the first key of the next node on the same level.
+//! The first node of each level begins at the empty key, and the first key of
+//! every branch is also the first key of its first child.
+
+use std::collections::BTreeSet;
+use std::ops::{Bound, RangeBounds};
+
+use bonsaidb_core::arc_bytes::serde::Bytes;
+use bonsaidb_core::arc_bytes::ArcBytes;
+use bonsaidb_core::connection::{self, Range};
+use bonsaidb_core::schema::view::Serialized;
+use nebari::io::any::AnyFile;
+use nebari::tree::{Operation, ScanEvaluation, Unversioned};
+use nebari::{LockedTransactionTree, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::views::ViewEntry;
+use crate::Error;
+
+/// The number of children each node has after being split.
+const FANOUT: usize = 8;
+/// The fewest children a node has before it is merged with a sibling.
+const MIN_CHILDREN: usize = FANOUT / 2;
+/// The most children a node has before it is split.
+const MAX_CHILDREN: usize = FANOUT * 2;
+
+type TransactionTree<'a> = LockedTransactionTree<'a, Unversioned, AnyFile>;
+
+/// A reduced value of one or more view entries.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedReduction {
+    /// The lowest key that `value` was reduced from.
+    key: Bytes,
+    value: Bytes,
+}
+
+impl CachedReduction {
+    fn from_entry(entry: &[u8]) -> Result<Option<Self>, Error> {
+        let entry = bincode::deserialize::<ViewEntry>(entry)?;
+        // Views that don't implement reduce store empty reduced values.
+        if entry.reduced_value.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Self {
+                key: entry.key,
+                value: entry.reduced_value,
+            }))
+        }
+    }
+
+    /// Returns the reduced value stored in a node, which is `None` if none of
+    /// the entries the node covers have a reduced value.
+    fn from_node(node: &[u8]) -> Result<Option<Self>, Error> {
+        bincode::deserialize(node).map_err(Error::from)
+    }
+}
+
+/// A view entry or node that is a child of a node being updated.
+struct Child {
+    /// The entry's key, or the first key the node covers.
+    key: Vec<u8>,
+    reduction: Option<CachedReduction>,
+}
+
+/// Updates the cached reductions of every node covering `changed_keys`. This
+/// must be called after the entries for `changed_keys` have been updated in
+/// `view_entries`.
+pub fn update(
+    view: &dyn Serialized,
+    view_entries: &mut TransactionTree<'_>,
+    reductions: &mut TransactionTree<'_>,
+    changed_keys: &BTreeSet<ArcBytes<'static>>,
+) -> Result<(), Error> {
+    // Find the leaves containing each changed key. The first leaf begins at
+    // the empty key, which creates it if the tree is empty.
+    let mut changed_nodes = BTreeSet::new();
+    let mut covered_until: Option<Vec<u8>> = None;
+    for key in changed_keys {
+        if covered_until
+            .as_ref()
+            .map_or(false, |covered_until| key[..] < covered_until[..])
+        {
+            continue;
+        }
+        let leaf = node_containing(reductions, 0, key)?.unwrap_or_default();
+        let leaf_end = next_node(reductions, 0, &leaf)?;
+        changed_nodes.insert(leaf);
+        if let Some(leaf_end) = leaf_end {
+            covered_until = Some(leaf_end);
+        } else {
+            break;
+        }
+    }
+
+    // Each node is reduced from its children, so the levels must be updated
+    // from the bottom up.
+    let mut level = 0;
+    while !changed_nodes.is_empty() {
+        let mut changed_parents = BTreeSet::new();
+        for node in changed_nodes {
+            if let Some(node) = rebalance(view, view_entries, reductions, level, node)? {
+                if let Some(parent) = node_containing(reductions, level + 1, &node)? {
+                    changed_parents.insert(parent);
+                }
+            }
+        }
+
+        if next_node(reductions, level, &[])?.is_none() {
+            // This level only contains a single node, which is the root.
+            let higher_levels = reductions
+                .get_range(&(Bound::Included(&[level + 1][..]), Bound::Unbounded))?
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>();
+            if !higher_levels.is_empty() {
+                reductions.modify(higher_levels, Operation::Remove)?;
+            }
+            break;
+        } else if level_is_empty(reductions, level + 1)? {
+            // This level has outgrown a single node and needs a new root.
+            changed_parents.insert(Vec::new());
+        }
+
+        changed_nodes = changed_parents;
+        level += 1;
+    }
+
+    Ok(())
+}
+
+/// Recomputes the node at `level` beginning at `start` from its children,
+/// splitting it or merging it with a sibling if it has too many or too few
+/// children. Returns the first key of the node that covers `start` afterwards,
+/// or `None` if the node was already merged into another node.
+fn rebalance(
+    view: &dyn Serialized,
+    view_entries: &mut TransactionTree<'_>,
+    reductions: &mut TransactionTree<'_>,
+    level: u8,
+    mut start: Vec<u8>,
+) -> Result<Option<Vec<u8>>, Error> {
+    // The first node of a level is never merged into another node, which
+    // allows it to be created by updating it.
+    if !start.is_empty() && reductions.get(&node_key(level, &start))?.is_none() {
+        return Ok(None);
+    }
+
+    let mut end = next_node(reductions, level, &start)?;
+    let mut children = load_children(view_entries, reductions, level, &start, end.as_deref())?;
+    if children.len() < MIN_CHILDREN {
+        // Nodes are only merged with siblings that share the same parent,
+        // which keeps the first key of each parent the first key of its first
+        // child. A node that is its parent's only child isn't merged.
+        let parent = node_containing(reductions, level + 1, &start)?.unwrap_or_default();
+        let parent_end = next_node(reductions, level + 1, &parent)?;
+        let next_sibling = end.clone().filter(|next| {
+            parent_end
+                .as_ref()
+                .map_or(true, |parent_end| next < parent_end)
+        });
+        if let Some(next_sibling) = next_sibling {
+            remove_node(reductions, level, &next_sibling)?;
+            end = next_node(reductions, level, &start)?;
+            children = load_children(view_entries, reductions, level, &start, end.as_deref())?;
+        } else if start != parent {
+            let previous_sibling = node_before(reductions, level, &start)?
+                .expect("only the first node of a level has no previous node");
+            remove_node(reductions, level, &start)?;
+            start = previous_sibling;
+            children = load_children(view_entries, reductions, level, &start, end.as_deref())?;
+        }
+    }
+
+    if children.len() > MAX_CHILDREN {
+        let sizes = split_sizes(children.len());
+        let mut children = children.into_iter();
+        for (index, size) in sizes.enumerate() {
+            let node = children.by_ref().take(size).collect::<Vec<_>>();
+            let node_start = if index == 0 {
+                start.clone()
+            } else {
+                node[0].key.clone()
+            };
+            write_node(view, reductions, level, &node_start, node)?;
+        }
+    } else {
+        write_node(view, reductions, level, &start, children)?;
+    }
+
+    Ok(Some(start))
+}
+
+/// Returns the entries or nodes one level below the node at `level` that
+/// begins at `start` and covers every key up to `end`.
+fn load_children(
+    view_entries: &mut TransactionTree<'_>,
+    reductions: &mut TransactionTree<'_>,
+    level: u8,
+    start: &[u8],
+    end: Option<&[u8]>,
+) -> Result<Vec<Child>, Error> {
+    if level == 0 {
+        view_entries
+            .get_range(&key_range(start, end))?
+            .into_iter()
+            .map(|(key, entry)| {
+                Ok(Child {
+                    key: key.to_vec(),
+                    reduction: CachedReduction::from_entry(&entry)?,
+                })
+            })
+            .collect()
+    } else {
+        let (first, last) = node_keys(level - 1, start, end);
+        reductions
+            .get_range(&(&first[..]..&last[..]))?
+            .into_iter()
+            .map(|(key, node)| {
+                Ok(Child {
+                    key: key[1..].to_vec(),
+                    reduction: CachedReduction::from_node(&node)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Stores the node at `level` beginning at `start`, reduced from `children`.
+fn write_node(
+    view: &dyn Serialized,
+    reductions: &mut TransactionTree<'_>,
+    level: u8,
+    start: &[u8],
+    children: Vec<Child>,
+) -> Result<(), Error> {
+    let reduction = rereduce(
+        view,
+        children
+            .into_iter()
+            .filter_map(|child| child.reduction)
+            .collect(),
+    )?;
+    reductions.set(node_key(level, start), bincode::serialize(&reduction)?)?;
+    Ok(())
+}
+
+fn remove_node(reductions: &mut TransactionTree<'_>, level: u8, start: &[u8]) -> Result<(), Error> {
+    reductions.modify(
+        vec![ArcBytes::from(node_key(level, start))],
+        Operation::Remove,
+    )?;
+    Ok(())
+}
+
+/// Returns the first key of the node at `level` that covers `key`, or `None`
+/// if `level` has no nodes.
+fn node_containing(
+    reductions: &mut TransactionTree<'_>,
+    level: u8,
+    key: &[u8],
+) -> Result<Option<Vec<u8>>, Error> {
+    let first = node_key(level, &[]);
+    let last = node_key(level, key);
+    last_node(reductions, &(&first[..]..=&last[..]))
+}
+
+/// Returns the first key of the node at `level` before the node beginning at
+/// `start`.
+fn node_before(
+    reductions: &mut TransactionTree<'_>,
+    level: u8,
+    start: &[u8],
+) -> Result<Option<Vec<u8>>, Error> {
+    let first = node_key(level, &[]);
+    let last = node_key(level, start);
+    last_node(reductions, &(&first[..]..&last[..]))
+}
+
+/// Returns the first key of the node at `level` after the node beginning at
+/// `start`, which is where the node beginning at `start` ends.
+fn next_node(
+    reductions: &mut TransactionTree<'_>,
+    level: u8,
+    start: &[u8],
+) -> Result<Option<Vec<u8>>, Error> {
+    let (first, last) = node_keys(level, start, None);
+    let mut next = None;
+    reductions.scan::<Error, _, _, _, _>(
+        &(Bound::Excluded(&first[..]), Bound::Excluded(&last[..])),
+        true,
+        |_, _, _| ScanEvaluation::ReadData,
+        |key, _| {
+            next = Some(key[1..].to_vec());
+            ScanEvaluation::Stop
+        },
+        |_, _, _| Ok(()),
+    )?;
+    Ok(next)
+}
+
+fn level_is_empty(reductions: &mut TransactionTree<'_>, level: u8) -> Result<bool, Error> {
+    let (first, last) = node_keys(level, &[], None);
+    Ok(last_node(reductions, &(&first[..]..&last[..]))?.is_none())
+}
+
+/// Returns the first key of the last node stored within `range`.
+fn last_node<'k, R: RangeBounds<&'k [u8]> + std::fmt::Debug>(
+    reductions: &mut TransactionTree<'_>,
+    range: &'k R,
+) -> Result<Option<Vec<u8>>, Error> {
+    let mut last = None;
+    reductions.scan::<Error, _, _, _, _>(
+        range,
+        false,
+        |_, _, _| ScanEvaluation::ReadData,
+        |key, _| {
+            last = Some(key[1..].to_vec());
+            ScanEvaluation::Stop
+        },
+        |_, _, _| Ok(()),
+    )?;
+    Ok(last)
+}
+
+/// Returns the number of children each node receives when a node with
+/// `children` children is split into nodes of about [`FANOUT`] children.
+fn split_sizes(children: usize) -> impl Iterator<Item = usize> {
+    let nodes = (children + FANOUT - 1) / FANOUT;
+    (0..nodes).map(move |node| children * (node + 1) / nodes - children * node / nodes)
+}
+
+/// Returns the reduced value of the entries in `view_entries` whose keys are
+/// contained within `range`, using the reductions cached in `reductions`.
+pub fn reduce(
+    view: &dyn Serialized,
+    view_entries: &Tree<Unversioned, AnyFile>,
+    reductions: &Tree<Unversioned, AnyFile>,
+    range: &Range<&[u8]>,
+) -> Result<Vec<u8>, Error> {
+    // The root is the only node on the highest level, and is stored last.
+    let mut root = None;
+    reductions.scan::<Error, _, _, _, _>(
+        &(..),
+        false,
+        |_, _, _| ScanEvaluation::ReadData,
+        |key, _| {
+            root = Some(key.clone());
+            ScanEvaluation::Stop
+        },
+        |_, _, _| Ok(()),
+    )?;
+
+    let mut values = Vec::new();
+    if let Some(root) = root {
+        match overlap(&[], None, range) {
+            Overlap::Complete => {
+                if let Some(node) = reductions.get(&root)? {
+                    values.extend(CachedReduction::from_node(&node)?);
+                }
+            }
+            Overlap::Partial => {
+                collect(
+                    view_entries,
+                    reductions,
+                    root[0],
+                    &[],
+                    None,
+                    range,
+                    &mut values,
+                )?;
+            }
+            Overlap::None => {}
+        }
+    }
+
+    if values.len() == 1 {
+        Ok(values.pop().unwrap().value.into_vec())
+    } else {
+        let mappings = values
+            .iter()
+            .map(|reduction| (&reduction.key[..], &reduction.value[..]))
+            .collect::<Vec<_>>();
+        view.reduce(&mappings, true).map_err(Error::from)
+    }
+}
+
+/// Collects the values needed to reduce the entries covered by the node at
+/// `level` beginning at `start` that are contained within `range`, in
+/// ascending key order. The node covers every key up to `end`, and `range`
+/// must partially overlap it.
+fn collect(
+    view_entries: &Tree<Unversioned, AnyFile>,
+    reductions: &Tree<Unversioned, AnyFile>,
+    level: u8,
+    start: &[u8],
+    end: Option<&[u8]>,
+    range: &Range<&[u8]>,
+    values: &mut Vec<CachedReduction>,
+) -> Result<(), Error> {
+    if level == 0 {
+        for (key, entry) in view_entries.get_range(&key_range(start, end))? {
+            if range.contains(&&key[..]) {
+                values.extend(CachedReduction::from_entry(&entry)?);
+            }
+        }
+        return Ok(());
+    }
+
+    let (first, last) = node_keys(level - 1, start, end);
+    let children = reductions.get_range(&(&first[..]..&last[..]))?;
+    for (index, (child_key, node)) in children.iter().enumerate() {
+        let child_start = &child_key[1..];
+        let child_end = children
+            .get(index + 1)
+            .map(|(next_key, _)| &next_key[1..])
+            .or(end);
+        match overlap(child_start, child_end, range) {
+            Overlap::Complete => values.extend(CachedReduction::from_node(node)?),
+            Overlap::Partial => collect(
+                view_entries,
+                reductions,
+                level - 1,
+                child_start,
+                child_end,
+                range,
+                values,
+            )?,
+            Overlap::None => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Reduces `values` into a single value. Returns `None` if `values` is empty.
+fn rereduce(
+    view: &dyn Serialized,
+    mut values: Vec<CachedReduction>,
+) -> Result<Option<CachedReduction>, Error> {
+    if values.len() <= 1 {
+        return Ok(values.pop());
+    }
+
+    let mappings = values
+        .iter()
+        .map(|reduction| (&reduction.key[..], &reduction.value[..]))
+        .collect::<Vec<_>>();
+    let value = view.reduce(&mappings, true)?;
+    Ok(Some(CachedReduction {
+        key: values.swap_remove(0).key,
+        value: Bytes::from(value),
+    }))
+}
+
+/// Returns the key the node at `level` beginning at `start` is stored under.
+/// Nodes are grouped by their level, which keeps each node's children
+/// adjacent to each other.
+fn node_key(level: u8, start: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(start.len() + 1);
+    key.push(level);
+    key.extend_from_slice(start);
+    key
+}
+
+/// Returns the keys bounding the nodes at `level` that begin within
+/// `start..end`. The first key is inclusive, and the last is exclusive.
+fn node_keys(level: u8, start: &[u8], end: Option<&[u8]>) -> (Vec<u8>, Vec<u8>) {
+    let last = end.map_or_else(|| vec![level + 1], |end| node_key(level, end));
+    (node_key(level, start), last)
+}
+
+/// Returns the range of keys from `start` up to `end`.
+fn key_range<'a>(start: &'a [u8], end: Option<&'a [u8]>) -> (Bound<&'a [u8]>, Bound<&'a [u8]>) {
+    (
+        Bound::Included(start),
+        end.map_or(Bound::Unbounded, Bound::Excluded),
+    )
+}
+
+enum Overlap {
+    None,
+    Partial,
+    Complete,
+}
+
+/// Returns how much of `range` overlaps the keys from `start` up to `end`.
+fn overlap(start: &[u8], end: Option<&[u8]>, range: &Range<&[u8]>) -> Overlap {
+    let starts_before = match range.start {
+        connection::Bound::Unbounded => true,
+        connection::Bound::Included(range_start) => range_start <= start,
+        connection::Bound::Excluded(range_start) => range_start < start,
+    };
+    let ends_after = match (range.end, end) {
+        (connection::Bound::Unbounded, _) => true,
+        (_, None) => false,
+        (
+            connection::Bound::Included(range_end) | connection::Bound::Excluded(range_end),
+            Some(end),
+        ) => end <= range_end,
+    };
+    if starts_before && ends_after {
+        return Overlap::Complete;
+    }
+
+    let starts_after = match (range.start, end) {
+        (
+            connection::Bound::Included(range_start) | connection::Bound::Excluded(range_start),
+            Some(end),
+        ) => range_start >= end,
+        _ => false,
+    };
+    let ends_before = match range.end {
+        connection::Bound::Unbounded => false,
+        connection::Bound::Included(range_end) => range_end < start,
+        connection::Bound::Excluded(range_end) => range_end <= start,
+    };
+    if starts_after || ends_before {
+        Overlap::None
+    } else {
+        Overlap::Partial
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(
+        start: connection::Bound<&'static [u8]>,
+        end: connection::Bound<&'static [u8]>,
+    ) -> Range<&'static [u8]> {
+        Range { start, end }
+    }
+
+    #[test]
+    fn splits() {
+        for children in MAX_CHILDREN + 1..MAX_CHILDREN + MIN_CHILDREN {
+            let sizes = split_sizes(children).collect::<Vec<_>>();
+            assert_eq!(sizes.iter().sum::<usize>(), children);
+            assert!(sizes
+                .iter()
+                .all(|size| (MIN_CHILDREN..=MAX_CHILDREN).contains(size)));
+        }
+        assert_eq!(split_sizes(FANOUT * 100).count(), 100);
+    }
+
+    #[test]
+    fn overlaps() {
+        use connection::Bound::{Excluded, Included, Unbounded};
+
+        let all = range(Unbounded, Unbounded);
+        assert!(matches!(overlap(b"", None, &all), Overlap::Complete));
+        assert!(matches!(
+            overlap(b"ab", Some(b"b"), &all),
+            Overlap::Complete
+        ));
+
+        let bounded = range(Included(b"ab"), Excluded(b"b"));
+        assert!(matches!(overlap(b"", None, &bounded), Overlap::Partial));
+        assert!(matches!(
+            overlap(b"a", Some(b"ac"), &bounded),
+            Overlap::Partial
+        ));
+        assert!(matches!(
+            overlap(b"ab", Some(b"b"), &bounded),
+            Overlap::Complete
+        ));
+        assert!(matches!(
+            overlap(b"aa", Some(b"ab"), &bounded),
+            Overlap::None
+        ));
+        assert!(matches!(overlap(b"b", None, &bounded), Overlap::None));
+
+        // Nodes that begin with the excluded start still contain later keys.
+        let after = range(Excluded(b"ab"), Unbounded);
+        assert!(matches!(
+            overlap(b"ab", Some(b"ac"), &after),
+            Overlap::Partial
+        ));
+        assert!(matches!(overlap(b"ac", None, &after), Overlap::Complete));
+        assert!(matches!(overlap(b"aa", Some(b"ab"), &after), Overlap::None));
+
+        // Nodes that end after an inclusive end may contain later keys.
+        let until = range(Unbounded, Included(b"ab"));
+        assert!(matches!(
+            overlap(b"aa", Some(b"ac"), &until),
+            Overlap::Partial
+        ));
+        assert!(matches!(
+            overlap(b"aa", Some(b"ab"), &until),
+            Overlap::Complete
+        ));
+        assert!(matches!(overlap(b"ac", None, &until), Overlap::None));
+    }
+}
@@ -18,11 +18,12 @@ use proc_macro_error::{abort, abort_call_site, proc_macro_error, ResultExt};
 use quote::ToTokens;
 use quote_use::{format_ident_namespaced as format_ident, quote_use as quote};
 use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::token::Paren;
 use syn::{
     parse_macro_input, parse_quote, Data, DataEnum, DataStruct, DeriveInput, Expr, Field, Fields,
-    FieldsNamed, FieldsUnnamed, Ident, Index, LitStr, Path, Token, Type, TypePath, TypeTuple,
-    Variant,
+    FieldsNamed, FieldsUnnamed, Ident, Index, LitInt, LitStr, Path, Token, Type, TypePath,
+    TypeTuple, Variant, Visibility,
 };
 
 // -----------------------------------------------------------------------------
@@ -60,7 +61,7 @@ fn core_path() -> Path {
 #[derive(Attribute)]
 #[attribute(ident = "collection")]
 #[attribute(
-    invalid_field = r#"Only `authority = "some-authority"`, `name = "some-name"`, `views = [SomeView, AnotherView]`, `primary_key = u64`, `natural_id = |contents: &Self| Some(contents.id)`, serialization = SerializationFormat` and `core = bonsaidb::core` are supported attributes"#
+    invalid_field = r#"Only `authority = "some-authority"`, `name = "some-name"`, `views = [SomeView, AnotherView]`, `full_text = [SomeIndex]`, `primary_key = u64`, `natural_id = |contents: &Self| Some(contents.id)`, serialization = SerializationFormat` and `core = bonsaidb::core` are supported attributes"#
 )]
 struct CollectionAttribute {
     authority: Option<Expr>,
@@ -71,6 +72,11 @@ struct CollectionAttribute {
     #[attribute(default)]
     #[attribute(expected = r#"Specify the `views` like so: `view = [SomeView, AnotherView]`"#)]
     views: Vec<Type>,
+    #[attribute(default)]
+    #[attribute(
+        expected = r#"Specify the `full_text` indexes like so: `full_text = [SomeIndex]`"#
+    )]
+    full_text: Vec<Type>,
     #[attribute(
         expected = r#"Specify the `serialization` like so: `serialization = Format` or `serialization = None` to disable deriving it"#
     )]
@@ -86,6 +92,18 @@ struct CollectionAttribute {
         expected = r#"Specify the `natural_id` like so: `natural_id = function_name` or `natural_id = |doc| { .. }`"#
     )]
     natural_id: Option<Expr>,
+    #[attribute(default)]
+    #[attribute(
+        expected = r#"Specify the `unique` constraints like so: `unique = [email, (tenant_id, email)]`"#
+    )]
+    unique: Vec<Expr>,
+    #[attribute(expected = r#"Specify the `version` like so: `version = 2`"#)]
+    version: Option<LitInt>,
+    #[attribute(default)]
+    #[attribute(
+        expected = r#"Specify the `migrations` like so: `migrations = [upgrade_v0, upgrade_v1]`"#
+    )]
+    migrations: Vec<Expr>,
     #[attribute(expected = r#"Specify the the path to `core` like so: `core = bosaidb::core`"#)]
     core: Option<Path>,
 }
@@ -93,22 +111,30 @@ struct CollectionAttribute {
 /// Derives the `bonsaidb::core::schema::Collection` trait.
 #[proc_macro_error]
 /// `#[collection(authority = "Authority", name = "Name", views = [a, b, c])]`
+/// `#[collection(version = 2, migrations = [upgrade_v0, upgrade_v1])]`
+/// `#[collection(unique = [email, (tenant_id, username)])]`
+/// `#[collection(full_text = [MessageSearch])]`
 #[proc_macro_derive(Collection, attributes(collection))]
 pub fn collection_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let DeriveInput {
         attrs,
+        vis,
         ident,
         generics,
-        ..
+        data,
     } = parse_macro_input!(input as DeriveInput);
 
     let CollectionAttribute {
         authority,
         name,
-        views,
+        mut views,
+        full_text,
         serialization,
         primary_key,
         natural_id,
+        unique,
+        version,
+        migrations,
         core,
         encryption_key,
         encryption_required,
@@ -125,6 +151,23 @@ pub fn collection_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 
     let primary_key = primary_key.unwrap_or_else(|| parse_quote!(u64));
 
+    if !unique.is_empty() {
+        if !generics.params.is_empty() {
+            abort_call_site!("`collection(unique)` is not supported on generic collections")
+        }
+        if matches!(&serialization, Some(serialization) if serialization.is_ident("None")) {
+            abort_call_site!(
+                "`collection(unique)` requires the collection to be its own `SerializedCollection::Contents`, which `serialization = None` prevents"
+            )
+        }
+    }
+    let unique_views = unique
+        .iter()
+        .map(|constraint| unique_view(constraint, &vis, &ident, &data, &core))
+        .collect::<Vec<_>>();
+    views.extend(unique_views.iter().map(|(view, _)| parse_quote!(#view)));
+    let unique_views = unique_views.into_iter().map(|(_, definition)| definition);
+
     let serialization = match serialization {
         Some(serialization) if serialization.is_ident("None") => {
             if let Some(natural_id) = natural_id {
@@ -197,6 +240,32 @@ pub fn collection_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStr
         }
     });
 
+    let version_number = version
+        .as_ref()
+        .map_or(Ok(0), LitInt::base10_parse::<u64>)
+        .unwrap_or_abort();
+    let Some(first_migration_version) = version_number.checked_sub(migrations.len() as u64) else {
+        abort_call_site!(
+            "`collection(migrations)` can not contain more migrations than `collection(version)`"
+        )
+    };
+    let version = version.map(|version| {
+        quote! {
+            fn version() -> u64 {
+                #version
+            }
+        }
+    });
+    let migrations = (!migrations.is_empty()).then(|| {
+        let from_versions = first_migration_version..version_number;
+        quote! {
+            fn define_migrations(schema: &mut #core::schema::Schematic) -> Result<(), #core::Error> {
+                #( schema.define_migration::<Self>(#from_versions, #migrations)?; )*
+                Ok(())
+            }
+        }
+    });
+
     quote! {
         impl #impl_generics #core::schema::Collection for #ident #ty_generics #where_clause {
             type PrimaryKey = #primary_key;
@@ -206,15 +275,147 @@ pub fn collection_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStr
             }
             fn define_views(schema: &mut #core::schema::Schematic) -> Result<(), #core::Error> {
                 #( schema.define_view(#views)?; )*
+                #( schema.define_full_text_index(#full_text)?; )*
                 Ok(())
             }
             #encryption
+            #version
+            #migrations
         }
         #serialization
+        #(#unique_views)*
     }
     .into()
 }
 
+/// Generates the unique view that enforces `constraint`, which is either a
+/// single field or a tuple of fields. Returns the name of the view type and
+/// its definition.
+fn unique_view(
+    constraint: &Expr,
+    vis: &Visibility,
+    collection: &Ident,
+    data: &Data,
+    core: &Path,
+) -> (Ident, TokenStream) {
+    let fields = unique_constraint_fields(constraint);
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(FieldsNamed { named, .. }),
+        ..
+    }) = data
+    else {
+        abort!(
+            constraint,
+            "`collection(unique)` requires a struct with named fields"
+        )
+    };
+    let types = fields
+        .iter()
+        .map(|field| {
+            named
+                .iter()
+                .find(|named| named.ident.as_ref() == Some(field))
+                .map_or_else(
+                    || abort!(field, "`{}` is not a field of `{}`", field, collection),
+                    |named| named.ty.clone(),
+                )
+        })
+        .collect::<Vec<_>>();
+
+    let field_names = fields.iter().map(Ident::to_string).collect::<Vec<_>>();
+    let view = Ident::new(
+        &format!(
+            "{collection}Unique{}",
+            field_names
+                .iter()
+                .map(|field| pascal_case(field))
+                .collect::<Vec<_>>()
+                .join("And")
+        ),
+        constraint.span(),
+    );
+    let view_name = format!("unique-{}", field_names.join("-"));
+    let doc = format!(
+        "A unique view ensuring no two `{collection}` documents share the same `{}`.",
+        field_names.join("`, `")
+    );
+    let (key, emitted) = if let ([ty], [field]) = (&types[..], &fields[..]) {
+        (quote!(#ty), quote!(contents.#field))
+    } else {
+        (quote!((#(#types,)*)), quote!((#(contents.#fields,)*)))
+    };
+
+    let definition = quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone)]
+        #vis struct #view;
+
+        impl #core::schema::View for #view {
+            type Collection = #collection;
+            type Key = #key;
+            type Value = ();
+
+            fn name(&self) -> #core::schema::Name {
+                #core::schema::Name::new(#view_name)
+            }
+        }
+
+        impl #core::schema::CollectionViewSchema for #view {
+            type View = Self;
+
+            fn unique(&self) -> bool {
+                true
+            }
+
+            fn map(
+                &self,
+                document: #core::document::CollectionDocument<#collection>,
+            ) -> #core::schema::ViewMapResult<Self::View> {
+                let contents = document.contents;
+                #core::document::Emit::emit_key(&document.header, #emitted)
+            }
+        }
+
+        impl #core::schema::DefaultViewSerialization for #view {}
+    };
+
+    (view, definition)
+}
+
+/// Returns the fields named by a `collection(unique)` constraint.
+fn unique_constraint_fields(constraint: &Expr) -> Vec<Ident> {
+    match constraint {
+        Expr::Path(path) => match path.path.get_ident() {
+            Some(field) => vec![field.clone()],
+            None => abort!(path, "expected a field name"),
+        },
+        Expr::Paren(paren) => unique_constraint_fields(&paren.expr),
+        Expr::Tuple(tuple) if !tuple.elems.is_empty() => tuple
+            .elems
+            .iter()
+            .flat_map(unique_constraint_fields)
+            .collect(),
+        _ => abort!(
+            constraint,
+            "expected a field name or a tuple of field names, like `email` or `(tenant_id, email)`"
+        ),
+    }
+}
+
+fn pascal_case(snake_case: &str) -> String {
+    snake_case
+        .split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
 #[derive(Attribute)]
 #[attribute(ident = "view")]
 #[attribute(
@@ -967,6 +1168,66 @@ pub fn api_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     .into()
 }
 
+#[derive(Attribute)]
+#[attribute(ident = "key_value_namespace")]
+#[attribute(
+    invalid_field = r#"Only `name = "name"`, `key = KeyType`, `value = ValueType` and `core = bonsaidb::core` are supported attributes"#
+)]
+struct KeyValueNamespaceAttribute {
+    name: Option<LitStr>,
+    #[attribute(
+        missing = r#"You need to specify the key type via `#[key_value_namespace(key = KeyType)]`"#
+    )]
+    #[attribute(expected = r#"Specify the key type like so: `key = KeyType`"#)]
+    key: Type,
+    #[attribute(
+        missing = r#"You need to specify the value type via `#[key_value_namespace(value = ValueType)]`"#
+    )]
+    #[attribute(expected = r#"Specify the value type like so: `value = ValueType`"#)]
+    value: Type,
+    #[attribute(expected = r#"Specify the the path to `core` like so: `core = bosaidb::core`"#)]
+    core: Option<Path>,
+}
+
+/// Derives the `bonsaidb::core::keyvalue::KeyValueNamespace` trait.
+#[proc_macro_error]
+/// `#[key_value_namespace(name = "name", key = KeyType, value = ValueType, core = bonsaidb::core)]`
+/// `name` and `core` are optional
+#[proc_macro_derive(KeyValueNamespace, attributes(key_value_namespace))]
+pub fn key_value_namespace_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let DeriveInput {
+        attrs,
+        ident,
+        generics,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
+
+    let KeyValueNamespaceAttribute {
+        name,
+        key,
+        value,
+        core,
+    } = KeyValueNamespaceAttribute::from_attributes(&attrs).unwrap_or_abort();
+
+    let core = core.unwrap_or_else(core_path);
+
+    let name = name
+        .as_ref()
+        .map_or_else(|| ident.to_string(), LitStr::value);
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #core::keyvalue::KeyValueNamespace for #ident #ty_generics #where_clause {
+            type Key = #key;
+            type Value = #value;
+
+            const NAME: &'static str = #name;
+        }
+    }
+    .into()
+}
+
 // -----------------------------------------------------------------------------
 //     - File Macros -
 // -----------------------------------------------------------------------------
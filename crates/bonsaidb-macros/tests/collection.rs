@@ -158,3 +158,102 @@ fn natural_id() {
     #[collection( natural_id = |_:&Self| Some(1_u64))]
     struct Test;
 }
+
+#[test]
+fn version_and_migrations() {
+    use bonsaidb::core::document::OwnedDocument;
+
+    #[derive(Collection, Debug, Deserialize, Serialize)]
+    #[collection(name = "Name")]
+    #[collection(version = 3, migrations = [upgrade, |_: &mut OwnedDocument| Ok(())])]
+    struct Test;
+
+    fn upgrade(_document: &mut OwnedDocument) -> Result<(), bonsaidb::core::Error> {
+        Ok(())
+    }
+
+    assert_eq!(Test::version(), 3);
+
+    let mut schematic = Schematic::from_schema::<Test>().unwrap();
+    let collection = Test::collection_name();
+    assert_eq!(schematic.collection_version(&collection), Some(3));
+    assert!(schematic.migration(&collection, 0).is_none());
+    assert!(schematic.migration(&collection, 1).is_some());
+    assert!(schematic.migration(&collection, 2).is_some());
+    assert!(schematic.define_migration::<Test>(3, upgrade).is_err());
+}
+
+#[test]
+fn unique() {
+    #[derive(Collection, Debug, Deserialize, Serialize)]
+    #[collection(name = "User", unique = [email, (tenant_id, user_name)])]
+    struct User {
+        tenant_id: u32,
+        user_name: String,
+        email: String,
+    }
+
+    assert_eq!(UserUniqueEmail.name(), Name::new("unique-email"));
+    assert!(UserUniqueEmail.unique());
+    assert_eq!(
+        UserUniqueTenantIdAndUserName.name(),
+        Name::new("unique-tenant_id-user_name")
+    );
+    assert!(UserUniqueTenantIdAndUserName.unique());
+
+    let schematic = Schematic::from_schema::<User>().unwrap();
+    assert!(schematic.view::<UserUniqueEmail>().is_ok());
+    assert!(schematic.view::<UserUniqueTenantIdAndUserName>().is_ok());
+
+    let mapping = UserUniqueTenantIdAndUserName
+        .map(CollectionDocument {
+            header: bonsaidb::core::document::CollectionHeader {
+                id: 1,
+                revision: bonsaidb::core::document::Revision::new(b"user"),
+            },
+            contents: User {
+                tenant_id: 7,
+                user_name: String::from("ecton"),
+                email: String::from("ecton@example.com"),
+            },
+        })
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+    assert_eq!(mapping.key, (7, String::from("ecton")));
+}
+
+#[test]
+fn full_text() {
+    use bonsaidb::core::schema::view::full_text::FullTextView;
+    use bonsaidb::core::schema::FullTextIndex;
+
+    #[derive(Collection, Debug, Serialize, Deserialize)]
+    #[collection(name = "messages", full_text = [MessageSearch])]
+    struct Message {
+        body: String,
+    }
+
+    #[derive(Debug)]
+    struct MessageSearch;
+
+    impl FullTextIndex for MessageSearch {
+        type Collection = Message;
+
+        fn name(&self) -> Name {
+            Name::new("search")
+        }
+
+        fn text(
+            &self,
+            document: CollectionDocument<Message>,
+        ) -> Result<Vec<String>, bonsaidb::core::Error> {
+            Ok(vec![document.contents.body])
+        }
+    }
+
+    let schematic = Schematic::from_schema::<Message>().unwrap();
+    let view = schematic.view::<FullTextView<MessageSearch>>().unwrap();
+    assert_eq!(view.view_name().name, Name::new("search"));
+}
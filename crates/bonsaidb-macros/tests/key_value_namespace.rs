@@ -0,0 +1,48 @@
+use bonsaidb::core::keyvalue::KeyValueNamespace;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+struct Session {
+    user_id: u64,
+}
+
+#[test]
+fn core() {
+    #[derive(KeyValueNamespace)]
+    #[key_value_namespace(name = "sessions", key = u64, value = Session)]
+    #[key_value_namespace(core = ::bonsaidb::core)]
+    struct Sessions;
+
+    assert_eq!(Sessions::NAME, "sessions");
+}
+
+#[test]
+fn name_only() {
+    #[derive(KeyValueNamespace)]
+    #[key_value_namespace(name = "sessions", key = u64, value = Session)]
+    struct Sessions;
+
+    assert_eq!(Sessions::NAME, "sessions");
+}
+
+#[test]
+fn default_name() {
+    #[derive(KeyValueNamespace)]
+    #[key_value_namespace(key = String, value = Session)]
+    struct Sessions;
+
+    assert_eq!(Sessions::NAME, "Sessions");
+}
+
+#[test]
+fn key_encoding() {
+    #[derive(KeyValueNamespace)]
+    #[key_value_namespace(key = u64, value = Session)]
+    struct Sessions;
+
+    let encoded = Sessions::encode_key(&258).unwrap();
+    assert_eq!(encoded, "0000000000000102");
+    assert_eq!(Sessions::decode_key(&encoded).unwrap(), 258);
+    // The encoding preserves the ordering of the keys.
+    assert!(Sessions::encode_key(&1).unwrap() < Sessions::encode_key(&256).unwrap());
+}
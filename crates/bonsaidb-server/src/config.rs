@@ -12,7 +12,9 @@ use bonsaidb_core::permissions::{Permissions, Statement};
 use bonsaidb_core::schema::Schema;
 #[cfg(feature = "compression")]
 use bonsaidb_local::config::Compression;
-use bonsaidb_local::config::{Builder, KeyValuePersistence, StorageConfiguration};
+use bonsaidb_local::config::{
+    Builder, KeyValueEviction, KeyValuePersistence, StorageConfiguration,
+};
 #[cfg(feature = "encryption")]
 use bonsaidb_local::vault::AnyVaultKeyStorage;
 
@@ -266,6 +268,16 @@ impl<B: Backend> Builder for ServerConfiguration<B> {
         self
     }
 
+    fn key_value_notifications(mut self, enabled: bool) -> Self {
+        self.storage.key_value_notifications = enabled;
+        self
+    }
+
+    fn key_value_eviction(mut self, eviction: KeyValueEviction) -> Self {
+        self.storage.key_value_eviction = Some(eviction);
+        self
+    }
+
     fn authenticated_permissions<P: Into<Permissions>>(
         mut self,
         authenticated_permissions: P,
@@ -2,23 +2,28 @@ use bonsaidb_core::api::ApiName;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::async_trait::async_trait;
 use bonsaidb_core::connection::{
-    AsyncConnection, AsyncLowLevelConnection, AsyncStorageConnection, HasSession,
+    AsyncConnection, AsyncLowLevelConnection, AsyncResultChunks, AsyncStorageConnection,
+    HasSession, SessionId,
 };
 use bonsaidb_core::keyvalue::AsyncKeyValue;
+use bonsaidb_core::limits::STREAM_IDLE_TIMEOUT;
 use bonsaidb_core::networking::{
     AlterUserPermissionGroupMembership, AlterUserRoleMembership, ApplyTransaction, AssumeIdentity,
-    Compact, CompactCollection, CompactKeyValueStore, Count, CreateDatabase, CreateSubscriber,
-    CreateUser, DeleteDatabase, DeleteDocs, DeleteUser, ExecuteKeyOperation, Get, GetMultiple,
-    LastTransactionId, List, ListAvailableSchemas, ListDatabases, ListExecutedTransactions,
-    ListHeaders, LogOutSession, Publish, PublishToAll, Query, QueryWithDocs, Reduce, ReduceGrouped,
-    SubscribeTo, UnregisterSubscriber, UnsubscribeFrom,
+    CloseStream, Compact, CompactCollection, CompactKeyValueStore, Count, CreateDatabase,
+    CreateSubscriber, CreateUser, DeleteDatabase, DeleteDocs, DeleteUser, ExecuteKeyOperation, Get,
+    GetMultiple, LastTransactionId, List, ListAvailableSchemas, ListDatabases,
+    ListExecutedTransactions, ListHeaders, ListProjected, LogOutSession, NextChunk, Publish,
+    PublishToAll, Query, QueryViewSet, QueryWithDocs, QueryWithProjectedDocs, Reduce,
+    ReduceGrouped, ResultChunk, StreamList, StreamQuery, StreamQueryWithDocs, SubscribeTo,
+    SubscribeToCollectionChanges, SubscribeToKeyChanges, UnregisterSubscriber, UnsubscribeFrom,
 };
 #[cfg(feature = "password-hashing")]
 use bonsaidb_core::networking::{Authenticate, SetUserPassword};
 use bonsaidb_core::pubsub::AsyncPubSub;
+use futures::StreamExt;
 
 use crate::api::{Handler, HandlerError, HandlerResult, HandlerSession};
-use crate::{Backend, Error, ServerConfiguration};
+use crate::{Backend, ConnectedClient, Error, ServerConfiguration};
 
 #[cfg_attr(not(feature = "password-hashing"), allow(unused_mut))]
 pub fn register_api_handlers<B: Backend>(
@@ -29,6 +34,7 @@ pub fn register_api_handlers<B: Backend>(
         .with_api::<ServerDispatcher, AlterUserRoleMembership>()?
         .with_api::<ServerDispatcher, ApplyTransaction>()?
         .with_api::<ServerDispatcher, AssumeIdentity>()?
+        .with_api::<ServerDispatcher, CloseStream>()?
         .with_api::<ServerDispatcher, Compact>()?
         .with_api::<ServerDispatcher, CompactCollection>()?
         .with_api::<ServerDispatcher, CompactKeyValueStore>()?
@@ -45,17 +51,26 @@ pub fn register_api_handlers<B: Backend>(
         .with_api::<ServerDispatcher, LastTransactionId>()?
         .with_api::<ServerDispatcher, List>()?
         .with_api::<ServerDispatcher, ListHeaders>()?
+        .with_api::<ServerDispatcher, ListProjected>()?
         .with_api::<ServerDispatcher, ListAvailableSchemas>()?
         .with_api::<ServerDispatcher, ListDatabases>()?
         .with_api::<ServerDispatcher, ListExecutedTransactions>()?
         .with_api::<ServerDispatcher, LogOutSession>()?
+        .with_api::<ServerDispatcher, NextChunk>()?
         .with_api::<ServerDispatcher, Publish>()?
         .with_api::<ServerDispatcher, PublishToAll>()?
         .with_api::<ServerDispatcher, Query>()?
         .with_api::<ServerDispatcher, QueryWithDocs>()?
+        .with_api::<ServerDispatcher, QueryWithProjectedDocs>()?
+        .with_api::<ServerDispatcher, QueryViewSet>()?
         .with_api::<ServerDispatcher, Reduce>()?
         .with_api::<ServerDispatcher, ReduceGrouped>()?
+        .with_api::<ServerDispatcher, StreamList>()?
+        .with_api::<ServerDispatcher, StreamQuery>()?
+        .with_api::<ServerDispatcher, StreamQueryWithDocs>()?
         .with_api::<ServerDispatcher, SubscribeTo>()?
+        .with_api::<ServerDispatcher, SubscribeToCollectionChanges>()?
+        .with_api::<ServerDispatcher, SubscribeToKeyChanges>()?
         .with_api::<ServerDispatcher, UnregisterSubscriber>()?
         .with_api::<ServerDispatcher, UnsubscribeFrom>()?;
 
@@ -327,6 +342,7 @@ impl<B: Backend> Handler<B, List> for ServerDispatcher {
                 command.ids,
                 command.order,
                 command.limit,
+                command.filter,
                 &command.collection,
             )
             .await
@@ -334,6 +350,30 @@ impl<B: Backend> Handler<B, List> for ServerDispatcher {
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<B, ListProjected> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: ListProjected,
+    ) -> HandlerResult<ListProjected> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.list.database)
+            .await?;
+        database
+            .list_projected_from_collection(
+                command.list.ids,
+                command.list.order,
+                command.list.limit,
+                command.list.filter,
+                command.projection,
+                &command.list.collection,
+            )
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<B, ListHeaders> for ServerDispatcher {
     async fn handle(
@@ -349,6 +389,7 @@ impl<B: Backend> Handler<B, ListHeaders> for ServerDispatcher {
                 command.0.ids,
                 command.0.order,
                 command.0.limit,
+                command.0.filter,
                 &command.0.collection,
             )
             .await
@@ -383,6 +424,7 @@ impl<B: Backend> Handler<B, Query> for ServerDispatcher {
                 command.key,
                 command.order,
                 command.limit,
+                command.cursor,
                 command.access_policy,
             )
             .await
@@ -398,21 +440,66 @@ impl<B: Backend> Handler<B, QueryWithDocs> for ServerDispatcher {
     ) -> HandlerResult<QueryWithDocs> {
         let database = session
             .as_client
-            .database_without_schema(&command.0.database)
+            .database_without_schema(&command.query.database)
             .await?;
         database
             .query_by_name_with_docs(
-                &command.0.view,
-                command.0.key,
-                command.0.order,
-                command.0.limit,
-                command.0.access_policy,
+                &command.query.view,
+                command.query.key,
+                command.query.order,
+                command.query.limit,
+                command.query.cursor,
+                command.query.access_policy,
+                command.filter,
             )
             .await
             .map_err(HandlerError::from)
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<B, QueryWithProjectedDocs> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: QueryWithProjectedDocs,
+    ) -> HandlerResult<QueryWithProjectedDocs> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.query.query.database)
+            .await?;
+        database
+            .query_by_name_with_projected_docs(
+                &command.query.query.view,
+                command.query.query.key,
+                command.query.query.order,
+                command.query.query.limit,
+                command.query.query.cursor,
+                command.query.query.access_policy,
+                command.query.filter,
+                command.projection,
+            )
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<B, QueryViewSet> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: QueryViewSet,
+    ) -> HandlerResult<QueryViewSet> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.database)
+            .await?;
+        database
+            .query_view_set(command.query, command.limit)
+            .await
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<B, Reduce> for ServerDispatcher {
     async fn handle(session: HandlerSession<'_, B>, command: Reduce) -> HandlerResult<Reduce> {
@@ -586,6 +673,40 @@ impl<B: Backend> Handler<B, SubscribeTo> for ServerDispatcher {
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<B, SubscribeToCollectionChanges> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: SubscribeToCollectionChanges,
+    ) -> HandlerResult<SubscribeToCollectionChanges> {
+        session
+            .client
+            .subscribe_to_collection_changes_by_id(
+                command.subscriber_id,
+                &command.collection,
+                session.as_client.session().and_then(|session| session.id),
+            )
+            .map_err(HandlerError::from)
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<B, SubscribeToKeyChanges> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: SubscribeToKeyChanges,
+    ) -> HandlerResult<SubscribeToKeyChanges> {
+        session
+            .client
+            .subscribe_to_key_changes_by_id(
+                command.subscriber_id,
+                command.namespace.as_deref(),
+                session.as_client.session().and_then(|session| session.id),
+            )
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<B, UnsubscribeFrom> for ServerDispatcher {
     async fn handle(
@@ -619,6 +740,189 @@ impl<B: Backend> Handler<B, UnregisterSubscriber> for ServerDispatcher {
     }
 }
 
+#[async_trait]
+impl<B: Backend> Handler<B, StreamList> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: StreamList,
+    ) -> HandlerResult<StreamList> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.list.database)
+            .await?;
+        let session_id = session.as_client.session().and_then(|session| session.id);
+        let (sender, receiver) = flume::bounded(0);
+        let stream_id = session.client.register_stream(receiver, session_id)?;
+        let client = session.client.clone();
+        tokio::task::spawn(async move {
+            let chunks = database.list_chunks_from_collection(
+                command.list.ids,
+                command.list.order,
+                command.list.limit,
+                command.list.filter,
+                command.chunk_size,
+                &command.list.collection,
+            );
+            forward_chunks(
+                chunks,
+                ResultChunk::Documents,
+                sender,
+                &client,
+                stream_id,
+                session_id,
+            )
+            .await;
+        });
+        Ok(stream_id)
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<B, StreamQuery> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: StreamQuery,
+    ) -> HandlerResult<StreamQuery> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.query.database)
+            .await?;
+        let session_id = session.as_client.session().and_then(|session| session.id);
+        let (sender, receiver) = flume::bounded(0);
+        let stream_id = session.client.register_stream(receiver, session_id)?;
+        let client = session.client.clone();
+        tokio::task::spawn(async move {
+            let chunks = database.query_chunks_by_name(
+                &command.query.view,
+                command.query.key,
+                command.query.order,
+                command.query.limit,
+                command.query.cursor,
+                command.query.access_policy,
+                command.chunk_size,
+            );
+            forward_chunks(
+                chunks,
+                ResultChunk::Mappings,
+                sender,
+                &client,
+                stream_id,
+                session_id,
+            )
+            .await;
+        });
+        Ok(stream_id)
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<B, StreamQueryWithDocs> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: StreamQueryWithDocs,
+    ) -> HandlerResult<StreamQueryWithDocs> {
+        let database = session
+            .as_client
+            .database_without_schema(&command.query.query.database)
+            .await?;
+        let session_id = session.as_client.session().and_then(|session| session.id);
+        let (sender, receiver) = flume::bounded(0);
+        let stream_id = session.client.register_stream(receiver, session_id)?;
+        let client = session.client.clone();
+        tokio::task::spawn(async move {
+            let chunks = database.query_chunks_by_name_with_docs(
+                &command.query.query.view,
+                command.query.query.key,
+                command.query.query.order,
+                command.query.query.limit,
+                command.query.query.cursor,
+                command.query.query.access_policy,
+                command.query.filter,
+                command.chunk_size,
+            );
+            forward_chunks(
+                chunks,
+                ResultChunk::MappedDocuments,
+                sender,
+                &client,
+                stream_id,
+                session_id,
+            )
+            .await;
+        });
+        Ok(stream_id)
+    }
+}
+
+/// Sends each chunk from `chunks` to `sender` after converting it with
+/// `convert`, followed by `None` once every chunk has been sent. Each chunk is
+/// only read once the previous chunk has been received, and reading stops
+/// once the stream is closed. If the client doesn't receive a chunk within
+/// [`STREAM_IDLE_TIMEOUT`], the stream is closed.
+async fn forward_chunks<B: Backend, T>(
+    mut chunks: AsyncResultChunks<'_, T>,
+    convert: fn(T) -> ResultChunk,
+    sender: flume::Sender<Option<Result<ResultChunk, bonsaidb_core::Error>>>,
+    client: &ConnectedClient<B>,
+    stream_id: u64,
+    session_id: Option<SessionId>,
+) {
+    loop {
+        let chunk = chunks.next().await.map(|chunk| chunk.map(convert));
+        let finished = !matches!(chunk, Some(Ok(_)));
+        match tokio::time::timeout(STREAM_IDLE_TIMEOUT, sender.send_async(chunk)).await {
+            Ok(Ok(())) if !finished => {}
+            Ok(_) => break,
+            Err(_) => {
+                // The client stopped reading from the stream.
+                drop(client.close_stream(stream_id, session_id));
+                break;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<B, NextChunk> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: NextChunk,
+    ) -> HandlerResult<NextChunk> {
+        let session_id = session.as_client.session().and_then(|session| session.id);
+        let stream = session
+            .client
+            .result_stream(command.stream_id, session_id)?;
+        match stream.recv_async().await {
+            Ok(Some(Ok(chunk))) => Ok(Some(chunk)),
+            Ok(Some(Err(err))) => {
+                session.client.close_stream(command.stream_id, session_id)?;
+                Err(HandlerError::from(err))
+            }
+            Ok(None) | Err(_) => {
+                // Every chunk has been sent.
+                session.client.close_stream(command.stream_id, session_id)?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<B: Backend> Handler<B, CloseStream> for ServerDispatcher {
+    async fn handle(
+        session: HandlerSession<'_, B>,
+        command: CloseStream,
+    ) -> HandlerResult<CloseStream> {
+        session
+            .client
+            .close_stream(
+                command.stream_id,
+                session.as_client.session().and_then(|session| session.id),
+            )
+            .map_err(HandlerError::from)
+    }
+}
+
 #[async_trait]
 impl<B: Backend> Handler<B, ExecuteKeyOperation> for ServerDispatcher {
     async fn handle(
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use async_lock::{Mutex, MutexGuard};
@@ -8,8 +9,10 @@ use bonsaidb_core::api;
 use bonsaidb_core::api::ApiName;
 use bonsaidb_core::arc_bytes::serde::Bytes;
 use bonsaidb_core::connection::{Session, SessionId};
-use bonsaidb_core::networking::MessageReceived;
+use bonsaidb_core::limits::STREAM_MAX_OPEN_PER_SESSION;
+use bonsaidb_core::networking::{MessageReceived, ResultChunk};
 use bonsaidb_core::pubsub::{Receiver, Subscriber as _};
+use bonsaidb_core::schema::CollectionName;
 use bonsaidb_local::Subscriber;
 use bonsaidb_utils::fast_async_lock;
 use derive_where::derive_where;
@@ -43,14 +46,21 @@ struct Data<B: Backend = NoBackend> {
     transport: Transport,
     response_sender: Sender<(Option<SessionId>, ApiName, Bytes)>,
     client_data: Mutex<Option<B::ClientData>>,
+    next_stream_id: AtomicU64,
 }
 
 #[derive(Debug)]
 struct ClientSession {
     session: Session,
     subscribers: HashMap<u64, Subscriber>,
+    streams: HashMap<u64, ResultStream>,
 }
 
+/// The chunks of results of a stream opened by a client, followed by `None`
+/// once every chunk has been sent. The task producing the chunks waits for
+/// each chunk to be received before producing the next.
+pub(crate) type ResultStream = flume::Receiver<Option<Result<ResultChunk, bonsaidb_core::Error>>>;
+
 impl<B: Backend> ConnectedClient<B> {
     /// Returns the address of the connected client.
     #[must_use]
@@ -71,6 +81,7 @@ impl<B: Backend> ConnectedClient<B> {
             ClientSession {
                 session,
                 subscribers: HashMap::default(),
+                streams: HashMap::default(),
             },
         );
     }
@@ -194,6 +205,50 @@ impl<B: Backend> ConnectedClient<B> {
         }
     }
 
+    pub(crate) fn subscribe_to_collection_changes_by_id(
+        &self,
+        subscriber_id: u64,
+        collection: &CollectionName,
+        check_session_id: Option<SessionId>,
+    ) -> Result<(), crate::Error> {
+        let mut sessions = self.data.sessions.write();
+        if let Some(client_session) = sessions.get_mut(&check_session_id) {
+            if let Some(subscriber) = client_session.subscribers.get(&subscriber_id) {
+                subscriber.subscribe_to_collection_changes(collection)?;
+                Ok(())
+            } else {
+                Err(Error::other(
+                    "bonsaidb-server pubsub",
+                    "invalid subscriber id",
+                ))
+            }
+        } else {
+            Err(Error::other("bonsaidb-server auth", "invalid session id"))
+        }
+    }
+
+    pub(crate) fn subscribe_to_key_changes_by_id(
+        &self,
+        subscriber_id: u64,
+        namespace: Option<&str>,
+        check_session_id: Option<SessionId>,
+    ) -> Result<(), crate::Error> {
+        let mut sessions = self.data.sessions.write();
+        if let Some(client_session) = sessions.get_mut(&check_session_id) {
+            if let Some(subscriber) = client_session.subscribers.get(&subscriber_id) {
+                subscriber.subscribe_to_key_changes(namespace)?;
+                Ok(())
+            } else {
+                Err(Error::other(
+                    "bonsaidb-server pubsub",
+                    "invalid subscriber id",
+                ))
+            }
+        } else {
+            Err(Error::other("bonsaidb-server auth", "invalid session id"))
+        }
+    }
+
     pub(crate) fn unsubscribe_by_id(
         &self,
         subscriber_id: u64,
@@ -235,6 +290,61 @@ impl<B: Backend> ConnectedClient<B> {
             Err(Error::other("bonsaidb-server auth", "invalid session id"))
         }
     }
+
+    pub(crate) fn register_stream(
+        &self,
+        stream: ResultStream,
+        session_id: Option<SessionId>,
+    ) -> Result<u64, crate::Error> {
+        let mut sessions = self.data.sessions.write();
+        if let Some(client_session) = sessions.get_mut(&session_id) {
+            if client_session.streams.len() >= STREAM_MAX_OPEN_PER_SESSION as usize {
+                return Err(Error::other(
+                    "bonsaidb-server streams",
+                    "too many open streams",
+                ));
+            }
+            let stream_id = self.data.next_stream_id.fetch_add(1, Ordering::Relaxed);
+            client_session.streams.insert(stream_id, stream);
+            Ok(stream_id)
+        } else {
+            Err(Error::other("bonsaidb-server auth", "invalid session id"))
+        }
+    }
+
+    pub(crate) fn result_stream(
+        &self,
+        stream_id: u64,
+        check_session_id: Option<SessionId>,
+    ) -> Result<ResultStream, crate::Error> {
+        let sessions = self.data.sessions.read();
+        if let Some(client_session) = sessions.get(&check_session_id) {
+            if let Some(stream) = client_session.streams.get(&stream_id) {
+                Ok(stream.clone())
+            } else {
+                Err(Error::other("bonsaidb-server streams", "invalid stream id"))
+            }
+        } else {
+            Err(Error::other("bonsaidb-server auth", "invalid session id"))
+        }
+    }
+
+    pub(crate) fn close_stream(
+        &self,
+        stream_id: u64,
+        check_session_id: Option<SessionId>,
+    ) -> Result<(), crate::Error> {
+        let mut sessions = self.data.sessions.write();
+        if let Some(client_session) = sessions.get_mut(&check_session_id) {
+            if client_session.streams.remove(&stream_id).is_some() {
+                Ok(())
+            } else {
+                Err(Error::other("bonsaidb-server streams", "invalid stream id"))
+            }
+        } else {
+            Err(Error::other("bonsaidb-server auth", "invalid session id"))
+        }
+    }
 }
 
 /// A locked reference to associated client data.
@@ -276,6 +386,7 @@ impl<B: Backend> OwnedClient<B> {
             ClientSession {
                 session: default_session,
                 subscribers: HashMap::default(),
+                streams: HashMap::default(),
             },
         );
         Self {
@@ -287,6 +398,7 @@ impl<B: Backend> OwnedClient<B> {
                     response_sender,
                     sessions: RwLock::new(session),
                     client_data: Mutex::default(),
+                    next_stream_id: AtomicU64::default(),
                 }),
             },
             runtime: Arc::new(tokio::runtime::Handle::current()),
@@ -2,9 +2,12 @@ use std::ops::Deref;
 
 use async_trait::async_trait;
 use bonsaidb_core::connection::{
-    AccessPolicy, AsyncLowLevelConnection, HasSchema, HasSession, Range, SerializedQueryKey, Sort,
+    AccessPolicy, AsyncLowLevelConnection, AsyncResultChunks, HasSchema, HasSession, Range,
+    SerializedQueryKey, Sort, ViewCursor, ViewSetQuery,
+};
+use bonsaidb_core::document::{
+    DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection,
 };
-use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
 use bonsaidb_core::keyvalue::AsyncKeyValue;
 use bonsaidb_core::permissions::Permissions;
 use bonsaidb_core::pubsub::AsyncPubSub;
@@ -152,22 +155,51 @@ impl<B: Backend> AsyncLowLevelConnection for ServerDatabase<B> {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
         self.db
-            .list_from_collection(ids, order, limit, collection)
+            .list_from_collection(ids, order, limit, filter, collection)
             .await
     }
 
+    async fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, bonsaidb_core::Error> {
+        self.db
+            .list_projected_from_collection(ids, order, limit, filter, projection, collection)
+            .await
+    }
+
+    fn list_chunks_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        chunk_size: u32,
+        collection: &CollectionName,
+    ) -> AsyncResultChunks<'_, Vec<OwnedDocument>> {
+        self.db
+            .list_chunks_from_collection(ids, order, limit, filter, chunk_size, collection)
+    }
+
     async fn list_headers_from_collection(
         &self,
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, bonsaidb_core::Error> {
         self.db
-            .list_headers_from_collection(ids, order, limit, collection)
+            .list_headers_from_collection(ids, order, limit, filter, collection)
             .await
     }
 
@@ -200,10 +232,11 @@ impl<B: Backend> AsyncLowLevelConnection for ServerDatabase<B> {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, bonsaidb_core::Error> {
         self.db
-            .query_by_name(view, key, order, limit, access_policy)
+            .query_by_name(view, key, order, limit, cursor, access_policy)
             .await
     }
 
@@ -213,13 +246,86 @@ impl<B: Backend> AsyncLowLevelConnection for ServerDatabase<B> {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
         self.db
-            .query_by_name_with_docs(view, key, order, limit, access_policy)
+            .query_by_name_with_docs(view, key, order, limit, cursor, access_policy, filter)
+            .await
+    }
+
+    fn query_chunks_by_name(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, Vec<schema::view::map::Serialized>> {
+        self.db
+            .query_chunks_by_name(view, key, order, limit, cursor, access_policy, chunk_size)
+    }
+
+    fn query_chunks_by_name_with_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, schema::view::map::MappedSerializedDocuments> {
+        self.db.query_chunks_by_name_with_docs(
+            view,
+            key,
+            order,
+            limit,
+            cursor,
+            access_policy,
+            filter,
+            chunk_size,
+        )
+    }
+
+    async fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<schema::view::map::MappedSerializedDocuments<ProjectedDocument>, bonsaidb_core::Error>
+    {
+        self.db
+            .query_by_name_with_projected_docs(
+                view,
+                key,
+                order,
+                limit,
+                cursor,
+                access_policy,
+                filter,
+                projection,
+            )
             .await
     }
 
+    async fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        self.db.query_view_set(query, limit).await
+    }
+
     async fn reduce_by_name(
         &self,
         view: &ViewName,
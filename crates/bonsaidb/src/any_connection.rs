@@ -1,11 +1,14 @@
 use bonsaidb_client::{AsyncClient, AsyncRemoteDatabase};
 use bonsaidb_core::async_trait::async_trait;
 use bonsaidb_core::connection::{
-    self, AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncStorageConnection,
-    HasSchema, HasSession, IdentityReference, Range, SerializedQueryKey, Session, Sort,
+    self, AccessPolicy, AsyncConnection, AsyncLowLevelConnection, AsyncResultChunks,
+    AsyncStorageConnection, HasSchema, HasSession, IdentityReference, Range, SerializedQueryKey,
+    Session, Sort, ViewCursor, ViewSetQuery,
 };
-use bonsaidb_core::document::{DocumentId, Header, OwnedDocument};
-use bonsaidb_core::schema::view::map::MappedSerializedValue;
+use bonsaidb_core::document::{
+    DocumentId, Filter, Header, OwnedDocument, ProjectedDocument, Projection,
+};
+use bonsaidb_core::schema::view::map::{MappedSerializedDocuments, MappedSerializedValue};
 use bonsaidb_core::schema::{
     self, Collection, CollectionName, Nameable, Schema, SchemaName, SchemaSummary, Schematic,
     ViewName,
@@ -338,17 +341,45 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
         match self {
             Self::Local(server) => {
                 server
-                    .list_from_collection(ids, order, limit, collection)
+                    .list_from_collection(ids, order, limit, filter, collection)
+                    .await
+            }
+            Self::Networked(client) => {
+                client
+                    .list_from_collection(ids, order, limit, filter, collection)
+                    .await
+            }
+        }
+    }
+
+    async fn list_projected_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        projection: Projection,
+        collection: &CollectionName,
+    ) -> Result<Vec<ProjectedDocument>, bonsaidb_core::Error> {
+        match self {
+            Self::Local(server) => {
+                server
+                    .list_projected_from_collection(
+                        ids, order, limit, filter, projection, collection,
+                    )
                     .await
             }
             Self::Networked(client) => {
                 client
-                    .list_from_collection(ids, order, limit, collection)
+                    .list_projected_from_collection(
+                        ids, order, limit, filter, projection, collection,
+                    )
                     .await
             }
         }
@@ -359,17 +390,18 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
         ids: Range<DocumentId>,
         order: Sort,
         limit: Option<u32>,
+        filter: Option<Filter>,
         collection: &CollectionName,
     ) -> Result<Vec<Header>, bonsaidb_core::Error> {
         match self {
             Self::Local(server) => {
                 server
-                    .list_headers_from_collection(ids, order, limit, collection)
+                    .list_headers_from_collection(ids, order, limit, filter, collection)
                     .await
             }
             Self::Networked(client) => {
                 client
-                    .list_headers_from_collection(ids, order, limit, collection)
+                    .list_headers_from_collection(ids, order, limit, filter, collection)
                     .await
             }
         }
@@ -413,17 +445,18 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
     ) -> Result<Vec<schema::view::map::Serialized>, bonsaidb_core::Error> {
         match self {
             Self::Local(server) => {
                 server
-                    .query_by_name(view, key, order, limit, access_policy)
+                    .query_by_name(view, key, order, limit, cursor, access_policy)
                     .await
             }
             Self::Networked(client) => {
                 client
-                    .query_by_name(view, key, order, limit, access_policy)
+                    .query_by_name(view, key, order, limit, cursor, access_policy)
                     .await
             }
         }
@@ -435,22 +468,79 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
         key: Option<SerializedQueryKey>,
         order: Sort,
         limit: Option<u32>,
+        cursor: Option<ViewCursor>,
         access_policy: AccessPolicy,
+        filter: Option<Filter>,
     ) -> Result<schema::view::map::MappedSerializedDocuments, bonsaidb_core::Error> {
         match self {
             Self::Local(server) => {
                 server
-                    .query_by_name_with_docs(view, key, order, limit, access_policy)
+                    .query_by_name_with_docs(view, key, order, limit, cursor, access_policy, filter)
+                    .await
+            }
+            Self::Networked(client) => {
+                client
+                    .query_by_name_with_docs(view, key, order, limit, cursor, access_policy, filter)
+                    .await
+            }
+        }
+    }
+
+    async fn query_by_name_with_projected_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        projection: Projection,
+    ) -> Result<schema::view::map::MappedSerializedDocuments<ProjectedDocument>, bonsaidb_core::Error>
+    {
+        match self {
+            Self::Local(server) => {
+                server
+                    .query_by_name_with_projected_docs(
+                        view,
+                        key,
+                        order,
+                        limit,
+                        cursor,
+                        access_policy,
+                        filter,
+                        projection,
+                    )
                     .await
             }
             Self::Networked(client) => {
                 client
-                    .query_by_name_with_docs(view, key, order, limit, access_policy)
+                    .query_by_name_with_projected_docs(
+                        view,
+                        key,
+                        order,
+                        limit,
+                        cursor,
+                        access_policy,
+                        filter,
+                        projection,
+                    )
                     .await
             }
         }
     }
 
+    async fn query_view_set(
+        &self,
+        query: ViewSetQuery,
+        limit: Option<u32>,
+    ) -> Result<Vec<OwnedDocument>, bonsaidb_core::Error> {
+        match self {
+            Self::Local(server) => server.query_view_set(query, limit).await,
+            Self::Networked(client) => client.query_view_set(query, limit).await,
+        }
+    }
+
     async fn reduce_by_name(
         &self,
         view: &ViewName,
@@ -494,6 +584,90 @@ impl<B: Backend> AsyncLowLevelConnection for AnyDatabase<B> {
             Self::Networked(client) => client.delete_docs_by_name(view, key, access_policy).await,
         }
     }
+
+    fn list_chunks_from_collection(
+        &self,
+        ids: Range<DocumentId>,
+        order: Sort,
+        limit: Option<u32>,
+        filter: Option<Filter>,
+        chunk_size: u32,
+        collection: &CollectionName,
+    ) -> AsyncResultChunks<'_, Vec<OwnedDocument>> {
+        match self {
+            Self::Local(server) => server
+                .list_chunks_from_collection(ids, order, limit, filter, chunk_size, collection),
+            Self::Networked(client) => client
+                .list_chunks_from_collection(ids, order, limit, filter, chunk_size, collection),
+        }
+    }
+
+    fn query_chunks_by_name(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, Vec<schema::view::map::Serialized>> {
+        match self {
+            Self::Local(server) => server.query_chunks_by_name(
+                view,
+                key,
+                order,
+                limit,
+                cursor,
+                access_policy,
+                chunk_size,
+            ),
+            Self::Networked(client) => client.query_chunks_by_name(
+                view,
+                key,
+                order,
+                limit,
+                cursor,
+                access_policy,
+                chunk_size,
+            ),
+        }
+    }
+
+    fn query_chunks_by_name_with_docs(
+        &self,
+        view: &ViewName,
+        key: Option<SerializedQueryKey>,
+        order: Sort,
+        limit: Option<u32>,
+        cursor: Option<ViewCursor>,
+        access_policy: AccessPolicy,
+        filter: Option<Filter>,
+        chunk_size: u32,
+    ) -> AsyncResultChunks<'_, MappedSerializedDocuments> {
+        match self {
+            Self::Local(server) => server.query_chunks_by_name_with_docs(
+                view,
+                key,
+                order,
+                limit,
+                cursor,
+                access_policy,
+                filter,
+                chunk_size,
+            ),
+            Self::Networked(client) => client.query_chunks_by_name_with_docs(
+                view,
+                key,
+                order,
+                limit,
+                cursor,
+                access_policy,
+                filter,
+                chunk_size,
+            ),
+        }
+    }
 }
 
 impl<B: Backend> HasSchema for AnyDatabase<B> {
@@ -1,28 +1,19 @@
-//! This example shows a naive approach to implementing text search. It works by
-//! creating a View with the Key being each word contained within a document.
-//! This example uses the Value type to store which field the match came from.
+//! This example shows how to search a collection using a full-text index.
 //!
-//! While this is a naive approach, this can be used to build a fairly robust
-//! search. After retrieving the query results from the view, analysis can be
-//! done on the results to rank the matched documents based on how many times a
-//! keyword hit, which fields it matched upon, etc.
-//!
-//! While this approach can be powerful, it pales in comparsion to full text
-//! search capabilities. The tracking issue for adding full text indexes to
-//! BonsaiDb is here: <https://github.com/khonsulabs/bonsaidb/issues/149>.
-use std::str::Chars;
+//! A [`FullTextIndex`] splits the text of each document into terms, skipping
+//! common words like "the" and "and", and stemming words like "eggs" into
+//! "egg". Searches return documents ranked by how relevant they are to the
+//! query, and phrases surrounded by double quotes must appear in every result.
 use std::time::SystemTime;
 
-use bonsaidb::core::document::{CollectionDocument, Emit};
-use bonsaidb::core::schema::{
-    Collection, CollectionViewSchema, SerializedCollection, SerializedView, View, ViewMapResult,
-};
+use bonsaidb::core::document::CollectionDocument;
+use bonsaidb::core::schema::{Collection, FullTextIndex, Name, SerializedCollection};
 use bonsaidb::local::config::{Builder, StorageConfiguration};
 use bonsaidb::local::Database;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Collection)]
-#[collection(name = "messages", views = [MessagesByWords])]
+#[collection(name = "messages", full_text = [MessageSearch])]
 struct Message {
     pub timestamp: SystemTime,
     pub subject: String,
@@ -40,25 +31,23 @@ impl Message {
     }
 }
 
-#[derive(View, Debug, Clone)]
-#[view(name = "by-keyword", collection = Message, key = String, value = String)]
-struct MessagesByWords;
+#[derive(Debug)]
+struct MessageSearch;
+
+impl FullTextIndex for MessageSearch {
+    type Collection = Message;
 
-impl CollectionViewSchema for MessagesByWords {
-    type View = Self;
+    fn name(&self) -> Name {
+        Name::new("search")
+    }
 
-    fn map(
+    fn text(
         &self,
-        document: CollectionDocument<<Self::View as View>::Collection>,
-    ) -> ViewMapResult<Self::View> {
-        // Emit a key/value mapping for each word found in the subject and body.
-        let subject_words =
-            keywords(&document.contents.subject).map(|word| (word, String::from("subject")));
-        let body_words = keywords(&document.contents.body).map(|word| (word, String::from("body")));
-        subject_words
-            .chain(body_words)
-            .map(|(key, value)| document.header.emit_key_and_value(key, value))
-            .collect()
+        document: CollectionDocument<Message>,
+    ) -> Result<Vec<String>, bonsaidb::core::Error> {
+        // The subject and body are indexed separately, which prevents phrases
+        // from matching across them.
+        Ok(vec![document.contents.subject, document.contents.body])
     }
 }
 
@@ -69,68 +58,23 @@ fn main() -> Result<(), bonsaidb::core::Error> {
     Message::new("Re: Groceries", "2% milk? How are our eggs?").push_into(&db)?;
     Message::new("Re: Groceries", "Yes. We could use another dozen eggs.").push_into(&db)?;
 
-    for result in &MessagesByWords::entries(&db)
-        .with_key("eggs")
-        .query_with_collection_docs()?
-    {
+    for result in MessageSearch.search(&db, "eggs").query_with_docs()? {
         println!(
-            "Contained `eggs` in field {} : {:?}",
-            result.value, result.document
+            "Matched `eggs` with a score of {:.3}: {:?}",
+            result.score, result.document
         );
     }
 
-    for message in MessagesByWords::entries(&db)
-        .with_key_prefix("doz")
-        .query_with_collection_docs()?
-        .documents
+    for result in MessageSearch
+        .search(&db, r#""dozen egg""#)
+        .query_with_docs()?
     {
-        println!("Contained a word starting with `doz`: {message:?}");
+        println!("Contained the phrase `dozen egg`: {:?}", result.document);
     }
 
     Ok(())
 }
 
-/// Splits `source` into "words", where words are:
-///
-/// - Contiguous sequences of alphanumeric characters
-/// - At least 4 characters long (Avoids "the", "and", etc, but it is *overly*
-///   restrictive).
-/// - Non-alphanumeric is always considered a word break and excluded from
-///   results. This means that "m.d." will never yield any 'words' in this
-///   algorithm, because 'm' and 'd' will be considered separate words and
-///   excluded for being too short.
-fn keywords(source: &str) -> impl Iterator<Item = String> + '_ {
-    struct KeywordEmitter<'a> {
-        chars: Chars<'a>,
-    }
-
-    impl<'a> Iterator for KeywordEmitter<'a> {
-        type Item = String;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            let mut word = String::new();
-            for ch in &mut self.chars {
-                if ch.is_alphanumeric() {
-                    for ch in ch.to_lowercase() {
-                        word.push(ch);
-                    }
-                } else if !word.is_empty() {
-                    if word.len() > 3 {
-                        return Some(word);
-                    }
-                    word.clear();
-                }
-            }
-
-            (word.len() > 3).then_some(word)
-        }
-    }
-
-    KeywordEmitter {
-        chars: source.chars(),
-    }
-}
-
 #[test]
 fn runs() {
     main().unwrap()